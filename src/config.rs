@@ -0,0 +1,681 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use regex::{Regex, RegexBuilder};
+
+use crate::reddit::quota::ToolCategory;
+
+/// Settings read once at startup, keyed by the environment variable each one
+/// stands in for.
+#[derive(Debug, Default)]
+struct StartupSettings {
+    config_path: Option<PathBuf>,
+    /// From the command line, which wins over the environment.
+    flags: BTreeMap<&'static str, String>,
+    /// From the `[reddit]` and `[server]` sections of the config file, which
+    /// the environment overrides.
+    file: BTreeMap<&'static str, String>,
+}
+
+static STARTUP: OnceLock<StartupSettings> = OnceLock::new();
+
+/// Records the config file and command line flags. Call once, before anything
+/// reads a setting.
+pub fn init(config_path: Option<PathBuf>, flags: BTreeMap<&'static str, String>) {
+    let file = config_path
+        .as_deref()
+        .map(|path| match RuntimeConfig::load(path) {
+            Ok(config) => config.startup_settings(),
+            Err(e) => {
+                tracing::error!("{}; using the environment only", e);
+                BTreeMap::new()
+            }
+        })
+        .unwrap_or_default();
+    let settings = StartupSettings {
+        config_path,
+        flags,
+        file,
+    };
+    if STARTUP.set(settings).is_err() {
+        tracing::warn!("Startup settings were already initialized");
+    }
+}
+
+/// Reads the setting `key`: a command line flag, then the environment, then
+/// the file named by `<key>_FILE` (Docker/Kubernetes secrets), then the config
+/// file.
+pub fn env_or_file(key: &str) -> Option<String> {
+    let startup = STARTUP.get();
+    if let Some(value) = startup.and_then(|startup| startup.flags.get(key)) {
+        return Some(value.clone());
+    }
+    if let Ok(value) = env::var(key) {
+        return Some(value);
+    }
+
+    if let Ok(path) = env::var(format!("{}_FILE", key)) {
+        return match fs::read_to_string(&path) {
+            Ok(value) => Some(value.trim_end_matches(['\r', '\n']).to_string()),
+            Err(e) => {
+                tracing::error!("Failed to read {}_FILE from {}: {}", key, path, e);
+                None
+            }
+        };
+    }
+    startup.and_then(|startup| startup.file.get(key).cloned())
+}
+
+/// Settings loaded from the TOML file named by `--config` or `CONFIG_FILE`.
+/// Everything but `[reddit]` and `[server]` can be changed while the server runs.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    pub reddit: RedditSettings,
+    pub server: ServerSettings,
+    pub tools: ToolsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub content: ContentConfig,
+    pub cooldowns: CooldownConfig,
+    pub quotas: QuotaConfig,
+    pub exports: ExportConfig,
+    pub inbox: InboxConfig,
+    pub replies: ReplyConfig,
+    pub links: LinkConfig,
+    pub notifications: NotificationConfig,
+    /// Named moderation action bundles run by run_macro.
+    pub macros: BTreeMap<String, MacroConfig>,
+}
+
+/// Credentials and identity, read at startup. Each one is overridden by the
+/// environment variable of the same name in upper case, e.g. `CLIENT_ID`,
+/// except `username`, `password`, `totp_secret`, `user_agent`, `auth_mode`,
+/// `app_type` and `device_id`, which are prefixed with `REDDIT_`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedditSettings {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub redirect_url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub totp_secret: Option<String>,
+    pub user_agent: Option<String>,
+    /// `user` or `app_only`.
+    pub auth_mode: Option<String>,
+    /// `script`, `web` or `installed`.
+    pub app_type: Option<String>,
+    pub device_id: Option<String>,
+}
+
+/// How MCP is served, read at startup and overridden by `MCP_TRANSPORT`,
+/// `MCP_BIND` and `HEALTH_BIND`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    /// `stdio` (default) or `http`.
+    pub transport: Option<String>,
+    pub bind: Option<String>,
+    pub health_bind: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolsConfig {
+    /// When set, only these tools are advertised and callable.
+    pub allowlist: Option<Vec<String>>,
+    /// When set, only tools of these categories (read, write, local) are
+    /// advertised and callable.
+    pub groups: Option<Vec<ToolCategory>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub max_concurrent_requests: Option<usize>,
+    pub interactive_reserved_requests: Option<usize>,
+}
+
+/// Personal data handling of the export tools.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportConfig {
+    /// Replace usernames with pseudonyms and remove emails and phone numbers
+    /// unless a call asks otherwise.
+    pub scrub_pii: bool,
+    /// Secret the pseudonyms are derived from. Keep it to get the same
+    /// pseudonyms in later exports.
+    pub pseudonym_key: Option<String>,
+}
+
+/// Human approval of the comments and messages drafted through the tools.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReplyConfig {
+    /// Queue post_comment, send_message and send_modmail_reply until
+    /// approve_reply releases them, instead of sending them right away.
+    pub require_approval: bool,
+}
+
+/// Limits of notify_thread_participants, which messages many users at once.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// Most users one call messages.
+    pub max_recipients: u32,
+    /// A user is notified at most once within this many hours.
+    pub cooldown_hours: u32,
+    /// Pause between two messages.
+    pub send_interval_seconds: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            max_recipients: 25,
+            cooldown_hours: 24,
+            send_interval_seconds: 1,
+        }
+    }
+}
+
+/// Caps on the tool calls made through this server within a sliding window.
+/// Unset categories are unlimited.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotaConfig {
+    pub window_minutes: u32,
+    pub read: Option<u32>,
+    pub write: Option<u32>,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            window_minutes: 60,
+            read: None,
+            write: None,
+        }
+    }
+}
+
+impl QuotaConfig {
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(u64::from(self.window_minutes.max(1)) * 60)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentConfig {
+    /// When false, over_18 posts are dropped from every listing.
+    pub allow_nsfw: bool,
+    /// What happens to emoji in tool results.
+    pub emoji: EmojiMode,
+    /// Words replaced with `[masked]` in the texts of tool results, matched
+    /// as whole words regardless of case.
+    pub masked_words: Vec<String>,
+}
+
+impl Default for ContentConfig {
+    fn default() -> Self {
+        Self {
+            allow_nsfw: true,
+            emoji: EmojiMode::Keep,
+            masked_words: Vec::new(),
+        }
+    }
+}
+
+/// Clean-up of the URLs of link posts before they are submitted, since many
+/// subreddits remove shortened or tracking links.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkConfig {
+    /// Replace links of the `shorteners` hosts with the URL they redirect to.
+    pub resolve_shorteners: bool,
+    pub shorteners: Vec<String>,
+    /// Remove the `tracking_parameters` from the query string.
+    pub strip_tracking: bool,
+    /// Query parameter names; a trailing `*` matches any suffix, e.g. `utm_*`.
+    pub tracking_parameters: Vec<String>,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        Self {
+            resolve_shorteners: true,
+            shorteners: strings(&[
+                "bit.ly",
+                "buff.ly",
+                "goo.gl",
+                "is.gd",
+                "lnkd.in",
+                "ow.ly",
+                "rebrand.ly",
+                "shorturl.at",
+                "t.co",
+                "t.ly",
+                "tiny.cc",
+                "tinyurl.com",
+            ]),
+            strip_tracking: true,
+            tracking_parameters: strings(&[
+                "utm_*", "fbclid", "gclid", "dclid", "msclkid", "igshid", "mc_cid", "mc_eid",
+                "_hsenc", "_hsmi", "yclid",
+            ]),
+        }
+    }
+}
+
+/// Handling of emoji for hosts that cannot display or speak them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiMode {
+    Keep,
+    Strip,
+    /// Common emoji become their :name:, typographic punctuation becomes ASCII
+    /// and other emoji are removed.
+    Transliterate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownMode {
+    /// Submit anyway and report the violation.
+    Warn,
+    /// Refuse to submit unless the caller overrides the cooldown.
+    Block,
+}
+
+/// Posting cadence limits applied by submit_post, per subreddit.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownConfig {
+    pub mode: CooldownMode,
+    pub max_posts: u32,
+    pub window_hours: u32,
+    /// Overrides keyed by subreddit name.
+    pub subreddits: BTreeMap<String, CooldownOverride>,
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self {
+            mode: CooldownMode::Warn,
+            max_posts: 1,
+            window_hours: 24,
+            subreddits: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownOverride {
+    pub mode: Option<CooldownMode>,
+    pub max_posts: Option<u32>,
+    pub window_hours: Option<u32>,
+}
+
+/// The cadence rule in effect for one subreddit.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct CooldownRule {
+    pub mode: CooldownMode,
+    pub max_posts: u32,
+    pub window_hours: u32,
+}
+
+impl CooldownConfig {
+    pub fn rule_for(&self, subreddit: &str) -> CooldownRule {
+        let custom = self
+            .subreddits
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(subreddit))
+            .map(|(_, custom)| custom.clone())
+            .unwrap_or_default();
+        CooldownRule {
+            mode: custom.mode.unwrap_or(self.mode),
+            max_posts: custom.max_posts.unwrap_or(self.max_posts),
+            window_hours: custom.window_hours.unwrap_or(self.window_hours),
+        }
+    }
+}
+
+/// Priorities of inbox items, from the first rule that matches.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InboxConfig {
+    /// Priority of the items no rule matches.
+    pub default_priority: InboxPriority,
+    pub rules: Vec<InboxRule>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+/// The `subject` of an inbox rule, compiled when the config is loaded so an
+/// invalid pattern is rejected there.
+#[derive(Debug, Clone)]
+pub struct SubjectPattern(Regex);
+
+impl PartialEq for SubjectPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SubjectPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map(SubjectPattern)
+            .map_err(|e| serde::de::Error::custom(format!("invalid subject pattern: {}", e)))
+    }
+}
+
+/// Matches an inbox item when every condition that is set matches.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InboxRule {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub priority: InboxPriority,
+    /// Username of the sender.
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// Regular expression matched against the subject, regardless of case.
+    #[serde(default)]
+    pub subject: Option<SubjectPattern>,
+    /// Subreddit a comment reply or subreddit message comes from.
+    #[serde(default)]
+    pub subreddit: Option<String>,
+    /// Matches when the subject or body contains any of these, regardless of case.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl InboxRule {
+    fn matches(&self, sender: &str, subject: &str, subreddit: Option<&str>, body: &str) -> bool {
+        let same = |expected: &str, actual: &str, prefix: &str| {
+            let expected = expected.trim();
+            expected
+                .strip_prefix(prefix)
+                .unwrap_or(expected)
+                .eq_ignore_ascii_case(actual)
+        };
+        self.sender
+            .as_deref()
+            .is_none_or(|expected| same(expected, sender, "u/"))
+            && self
+                .subreddit
+                .as_deref()
+                .is_none_or(|expected| subreddit.is_some_and(|actual| same(expected, actual, "r/")))
+            && self
+                .subject
+                .as_ref()
+                .is_none_or(|pattern| pattern.0.is_match(subject))
+            && (self.keywords.is_empty() || {
+                let text = format!("{}\n{}", subject, body).to_lowercase();
+                self.keywords
+                    .iter()
+                    .any(|keyword| text.contains(&keyword.to_lowercase()))
+            })
+    }
+}
+
+impl InboxConfig {
+    /// The priority of an item and the rule that set it, by name or as
+    /// `#<position>` for unnamed rules.
+    pub fn classify(
+        &self,
+        sender: &str,
+        subject: &str,
+        subreddit: Option<&str>,
+        body: &str,
+    ) -> (InboxPriority, Option<String>) {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(sender, subject, subreddit, body))
+            .map(|(index, rule)| {
+                let label = rule
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("#{}", index + 1));
+                (rule.priority, Some(label))
+            })
+            .unwrap_or((self.default_priority, None))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.sender.is_none()
+                && rule.subject.is_none()
+                && rule.subreddit.is_none()
+                && rule.keywords.is_empty()
+            {
+                return Err(format!(
+                    "inbox rule #{} has no condition; set default_priority instead",
+                    index + 1
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MacroConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub steps: Vec<MacroStep>,
+}
+
+/// One action of a macro. Texts may use the `{author}` and `{subreddit}`
+/// placeholders, which are filled in from the target.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MacroStep {
+    Remove {
+        #[serde(default)]
+        spam: bool,
+    },
+    Approve,
+    Lock,
+    Unlock,
+    Ban {
+        /// Omit for a permanent ban.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_days: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
+    },
+    ModNote {
+        note: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    /// A distinguished reply from the moderator account.
+    Reply {
+        text: String,
+        #[serde(default)]
+        sticky: bool,
+    },
+}
+
+impl MacroStep {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MacroStep::Remove { .. } => "remove",
+            MacroStep::Approve => "approve",
+            MacroStep::Lock => "lock",
+            MacroStep::Unlock => "unlock",
+            MacroStep::Ban { .. } => "ban",
+            MacroStep::ModNote { .. } => "mod_note",
+            MacroStep::Reply { .. } => "reply",
+        }
+    }
+}
+
+impl RuntimeConfig {
+    pub fn path() -> Option<PathBuf> {
+        match STARTUP.get() {
+            Some(startup) => startup.config_path.clone(),
+            None => env::var("CONFIG_FILE").ok().map(PathBuf::from),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let config: Self =
+            toml::from_str(&contents).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        config
+            .inbox
+            .validate()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn tool_allowed(&self, tool: &str) -> bool {
+        self.tools
+            .allowlist
+            .as_ref()
+            .is_none_or(|allowlist| allowlist.iter().any(|allowed| allowed == tool))
+            && self
+                .tools
+                .groups
+                .as_ref()
+                .is_none_or(|groups| groups.contains(&ToolCategory::of(tool)))
+    }
+
+    /// Whether a reload changed settings that only apply after a restart.
+    pub fn startup_changed(&self, other: &RuntimeConfig) -> bool {
+        self.reddit != other.reddit || self.server != other.server
+    }
+
+    fn startup_settings(&self) -> BTreeMap<&'static str, String> {
+        let reddit = &self.reddit;
+        let server = &self.server;
+        [
+            ("CLIENT_ID", &reddit.client_id),
+            ("CLIENT_SECRET", &reddit.client_secret),
+            ("REDIRECT_URL", &reddit.redirect_url),
+            ("REDDIT_USERNAME", &reddit.username),
+            ("REDDIT_PASSWORD", &reddit.password),
+            ("REDDIT_TOTP_SECRET", &reddit.totp_secret),
+            ("REDDIT_USER_AGENT", &reddit.user_agent),
+            ("REDDIT_AUTH_MODE", &reddit.auth_mode),
+            ("REDDIT_APP_TYPE", &reddit.app_type),
+            ("REDDIT_DEVICE_ID", &reddit.device_id),
+            ("MCP_TRANSPORT", &server.transport),
+            ("MCP_BIND", &server.bind),
+            ("HEALTH_BIND", &server.health_bind),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value.clone()?)))
+        .collect()
+    }
+}
+
+/// The current runtime configuration, swapped atomically on reload.
+#[derive(Debug, Clone, Default)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<RuntimeConfig>>,
+}
+
+impl SharedConfig {
+    pub fn from_env() -> Self {
+        let config = match RuntimeConfig::path() {
+            Some(path) => RuntimeConfig::load(&path).unwrap_or_else(|e| {
+                tracing::error!("{}; using defaults", e);
+                RuntimeConfig::default()
+            }),
+            None => RuntimeConfig::default(),
+        };
+
+        Self {
+            inner: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    pub fn get(&self) -> RuntimeConfig {
+        self.inner.read().expect("config lock poisoned").clone()
+    }
+
+    /// Installs `config` and returns the one it replaced.
+    pub fn replace(&self, config: RuntimeConfig) -> RuntimeConfig {
+        std::mem::replace(
+            &mut *self.inner.write().expect("config lock poisoned"),
+            config,
+        )
+    }
+}
+
+/// Watches `path` and calls `on_change` with every successfully parsed new version.
+/// Invalid edits are logged and ignored, so the previous configuration stays active.
+/// The watcher stops when the returned handle is dropped.
+pub fn watch<F>(path: PathBuf, on_change: F) -> notify::Result<RecommendedWatcher>
+where
+    F: Fn(RuntimeConfig) + Send + 'static,
+{
+    // Editors usually replace the file instead of writing it in place, so watch the directory.
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let touches_config = event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name().map(|name| name.to_os_string()) == file_name);
+        if !touches_config || event.kind.is_access() || event.kind.is_remove() {
+            return;
+        }
+
+        match RuntimeConfig::load(&path) {
+            Ok(config) => on_change(config),
+            Err(e) => tracing::error!("{}; keeping the previous configuration", e),
+        }
+    })?;
+    watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+    Ok(watcher)
+}
@@ -1,26 +1,87 @@
 use anyhow::{Ok, Result};
-use rmcp::ServiceExt;
-use rmcp::transport::stdio;
-use tracing_subscriber::EnvFilter;
+use clap::Parser;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
+mod config;
 mod reddit;
+mod server;
+mod storage;
+mod telemetry;
 use crate::reddit::client::RedditClient;
 
+/// Command line flags. Each one overrides the environment variable and the
+/// config file setting it stands in for.
+#[derive(Debug, Parser)]
+#[command(version, about = "Reddit MCP server")]
+struct Cli {
+    /// TOML config file, see config.example.toml.
+    #[arg(long, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+    /// How MCP is served (MCP_TRANSPORT).
+    #[arg(long, value_parser = ["stdio", "http"])]
+    transport: Option<String>,
+    /// Address of the MCP HTTP server (MCP_BIND).
+    #[arg(long)]
+    bind: Option<String>,
+    /// Address of the /healthz probe (HEALTH_BIND).
+    #[arg(long)]
+    health_bind: Option<String>,
+    /// user or app_only (REDDIT_AUTH_MODE).
+    #[arg(long, value_parser = ["user", "app_only"])]
+    auth_mode: Option<String>,
+}
+
+impl Cli {
+    fn flags(&self) -> BTreeMap<&'static str, String> {
+        [
+            ("MCP_TRANSPORT", &self.transport),
+            ("MCP_BIND", &self.bind),
+            ("HEALTH_BIND", &self.health_bind),
+            ("REDDIT_AUTH_MODE", &self.auth_mode),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value.clone()?)))
+        .collect()
+    }
+}
+
 #[tokio::main()]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into()))
-        .with_writer(std::io::stderr)
-        .with_ansi(false)
-        .init();
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let tracer_provider = telemetry::init();
+    config::init(cli.config.clone(), cli.flags());
 
     tracing::info!("Starting Reddit MCP server..");
 
-    let service = RedditClient::new().serve(stdio()).await.inspect_err(|e| {
-        tracing::error!("Server error: {:?}", e);
-    })?;
+    let client = RedditClient::new()?;
+    client.spawn_credential_check();
+    client.spawn_monitors();
+    client.spawn_config_backups();
+    client.spawn_inbox_watch();
+    client.spawn_experiments();
+
+    // Keeps watching the config file for as long as the server runs.
+    let _config_watcher = config::RuntimeConfig::path().and_then(|path| {
+        let watched = client.clone();
+        let runtime = tokio::runtime::Handle::current();
+        config::watch(path.clone(), move |config| {
+            let client = watched.clone();
+            runtime.spawn(async move { client.apply_config(config).await });
+        })
+        .inspect(|_| tracing::info!("Watching {} for configuration changes", path.display()))
+        .inspect_err(|e| tracing::error!("Failed to watch {}: {}", path.display(), e))
+        .ok()
+    });
+    if config::env_or_file("MCP_TRANSPORT").is_some_and(|transport| transport == "http") {
+        server::serve_http(client.clone()).await?;
+    } else {
+        server::serve_stdio(client.clone()).await?;
+    }
 
-    service.waiting().await?;
+    client.revoke_tokens_on_shutdown().await;
+    telemetry::shutdown(tracer_provider);
 
     Ok(())
 }
@@ -0,0 +1,130 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::config::env_or_file;
+use crate::reddit::auth::AuthMode;
+
+/// Name of the account configured with `REDDIT_USERNAME`/`REDDIT_PASSWORD`.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+tokio::task_local! {
+    /// The account the running tool call acts as, from its `account` argument.
+    static CURRENT_ACCOUNT: String;
+}
+
+/// Runs `future` as the account named `name`.
+pub async fn with_account<F: Future>(name: String, future: F) -> F::Output {
+    CURRENT_ACCOUNT.scope(name, future).await
+}
+
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub name: String,
+    pub username: String,
+    /// Accounts signed in through authorize_account need no password.
+    pub password: Option<String>,
+    /// Base32 secret of the authenticator app, for accounts with two-factor
+    /// authentication.
+    pub totp_secret: Option<String>,
+}
+
+/// The Reddit accounts the server can act as: the default one plus those
+/// named in `REDDIT_ACCOUNTS`, each configured with
+/// `REDDIT_ACCOUNT_<NAME>_USERNAME`, `REDDIT_ACCOUNT_<NAME>_PASSWORD` and
+/// optionally `REDDIT_ACCOUNT_<NAME>_TOTP_SECRET`.
+#[derive(Debug, Clone)]
+pub struct Accounts {
+    accounts: Arc<Vec<Account>>,
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+impl Accounts {
+    pub fn from_env(auth_mode: AuthMode) -> Self {
+        // Empty until configured; application-only tokens act as no account.
+        let mut accounts = vec![Account {
+            name: DEFAULT_ACCOUNT.to_string(),
+            username: env_or_file("REDDIT_USERNAME").unwrap_or_default(),
+            password: env_or_file("REDDIT_PASSWORD"),
+            totp_secret: env_or_file("REDDIT_TOTP_SECRET"),
+        }];
+        if auth_mode == AuthMode::AppOnly {
+            return Self {
+                accounts: Arc::new(accounts),
+            };
+        }
+
+        let names = env_or_file("REDDIT_ACCOUNTS").unwrap_or_default();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let name = name.to_ascii_lowercase();
+            if !valid_name(&name) || accounts.iter().any(|account| account.name == name) {
+                tracing::error!(
+                    "Skipping account {:?} in REDDIT_ACCOUNTS: names must be unique and use only letters, digits, - or _",
+                    name
+                );
+                continue;
+            }
+            let variable = |field: &str| {
+                format!(
+                    "REDDIT_ACCOUNT_{}_{}",
+                    name.to_ascii_uppercase().replace('-', "_"),
+                    field
+                )
+            };
+            let Some(username) = env_or_file(&variable("USERNAME")) else {
+                tracing::error!(
+                    "Skipping account {}: {} is not set",
+                    name,
+                    variable("USERNAME")
+                );
+                continue;
+            };
+            let password = env_or_file(&variable("PASSWORD"));
+            let totp_secret = env_or_file(&variable("TOTP_SECRET"));
+            accounts.push(Account {
+                name,
+                username,
+                password,
+                totp_secret,
+            });
+        }
+        Self {
+            accounts: Arc::new(accounts),
+        }
+    }
+
+    pub fn all(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Looks an account up by its name or its Reddit username.
+    pub fn get(&self, name: &str) -> Option<&Account> {
+        let name = name.trim().trim_start_matches("u/");
+        self.accounts.iter().find(|account| {
+            account.name.eq_ignore_ascii_case(name) || account.username.eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// The account of the running tool call, or the default one outside of
+    /// tool calls, e.g. in monitors and scheduled backups.
+    pub fn current(&self) -> &Account {
+        CURRENT_ACCOUNT
+            .try_with(|name| {
+                self.accounts
+                    .iter()
+                    .position(|account| account.name == *name)
+            })
+            .ok()
+            .flatten()
+            .map_or(&self.accounts[0], |index| &self.accounts[index])
+    }
+}
@@ -0,0 +1,123 @@
+use crate::config::env_or_file;
+
+/// Tools that act as, or on behalf of, the signed-in account, and so cannot
+/// work with an application-only token.
+const USER_CONTEXT_TOOLS: [&str; 46] = [
+    "add_post_to_collection",
+    "approve_reply",
+    "authorize_account",
+    "backup_subreddit_config",
+    "bootstrap_subreddit",
+    "check_posting_cooldown",
+    "cleanup_account",
+    "create_collection",
+    "create_sticky_comment_monitor",
+    "create_traffic_anomaly_monitor",
+    "delete_monitor",
+    "diff_config",
+    "enforce_post_flair",
+    "export_ban_list",
+    "get_account_hygiene_report",
+    "get_deletion_progress",
+    "get_home_feed",
+    "get_inbox",
+    "get_mod_dashboard",
+    "get_mod_team_activity",
+    "get_modmail_context",
+    "get_monitor",
+    "get_user_risk_summary",
+    "list_accounts",
+    "list_macros",
+    "list_pending_replies",
+    "list_monitors",
+    "list_recent_removals",
+    "modqueue_changes",
+    "notify_thread_participants",
+    "post_comment",
+    "preview_history_deletion",
+    "preview_monitor",
+    "reject_reply",
+    "remove_post_from_collection",
+    "restore_removed_items",
+    "run_history_deletion",
+    "run_macro",
+    "schedule_config_backup",
+    "send_message",
+    "send_modmail_reply",
+    "submit_post",
+    "submit_title_variant",
+    "submit_to_multiple",
+    "suggest_removal_reasons",
+    "whoami",
+];
+
+/// The type the app was registered as on reddit.com/prefs/apps, from
+/// `REDDIT_APP_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppType {
+    /// Script and web apps, which authenticate with their client secret.
+    Confidential,
+    /// Installed apps have no secret and cannot use the password grant.
+    /// Their application-only tokens are issued for a device id.
+    Installed,
+}
+
+impl AppType {
+    pub fn from_env() -> Self {
+        match env_or_file("REDDIT_APP_TYPE").as_deref() {
+            Some("installed") => AppType::Installed,
+            Some("script") | Some("web") | None => AppType::Confidential,
+            Some(other) => {
+                tracing::error!(
+                    "Unknown REDDIT_APP_TYPE {}; expected script, web or installed, using script",
+                    other
+                );
+                AppType::Confidential
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AppType::Confidential => "confidential",
+            AppType::Installed => "installed",
+        }
+    }
+}
+
+/// How the server authenticates with Reddit, from `REDDIT_AUTH_MODE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    /// As the configured account: password grant or a stored refresh token.
+    User,
+    /// `client_credentials` with only `CLIENT_ID`/`CLIENT_SECRET`. Read-only,
+    /// and tools that need an account are hidden.
+    AppOnly,
+}
+
+impl AuthMode {
+    pub fn from_env() -> Self {
+        match env_or_file("REDDIT_AUTH_MODE").as_deref() {
+            Some("app_only") => AuthMode::AppOnly,
+            Some("user") | None => AuthMode::User,
+            Some(other) => {
+                tracing::error!(
+                    "Unknown REDDIT_AUTH_MODE {}; expected user or app_only, using user",
+                    other
+                );
+                AuthMode::User
+            }
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuthMode::User => "user",
+            AuthMode::AppOnly => "app_only",
+        }
+    }
+
+    pub fn tool_available(self, tool: &str) -> bool {
+        self == AuthMode::User || !USER_CONTEXT_TOOLS.contains(&tool)
+    }
+}
@@ -0,0 +1,638 @@
+//! A reimplementation of the commonly used part of AutoModerator, for testing
+//! rules before they are saved to the wiki.
+//!
+//! Only the YAML subset that AutoModerator rules use in practice is parsed:
+//! `---` separated documents of `key: value` mappings, nested mappings, flow
+//! (`[a, b]`) and block (`- a`) lists of scalars, quoted scalars and `|`/`>`
+//! block scalars.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{AutomodCheck, AutomodRuleResult};
+
+const TEXT_FIELDS: [&str; 4] = ["title", "body", "url", "domain"];
+const ACTION_KEYS: [&str; 13] = [
+    "action",
+    "action_reason",
+    "comment",
+    "comment_locked",
+    "comment_stickied",
+    "message",
+    "message_subject",
+    "modmail",
+    "modmail_subject",
+    "priority",
+    "moderators_exempt",
+    "report_reason",
+    "overwrite_flair",
+];
+const YAML_HINT: &str = "pass AutoModerator rules as YAML, separating rules with ---";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Scalar(String),
+    List(Vec<String>),
+    Map(Vec<(String, Node)>),
+}
+
+fn yaml_error(line: usize, message: &str) -> RedditError {
+    RedditError::invalid_input(format!("line {}: {}", line, message), YAML_HINT)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Cuts a `#` comment off a line, leaving `#` inside quotes alone.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..index],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Splits `text` at `separator`s that are outside quotes.
+fn split_outside_quotes(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == separator => {
+                parts.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            None => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn parse_scalar(text: &str, line: usize) -> Result<String, RedditError> {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('\'') {
+        let inner = inner
+            .strip_suffix('\'')
+            .ok_or_else(|| yaml_error(line, "unterminated single-quoted string"))?;
+        return Ok(inner.replace("''", "'"));
+    }
+    if let Some(inner) = text.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .ok_or_else(|| yaml_error(line, "unterminated double-quoted string"))?;
+        let mut value = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            // Unknown escapes are kept as written so regexes like "\d" survive.
+            match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some(other) => {
+                    value.push('\\');
+                    value.push(other);
+                }
+                None => value.push('\\'),
+            }
+        }
+        return Ok(value);
+    }
+    Ok(text.to_string())
+}
+
+fn parse_flow_list(text: &str, line: usize) -> Result<Vec<String>, RedditError> {
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .ok_or_else(|| yaml_error(line, "unterminated [ list"))?;
+    split_outside_quotes(inner, ',')
+        .into_iter()
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| parse_scalar(item, line))
+        .collect()
+}
+
+struct Parser<'a> {
+    /// Line number and text of every line of one document.
+    lines: Vec<(usize, &'a str)>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// The next line with content, skipping blank and comment-only lines.
+    fn peek(&mut self) -> Option<(usize, &'a str)> {
+        while let Some(&(number, text)) = self.lines.get(self.position) {
+            if strip_comment(text).trim().is_empty() {
+                self.position += 1;
+            } else {
+                return Some((number, text));
+            }
+        }
+        None
+    }
+
+    fn parse_map(&mut self, indent: usize) -> Result<Vec<(String, Node)>, RedditError> {
+        let mut entries = Vec::new();
+        while let Some((number, text)) = self.peek() {
+            let line_indent = indent_of(text);
+            if line_indent < indent {
+                break;
+            }
+            if line_indent > indent {
+                return Err(yaml_error(number, "unexpected indentation"));
+            }
+            self.position += 1;
+
+            let content = strip_comment(text).trim();
+            if content.starts_with('-') {
+                return Err(yaml_error(number, "expected key: value, found a list item"));
+            }
+            let (key, rest) = match split_outside_quotes(content, ':').as_slice() {
+                [key, rest @ ..] if !rest.is_empty() => {
+                    (parse_scalar(key, number)?, content[key.len() + 1..].trim())
+                }
+                _ => return Err(yaml_error(number, "expected key: value")),
+            };
+
+            let value = if rest.is_empty() {
+                match self.peek() {
+                    Some((_, next)) if indent_of(next) > indent => {
+                        let child_indent = indent_of(next);
+                        if strip_comment(next).trim_start().starts_with('-') {
+                            Node::List(self.parse_list(child_indent)?)
+                        } else {
+                            Node::Map(self.parse_map(child_indent)?)
+                        }
+                    }
+                    _ => Node::Scalar(String::new()),
+                }
+            } else if rest.starts_with('|') || rest.starts_with('>') {
+                Node::Scalar(self.parse_block_scalar(indent, rest.starts_with('>')))
+            } else if rest.starts_with('[') {
+                let mut list = rest.to_string();
+                while !list.trim_end().ends_with(']') {
+                    let Some(&(_, next)) = self.lines.get(self.position) else {
+                        return Err(yaml_error(number, "unterminated [ list"));
+                    };
+                    self.position += 1;
+                    list.push(' ');
+                    list.push_str(strip_comment(next).trim());
+                }
+                Node::List(parse_flow_list(&list, number)?)
+            } else {
+                Node::Scalar(parse_scalar(rest, number)?)
+            };
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+
+    fn parse_list(&mut self, indent: usize) -> Result<Vec<String>, RedditError> {
+        let mut items = Vec::new();
+        while let Some((number, text)) = self.peek() {
+            if indent_of(text) != indent {
+                break;
+            }
+            let Some(item) = strip_comment(text).trim().strip_prefix('-') else {
+                break;
+            };
+            self.position += 1;
+            items.push(parse_scalar(item, number)?);
+        }
+        Ok(items)
+    }
+
+    /// Reads the lines indented below a `key: |` or `key: >` line.
+    fn parse_block_scalar(&mut self, indent: usize, folded: bool) -> String {
+        let mut block: Vec<&str> = Vec::new();
+        while let Some(&(_, text)) = self.lines.get(self.position) {
+            if !text.trim().is_empty() && indent_of(text) <= indent {
+                break;
+            }
+            block.push(text);
+            self.position += 1;
+        }
+        let block_indent = block
+            .iter()
+            .filter(|text| !text.trim().is_empty())
+            .map(|text| indent_of(text))
+            .min()
+            .unwrap_or(0);
+        let lines: Vec<&str> = block
+            .iter()
+            .map(|text| text.get(block_indent..).unwrap_or_default())
+            .collect();
+        lines
+            .join(if folded { " " } else { "\n" })
+            .trim_end()
+            .to_string()
+    }
+}
+
+/// Parses `---` separated AutoModerator rules.
+fn parse_rules(yaml: &str) -> Result<Vec<Vec<(String, Node)>>, RedditError> {
+    let mut documents: Vec<Vec<(usize, &str)>> = vec![Vec::new()];
+    for (index, line) in yaml.lines().enumerate() {
+        if line.trim() == "---" {
+            documents.push(Vec::new());
+        } else if let Some(document) = documents.last_mut() {
+            document.push((index + 1, line.trim_end()));
+        }
+    }
+
+    let mut rules = Vec::new();
+    for lines in documents {
+        let mut parser = Parser { lines, position: 0 };
+        let Some((_, first)) = parser.peek() else {
+            continue;
+        };
+        let rule = parser.parse_map(indent_of(first))?;
+        if let Some((number, _)) = parser.peek() {
+            return Err(yaml_error(number, "unexpected indentation"));
+        }
+        rules.push(rule);
+    }
+    if rules.is_empty() {
+        return Err(RedditError::invalid_input("No rules were given", YAML_HINT));
+    }
+    Ok(rules)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleKind {
+    Submission,
+    Comment,
+}
+
+impl SampleKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SampleKind::Submission => "submission",
+            SampleKind::Comment => "comment",
+        }
+    }
+}
+
+/// The post or comment a rule is tested against.
+#[derive(Debug)]
+pub struct AutomodSample {
+    pub kind: SampleKind,
+    pub title: String,
+    pub body: String,
+    pub url: String,
+    pub author: Option<String>,
+    pub comment_karma: Option<i64>,
+    pub link_karma: Option<i64>,
+    pub account_age_days: Option<f64>,
+}
+
+impl AutomodSample {
+    fn domain(&self) -> String {
+        let without_scheme = self.url.split("://").last().unwrap_or_default();
+        let host = without_scheme
+            .split(['/', '?', '#', ':'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        host.strip_prefix("www.").unwrap_or(&host).to_string()
+    }
+
+    fn field(&self, field: &str) -> String {
+        match field {
+            "title" => self.title.clone(),
+            "body" => self.body.clone(),
+            "url" => self.url.clone(),
+            "domain" => self.domain(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// A condition key such as `~title+body (includes, regex)`.
+struct ConditionKey<'a> {
+    negated: bool,
+    fields: Vec<&'a str>,
+    modifiers: Vec<&'a str>,
+}
+
+fn parse_condition_key(key: &str) -> ConditionKey<'_> {
+    let (fields, modifiers) = match key.split_once('(') {
+        Some((fields, modifiers)) => (fields, modifiers.trim_end_matches(')')),
+        None => (key, ""),
+    };
+    let fields = fields.trim();
+    let (negated, fields) = match fields.strip_prefix('~') {
+        Some(fields) => (true, fields),
+        None => (false, fields),
+    };
+    ConditionKey {
+        negated,
+        fields: fields.split('+').map(str::trim).collect(),
+        modifiers: modifiers
+            .split(',')
+            .map(str::trim)
+            .filter(|modifier| !modifier.is_empty())
+            .collect(),
+    }
+}
+
+fn node_values(node: &Node) -> Option<Vec<String>> {
+    match node {
+        Node::Scalar(value) => Some(vec![value.clone()]),
+        Node::List(values) => Some(values.clone()),
+        Node::Map(_) => None,
+    }
+}
+
+/// Builds the regex AutoModerator would use for `values` checked against `field`.
+fn match_regex(field: &str, values: &[String], modifiers: &[&str]) -> Result<Regex, String> {
+    let is_regex = modifiers.contains(&"regex");
+    let alternatives = values
+        .iter()
+        .map(|value| {
+            if is_regex {
+                value.clone()
+            } else {
+                regex::escape(value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("|");
+
+    let mode = modifiers.iter().copied().find(|modifier| {
+        matches!(
+            *modifier,
+            "includes" | "includes-word" | "starts-with" | "ends-with" | "full-exact" | "full-text"
+        )
+    });
+    let pattern = match (mode, field) {
+        (Some("includes"), _) | (None, "url") => format!("(?:{})", alternatives),
+        (Some("starts-with"), _) => format!("^(?:{})", alternatives),
+        (Some("ends-with"), _) => format!("(?:{})$", alternatives),
+        (Some("full-exact"), _) | (None, "name") => format!("^(?:{})$", alternatives),
+        (Some("full-text"), _) => format!(r"^\W*(?:{})\W*$", alternatives),
+        // A domain also matches its subdomains.
+        (None, "domain") => format!(r"(?:^|\.)(?:{})$", alternatives),
+        _ => format!(r"(?:^|\W)(?:{})(?:$|\W)", alternatives),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!modifiers.contains(&"case-sensitive"))
+        .build()
+        .map_err(|e| format!("invalid regex: {}", e))
+}
+
+/// Checks a text condition, returning whether it matched and why.
+fn check_text(
+    key: &ConditionKey,
+    values: &[String],
+    text_of: impl Fn(&str) -> String,
+) -> Result<(bool, String), String> {
+    let mut found = None;
+    for field in &key.fields {
+        let regex = match_regex(field, values, &key.modifiers)?;
+        let text = text_of(field);
+        if let Some(found_match) = regex.find(&text) {
+            found = Some(format!(
+                "{} contains {:?}",
+                field,
+                found_match.as_str().trim()
+            ));
+            break;
+        }
+    }
+    let matched = found.is_some() != key.negated;
+    let detail = match (found, key.negated) {
+        (Some(found), false) => found,
+        (Some(found), true) => format!("{}, which the ~ rules out", found),
+        (None, _) => format!("no match in {}", key.fields.join(" or ")),
+    };
+    Ok((matched, detail))
+}
+
+/// Parses a threshold such as `< 100`, `>= 7 days` or `10` (which means `> 10`).
+fn parse_threshold(text: &str, with_units: bool) -> Option<(&'static str, f64)> {
+    let text = text.trim();
+    let (operator, rest) = ["<=", ">=", "<", ">"]
+        .into_iter()
+        .find_map(|operator| text.strip_prefix(operator).map(|rest| (operator, rest)))
+        .unwrap_or((">", text));
+    let mut parts = rest.split_whitespace();
+    let number: f64 = parts.next()?.parse().ok()?;
+    let days = match parts.next() {
+        None => 1.0,
+        Some(_) if !with_units => return None,
+        Some(unit) => match unit.trim_end_matches('s') {
+            "minute" => 1.0 / (24.0 * 60.0),
+            "hour" => 1.0 / 24.0,
+            "day" => 1.0,
+            "week" => 7.0,
+            "month" => 30.0,
+            "year" => 365.0,
+            _ => return None,
+        },
+    };
+    Some((operator, number * days))
+}
+
+fn compare(operator: &str, actual: f64, threshold: f64) -> bool {
+    match operator {
+        "<" => actual < threshold,
+        "<=" => actual <= threshold,
+        ">=" => actual >= threshold,
+        _ => actual > threshold,
+    }
+}
+
+fn evaluate_author(
+    conditions: &[(String, Node)],
+    sample: &AutomodSample,
+    checks: &mut Vec<AutomodCheck>,
+    skipped: &mut Vec<String>,
+) {
+    let satisfy_any = conditions.iter().any(|(key, node)| {
+        key == "satisfy_any_threshold" && *node == Node::Scalar("true".to_string())
+    });
+    let mut thresholds = Vec::new();
+
+    for (key, node) in conditions {
+        let condition = format!("author.{}", key);
+        let actual = match key.as_str() {
+            "satisfy_any_threshold" => continue,
+            "comment_karma" => sample.comment_karma.map(|karma| karma as f64),
+            "post_karma" | "link_karma" => sample.link_karma.map(|karma| karma as f64),
+            "combined_karma" => sample
+                .comment_karma
+                .zip(sample.link_karma)
+                .map(|(comment, link)| (comment + link) as f64),
+            "account_age" => sample.account_age_days,
+            _ => {
+                let parsed = parse_condition_key(key);
+                if parsed.fields == ["name"]
+                    && let Some(values) = node_values(node)
+                {
+                    let Some(author) = &sample.author else {
+                        skipped.push(format!("{}: the sample has no author", condition));
+                        continue;
+                    };
+                    match check_text(&parsed, &values, |_| author.clone()) {
+                        Ok((matched, detail)) => checks.push(AutomodCheck {
+                            condition,
+                            matched,
+                            detail,
+                        }),
+                        Err(e) => skipped.push(format!("{}: {}", condition, e)),
+                    }
+                } else {
+                    skipped.push(format!("{}: not supported by the sandbox", condition));
+                }
+                continue;
+            }
+        };
+
+        let Node::Scalar(value) = node else {
+            skipped.push(format!("{}: expected a single threshold", condition));
+            continue;
+        };
+        let Some((operator, threshold)) = parse_threshold(value, key == "account_age") else {
+            skipped.push(format!(
+                "{}: {:?} is not a valid threshold",
+                condition, value
+            ));
+            continue;
+        };
+        let Some(actual) = actual else {
+            skipped.push(format!("{}: the sample does not include it", condition));
+            continue;
+        };
+        thresholds.push(AutomodCheck {
+            matched: compare(operator, actual, threshold),
+            detail: format!("{} (needs {} {})", actual, operator, threshold),
+            condition,
+        });
+    }
+
+    if satisfy_any && !thresholds.is_empty() {
+        checks.push(AutomodCheck {
+            condition: "author thresholds (satisfy_any_threshold)".to_string(),
+            matched: thresholds.iter().any(|check| check.matched),
+            detail: thresholds
+                .iter()
+                .map(|check| format!("{}: {}", check.condition, check.detail))
+                .collect::<Vec<_>>()
+                .join("; "),
+        });
+    } else {
+        checks.extend(thresholds);
+    }
+}
+
+fn evaluate_rule(
+    rule: usize,
+    conditions: &[(String, Node)],
+    sample: &AutomodSample,
+) -> AutomodRuleResult {
+    let mut checks = Vec::new();
+    let mut skipped = Vec::new();
+    let mut action = None;
+    let mut action_reason = None;
+
+    for (key, node) in conditions {
+        match key.as_str() {
+            "action" => action = node_values(node).and_then(|values| values.into_iter().next()),
+            "action_reason" => {
+                action_reason = node_values(node).and_then(|values| values.into_iter().next())
+            }
+            key if ACTION_KEYS.contains(&key) || key.starts_with("set_") => {}
+            "type" => {
+                let wanted = node_values(node)
+                    .and_then(|values| values.into_iter().next())
+                    .unwrap_or_default();
+                let matched = match (wanted.as_str(), sample.kind) {
+                    ("any", _) => true,
+                    ("submission", SampleKind::Submission) => true,
+                    ("link submission", SampleKind::Submission) => !sample.url.is_empty(),
+                    ("text submission", SampleKind::Submission) => sample.url.is_empty(),
+                    ("comment", SampleKind::Comment) => true,
+                    _ => false,
+                };
+                checks.push(AutomodCheck {
+                    condition: "type".to_string(),
+                    matched,
+                    detail: format!("rule type {:?}, sample is a {}", wanted, sample.kind.name()),
+                });
+            }
+            "author" => match node {
+                Node::Map(author) => evaluate_author(author, sample, &mut checks, &mut skipped),
+                _ => evaluate_author(
+                    &[("name".to_string(), node.clone())],
+                    sample,
+                    &mut checks,
+                    &mut skipped,
+                ),
+            },
+            _ => {
+                let parsed = parse_condition_key(key);
+                let values = node_values(node);
+                let supported = parsed
+                    .fields
+                    .iter()
+                    .all(|field| TEXT_FIELDS.contains(field));
+                match values {
+                    Some(values) if supported => {
+                        match check_text(&parsed, &values, |field| sample.field(field)) {
+                            Ok((matched, detail)) => checks.push(AutomodCheck {
+                                condition: key.clone(),
+                                matched,
+                                detail,
+                            }),
+                            Err(e) => skipped.push(format!("{}: {}", key, e)),
+                        }
+                    }
+                    _ => skipped.push(format!("{}: not supported by the sandbox", key)),
+                }
+            }
+        }
+    }
+
+    AutomodRuleResult {
+        rule,
+        matched: checks.iter().all(|check| check.matched),
+        conclusive: skipped.is_empty(),
+        action,
+        action_reason,
+        checks,
+        skipped,
+    }
+}
+
+/// Evaluates every rule in `yaml` against `sample`.
+pub fn test_rules(
+    yaml: &str,
+    sample: &AutomodSample,
+) -> Result<Vec<AutomodRuleResult>, RedditError> {
+    Ok(parse_rules(yaml)?
+        .iter()
+        .enumerate()
+        .map(|(index, conditions)| evaluate_rule(index + 1, conditions, sample))
+        .collect())
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::reddit::models::Post;
+
+const MAX_CACHED_POSTS: usize = 100;
+const MAX_ANCHOR_AGE: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug)]
+struct CachedListing {
+    posts: Vec<Post>,
+    fetched_at: Instant,
+}
+
+/// Cache of `new` listings keyed by subreddit.
+///
+/// Re-fetches only ask Reddit for posts newer than the first cached fullname
+/// (`before=<fullname>`) and merge them in front of the cached posts. Anchors
+/// older than fifteen minutes are not trusted, since a deleted anchor post makes
+/// Reddit silently return an empty page.
+#[derive(Debug, Clone, Default)]
+pub struct ListingCache {
+    entries: Arc<Mutex<HashMap<String, CachedListing>>>,
+}
+
+impl ListingCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fullname of the newest cached post, if the cached listing is still usable as an anchor.
+    pub fn anchor(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().expect("listing cache lock poisoned");
+        entries
+            .get(key)
+            .filter(|entry| entry.fetched_at.elapsed() < MAX_ANCHOR_AGE)
+            .and_then(|entry| entry.posts.first())
+            .map(|post| post.name.clone())
+    }
+
+    /// Replaces the cached listing with a freshly fetched one.
+    pub fn store(&self, key: &str, posts: &[Post]) {
+        let mut entries = self.entries.lock().expect("listing cache lock poisoned");
+        entries.insert(
+            key.to_string(),
+            CachedListing {
+                posts: posts.iter().take(MAX_CACHED_POSTS).cloned().collect(),
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Puts `newer` in front of the cached posts and returns the merged listing.
+    pub fn merge(&self, key: &str, newer: Vec<Post>) -> Vec<Post> {
+        let mut entries = self.entries.lock().expect("listing cache lock poisoned");
+        let entry = entries.entry(key.to_string()).or_insert(CachedListing {
+            posts: Vec::new(),
+            fetched_at: Instant::now(),
+        });
+
+        let fresh: HashSet<String> = newer.iter().map(|post| post.name.clone()).collect();
+        let mut merged = newer;
+        merged.extend(
+            entry
+                .posts
+                .drain(..)
+                .filter(|post| !fresh.contains(&post.name)),
+        );
+        merged.truncate(MAX_CACHED_POSTS);
+
+        entry.posts = merged.clone();
+        entry.fetched_at = Instant::now();
+        merged
+    }
+}
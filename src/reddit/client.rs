@@ -1,54 +1,342 @@
 use anyhow::Result;
-use dotenv::dotenv;
 use reqwest::{Client, StatusCode, header};
 use rmcp::{
-    ServerHandler,
-    model::{ServerCapabilities, ServerInfo},
-    schemars, tool,
+    RoleServer, ServerHandler,
+    handler::server::tool::ToolCallContext,
+    model::{
+        CallToolRequestParam, CallToolResult, IntoContents, ListToolsResult, LoggingLevel,
+        LoggingMessageNotificationParam, PaginatedRequestParam, ServerCapabilities, ServerInfo,
+    },
+    service::{Peer, RequestContext, ServiceError},
+    tool,
 };
-use std::env;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::Instrument;
 use uuid::Uuid;
 
-use crate::reddit::models::{AccessTokenRequest, AccessTokenResponse, SearchSubredditNameRequest};
+use crate::config::{EmojiMode, RuntimeConfig, SharedConfig, env_or_file};
+use crate::reddit::accounts::{Account, Accounts, DEFAULT_ACCOUNT, with_account};
+use crate::reddit::auth::{AppType, AuthMode};
+use crate::reddit::cache::ListingCache;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::idempotency::IdempotencyStore;
+use crate::reddit::language::{filter_result, take_language, with_language_parameter};
+use crate::reddit::limiter::{Priority, RequestLimiter};
+use crate::reddit::models::{
+    AccessTokenRequest, AccessTokenResponse, AccessTokenStatusResponse, AccountCapabilities,
+    ApiForm, ApiJsonResponse, ClientCredentialsRequest, InfoItem, InfoRequest, Listing,
+    ListingRequest, Post, QuotaUsageResponse, RawJsonRequest, RefreshTokenRequest,
+    SearchSubredditNameRequest, SearchSubredditNamesResponse, ServerCapabilitiesResponse,
+    SnapshotPageResponse, StoredTokens, SubredditListingResponse, ToolGroupCapabilities,
+    UnavailableTool, UserSearchRequest, UserSummary, UsernameAvailableRequest,
+    UsernameCheckResponse,
+};
+use crate::reddit::pager::SnapshotPager;
+use crate::reddit::projection::{
+    map_json_result, project_result, take_fields, with_fields_parameter,
+};
+use crate::reddit::quota::{QuotaTracker, ToolCategory};
+use crate::reddit::scopes::{GrantedScopes, required_scopes};
+use crate::reddit::session::SeenItems;
+use crate::reddit::tables::{render_result, take_format, with_format_parameter};
+use crate::reddit::tasks::TaskRegistry;
+use crate::reddit::text::{mask_pattern, mask_value, sanitize_value};
+use crate::reddit::token_store::TokenStore;
+use crate::reddit::totp::totp;
+use crate::reddit::unfurl::Unfurler;
+use crate::reddit::validation::{
+    ValidatedNames, closest_matches, normalize_subreddit, normalize_username,
+};
+use crate::storage::Storage;
 
 const AUTH_URL: &str = "https://www.reddit.com/api/v1/access_token";
+/// Application-only grant of installed apps, which have no secret.
+const INSTALLED_CLIENT_GRANT: &str = "https://oauth.reddit.com/grants/installed_client";
 const BASE_URL: &str = "https://oauth.reddit.com/api";
-const USER_AGENT: &str = "reddit:mcp:v1 (by /u/boringly_boring)";
+const OAUTH_URL: &str = "https://oauth.reddit.com";
+const MAX_PAGE_SIZE: u32 = 100;
+const LISTING_SORTS: [&str; 5] = ["hot", "new", "top", "rising", "controversial"];
+const DEFAULT_USER_AGENT: &str = "reddit:mcp:v1 (by /u/boringly_boring)";
+
+mod account;
+mod accounts;
+mod analytics;
+mod authorization;
+mod automod;
+mod backups;
+mod bans;
+mod bootstrap;
+mod collections;
+mod compliance;
+mod deletion;
+mod experiments;
+mod flair;
+mod inbox;
+mod jobs;
+mod listings;
+mod macros;
+mod moderation;
+mod modmail;
+mod monitors;
+mod notifications;
+mod polls;
+mod posts;
+mod predictions;
+mod removals;
+mod replies;
+mod revocation;
+mod saved_searches;
+mod search;
+mod submissions;
+mod subreddits;
+mod threads;
+mod users;
+
+/// Tokens are refreshed this long before Reddit would expire them.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct CachedToken {
+    auth_token: String,
+    expires_at: Instant,
+    scope: String,
+}
+
+impl CachedToken {
+    fn from_response(response: &AccessTokenResponse) -> Self {
+        Self {
+            auth_token: format!("Bearer {}", response.access_token),
+            expires_at: Instant::now()
+                + Duration::from_secs(u64::try_from(response.expires_in).unwrap_or_default()),
+            scope: response.scope.clone(),
+        }
+    }
+
+    /// The access token persisted by an earlier run, if it is of the kind the
+    /// current mode needs and still usable for a while.
+    fn from_stored(tokens: &StoredTokens, auth_mode: AuthMode) -> Option<Self> {
+        let access_token = tokens.access_token.as_ref()?;
+        let remaining = tokens.access_token_expires_utc - chrono::Utc::now().timestamp();
+        let remaining = Duration::from_secs(u64::try_from(remaining).ok()?);
+        if tokens.app_only != (auth_mode == AuthMode::AppOnly) || remaining <= TOKEN_REFRESH_MARGIN
+        {
+            return None;
+        }
+        Some(Self {
+            auth_token: format!("Bearer {}", access_token),
+            expires_at: Instant::now() + remaining,
+            scope: tokens.scope.clone(),
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct RedditClient {
     client: Client,
-    client_id: String,
-    client_secret: String,
-    username: String,
-    password: String,
-    redirect_url: String,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    app_type: AppType,
+    auth_mode: AuthMode,
+    accounts: Accounts,
+    user_agent: String,
+    redirect_url: Option<String>,
+    limiter: RequestLimiter,
+    quotas: QuotaTracker,
+    listing_cache: ListingCache,
+    seen_items: SeenItems,
+    snapshot_pager: SnapshotPager,
+    subreddit_names: ValidatedNames,
+    usernames: ValidatedNames,
+    config: SharedConfig,
+    peers: Arc<Mutex<Vec<Peer<RoleServer>>>>,
+    /// Access tokens by account name.
+    token: Arc<tokio::sync::Mutex<HashMap<String, CachedToken>>>,
+    /// OAuth scopes of the latest token of each account.
+    scopes: Arc<Mutex<HashMap<String, GrantedScopes>>>,
+    /// Usernames of accounts signed in through authorize_account, by account name.
+    signed_in_usernames: Arc<Mutex<HashMap<String, String>>>,
+    tokens: TokenStore,
+    storage: Storage,
+    idempotency: IdempotencyStore,
+    /// Background tasks and running bulk jobs.
+    tasks: TaskRegistry,
+    unfurler: Unfurler,
 }
 
-#[tool(tool_box)]
 impl RedditClient {
-    #[allow(dead_code)]
-    pub fn new() -> Self {
-        dotenv().ok();
+    /// A clone for a new MCP session: everything is shared except the posts
+    /// already returned, which exclude_seen filters per session.
+    pub fn for_session(&self) -> Self {
+        Self {
+            seen_items: SeenItems::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Builds the client from the configuration. Missing credentials are not
+    /// an error here: the tools that need them report which setting is missing.
+    pub fn new() -> Result<Self, RedditError> {
+        let user_agent =
+            env_or_file("REDDIT_USER_AGENT").unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        let http_error = |e: reqwest::Error| {
+            RedditError::new(
+                ErrorKind::Other,
+                format!("Failed to create the HTTP client: {}", e),
+                "check that REDDIT_USER_AGENT is a valid header value and that the system's TLS certificates can be loaded",
+            )
+        };
         let client: Client = Client::builder()
-            .user_agent(USER_AGENT)
+            .user_agent(&user_agent)
             .build()
-            .expect("Failed to create http client");
+            .map_err(http_error)?;
+        let unfurler = Unfurler::new(&user_agent).map_err(http_error)?;
+
+        let client_id = env_or_file("CLIENT_ID");
+        let app_type = AppType::from_env();
+        // Installed apps authenticate with an empty secret.
+        let client_secret = match app_type {
+            AppType::Confidential => env_or_file("CLIENT_SECRET"),
+            AppType::Installed => Some(String::new()),
+        };
+        let auth_mode = AuthMode::from_env();
+        let accounts = Accounts::from_env(auth_mode);
+        let redirect_url = env_or_file("REDIRECT_URL");
+        for (key, value) in [("CLIENT_ID", &client_id), ("CLIENT_SECRET", &client_secret)] {
+            if value.is_none() {
+                tracing::warn!(
+                    "{} is not set; tools calling Reddit will fail until it is",
+                    key
+                );
+            }
+        }
 
-        let client_id: String = env::var("CLIENT_ID").expect("Expected Client Id");
-        let client_secret: String = env::var("CLIENT_SECRET").expect("Excepted Client Secret");
-        let username: String = env::var("REDDIT_USERNAME").expect("Expected Reddit Username");
-        let password: String = env::var("REDDIT_PASSWORD").expect("Execpted Reddit Password");
-        let redirect_url: String =
-            env::var("REDIRECT_URL").expect("Exceped Redirect Url added during app registration");
+        let config = SharedConfig::from_env();
+        let limiter = RequestLimiter::from_env();
+        let rate_limit = config.get().rate_limit;
+        limiter.reconfigure(
+            rate_limit.max_concurrent_requests,
+            rate_limit.interactive_reserved_requests,
+        );
 
-        Self {
+        let storage = Storage::open_from_env().map_err(RedditError::storage)?;
+
+        Ok(Self {
             client,
             client_id,
             client_secret,
-            username,
-            password,
+            app_type,
+            auth_mode,
+            accounts,
             redirect_url,
+            limiter,
+            quotas: QuotaTracker::new(),
+            listing_cache: ListingCache::new(),
+            seen_items: SeenItems::new(),
+            snapshot_pager: SnapshotPager::new(),
+            subreddit_names: ValidatedNames::new(),
+            usernames: ValidatedNames::new(),
+            config,
+            peers: Arc::new(Mutex::new(Vec::new())),
+            token: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            scopes: Arc::new(Mutex::new(HashMap::new())),
+            signed_in_usernames: Arc::new(Mutex::new(HashMap::new())),
+            tokens: TokenStore::new(storage.clone()),
+            idempotency: IdempotencyStore::new(storage.clone()),
+            tasks: TaskRegistry::new(),
+            storage,
+            unfurler,
+            user_agent,
+        })
+    }
+
+    /// The id and secret of the Reddit app, which every token request needs.
+    fn app_credentials(&self) -> Result<(&str, &str), RedditError> {
+        let client_id = self.client_id.as_deref().ok_or_else(|| {
+            RedditError::missing_setting("CLIENT_ID", "client_id", "the id of the Reddit app")
+        })?;
+        let client_secret = self.client_secret.as_deref().ok_or_else(|| {
+            RedditError::missing_setting(
+                "CLIENT_SECRET",
+                "client_secret",
+                "the secret of the Reddit app, or set REDDIT_APP_TYPE=installed for an installed app without one",
+            )
+        })?;
+        Ok((client_id, client_secret))
+    }
+
+    /// The redirect URI registered for the app, which the browser sign-in needs.
+    fn redirect_url(&self) -> Result<&str, RedditError> {
+        self.redirect_url.as_deref().ok_or_else(|| {
+            RedditError::missing_setting(
+                "REDIRECT_URL",
+                "redirect_url",
+                "the redirect URI registered for the app, e.g. http://localhost:65010/authorize_callback",
+            )
+        })
+    }
+
+    /// Installs a reloaded runtime configuration. Connected clients are told to
+    /// refresh their tool list when the allowlist changed.
+    pub async fn apply_config(&self, config: RuntimeConfig) {
+        let previous = self.config.replace(config.clone());
+        if previous == config {
+            return;
+        }
+        tracing::info!("Applied reloaded configuration");
+        if previous.startup_changed(&config) {
+            tracing::warn!("Changes to [reddit] and [server] apply after a restart");
+        }
+
+        self.limiter.reconfigure(
+            config.rate_limit.max_concurrent_requests,
+            config.rate_limit.interactive_reserved_requests,
+        );
+
+        if previous.tools != config.tools {
+            self.broadcast(|peer| async move { peer.notify_tool_list_changed().await })
+                .await;
+        }
+    }
+
+    /// Sends a notification to every connected client, dropping the ones that disconnected.
+    async fn broadcast<F, Fut>(&self, send: F)
+    where
+        F: Fn(Peer<RoleServer>) -> Fut,
+        Fut: Future<Output = Result<(), ServiceError>>,
+    {
+        let peers = self.peers.lock().expect("peers lock poisoned").clone();
+        let mut live = Vec::new();
+        for peer in peers {
+            match send(peer.clone()).await {
+                Ok(()) => live.push(peer),
+                Err(e) => tracing::debug!("Dropping disconnected peer: {}", e),
+            }
+        }
+        *self.peers.lock().expect("peers lock poisoned") = live;
+    }
+
+    /// Pushes a log message to the connected clients, e.g. a monitor alert.
+    async fn notify_clients(&self, level: LoggingLevel, logger: &str, data: serde_json::Value) {
+        self.broadcast(|peer| {
+            let message = LoggingMessageNotificationParam {
+                level: level.clone(),
+                logger: Some(logger.to_string()),
+                data: data.clone(),
+            };
+            async move { peer.notify_logging_message(message).await }
+        })
+        .await;
+    }
+
+    fn apply_content_policy(&self, posts: Vec<Post>) -> Vec<Post> {
+        if self.config.get().content.allow_nsfw {
+            posts
+        } else {
+            posts.into_iter().filter(|post| !post.over_18).collect()
         }
     }
 
@@ -57,90 +345,757 @@ impl RedditClient {
         url: &str,
         auth_token: &str,
         json_data: D,
-    ) -> Result<T, String>
+    ) -> Result<T, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+        D: serde::Serialize,
+    {
+        self.get_request_with_priority(url, auth_token, json_data, Priority::Interactive)
+            .await
+    }
+
+    async fn get_request_with_priority<T, D>(
+        &self,
+        url: &str,
+        auth_token: &str,
+        json_data: D,
+        priority: Priority,
+    ) -> Result<T, RedditError>
     where
         T: serde::de::DeserializeOwned,
         D: serde::Serialize,
     {
+        let _permit = self.limiter.acquire(priority).await;
+
         tracing::info!("Making GET request to: {}", url);
 
         let headers = header::HeaderMap::new();
 
+        let span = tracing::info_span!(
+            "reddit_request",
+            otel.name = "GET",
+            http.method = "GET",
+            url = url,
+            http.status_code = tracing::field::Empty
+        );
         let response = self
             .client
             .get(url)
             .headers(headers)
-            .header(header::USER_AGENT, USER_AGENT)
+            .header(header::USER_AGENT, &self.user_agent)
             .header(header::AUTHORIZATION, auth_token)
             .query(&json_data)
             .send()
+            .instrument(span.clone())
             .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+            .map_err(RedditError::network)?;
+        span.record("http.status_code", response.status().as_u16());
 
         tracing::info!("Received response: {:?}", response);
 
         match response.status() {
-            StatusCode::OK => response
-                .json::<T>()
-                .await
-                .map_err(|e| format!("Failed to parse the response: {}", e)),
-            status => Err(format!("Request failed with status: {}", status)),
+            StatusCode::OK => response.json::<T>().await.map_err(RedditError::parse),
+            status => {
+                if status == StatusCode::UNAUTHORIZED {
+                    self.invalidate_token(auth_token).await;
+                }
+                Err(RedditError::from_status(status, response.headers(), url))
+            }
         }
     }
 
-    async fn post_request<T, D>(&self, url: &str, post_data: D) -> Result<T, String>
+    async fn post_request<T, D>(&self, url: &str, post_data: D) -> Result<T, RedditError>
     where
         T: serde::de::DeserializeOwned,
         D: serde::Serialize,
     {
+        let _permit = self.limiter.acquire(Priority::Interactive).await;
+
         tracing::info!("Making POST request to: {}", url);
 
         let headers = header::HeaderMap::new();
 
+        let span = tracing::info_span!(
+            "reddit_request",
+            otel.name = "POST",
+            http.method = "POST",
+            url = url,
+            http.status_code = tracing::field::Empty
+        );
+        let (client_id, client_secret) = self.app_credentials()?;
         let response = self
             .client
             .post(url)
-            .basic_auth(self.client_id.clone(), Some(self.client_secret.clone()))
+            .basic_auth(client_id, Some(client_secret))
             .headers(headers)
-            .header(header::USER_AGENT, USER_AGENT)
+            .header(header::USER_AGENT, &self.user_agent)
             .form(&post_data)
             .send()
+            .instrument(span.clone())
             .await
-            .map_err(|e| format!("PSOT request failed: {}", e))?;
+            .map_err(RedditError::network)?;
+        span.record("http.status_code", response.status().as_u16());
 
         tracing::info!("Received response: {:?}", response);
 
         match response.status() {
-            StatusCode::OK => response
-                .json::<T>()
-                .await
-                .map_err(|e| format!("Failed to parse the request: {}", e)),
-            status => Err(format!("Request failed with status: {}", status)),
+            StatusCode::OK => response.json::<T>().await.map_err(RedditError::parse),
+            status => Err(RedditError::from_status(status, response.headers(), url)),
         }
     }
 
-    #[tool(description = "Get access_token to authenticate from reddit")]
-    async fn get_access_token(&self) -> String {
-        tracing::info!("Calling /api/access_token to get Authorization token");
+    async fn post_form<T, D>(&self, url: &str, auth_token: &str, form: D) -> Result<T, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+        D: serde::Serialize,
+    {
+        self.post_form_with_priority(url, auth_token, form, Priority::Interactive)
+            .await
+    }
+
+    /// POSTs `form` with `api_type=json` and returns the `data` of Reddit's
+    /// JSON envelope. The first error Reddit reports fails the call.
+    async fn post_api<T, D>(
+        &self,
+        url: &str,
+        auth_token: &str,
+        form: D,
+    ) -> Result<Option<T>, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+        D: serde::Serialize,
+    {
+        self.post_api_with_priority(url, auth_token, form, Priority::Interactive)
+            .await
+    }
 
-        let access_token_request = AccessTokenRequest {
-            grant_type: "password".to_string(),
-            username: self.username.clone(),
-            password: self.password.clone(),
+    async fn post_api_with_priority<T, D>(
+        &self,
+        url: &str,
+        auth_token: &str,
+        form: D,
+        priority: Priority,
+    ) -> Result<Option<T>, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+        D: serde::Serialize,
+    {
+        let response = self
+            .post_form_with_priority::<ApiJsonResponse<T>, ApiForm<D>>(
+                url,
+                auth_token,
+                ApiForm {
+                    form,
+                    api_type: "json",
+                },
+                priority,
+            )
+            .await?;
+        if let Some(error) = response.json.errors.first() {
+            return Err(RedditError::from_api(error));
+        }
+        if response.success == Some(false) {
+            return Err(RedditError::invalid_input(
+                format!("Reddit rejected the request to {}", url),
+                "check the tool arguments and retry",
+            ));
+        }
+        Ok(response.json.data)
+    }
+
+    async fn post_form_with_priority<T, D>(
+        &self,
+        url: &str,
+        auth_token: &str,
+        form: D,
+        priority: Priority,
+    ) -> Result<T, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+        D: serde::Serialize,
+    {
+        let _permit = self.limiter.acquire(priority).await;
+
+        tracing::info!("Making POST request to: {}", url);
+
+        let span = tracing::info_span!(
+            "reddit_request",
+            otel.name = "POST",
+            http.method = "POST",
+            url = url,
+            http.status_code = tracing::field::Empty
+        );
+        let response = self
+            .client
+            .post(url)
+            .header(header::USER_AGENT, &self.user_agent)
+            .header(header::AUTHORIZATION, auth_token)
+            .form(&form)
+            .send()
+            .instrument(span.clone())
+            .await
+            .map_err(RedditError::network)?;
+        span.record("http.status_code", response.status().as_u16());
+
+        tracing::info!("Received response: {:?}", response);
+
+        match response.status() {
+            StatusCode::OK => response.json::<T>().await.map_err(RedditError::parse),
+            status => {
+                if status == StatusCode::UNAUTHORIZED {
+                    self.invalidate_token(auth_token).await;
+                }
+                Err(RedditError::from_status(status, response.headers(), url))
+            }
+        }
+    }
+
+    async fn search_names(
+        &self,
+        query: &str,
+        exact: bool,
+        auth_token: &str,
+    ) -> Result<Vec<String>, RedditError> {
+        let url = format!("{}/search_reddit_names", BASE_URL);
+        let search_request = SearchSubredditNameRequest {
+            exact,
+            include_over_18: true,
+            include_unadvertisable: true,
+            query: query.to_string(),
+            search_query_id: Uuid::new_v4().to_string(),
+            typeahead_active: false,
         };
 
-        let access_token_response = self
-            .post_request::<AccessTokenResponse, AccessTokenRequest>(
-                &AUTH_URL,
-                access_token_request,
+        self.get_request::<SearchSubredditNamesResponse, SearchSubredditNameRequest>(
+            &url,
+            auth_token,
+            search_request,
+        )
+        .await
+        .map(|response| response.names)
+    }
+
+    /// Normalizes a user supplied subreddit and confirms it exists, returning its canonical name.
+    async fn resolve_subreddit(
+        &self,
+        input: &str,
+        auth_token: &str,
+    ) -> Result<String, RedditError> {
+        let name = normalize_subreddit(input)?;
+        if let Some(canonical) = self.subreddit_names.get(&name) {
+            return Ok(canonical);
+        }
+
+        // Exact searches answer 404 when the subreddit does not exist.
+        match self.search_names(&name, true, auth_token).await {
+            Ok(names) => {
+                if let Some(canonical) = names.into_iter().next() {
+                    self.subreddit_names.insert(&canonical);
+                    return Ok(canonical);
+                }
+            }
+            Err(e) if e.kind == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        let prefix: String = name.chars().take(3).collect();
+        let candidates = self
+            .search_names(&prefix, false, auth_token)
+            .await
+            .unwrap_or_default();
+        let suggestions = closest_matches(&name, candidates, 3);
+
+        let hint = if suggestions.is_empty() {
+            "use search_subreddit_names to look up the right name".to_string()
+        } else {
+            format!(
+                "did you mean {}?",
+                suggestions
+                    .iter()
+                    .map(|s| format!("r/{}", s))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        Err(RedditError::not_found(
+            format!("Subreddit r/{} does not exist", name),
+            hint,
+        ))
+    }
+
+    /// Normalizes a user supplied username and confirms the account exists.
+    async fn resolve_username(&self, input: &str, auth_token: &str) -> Result<String, RedditError> {
+        let name = normalize_username(input)?;
+        if let Some(canonical) = self.usernames.get(&name) {
+            return Ok(canonical);
+        }
+
+        // username_available is true when nobody owns the name, i.e. the account does not exist.
+        let url = format!("{}/username_available", BASE_URL);
+        let available = self
+            .get_request::<bool, UsernameAvailableRequest>(
+                &url,
+                auth_token,
+                UsernameAvailableRequest { user: name.clone() },
             )
+            .await?;
+        if !available {
+            self.usernames.insert(&name);
+            return Ok(name);
+        }
+
+        let url = format!("{}/users/search", OAUTH_URL);
+        let candidates = self
+            .get_request::<Listing<UserSummary>, UserSearchRequest>(
+                &url,
+                auth_token,
+                UserSearchRequest {
+                    q: name.clone(),
+                    limit: 10,
+                },
+            )
+            .await
+            .map(|listing| {
+                listing
+                    .data
+                    .children
+                    .into_iter()
+                    .map(|child| child.data.name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let suggestions = closest_matches(&name, candidates, 3);
+
+        let hint = if suggestions.is_empty() {
+            "check the spelling of the username".to_string()
+        } else {
+            format!(
+                "did you mean {}?",
+                suggestions
+                    .iter()
+                    .map(|s| format!("u/{}", s))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        Err(RedditError::not_found(
+            format!("User u/{} does not exist", name),
+            hint,
+        ))
+    }
+
+    async fn collect_listing<T>(
+        &self,
+        url: &str,
+        auth_token: &str,
+        limit: u32,
+        deadline: &Deadline,
+    ) -> Result<(Vec<T>, Option<String>), RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut posts: Vec<T> = Vec::new();
+        let mut after: Option<String> = None;
+
+        while posts.len() < limit as usize {
+            if deadline.is_expired() {
+                return Ok((posts, Some(deadline.truncation_reason())));
+            }
+
+            let listing_request = ListingRequest {
+                limit: (limit - posts.len() as u32).min(MAX_PAGE_SIZE),
+                after: after.clone(),
+                before: None,
+                raw_json: 1,
+            };
+            let page = match deadline
+                .run(self.get_request::<Listing<T>, ListingRequest>(
+                    url,
+                    auth_token,
+                    listing_request,
+                ))
+                .await
+            {
+                Ok(page) => page?,
+                Err(_) => return Ok((posts, Some(deadline.truncation_reason()))),
+            };
+
+            let fetched = page.data.children.len();
+            posts.extend(page.data.children.into_iter().map(|child| child.data));
+            after = page.data.after;
+            if after.is_none() || fetched == 0 {
+                break;
+            }
+        }
+
+        Ok((posts, None))
+    }
+
+    /// Fetches a single post by id, as a `Post` or a type with more details.
+    async fn fetch_post<T>(&self, post_id: &str, auth_token: &str) -> Result<T, RedditError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}/by_id/t3_{}", OAUTH_URL, post_id);
+        self.get_request::<Listing<T>, RawJsonRequest>(
+            &url,
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await?
+        .data
+        .children
+        .into_iter()
+        .next()
+        .map(|child| child.data)
+        .ok_or_else(|| {
+            RedditError::not_found(
+                format!("Post {} was not found", post_id),
+                "check the post id or pass the post's reddit.com URL",
+            )
+        })
+    }
+
+    /// Looks up posts and comments by fullname through `/api/info`, 100 per request.
+    async fn fetch_info(
+        &self,
+        fullnames: &[String],
+        auth_token: &str,
+    ) -> Result<HashMap<String, InfoItem>, RedditError> {
+        let url = format!("{}/info", BASE_URL);
+        let mut items = HashMap::new();
+
+        for chunk in fullnames.chunks(MAX_PAGE_SIZE as usize) {
+            let listing = self
+                .get_request::<Listing<InfoItem>, InfoRequest>(
+                    &url,
+                    auth_token,
+                    InfoRequest {
+                        id: chunk.join(","),
+                        raw_json: 1,
+                    },
+                )
+                .await?;
+            items.extend(
+                listing
+                    .data
+                    .children
+                    .into_iter()
+                    .map(|child| (child.data.name.clone(), child.data)),
+            );
+        }
+
+        Ok(items)
+    }
+
+    #[tool(
+        description = "Check the access token the server uses for the account, without revealing it: how long it stays valid and the OAuth scopes it was granted. The other tools fetch and refresh it on their own."
+    )]
+    async fn get_access_token(&self) -> Result<String, RedditError> {
+        let account = self.account();
+        let (_, scope) = self.account_token(account).await?;
+        self.record_scopes(&account.name, &scope).await;
+        let expires_in_secs = self
+            .token
+            .lock()
+            .await
+            .get(&account.name)
+            .map(|token| {
+                token
+                    .expires_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs()
+            })
+            .unwrap_or_default();
+
+        serde_json::to_string(&AccessTokenStatusResponse {
+            account: account.name.clone(),
+            expires_in_secs,
+            scopes: GrantedScopes::parse(&scope).names(),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    /// The account the running tool call acts as.
+    fn account(&self) -> &Account {
+        self.accounts.current()
+    }
+
+    /// The `Authorization` header value for the account of the running tool
+    /// call. The token is taken from the token store or fetched on first use,
+    /// and refreshed shortly before it expires.
+    async fn auth_token(&self) -> Result<String, RedditError> {
+        let account = self.account();
+        let (auth_token, scope) = self.account_token(account).await?;
+        self.record_scopes(&account.name, &scope).await;
+        Ok(auth_token)
+    }
+
+    /// The `Authorization` header value and the scopes of `account`'s token.
+    async fn account_token(&self, account: &Account) -> Result<(String, String), RedditError> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.get(&account.name)
+            && Instant::now() + TOKEN_REFRESH_MARGIN < token.expires_at
+        {
+            return Ok((token.auth_token.clone(), token.scope.clone()));
+        }
+        if !cached.contains_key(&account.name)
+            && let Some(token) = self
+                .tokens
+                .load(&account.name)?
+                .and_then(|tokens| CachedToken::from_stored(&tokens, self.auth_mode))
+        {
+            tracing::info!("Resuming {} with the stored access token", account.name);
+            let token_and_scope = (token.auth_token.clone(), token.scope.clone());
+            cached.insert(account.name.clone(), token);
+            return Ok(token_and_scope);
+        }
+
+        let response = self.request_access_token(account).await?;
+        tracing::info!(
+            "Fetched an access token for {} valid for {}s",
+            account.name,
+            response.expires_in
+        );
+        if let Err(e) = self.persist_tokens(&account.name, &response) {
+            tracing::warn!("Failed to store the access token: {}", e);
+        }
+        let token = CachedToken::from_response(&response);
+        let token_and_scope = (token.auth_token.clone(), token.scope.clone());
+        cached.insert(account.name.clone(), token);
+        Ok(token_and_scope)
+    }
+
+    /// Remembers the scopes granted to `account` and has the clients refresh
+    /// their tool list when they changed.
+    async fn record_scopes(&self, account: &str, scope: &str) {
+        let granted = GrantedScopes::parse(scope);
+        let previous = self
+            .scopes
+            .lock()
+            .expect("scopes lock poisoned")
+            .insert(account.to_string(), granted.clone());
+        if previous.as_ref() == Some(&granted) {
+            return;
+        }
+        let missing = granted.missing_for_any_tool();
+        if !missing.is_empty() {
+            tracing::info!(
+                "The token of {} lacks the scopes {:?}; tools needing them are hidden",
+                account,
+                missing
+            );
+        }
+        self.broadcast(|peer| async move { peer.notify_tool_list_changed().await })
             .await;
+    }
+
+    /// Scopes `tool` needs that the account named `account` was not granted.
+    /// Unknown until the account's first token.
+    fn missing_scopes(&self, account: &str, tool: &str) -> Vec<&'static str> {
+        self.scopes
+            .lock()
+            .expect("scopes lock poisoned")
+            .get(account)
+            .map(|granted| granted.missing(tool))
+            .unwrap_or_default()
+    }
+
+    /// Whether some account may use `tool`, as far as their scopes are known.
+    fn tool_in_scope(&self, tool: &str) -> bool {
+        self.accounts
+            .all()
+            .iter()
+            .any(|account| self.missing_scopes(&account.name, tool).is_empty())
+    }
+
+    /// Saves the tokens of `response` in the token store. A refresh token
+    /// stored earlier is kept when the response does not carry a new one.
+    fn persist_tokens(
+        &self,
+        account: &str,
+        response: &AccessTokenResponse,
+    ) -> Result<(), RedditError> {
+        let mut tokens = self.tokens.load(account)?.unwrap_or_default();
+        if response.refresh_token.is_some() {
+            tokens.refresh_token = response.refresh_token.clone();
+        }
+        tokens.access_token = Some(response.access_token.clone());
+        tokens.access_token_expires_utc =
+            chrono::Utc::now().timestamp() + i64::from(response.expires_in);
+        tokens.app_only = self.auth_mode == AuthMode::AppOnly;
+        tokens.scope = response.scope.clone();
+        self.tokens.save(account, &tokens)
+    }
+
+    /// Stores and installs the tokens of a new sign-in.
+    async fn install_token(
+        &self,
+        account: &str,
+        response: &AccessTokenResponse,
+    ) -> Result<(), RedditError> {
+        let mut cached = self.token.lock().await;
+        self.persist_tokens(account, response)?;
+        cached.insert(account.to_string(), CachedToken::from_response(response));
+        drop(cached);
+        self.record_scopes(account, &response.scope).await;
+        Ok(())
+    }
+
+    /// Forgets a token Reddit rejected, unless it was already replaced.
+    async fn invalidate_token(&self, auth_token: &str) {
+        let mut cached = self.token.lock().await;
+        let rejected = cached
+            .iter()
+            .find(|(_, token)| token.auth_token == auth_token)
+            .map(|(account, _)| account.clone());
+        if let Some(account) = rejected {
+            tracing::info!("Discarding the rejected access token of {}", account);
+            cached.remove(&account);
+            let stored = self.tokens.load(&account).map(|tokens| {
+                tokens.filter(|tokens| {
+                    tokens
+                        .access_token
+                        .as_ref()
+                        .is_some_and(|token| format!("Bearer {}", token) == auth_token)
+                })
+            });
+            if let Ok(Some(mut tokens)) = stored {
+                tokens.access_token = None;
+                tokens.access_token_expires_utc = 0;
+                if let Err(e) = self.tokens.save(&account, &tokens) {
+                    tracing::warn!("Failed to discard the stored access token: {}", e);
+                }
+            }
+        }
+    }
 
-        match access_token_response {
-            Ok(token) => token.access_token,
+    /// Requests a new token: an application-only one in app-only mode,
+    /// otherwise for the configured account with the refresh token stored by
+    /// authorize_account when there is one, or else with the password.
+    async fn request_access_token(
+        &self,
+        account: &Account,
+    ) -> Result<AccessTokenResponse, RedditError> {
+        tracing::info!("Calling /api/access_token to get Authorization token");
+
+        let deadline = Deadline::for_tool("get_access_token");
+        let refresh_token = self
+            .tokens
+            .load(&account.name)?
+            .and_then(|tokens| tokens.refresh_token);
+        let (rejected_hint, access_token_response) = match (refresh_token, &account.password) {
+            _ if self.auth_mode == AuthMode::AppOnly => {
+                let request = match self.app_type {
+                    AppType::Confidential => ClientCredentialsRequest {
+                        grant_type: "client_credentials",
+                        device_id: None,
+                    },
+                    AppType::Installed => ClientCredentialsRequest {
+                        grant_type: INSTALLED_CLIENT_GRANT,
+                        device_id: Some(self.tokens.device_id()?),
+                    },
+                };
+                (
+                    "the app was not accepted for application-only access; check CLIENT_ID and that REDDIT_APP_TYPE matches the type the app was registered as",
+                    deadline
+                        .run(
+                            self.post_request::<AccessTokenResponse, ClientCredentialsRequest>(
+                                AUTH_URL, request,
+                            ),
+                        )
+                        .await,
+                )
+            }
+            (Some(refresh_token), _) => (
+                "the stored refresh token was revoked or expired; call authorize_account to sign in again",
+                deadline
+                    .run(
+                        self.post_request::<AccessTokenResponse, RefreshTokenRequest>(
+                            AUTH_URL,
+                            RefreshTokenRequest {
+                                grant_type: "refresh_token",
+                                refresh_token,
+                            },
+                        ),
+                    )
+                    .await,
+            ),
+            (None, Some(_)) if self.app_type == AppType::Installed => {
+                return Err(RedditError::invalid_input(
+                    "Installed apps cannot sign in with a password",
+                    "leave the account's password unset and call authorize_account to sign in through the browser, or register a script app",
+                ));
+            }
+            (None, Some(_)) if account.username.is_empty() => {
+                return Err(RedditError::missing_setting(
+                    "REDDIT_USERNAME",
+                    "username",
+                    "the username of the account to sign in with",
+                ));
+            }
+            (None, Some(password)) => {
+                // Reddit takes the one-time code of two-factor accounts as password:code.
+                let (password, rejected_hint) = match &account.totp_secret {
+                    Some(secret) => (
+                        format!(
+                            "{}:{}",
+                            password,
+                            totp(secret, chrono::Utc::now().timestamp())?
+                        ),
+                        "the account's password or one-time code was rejected; check the TOTP secret and that the system clock is accurate, since codes are only valid for 30 seconds",
+                    ),
+                    None => (
+                        password.clone(),
+                        "the account's username and password were rejected, or the app is not registered as a script app; accounts with two-factor authentication also need REDDIT_TOTP_SECRET",
+                    ),
+                };
+                (
+                    rejected_hint,
+                    deadline
+                        .run(
+                            self.post_request::<AccessTokenResponse, AccessTokenRequest>(
+                                AUTH_URL,
+                                AccessTokenRequest {
+                                    grant_type: "password".to_string(),
+                                    username: account.username.clone(),
+                                    password,
+                                },
+                            ),
+                        )
+                        .await,
+                )
+            }
+            (None, None) => {
+                return Err(RedditError::new(
+                    ErrorKind::Unauthorized,
+                    "No credentials to get an access token with",
+                    "set the account's password (REDDIT_PASSWORD or REDDIT_ACCOUNT_<NAME>_PASSWORD) or call authorize_account to sign in through the browser",
+                ));
+            }
+        };
+
+        match access_token_response.and_then(|response| response) {
+            Ok(token) => Ok(token),
+            // Reddit answers a rejected grant with 200 and {"error": "invalid_grant"}.
+            Err(e) if e.kind == ErrorKind::Parse => {
+                tracing::error!("The sign-in of {} was rejected: {}", account.name, e);
+                Err(RedditError::new(
+                    ErrorKind::Unauthorized,
+                    format!("Reddit rejected the sign-in of account {}", account.name),
+                    rejected_hint,
+                ))
+            }
             Err(e) => {
                 tracing::error!("Failed to fetch the access token: {}", e);
-                "Unable to fetch access_token from reddit".to_string()
+                let hint = match e.kind {
+                    ErrorKind::Unauthorized => {
+                        "CLIENT_ID/CLIENT_SECRET were rejected; copy them again from https://www.reddit.com/prefs/apps"
+                    }
+                    _ => &e.hint,
+                }
+                .to_string();
+                Err(e.with_hint(hint))
             }
         }
     }
@@ -167,45 +1122,724 @@ impl RedditClient {
         #[tool(param)]
         #[schemars(description = "If type_ahead is False")]
         type_ahead: bool,
-        #[tool(param)]
-        #[schemars(
-            description = "Access token from reddit access_token api to authenticate requests"
-        )]
-        access_token: String,
-    ) -> Result<String, String> {
+    ) -> Result<String, RedditError> {
         tracing::info!("Calling /api/search_reddit_names.json");
 
         let url = format!("{}/search_reddit_names", BASE_URL);
         let uuid = Uuid::new_v4();
-        let auth_token = format!("Bearer {}", access_token);
+        let auth_token = self.auth_token().await?;
 
         let search_subreddit_names_request = SearchSubredditNameRequest {
-            exact: exact,
-            include_over_18: include_over_18,
-            include_unadvertisable: include_unadvertisable,
-            query: query,
+            exact,
+            include_over_18,
+            include_unadvertisable,
+            query,
             search_query_id: uuid.to_string(),
             typeahead_active: type_ahead,
         };
 
-        let search_response = self
-            .get_request::<String, SearchSubredditNameRequest>(
-                &url,
-                &auth_token,
-                search_subreddit_names_request,
+        let search_response = Deadline::for_tool("search_subreddit_names")
+            .run(
+                self.get_request::<SearchSubredditNamesResponse, SearchSubredditNameRequest>(
+                    &url,
+                    &auth_token,
+                    search_subreddit_names_request,
+                ),
             )
-            .await;
-        search_response
+            .await??;
+
+        serde_json::to_string(&search_response).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get the newest posts of a subreddit. Repeated calls only download posts that are newer than the previous call and merge them with the cached listing."
+    )]
+    async fn get_new_posts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of posts to return (at most 100)")]
+        limit: u32,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "If unfurl_links is true, link posts include a preview (title, description, image) of the page they point to. Defaults to false."
+        )]
+        unfurl_links: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Calling /r/{}/new", subreddit);
+
+        let deadline = Deadline::for_tool("get_new_posts");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let url = format!("{}/r/{}/new", OAUTH_URL, subreddit);
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let cache_key = format!("r/{}/new", subreddit.to_lowercase());
+
+        let mut response = SubredditListingResponse {
+            subreddit: subreddit.clone(),
+            posts: Vec::new(),
+            new_since_last_fetch: 0,
+            served_from_cache: false,
+            excluded_seen: 0,
+            truncated: false,
+            truncated_reason: None,
+        };
+
+        let mut posts: Option<Vec<Post>> = None;
+        if let Some(anchor) = self.listing_cache.anchor(&cache_key) {
+            let listing_request = ListingRequest {
+                limit: MAX_PAGE_SIZE,
+                after: None,
+                before: Some(anchor),
+                raw_json: 1,
+            };
+            let newer = deadline
+                .run(self.get_request::<Listing<Post>, ListingRequest>(
+                    &url,
+                    &auth_token,
+                    listing_request,
+                ))
+                .await??;
+
+            // A full page of newer posts means there may be a gap, so fall back to a full fetch.
+            if newer.data.children.len() < MAX_PAGE_SIZE as usize {
+                let newer: Vec<Post> = newer.data.children.into_iter().map(|c| c.data).collect();
+                response.new_since_last_fetch = newer.len();
+                response.served_from_cache = true;
+                posts = Some(self.listing_cache.merge(&cache_key, newer));
+            }
+        }
+
+        let mut posts = match posts {
+            Some(posts) => posts,
+            None => {
+                let (posts, truncated_reason) = self
+                    .collect_listing(&url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                    .await?;
+                self.listing_cache.store(&cache_key, &posts);
+                response.new_since_last_fetch = posts.len();
+                response.truncated = truncated_reason.is_some();
+                response.truncated_reason = truncated_reason;
+                posts
+            }
+        };
+
+        if exclude_seen.unwrap_or(false) {
+            let (unseen, excluded) = self.seen_items.exclude_seen(posts);
+            posts = unseen;
+            response.excluded_seen = excluded;
+        }
+        let mut posts = self.apply_content_policy(posts);
+        posts.truncate(limit as usize);
+        self.seen_items.mark_seen(&posts);
+        if unfurl_links.unwrap_or(false) {
+            self.unfurler.unfurl(&mut posts).await;
+        }
+        response.posts = posts;
+
+        serde_json::to_string(&response).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Read a subreddit listing page by page as a consistent snapshot. Pass the returned snapshot_id to get the following pages without duplicates while the listing keeps changing."
+    )]
+    async fn get_posts_page(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Listing sort: hot, new, top, rising or controversial")]
+        sort: String,
+        #[tool(param)]
+        #[schemars(description = "Number of posts per page (at most 100)")]
+        page_size: u32,
+        #[tool(param)]
+        #[schemars(
+            description = "snapshot_id returned by a previous call. Omit it to start a new snapshot."
+        )]
+        snapshot_id: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "1-based page number. Pages already read are replayed unchanged; defaults to the next page."
+        )]
+        page: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "If unfurl_links is true, link posts include a preview (title, description, image) of the page they point to. Defaults to false."
+        )]
+        unfurl_links: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Calling /r/{}/{} with snapshot pagination", subreddit, sort);
+
+        if !LISTING_SORTS.contains(&sort.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unsupported sort {}", sort),
+                format!("use one of {}", LISTING_SORTS.join(", ")),
+            ));
+        }
+
+        let deadline = Deadline::for_tool("get_posts_page");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let url = format!("{}/r/{}/{}", OAUTH_URL, subreddit, sort);
+        let page_size = page_size.clamp(1, MAX_PAGE_SIZE) as usize;
+        let listing = format!("r/{}/{}", subreddit.to_lowercase(), sort);
+
+        let mut snapshot = self
+            .snapshot_pager
+            .checkout(snapshot_id.as_deref(), &listing)?;
+        let next_page = snapshot.pages.len() + 1;
+        let requested = page.map(|page| page.max(1) as usize).unwrap_or(next_page);
+
+        let mut response = SnapshotPageResponse {
+            snapshot_id: snapshot.id.clone(),
+            listing,
+            page: requested,
+            posts: Vec::new(),
+            duplicates_skipped: 0,
+            exhausted: false,
+            truncated: false,
+            truncated_reason: None,
+        };
+
+        if requested < next_page {
+            response.posts = snapshot.pages[requested - 1].clone();
+        } else if requested > next_page {
+            self.snapshot_pager.checkin(snapshot);
+            return Err(RedditError::invalid_input(
+                format!("Page {} of this snapshot has not been read yet", requested),
+                format!(
+                    "pages must be read in order; request page {} next",
+                    next_page
+                ),
+            ));
+        } else {
+            let mut anchors = snapshot.anchors().into_iter();
+            let mut after = anchors.next();
+
+            while response.posts.len() < page_size && !snapshot.exhausted {
+                if deadline.is_expired() {
+                    response.truncated = true;
+                    response.truncated_reason = Some(deadline.truncation_reason());
+                    break;
+                }
+
+                let listing_request = ListingRequest {
+                    limit: (page_size - response.posts.len()) as u32,
+                    after: after.clone(),
+                    before: None,
+                    raw_json: 1,
+                };
+                let fetched = match deadline
+                    .run(self.get_request::<Listing<Post>, ListingRequest>(
+                        &url,
+                        &auth_token,
+                        listing_request,
+                    ))
+                    .await
+                {
+                    Ok(Ok(fetched)) => fetched,
+                    Ok(Err(e)) => {
+                        self.snapshot_pager.checkin(snapshot);
+                        return Err(e);
+                    }
+                    Err(_) => {
+                        response.truncated = true;
+                        response.truncated_reason = Some(deadline.truncation_reason());
+                        break;
+                    }
+                };
+
+                if fetched.data.children.is_empty() {
+                    // The anchor may have been deleted; retry from an earlier post of the snapshot.
+                    match anchors.next() {
+                        Some(anchor) if after.is_some() => after = Some(anchor),
+                        _ => snapshot.exhausted = true,
+                    }
+                    continue;
+                }
+
+                let candidates = self.apply_content_policy(
+                    fetched.data.children.into_iter().map(|c| c.data).collect(),
+                );
+                let room = page_size - response.posts.len();
+                let (accepted, duplicates) = snapshot.accept(candidates, room);
+                response.posts.extend(accepted);
+                response.duplicates_skipped += duplicates;
+
+                match fetched.data.after {
+                    Some(next) => after = Some(next),
+                    None => snapshot.exhausted = true,
+                }
+            }
+
+            if !response.posts.is_empty() {
+                snapshot.record_page(response.posts.clone());
+            }
+        }
+
+        response.exhausted = snapshot.exhausted && requested >= snapshot.pages.len();
+        self.seen_items.mark_seen(&response.posts);
+        self.snapshot_pager.checkin(snapshot);
+        if unfurl_links.unwrap_or(false) {
+            self.unfurler.unfurl(&mut response.posts).await;
+        }
+
+        serde_json::to_string(&response).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Check that a Reddit user exists. Accepts u/name, /user/name or profile URLs and suggests similar usernames for typos."
+    )]
+    async fn check_username(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Calling /api/username_available for {}", username);
+
+        let auth_token = self.auth_token().await?;
+        let username = Deadline::for_tool("check_username")
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+
+        serde_json::to_string(&UsernameCheckResponse {
+            username,
+            exists: true,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Report where the server keeps its local state, the state format version and how much is stored per area."
+    )]
+    async fn state_info(&self) -> Result<String, RedditError> {
+        tracing::info!("Reporting state directory usage");
+
+        let info = self.storage.info().map_err(RedditError::storage)?;
+        serde_json::to_string(&info).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Report how many read and write tool calls were made in the current quota window and how many remain under the [quotas] limits of the server configuration."
+    )]
+    async fn get_quota_usage(&self) -> Result<String, RedditError> {
+        tracing::info!("Reporting quota usage");
+
+        let quotas = self.config.get().quotas;
+        serde_json::to_string(&QuotaUsageResponse {
+            window_minutes: quotas.window_minutes.max(1),
+            categories: self.quotas.usage(&quotas),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Report what this deployment of the server allows: the auth mode and each account's granted scopes, which tools are available by group (read, write, local) and why the others are not, whether it is read-only or queues replies for approval, and where it keeps its state. Check it before planning work that needs write access."
+    )]
+    async fn get_server_capabilities(&self) -> Result<String, RedditError> {
+        tracing::info!("Reporting server capabilities");
+
+        let config = self.config.get();
+        let mut groups: Vec<ToolGroupCapabilities> =
+            [ToolCategory::Read, ToolCategory::Write, ToolCategory::Local]
+                .into_iter()
+                .map(|group| ToolGroupCapabilities {
+                    group,
+                    enabled: config
+                        .tools
+                        .groups
+                        .as_ref()
+                        .is_none_or(|groups| groups.contains(&group)),
+                    available: Vec::new(),
+                    unavailable: Vec::new(),
+                })
+                .collect();
+        let mut tools: Vec<String> = Self::tool_box()
+            .list()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        tools.sort();
+        for tool in tools {
+            let Some(group) = groups
+                .iter_mut()
+                .find(|group| group.group == ToolCategory::of(&tool))
+            else {
+                continue;
+            };
+            // In the order call_tool checks them.
+            let reason = if !group.enabled {
+                Some("excluded by [tools] groups".to_string())
+            } else if !config.tool_allowed(&tool) {
+                Some("not in the [tools] allowlist".to_string())
+            } else if !self.auth_mode.tool_available(&tool) {
+                Some("needs a Reddit account, but REDDIT_AUTH_MODE is app_only".to_string())
+            } else if !self.tool_in_scope(&tool) {
+                Some(format!(
+                    "no account was granted the OAuth scopes {}",
+                    required_scopes(&tool).join(", ")
+                ))
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => group.unavailable.push(UnavailableTool { tool, reason }),
+                None => group.available.push(tool),
+            }
+        }
+
+        let signed_in = self.token.lock().await;
+        let scopes = self.scopes.lock().expect("scopes lock poisoned");
+        let accounts = self
+            .accounts
+            .all()
+            .iter()
+            .map(|account| AccountCapabilities {
+                name: account.name.clone(),
+                signed_in: signed_in.contains_key(&account.name),
+                scopes: scopes.get(&account.name).map(GrantedScopes::names),
+            })
+            .collect();
+        drop(scopes);
+        drop(signed_in);
+
+        let read_only = groups
+            .iter()
+            .all(|group| group.group != ToolCategory::Write || group.available.is_empty());
+        let transport = match env_or_file("MCP_TRANSPORT").as_deref() {
+            Some("http") => "http",
+            _ => "stdio",
+        };
+        serde_json::to_string(&ServerCapabilitiesResponse {
+            version: env!("CARGO_PKG_VERSION"),
+            transport,
+            auth_mode: self.auth_mode.as_str(),
+            app_type: self.app_type.as_str(),
+            accounts,
+            read_only,
+            require_approval: config.replies.require_approval,
+            allow_nsfw: config.content.allow_nsfw,
+            groups,
+            state_dir: self.storage.root().to_path_buf(),
+            config_file: RuntimeConfig::path(),
+        })
+        .map_err(RedditError::serialize)
     }
 }
 
-#[tool(tool_box)]
+impl RedditClient {
+    rmcp::tool_box!(RedditClient {
+        get_access_token,
+        authorize_account,
+        list_accounts,
+        whoami,
+        revoke_token,
+        search_subreddit_names,
+        get_new_posts,
+        get_posts_page,
+        get_subreddit_posts,
+        get_home_feed,
+        get_site_feed,
+        get_post,
+        get_inbox,
+        check_username,
+        state_info,
+        get_quota_usage,
+        get_server_capabilities,
+        get_user_comments_with_context,
+        get_user_about,
+        get_user_overview,
+        get_user_posts,
+        get_user_comments,
+        get_thread_highlights,
+        get_comments,
+        tally_comment_poll,
+        expand_more_comments,
+        get_comment_context,
+        get_context_bundle,
+        search_posts_between,
+        get_topic_timeline,
+        resume_job,
+        list_jobs,
+        get_job_status,
+        pause_job,
+        cancel_job,
+        save_search,
+        list_saved_searches,
+        delete_saved_search,
+        run_saved_search,
+        get_subreddit_about,
+        get_subreddit_rules,
+        get_subreddit_sidebar,
+        get_sticky_posts,
+        compare_subreddits,
+        get_best_posting_times,
+        check_post_against_rules,
+        find_rising_posts,
+        extract_thread_mentions,
+        list_subreddit_emojis,
+        list_subreddit_awards,
+        notify_thread_participants,
+        add_notification_opt_out,
+        remove_notification_opt_out,
+        list_notification_opt_outs,
+        get_most_awarded,
+        find_similar_subreddits,
+        get_collection,
+        list_subreddit_collections,
+        create_collection,
+        add_post_to_collection,
+        remove_post_from_collection,
+        get_predictions,
+        compare_comment_sorts,
+        find_flashpoints,
+        get_account_hygiene_report,
+        cleanup_account,
+        preview_history_deletion,
+        run_history_deletion,
+        get_deletion_progress,
+        check_posting_cooldown,
+        submit_post,
+        submit_to_multiple,
+        submit_title_variant,
+        get_experiment_results,
+        list_macros,
+        run_macro,
+        suggest_removal_reasons,
+        get_modmail_context,
+        send_modmail_reply,
+        post_comment,
+        send_message,
+        list_pending_replies,
+        approve_reply,
+        reject_reply,
+        get_user_risk_summary,
+        modqueue_changes,
+        get_mod_dashboard,
+        create_sticky_comment_monitor,
+        create_traffic_anomaly_monitor,
+        list_monitors,
+        get_monitor,
+        delete_monitor,
+        preview_monitor,
+        enforce_post_flair,
+        export_ban_list,
+        get_mod_team_activity,
+        list_recent_removals,
+        restore_removed_items,
+        test_automod_rule,
+        backup_subreddit_config,
+        schedule_config_backup,
+        list_config_backups,
+        diff_config,
+        bootstrap_subreddit,
+    });
+}
+
 impl ServerHandler for RedditClient {
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::Error> {
+        let config = self.config.get();
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools: Self::tool_box()
+                .list()
+                .into_iter()
+                .filter(|tool| {
+                    config.tool_allowed(&tool.name)
+                        && self.auth_mode.tool_available(&tool.name)
+                        && self.tool_in_scope(&tool.name)
+                })
+                .map(|tool| self.with_account_parameter(tool))
+                .map(with_fields_parameter)
+                .map(with_language_parameter)
+                .map(with_format_parameter)
+                .collect(),
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let config = self.config.get();
+        if !config.tool_allowed(&request.name) {
+            let error = RedditError::invalid_input(
+                format!("Tool {} is disabled on this server", request.name),
+                "only tools listed in the [tools] allowlist of the server configuration can be called",
+            );
+            return Ok(CallToolResult::error(error.into_contents()));
+        }
+        if !self.auth_mode.tool_available(&request.name) {
+            let error = RedditError::invalid_input(
+                format!("Tool {} needs a Reddit account", request.name),
+                "the server runs with REDDIT_AUTH_MODE=app_only, which only allows read-only tools",
+            );
+            return Ok(CallToolResult::error(error.into_contents()));
+        }
+        let mut request = request;
+        let account = match self.call_account(&mut request) {
+            Ok(account) => account,
+            Err(error) => return Ok(CallToolResult::error(error.into_contents())),
+        };
+        let missing = self.missing_scopes(&account, &request.name);
+        if !missing.is_empty() {
+            let error = RedditError::new(
+                ErrorKind::Forbidden,
+                format!(
+                    "Tool {} needs the OAuth scopes {} that the token of {} was not granted",
+                    request.name,
+                    missing.join(", "),
+                    account
+                ),
+                format!(
+                    "call authorize_account with scopes including {} to sign in again",
+                    missing.join(", ")
+                ),
+            );
+            return Ok(CallToolResult::error(error.into_contents()));
+        }
+        let fields = match take_fields(&mut request) {
+            Ok(fields) => fields,
+            Err(error) => return Ok(CallToolResult::error(error.into_contents())),
+        };
+        let format = match take_format(&mut request) {
+            Ok(format) => format,
+            Err(error) => return Ok(CallToolResult::error(error.into_contents())),
+        };
+        let language = match take_language(&mut request) {
+            Ok(language) => language,
+            Err(error) => return Ok(CallToolResult::error(error.into_contents())),
+        };
+        if let Err(usage) = self.quotas.try_acquire(&request.name, &config.quotas) {
+            tracing::warn!("Quota exceeded for {}", request.name);
+            let error = RedditError::quota_exceeded(&request.name, &usage);
+            return Ok(CallToolResult::error(error.into_contents()));
+        }
+
+        let emoji = config.content.emoji;
+        let mask = mask_pattern(&config.content.masked_words);
+        let span = tracing::info_span!(
+            "tool_call",
+            otel.name = %request.name,
+            tool = %request.name,
+            account = %account
+        );
+        let deadline = Deadline::for_tool(&request.name);
+        let context = ToolCallContext::new(self, request, context);
+        let result = deadline
+            .run_call(with_account(account, Self::tool_box().call(context)))
+            .instrument(span)
+            .await;
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => return Ok(CallToolResult::error(error.into_contents())),
+        };
+        result.map(|result| {
+            let result = filter_result(result, language.as_ref());
+            let mut result = project_result(result, &fields);
+            if let Some(mask) = &mask {
+                result = map_json_result(result, |value| mask_value(value, mask));
+            }
+            if emoji != EmojiMode::Keep {
+                result = map_json_result(result, |value| sanitize_value(value, emoji));
+            }
+            render_result(result, format, &fields)
+        })
+    }
+
+    fn get_peer(&self) -> Option<Peer<RoleServer>> {
+        self.peers
+            .lock()
+            .expect("peers lock poisoned")
+            .last()
+            .cloned()
+    }
+
+    fn set_peer(&mut self, peer: Peer<RoleServer>) {
+        self.peers.lock().expect("peers lock poisoned").push(peer);
+    }
+
     fn get_info(&self) -> ServerInfo {
+        let mut instructions = "A MCP server for accessing Reddit".to_string();
+        let missing = self
+            .scopes
+            .lock()
+            .expect("scopes lock poisoned")
+            .get(DEFAULT_ACCOUNT)
+            .map(GrantedScopes::missing_for_any_tool)
+            .unwrap_or_default();
+        if !missing.is_empty() {
+            instructions.push_str(&format!(
+                ". Tools needing the OAuth scopes {} are hidden because the token was not granted them; authorize_account signs in with more scopes",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
         ServerInfo {
-            instructions: Some("A MCP server for accessing Reddit".into()),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            instructions: Some(instructions),
+            capabilities: ServerCapabilities::builder()
+                .enable_logging()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
             ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::reddit::quota::classified_tools;
+
+    fn tool_names() -> HashSet<String> {
+        RedditClient::tool_box()
+            .list()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_tool_is_classified() {
+        for tool in tool_names() {
+            assert!(
+                ToolCategory::classify(&tool).is_some(),
+                "{} is missing from WRITE_TOOLS, READ_TOOLS and LOCAL_TOOLS",
+                tool
+            );
+        }
+    }
+
+    #[test]
+    fn classified_tools_exist() {
+        let tools = tool_names();
+        for tool in classified_tools() {
+            assert!(
+                tools.contains(tool),
+                "{} is classified but not a tool",
+                tool
+            );
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use rmcp::tool;
+
+use super::{BASE_URL, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    AccountHygieneReport, CleanupResponse, Comment, DraftsResponse, FailedTarget, HygieneItem,
+    InactiveSubscription, Listing, ListingRequest, Post, RawJsonRequest, SubredditAbout,
+    SubscribeRequest, ThingRequest, UserList,
+};
+use crate::reddit::text::snippet;
+use crate::reddit::validation::normalize_subreddit;
+
+const HISTORY_LIMIT: u32 = 1000;
+const SUBSCRIPTION_LIMIT: u32 = 1000;
+const DEFAULT_SCORE_THRESHOLD: i64 = 1;
+const DEFAULT_MIN_AGE_DAYS: i64 = 365;
+const INACTIVE_AFTER_DAYS: f64 = 365.0;
+const HYGIENE_EXCERPT_CHARS: usize = 120;
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+impl RedditClient {
+    #[tool(
+        description = "Review the authenticated account: old low-scoring posts and comments, subscriptions to subreddits without a post in a year, blocked users and drafts. Use cleanup_account to act on the findings."
+    )]
+    pub(super) async fn get_account_hygiene_report(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Report posts and comments with a score at or below this (default 1)"
+        )]
+        score_threshold: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Only report items older than this many days (default 365)")]
+        min_age_days: Option<i64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Check every subscribed subreddit for activity. This takes one request per subscription; defaults to true."
+        )]
+        check_subscriptions: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!(
+            "Building account hygiene report for {}",
+            self.account().name
+        );
+
+        let deadline = Deadline::for_tool("get_account_hygiene_report");
+        let auth_token = self.auth_token().await?;
+        let score_threshold = score_threshold.unwrap_or(DEFAULT_SCORE_THRESHOLD);
+        let now = chrono::Utc::now().timestamp() as f64;
+        let cutoff =
+            now - min_age_days.unwrap_or(DEFAULT_MIN_AGE_DAYS).max(0) as f64 * SECONDS_PER_DAY;
+        let mut truncated_reason = None;
+
+        let url = format!("{}/user/{}/submitted", OAUTH_URL, self.account_username()?);
+        let (posts, reason) = self
+            .collect_listing::<Post>(&url, &auth_token, HISTORY_LIMIT, &deadline)
+            .await?;
+        truncated_reason = truncated_reason.or(reason);
+        let low_score_posts = posts
+            .into_iter()
+            .filter(|post| post.created_utc < cutoff && post.score <= score_threshold)
+            .map(|post| HygieneItem {
+                fullname: post.name,
+                subreddit: post.subreddit,
+                score: post.score,
+                created_utc: post.created_utc,
+                permalink: post.permalink,
+                excerpt: snippet(&post.title, HYGIENE_EXCERPT_CHARS),
+            })
+            .collect();
+
+        let url = format!("{}/user/{}/comments", OAUTH_URL, self.account_username()?);
+        let (comments, reason) = self
+            .collect_listing::<Comment>(&url, &auth_token, HISTORY_LIMIT, &deadline)
+            .await?;
+        truncated_reason = truncated_reason.or(reason);
+        let low_score_comments = comments
+            .into_iter()
+            .filter(|comment| comment.created_utc < cutoff && comment.score <= score_threshold)
+            .map(|comment| HygieneItem {
+                fullname: comment.name,
+                subreddit: comment.subreddit,
+                score: comment.score,
+                created_utc: comment.created_utc,
+                permalink: comment.permalink,
+                excerpt: snippet(&comment.body, HYGIENE_EXCERPT_CHARS),
+            })
+            .collect();
+
+        let url = format!("{}/subreddits/mine/subscriber", OAUTH_URL);
+        let (subscriptions, reason) = self
+            .collect_listing::<SubredditAbout>(&url, &auth_token, SUBSCRIPTION_LIMIT, &deadline)
+            .await?;
+        truncated_reason = truncated_reason.or(reason);
+
+        let mut inactive_subscriptions = Vec::new();
+        let mut subscriptions_checked = 0;
+        if check_subscriptions.unwrap_or(true) {
+            for subscription in &subscriptions {
+                // User profiles show up as u_<name> subscriptions.
+                if subscription.display_name.starts_with("u_") {
+                    continue;
+                }
+                if deadline.is_expired() {
+                    truncated_reason.get_or_insert_with(|| deadline.truncation_reason());
+                    break;
+                }
+                let url = format!("{}/r/{}/new", OAUTH_URL, subscription.display_name);
+                let newest = deadline
+                    .run(
+                        self.get_request_with_priority::<Listing<Post>, ListingRequest>(
+                            &url,
+                            &auth_token,
+                            ListingRequest {
+                                limit: 1,
+                                after: None,
+                                before: None,
+                                raw_json: 1,
+                            },
+                            Priority::Background,
+                        ),
+                    )
+                    .await;
+                let Ok(Ok(newest)) = newest else {
+                    continue;
+                };
+                subscriptions_checked += 1;
+
+                let last_post_utc = newest
+                    .data
+                    .children
+                    .first()
+                    .map(|child| child.data.created_utc);
+                if last_post_utc
+                    .is_none_or(|last| now - last > INACTIVE_AFTER_DAYS * SECONDS_PER_DAY)
+                {
+                    inactive_subscriptions.push(InactiveSubscription {
+                        subreddit: subscription.display_name.clone(),
+                        last_post_utc,
+                    });
+                }
+            }
+        }
+
+        let url = format!("{}/prefs/blocked", OAUTH_URL);
+        let blocked_users = deadline
+            .run(self.get_request::<UserList, RawJsonRequest>(
+                &url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await??
+            .data
+            .children
+            .len();
+        // Drafts are not part of the documented API, so a failure only leaves them out.
+        let url = format!("{}/api/v1/drafts", OAUTH_URL);
+        let drafts = deadline
+            .run(self.get_request::<DraftsResponse, RawJsonRequest>(
+                &url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await?
+            .ok()
+            .map(|drafts| drafts.drafts.len());
+
+        serde_json::to_string(&AccountHygieneReport {
+            username: self.account_username()?,
+            low_score_posts,
+            low_score_comments,
+            subscriptions: subscriptions.len(),
+            subscriptions_checked,
+            inactive_subscriptions,
+            blocked_users,
+            drafts,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Clean up the authenticated account: delete own posts and comments by fullname, or unsubscribe from subreddits. Without confirm=true nothing is changed and the planned actions are returned for review."
+    )]
+    pub(super) async fn cleanup_account(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Action: delete or unsubscribe")]
+        action: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Fullnames (t1_..., t3_...) to delete, or subreddit names to unsubscribe from"
+        )]
+        targets: Vec<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Set to true to carry out the action. Defaults to false, which only previews it."
+        )]
+        confirm: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Account cleanup {} of {} targets", action, targets.len());
+
+        let targets: Vec<String> = match action.as_str() {
+            "delete" => targets
+                .iter()
+                .map(|target| {
+                    let target = target.trim();
+                    if target.starts_with("t1_") || target.starts_with("t3_") {
+                        Ok(target.to_string())
+                    } else {
+                        Err(RedditError::invalid_input(
+                            format!("{:?} is not a post or comment fullname", target),
+                            "pass fullnames such as t3_abc123 or t1_def456 as returned by get_account_hygiene_report",
+                        ))
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+            "unsubscribe" => targets
+                .iter()
+                .map(|target| normalize_subreddit(target))
+                .collect::<Result<_, _>>()?,
+            _ => {
+                return Err(RedditError::invalid_input(
+                    format!("Unknown cleanup action {}", action),
+                    "use delete or unsubscribe",
+                ));
+            }
+        };
+
+        let confirmed = confirm.unwrap_or(false);
+        let mut response = CleanupResponse {
+            action: action.clone(),
+            confirmed,
+            targets: targets.clone(),
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        };
+        if !confirmed {
+            return serde_json::to_string(&response).map_err(RedditError::serialize);
+        }
+
+        let deadline = Deadline::for_tool("cleanup_account");
+        let auth_token = self.auth_token().await?;
+        for target in targets {
+            if deadline.is_expired() {
+                response.failed.push(FailedTarget {
+                    target,
+                    error: deadline.truncation_reason(),
+                });
+                continue;
+            }
+            let result = if action == "delete" {
+                let url = format!("{}/del", BASE_URL);
+                deadline
+                    .run(self.post_form::<serde_json::Value, ThingRequest>(
+                        &url,
+                        &auth_token,
+                        ThingRequest { id: target.clone() },
+                    ))
+                    .await
+            } else {
+                let url = format!("{}/subscribe", BASE_URL);
+                deadline
+                    .run(self.post_form::<serde_json::Value, SubscribeRequest>(
+                        &url,
+                        &auth_token,
+                        SubscribeRequest {
+                            action: "unsub".to_string(),
+                            sr_name: target.clone(),
+                        },
+                    ))
+                    .await
+            };
+            match result.and_then(|result| result) {
+                Ok(_) => response.succeeded.push(target),
+                Err(e) => response.failed.push(FailedTarget {
+                    target,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        serde_json::to_string(&response).map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,261 @@
+use std::future::Future;
+
+use rmcp::model::{CallToolRequestParam, Tool};
+use rmcp::tool;
+
+use super::RedditClient;
+use crate::reddit::accounts::{DEFAULT_ACCOUNT, with_account};
+use crate::reddit::auth::AuthMode;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{AccountSummary, WhoAmIResponse};
+
+/// Tools that never act as an account and so take no `account` argument.
+const ACCOUNTLESS_TOOLS: [&str; 2] = ["list_accounts", "state_info"];
+/// Id of the startup credential check in list_jobs.
+const CREDENTIAL_CHECK_TASK: &str = "credential_check";
+
+impl RedditClient {
+    fn account_names(&self) -> String {
+        self.accounts
+            .all()
+            .iter()
+            .map(|account| account.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Takes the `account` argument out of a tool call and returns the name
+    /// of the account it refers to, the default account when it is missing.
+    pub(super) fn call_account(
+        &self,
+        request: &mut CallToolRequestParam,
+    ) -> Result<String, RedditError> {
+        let argument = request
+            .arguments
+            .as_mut()
+            .and_then(|arguments| arguments.remove("account"));
+        let name = match argument {
+            None | Some(serde_json::Value::Null) => return Ok(DEFAULT_ACCOUNT.to_string()),
+            Some(serde_json::Value::String(name)) => name,
+            Some(other) => {
+                return Err(RedditError::invalid_input(
+                    format!("account must be a string, got {}", other),
+                    "pass an account name from list_accounts",
+                ));
+            }
+        };
+        self.accounts
+            .get(&name)
+            .map(|account| account.name.clone())
+            .ok_or_else(|| {
+                RedditError::invalid_input(
+                    format!("Unknown account {:?}", name),
+                    format!(
+                        "use one of: {}; more accounts are added with REDDIT_ACCOUNTS",
+                        self.account_names()
+                    ),
+                )
+            })
+    }
+
+    /// Runs background work of a record a tool call saved, e.g. a monitor, as
+    /// the account of that call. A record of an account that is no longer
+    /// configured fails instead of running as the default account.
+    pub(super) async fn as_record_account<T>(
+        &self,
+        account: &str,
+        future: impl Future<Output = Result<T, RedditError>>,
+    ) -> Result<T, RedditError> {
+        if !self
+            .accounts
+            .all()
+            .iter()
+            .any(|known| known.name == account)
+        {
+            return Err(RedditError::new(
+                ErrorKind::Configuration,
+                format!("Account {} is no longer configured", account),
+                format!(
+                    "add it to REDDIT_ACCOUNTS again, or recreate the record as one of: {}",
+                    self.account_names()
+                ),
+            ));
+        }
+        with_account(account.to_string(), future).await
+    }
+
+    /// The Reddit username of the running account: the configured one, or
+    /// the one learned when authorize_account signed it in.
+    pub(super) fn account_username(&self) -> Result<String, RedditError> {
+        let account = self.account();
+        if !account.username.is_empty() {
+            return Ok(account.username.clone());
+        }
+        if let Some(username) = self
+            .signed_in_usernames
+            .lock()
+            .expect("usernames lock poisoned")
+            .get(&account.name)
+        {
+            return Ok(username.clone());
+        }
+        if let Some(username) = self
+            .tokens
+            .load(&account.name)?
+            .and_then(|tokens| tokens.username)
+        {
+            self.signed_in_usernames
+                .lock()
+                .expect("usernames lock poisoned")
+                .insert(account.name.clone(), username.clone());
+            return Ok(username);
+        }
+        Err(RedditError::new(
+            ErrorKind::Configuration,
+            format!("The username of the account {} is unknown", account.name),
+            "set the account's username (REDDIT_USERNAME or REDDIT_ACCOUNT_<NAME>_USERNAME) or call authorize_account to sign it in",
+        ))
+    }
+
+    /// Keeps the username of an account signed in through authorize_account.
+    pub(super) fn remember_username(
+        &self,
+        account: &str,
+        username: String,
+    ) -> Result<(), RedditError> {
+        let mut tokens = self.tokens.load(account)?.unwrap_or_default();
+        tokens.username = Some(username.clone());
+        self.tokens.save(account, &tokens)?;
+        self.signed_in_usernames
+            .lock()
+            .expect("usernames lock poisoned")
+            .insert(account.to_string(), username);
+        Ok(())
+    }
+
+    /// Adds the optional `account` parameter to a listed tool when there are
+    /// several accounts to choose from.
+    pub(super) fn with_account_parameter(&self, mut tool: Tool) -> Tool {
+        if self.accounts.all().len() < 2 || ACCOUNTLESS_TOOLS.contains(&tool.name.as_ref()) {
+            return tool;
+        }
+        let mut schema = (*tool.input_schema).clone();
+        let properties = schema
+            .entry("properties")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(properties) = properties.as_object_mut() {
+            properties.insert(
+                "account".to_string(),
+                serde_json::json!({
+                    "type": ["string", "null"],
+                    "description": format!(
+                        "Account to act as: {} (default: {})",
+                        self.account_names(),
+                        DEFAULT_ACCOUNT
+                    ),
+                }),
+            );
+        }
+        tool.input_schema = schema.into();
+        tool
+    }
+
+    #[tool(
+        description = "List the Reddit accounts this server can act as. Pass an account's name as the account argument of any tool to act as it instead of the default account."
+    )]
+    pub(super) async fn list_accounts(&self) -> Result<String, RedditError> {
+        tracing::info!("Listing accounts");
+
+        let signed_in = self.token.lock().await;
+        let mut accounts = Vec::new();
+        for account in self.accounts.all() {
+            let stored = self.tokens.load(&account.name)?;
+            accounts.push(AccountSummary {
+                name: account.name.clone(),
+                username: match stored.as_ref().and_then(|tokens| tokens.username.clone()) {
+                    Some(username) if account.username.is_empty() => username,
+                    _ => account.username.clone(),
+                },
+                default: account.name == DEFAULT_ACCOUNT,
+                has_password: account.password.is_some(),
+                has_refresh_token: stored
+                    .as_ref()
+                    .is_some_and(|tokens| tokens.refresh_token.is_some()),
+                signed_in: signed_in.contains_key(&account.name),
+            });
+        }
+        serde_json::to_string(&accounts).map_err(RedditError::serialize)
+    }
+
+    /// The account of the running tool call as Reddit sees it, from `/api/v1/me`.
+    async fn who_am_i(&self) -> Result<WhoAmIResponse, RedditError> {
+        let auth_token = self.auth_token().await?;
+        let me = self.fetch_me(&auth_token).await?;
+        let account = self.account().name.clone();
+        let scopes = self
+            .scopes
+            .lock()
+            .expect("scopes lock poisoned")
+            .get(&account)
+            .map(|granted| granted.names())
+            .unwrap_or_default();
+        Ok(WhoAmIResponse {
+            account,
+            username: me.name,
+            link_karma: me.link_karma,
+            comment_karma: me.comment_karma,
+            created_utc: me.created_utc,
+            scopes,
+        })
+    }
+
+    /// Signs every account in once in the background and logs who it is, so
+    /// broken credentials show up at startup instead of at the first tool call.
+    pub fn spawn_credential_check(&self) {
+        let cancelled = self.tasks.register(
+            CREDENTIAL_CHECK_TASK,
+            CREDENTIAL_CHECK_TASK,
+            "Signs every account in once at startup",
+        );
+        let client = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = cancelled.cancelled() => {}
+                _ = client.check_credentials() => {}
+            }
+            client.tasks.finish(CREDENTIAL_CHECK_TASK);
+        });
+    }
+
+    async fn check_credentials(&self) {
+        if self.auth_mode == AuthMode::AppOnly {
+            match self.auth_token().await {
+                Ok(_) => tracing::info!("Signed in with an application-only token"),
+                Err(e) => tracing::error!("Application-only sign-in failed: {}", e),
+            }
+            return;
+        }
+        for account in self.accounts.all() {
+            let name = account.name.clone();
+            match with_account(name.clone(), self.who_am_i()).await {
+                Ok(me) => tracing::info!(
+                    "Account {} is signed in as u/{} with the scopes {}",
+                    name,
+                    me.username,
+                    me.scopes.join(" ")
+                ),
+                Err(e) => tracing::warn!("Account {} could not sign in: {}", name, e),
+            }
+        }
+    }
+
+    #[tool(
+        description = "Check the credentials by asking Reddit who the account is. Returns the authenticated username, karma and the OAuth scopes granted to its token."
+    )]
+    pub(super) async fn whoami(&self) -> Result<String, RedditError> {
+        tracing::info!("Checking the signed-in account");
+
+        let me = self.who_am_i().await?;
+        serde_json::to_string(&me).map_err(RedditError::serialize)
+    }
+}
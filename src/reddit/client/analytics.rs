@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Datelike, FixedOffset, Timelike};
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    Post, PostingSlot, PostingTimesResponse, RisingPost, RisingPostsResponse,
+};
+
+const TOP_TIMEFRAMES: [&str; 4] = ["week", "month", "year", "all"];
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const DEFAULT_SAMPLE_SIZE: u32 = 300;
+const MAX_SAMPLE_SIZE: u32 = 1000;
+const BEST_SLOTS: usize = 5;
+const BEST_DAYS: usize = 3;
+const BEST_HOURS: usize = 4;
+const DEFAULT_RISING_LIMIT: usize = 10;
+/// Posts older than this are past the point where joining early matters.
+const MAX_RISING_AGE_MINUTES: f64 = 6.0 * 60.0;
+/// How many times the subreddit's median velocity a post needs to be flagged.
+const TRENDING_FACTOR: f64 = 3.0;
+
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl RedditClient {
+    #[tool(
+        description = "Estimate the best time to post in a subreddit. Samples its top posts, buckets them by weekday and hour of submission and returns a heatmap with the strongest time slots."
+    )]
+    pub(super) async fn get_best_posting_times(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Top posts timeframe: week, month, year or all (default year)")]
+        timeframe: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of top posts to sample, 1-1000 (default 300)")]
+        sample_size: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "Report times in this offset from UTC in hours, e.g. -5 for US Eastern standard time (default 0)"
+        )]
+        utc_offset_hours: Option<i32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Estimating posting times for {}", subreddit);
+
+        let timeframe = timeframe.unwrap_or_else(|| "year".to_string());
+        if !TOP_TIMEFRAMES.contains(&timeframe.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown timeframe {}", timeframe),
+                format!("use one of {}", TOP_TIMEFRAMES.join(", ")),
+            ));
+        }
+        let utc_offset_hours = utc_offset_hours.unwrap_or(0);
+        let offset = FixedOffset::east_opt(utc_offset_hours * 3600).ok_or_else(|| {
+            RedditError::invalid_input(
+                format!("{} is not a valid UTC offset", utc_offset_hours),
+                "pass an offset between -23 and 23 hours",
+            )
+        })?;
+
+        let deadline = Deadline::for_tool("get_best_posting_times");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let url = format!("{}/r/{}/top?t={}", OAUTH_URL, subreddit, timeframe);
+        let sample_size = sample_size
+            .unwrap_or(DEFAULT_SAMPLE_SIZE)
+            .clamp(1, MAX_SAMPLE_SIZE);
+        let (posts, truncated_reason) = self
+            .collect_listing::<Post>(&url, &auth_token, sample_size, &deadline)
+            .await?;
+
+        let mut heatmap = vec![vec![0u32; 24]; 7];
+        let mut scores = vec![vec![0i64; 24]; 7];
+        for post in &posts {
+            let Some(created) = DateTime::from_timestamp(post.created_utc as i64, 0) else {
+                continue;
+            };
+            let created = created.with_timezone(&offset);
+            let day = created.weekday().num_days_from_monday() as usize;
+            let hour = created.hour() as usize;
+            heatmap[day][hour] += 1;
+            scores[day][hour] += post.score;
+        }
+
+        let mut slots: Vec<PostingSlot> = (0..7)
+            .flat_map(|day| (0..24).map(move |hour| (day, hour)))
+            .filter(|&(day, hour)| heatmap[day][hour] > 0)
+            .map(|(day, hour)| PostingSlot {
+                day: WEEKDAYS[day].to_string(),
+                hour: hour as u32,
+                posts: heatmap[day][hour],
+                average_score: scores[day][hour] as f64 / heatmap[day][hour] as f64,
+            })
+            .collect();
+        slots.sort_by(|a, b| {
+            b.posts
+                .cmp(&a.posts)
+                .then(b.average_score.total_cmp(&a.average_score))
+        });
+        slots.truncate(BEST_SLOTS);
+
+        let mut days: Vec<(usize, u32)> = heatmap
+            .iter()
+            .enumerate()
+            .map(|(day, hours)| (day, hours.iter().sum()))
+            .collect();
+        days.sort_by_key(|&(_, posts)| std::cmp::Reverse(posts));
+        let mut hours: Vec<(u32, u32)> = (0..24)
+            .map(|hour| (hour as u32, heatmap.iter().map(|day| day[hour]).sum()))
+            .collect();
+        hours.sort_by_key(|&(_, posts)| std::cmp::Reverse(posts));
+
+        serde_json::to_string(&PostingTimesResponse {
+            subreddit,
+            timeframe,
+            utc_offset_hours,
+            sampled_posts: posts.len(),
+            heatmap,
+            best_slots: slots,
+            best_days: days
+                .into_iter()
+                .take(BEST_DAYS)
+                .map(|(day, _)| WEEKDAYS[day].to_string())
+                .collect(),
+            best_hours: hours
+                .into_iter()
+                .take(BEST_HOURS)
+                .map(|(hour, _)| hour)
+                .collect(),
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Find young posts in a subreddit that are gaining points quickly. Samples the rising and new listings, computes score velocity (points per minute since creation) and flags posts well above the subreddit's typical pace."
+    )]
+    pub(super) async fn find_rising_posts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of posts to return (default 10)")]
+        limit: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Looking for rising posts in {}", subreddit);
+
+        let deadline = Deadline::for_tool("find_rising_posts");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let limit = limit
+            .unwrap_or(DEFAULT_RISING_LIMIT)
+            .clamp(1, MAX_PAGE_SIZE as usize);
+
+        let mut sample: Vec<Post> = Vec::new();
+        let mut fullnames = HashSet::new();
+        for listing in ["rising", "new"] {
+            let url = format!("{}/r/{}/{}", OAUTH_URL, subreddit, listing);
+            let (posts, _) = self
+                .collect_listing::<Post>(&url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                .await?;
+            sample.extend(
+                posts
+                    .into_iter()
+                    .filter(|post| fullnames.insert(post.name.clone())),
+            );
+        }
+
+        let now = chrono::Utc::now().timestamp() as f64;
+        let mut candidates: Vec<RisingPost> = self
+            .apply_content_policy(sample)
+            .into_iter()
+            .map(|post| {
+                let age_minutes = ((now - post.created_utc) / 60.0).max(1.0);
+                RisingPost {
+                    age_minutes,
+                    score_per_minute: post.score as f64 / age_minutes,
+                    comments_per_minute: post.num_comments as f64 / age_minutes,
+                    likely_to_trend: false,
+                    post,
+                }
+            })
+            .collect();
+        let sampled_posts = candidates.len();
+        let median_score_per_minute = median(
+            &mut candidates
+                .iter()
+                .map(|candidate| candidate.score_per_minute)
+                .collect::<Vec<_>>(),
+        );
+
+        candidates.retain(|candidate| candidate.age_minutes <= MAX_RISING_AGE_MINUTES);
+        for candidate in &mut candidates {
+            candidate.likely_to_trend = candidate.score_per_minute > 0.0
+                && candidate.score_per_minute >= median_score_per_minute * TRENDING_FACTOR;
+        }
+        candidates.sort_by(|a, b| b.score_per_minute.total_cmp(&a.score_per_minute));
+        candidates.truncate(limit);
+
+        serde_json::to_string(&RisingPostsResponse {
+            subreddit,
+            sampled_posts,
+            median_score_per_minute,
+            posts: candidates,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use axum::extract::Query;
+use axum::response::Html;
+use axum::{Router, routing::get};
+use rmcp::model::LoggingLevel;
+use rmcp::tool;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::{AUTH_URL, RedditClient};
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    AccessTokenResponse, AuthorizationCallback, AuthorizationCodeRequest, AuthorizationResponse,
+};
+
+const AUTHORIZE_URL: &str = "https://www.reddit.com/api/v1/authorize";
+/// How long the callback listener waits for the browser.
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Id of the callback listener in list_jobs; there is one redirect address.
+const AUTHORIZATION_TASK: &str = "authorization_listener";
+const DEFAULT_SCOPES: [&str; 20] = [
+    "identity",
+    "edit",
+    "flair",
+    "history",
+    "modconfig",
+    "modcontributors",
+    "modflair",
+    "modlog",
+    "modmail",
+    "modposts",
+    "modwiki",
+    "mysubreddits",
+    "privatemessages",
+    "read",
+    "report",
+    "save",
+    "submit",
+    "subscribe",
+    "vote",
+    "wikiread",
+];
+
+fn callback_page(title: &str, message: &str) -> Html<String> {
+    Html(format!(
+        "<!doctype html><html><head><title>{}</title></head><body><h1>{}</h1><p>{}</p></body></html>",
+        title, title, message
+    ))
+}
+
+impl RedditClient {
+    /// The local address and path `REDIRECT_URL` points at.
+    fn callback_listener_address(&self) -> Result<(String, String), RedditError> {
+        let redirect_url = self.redirect_url()?;
+        let invalid = || {
+            RedditError::invalid_input(
+                format!(
+                    "REDIRECT_URL {} is not a local http URL with a port",
+                    redirect_url
+                ),
+                "register a redirect URI like http://localhost:65010/authorize_callback for the app and set REDIRECT_URL to it",
+            )
+        };
+        let url = reqwest::Url::parse(redirect_url).map_err(|_| invalid())?;
+        let host = url.host_str().unwrap_or_default();
+        if url.scheme() != "http" || !["localhost", "127.0.0.1"].contains(&host) {
+            return Err(invalid());
+        }
+        let port = url.port().ok_or_else(invalid)?;
+        Ok((format!("127.0.0.1:{}", port), url.path().to_string()))
+    }
+
+    /// Exchanges the code the browser was redirected with for tokens and keeps
+    /// the refresh token for later runs.
+    async fn complete_authorization(
+        &self,
+        account: &str,
+        callback: AuthorizationCallback,
+    ) -> Result<AccessTokenResponse, RedditError> {
+        if let Some(error) = callback.error {
+            return Err(RedditError::new(
+                ErrorKind::Unauthorized,
+                format!("The authorization was not granted: {}", error),
+                "call authorize_account again and allow the access",
+            ));
+        }
+        let code = callback.code.unwrap_or_default();
+        let response = self
+            .post_request::<AccessTokenResponse, AuthorizationCodeRequest>(
+                AUTH_URL,
+                AuthorizationCodeRequest {
+                    grant_type: "authorization_code",
+                    code,
+                    redirect_uri: self.redirect_url()?.to_string(),
+                },
+            )
+            .await?;
+        if response.refresh_token.is_none() {
+            return Err(RedditError::parse(
+                "the token response has no refresh token",
+            ));
+        }
+
+        self.install_token(account, &response).await?;
+        // Accounts signed in this way usually have no username configured.
+        let me = self
+            .fetch_me(&format!("Bearer {}", response.access_token))
+            .await?;
+        self.remember_username(account, me.name)?;
+        Ok(response)
+    }
+
+    #[tool(
+        description = "Sign the account in through the browser with the OAuth2 authorization code flow, for accounts without a password (e.g. Google or Apple login). Returns a URL to open; after allowing access Reddit redirects to REDIRECT_URL, where the server listens for 5 minutes. The refresh token is stored in the state directory and used instead of the password from then on."
+    )]
+    pub(super) async fn authorize_account(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "OAuth scopes to request, e.g. [\"read\", \"submit\"] (default: every scope the tools use)"
+        )]
+        scopes: Option<Vec<String>>,
+    ) -> Result<String, RedditError> {
+        // The callback arrives outside of this tool call.
+        let account = self.account().name.clone();
+        tracing::info!("Starting the authorization code flow for {}", account);
+
+        let (address, path) = self.callback_listener_address()?;
+        let listener = tokio::net::TcpListener::bind(&address)
+            .await
+            .map_err(|e| {
+                RedditError::new(
+                    ErrorKind::Other,
+                    format!("Failed to listen on {}: {}", address, e),
+                    "an earlier authorize_account may still be waiting; finish it in the browser or retry in 5 minutes",
+                )
+            })?;
+
+        let scopes = scopes
+            .filter(|scopes| !scopes.is_empty())
+            .unwrap_or_else(|| DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect());
+        let state = Uuid::new_v4().to_string();
+        let mut authorize_url =
+            reqwest::Url::parse(AUTHORIZE_URL).expect("AUTHORIZE_URL is a valid URL");
+        authorize_url
+            .query_pairs_mut()
+            .append_pair("client_id", self.app_credentials()?.0)
+            .append_pair("response_type", "code")
+            .append_pair("state", &state)
+            .append_pair("redirect_uri", self.redirect_url()?)
+            .append_pair("duration", "permanent")
+            .append_pair("scope", &scopes.join(" "));
+
+        let done = CancellationToken::new();
+        let handler = {
+            let client = self.clone();
+            let done = done.clone();
+            let state = state.clone();
+            let account = account.clone();
+            move |Query(callback): Query<AuthorizationCallback>| async move {
+                // Stray requests, e.g. for a favicon, keep the listener waiting.
+                if callback.state.as_deref() != Some(state.as_str()) {
+                    return callback_page(
+                        "Unexpected request",
+                        "This request does not belong to the pending authorization.",
+                    );
+                }
+                let outcome = client.complete_authorization(&account, callback).await;
+                done.cancel();
+                match outcome {
+                    Ok(response) => {
+                        tracing::info!(
+                            "Authorization of {} completed with scopes {}",
+                            account,
+                            response.scope
+                        );
+                        client
+                            .notify_clients(
+                                LoggingLevel::Info,
+                                "authorization",
+                                serde_json::json!({
+                                    "authorized": true,
+                                    "account": account,
+                                    "scope": response.scope,
+                                }),
+                            )
+                            .await;
+                        callback_page(
+                            "Authorized",
+                            "reddit-mcp can now use this account. You can close this window.",
+                        )
+                    }
+                    Err(e) => {
+                        tracing::error!("Authorization failed: {}", e);
+                        client
+                            .notify_clients(
+                                LoggingLevel::Error,
+                                "authorization",
+                                serde_json::json!({
+                                    "authorized": false,
+                                    "account": account,
+                                    "error": e,
+                                }),
+                            )
+                            .await;
+                        callback_page("Authorization failed", &e.to_string())
+                    }
+                }
+            }
+        };
+        let app = Router::new().route(&path, get(handler));
+        let cancelled = self.tasks.register(
+            AUTHORIZATION_TASK,
+            AUTHORIZATION_TASK,
+            format!(
+                "Waits on {} for the authorization of the account {}",
+                address, account
+            ),
+        );
+        let tasks = self.tasks.clone();
+        tokio::spawn(async move {
+            let shutdown = async move {
+                tokio::select! {
+                    _ = done.cancelled() => {}
+                    _ = cancelled.cancelled() => {
+                        tracing::info!("The authorization was cancelled");
+                    }
+                    _ = tokio::time::sleep(AUTHORIZATION_TIMEOUT) => {
+                        tracing::warn!("The authorization was not completed in time");
+                    }
+                }
+            };
+            let result = axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await;
+            if let Err(e) = &result {
+                tracing::error!("Authorization callback listener failed: {}", e);
+            }
+            tasks.record_run(AUTHORIZATION_TASK, result.err().map(|e| e.to_string()));
+            tasks.finish(AUTHORIZATION_TASK);
+        });
+
+        serde_json::to_string(&AuthorizationResponse {
+            authorize_url: authorize_url.to_string(),
+            redirect_url: self.redirect_url()?.to_string(),
+            scopes,
+            expires_in_secs: AUTHORIZATION_TIMEOUT.as_secs(),
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,75 @@
+use rmcp::tool;
+
+use super::RedditClient;
+use crate::reddit::automod::{AutomodSample, SampleKind, test_rules};
+use crate::reddit::error::RedditError;
+use crate::reddit::models::AutomodTestResponse;
+
+impl RedditClient {
+    #[tool(
+        description = "Test AutoModerator rules against a sample post or comment without touching the subreddit. Evaluates the common conditions (type, title/body/url/domain with match modifiers and regex, author name, karma and account age) and reports for every rule whether it would match and which conditions could not be evaluated. Runs locally without calling Reddit."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn test_automod_rule(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "AutoModerator YAML: one rule, or several separated by --- lines"
+        )]
+        rules: String,
+        #[tool(param)]
+        #[schemars(description = "Sample kind: submission (default) or comment")]
+        kind: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Title of the sample submission")]
+        title: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Self text of the sample submission or body of the comment")]
+        body: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Link of the sample submission; leave empty for a text post")]
+        url: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Username of the sample's author")]
+        author: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Comment karma of the sample's author")]
+        author_comment_karma: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Post karma of the sample's author")]
+        author_post_karma: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Account age of the sample's author in days")]
+        author_account_age_days: Option<f64>,
+    ) -> Result<String, RedditError> {
+        let kind = match kind.as_deref().map(str::trim) {
+            None | Some("") | Some("submission") | Some("post") => SampleKind::Submission,
+            Some("comment") => SampleKind::Comment,
+            Some(other) => {
+                return Err(RedditError::invalid_input(
+                    format!("{:?} is not a valid sample kind", other),
+                    "pass kind submission or comment",
+                ));
+            }
+        };
+        let sample = AutomodSample {
+            kind,
+            title: title.unwrap_or_default(),
+            body: body.unwrap_or_default(),
+            url: url.unwrap_or_default(),
+            author,
+            comment_karma: author_comment_karma,
+            link_karma: author_post_karma,
+            account_age_days: author_account_age_days,
+        };
+
+        let results = test_rules(&rules, &sample)?;
+        serde_json::to_string(&AutomodTestResponse {
+            kind: kind.name(),
+            rules_tested: results.len(),
+            rules_matched: results.iter().filter(|result| result.matched).count(),
+            results,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
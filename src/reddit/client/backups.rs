@@ -0,0 +1,636 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use rmcp::tool;
+use serde_json::Value;
+
+use super::{OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    ConfigBackupSchedule, ConfigBackupSummary, ConfigBackupsResponse, ConfigChange,
+    ConfigChangeKind, ConfigDiffResponse, ConfigSnapshot, RawJsonRequest, TextDiff, Thing,
+    WikiPage,
+};
+use crate::reddit::validation::{normalize_subreddit, parse_timestamp};
+use crate::storage::Namespace;
+
+const DEFAULT_BACKUP_CHECK_SECS: u64 = 900;
+/// Id of the scheduled backup task in list_jobs.
+const BACKUP_TASK: &str = "config_backups";
+const DEFAULT_BACKUP_INTERVAL_HOURS: u32 = 24;
+/// Older backups of a subreddit are pruned beyond this count.
+const MAX_CONFIG_BACKUPS: usize = 60;
+const SCHEDULE_PREFIX: &str = "config-backup-";
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
+fn format_utc(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn snapshot_key(subreddit: &str, taken_utc: i64) -> String {
+    format!("{}-{}", subreddit.to_ascii_lowercase(), taken_utc)
+}
+
+fn schedule_key(subreddit: &str) -> String {
+    format!("{}{}", SCHEDULE_PREFIX, subreddit.to_ascii_lowercase())
+}
+
+/// Keeps a section's value, or records why it could not be read.
+fn keep<T>(
+    section: &str,
+    result: Result<T, RedditError>,
+    unavailable: &mut BTreeMap<String, String>,
+) -> Option<T> {
+    result
+        .inspect_err(|e| {
+            unavailable.insert(section.to_string(), e.error.clone());
+        })
+        .ok()
+}
+
+fn sections(snapshot: &ConfigSnapshot) -> Vec<(&'static str, Option<&Value>)> {
+    vec![
+        ("rules", snapshot.rules.as_ref()),
+        ("settings", snapshot.settings.as_ref()),
+        (
+            "link_flair_templates",
+            snapshot.link_flair_templates.as_ref(),
+        ),
+        (
+            "user_flair_templates",
+            snapshot.user_flair_templates.as_ref(),
+        ),
+    ]
+}
+
+fn summarize(snapshot: &ConfigSnapshot) -> ConfigBackupSummary {
+    let mut present: Vec<&'static str> = sections(snapshot)
+        .into_iter()
+        .filter(|(_, value)| value.is_some())
+        .map(|(name, _)| name)
+        .collect();
+    if snapshot.automoderator.is_some() {
+        present.insert(0, "automoderator");
+    }
+    ConfigBackupSummary {
+        taken_utc: snapshot.taken_utc,
+        taken_at: format_utc(snapshot.taken_utc),
+        sections: present,
+        unavailable: snapshot.unavailable.clone(),
+    }
+}
+
+/// Flattens `value` into leaf paths. Array entries are addressed by their
+/// `short_name` (rules) or `id` (flair templates) so reordering or inserting
+/// one does not show up as a change to every later entry.
+fn flatten(path: String, value: &Value, leaves: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, field) in fields {
+                flatten(format!("{}.{}", path, key), field, leaves);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, item) in items.iter().enumerate() {
+                let key = item
+                    .get("short_name")
+                    .or_else(|| item.get("id"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| index.to_string());
+                flatten(format!("{}[{}]", path, key), item, leaves);
+            }
+        }
+        _ => {
+            leaves.insert(path, value.clone());
+        }
+    }
+}
+
+fn diff_section(name: &str, before: &Value, after: &Value, changes: &mut Vec<ConfigChange>) {
+    let mut old = BTreeMap::new();
+    flatten(name.to_string(), before, &mut old);
+    let mut new = BTreeMap::new();
+    flatten(name.to_string(), after, &mut new);
+
+    for (path, before) in &old {
+        match new.get(path) {
+            None => changes.push(ConfigChange {
+                path: path.clone(),
+                change: ConfigChangeKind::Removed,
+                before: Some(before.clone()),
+                after: None,
+            }),
+            Some(after) if after != before => changes.push(ConfigChange {
+                path: path.clone(),
+                change: ConfigChangeKind::Changed,
+                before: Some(before.clone()),
+                after: Some(after.clone()),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (path, after) in new {
+        if !old.contains_key(&path) {
+            changes.push(ConfigChange {
+                path,
+                change: ConfigChangeKind::Added,
+                before: None,
+                after: Some(after),
+            });
+        }
+    }
+}
+
+/// Lines only in `after` and lines only in `before`, counting repeated lines.
+fn diff_lines(before: &str, after: &str) -> TextDiff {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for line in before.lines() {
+        *remaining.entry(line).or_insert(0) += 1;
+    }
+    let mut added_lines = Vec::new();
+    for line in after.lines() {
+        match remaining.get_mut(line) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => added_lines.push(line.to_string()),
+        }
+    }
+    let removed_lines = before
+        .lines()
+        .filter(|line| {
+            remaining.get_mut(line).is_some_and(|count| {
+                let removed = *count > 0;
+                *count = count.saturating_sub(1);
+                removed
+            })
+        })
+        .map(str::to_string)
+        .collect();
+    TextDiff {
+        added_lines,
+        removed_lines,
+    }
+}
+
+impl RedditClient {
+    /// Reads the automod config, rules, settings and flair templates of a subreddit.
+    /// Sections the account may not read are recorded as unavailable.
+    async fn fetch_config_snapshot(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+        priority: Priority,
+    ) -> Result<ConfigSnapshot, RedditError> {
+        let mut unavailable = BTreeMap::new();
+        let raw_json = || RawJsonRequest { raw_json: 1 };
+
+        let automoderator = self
+            .get_request_with_priority::<Thing<WikiPage>, RawJsonRequest>(
+                &format!("{}/r/{}/wiki/config/automoderator", OAUTH_URL, subreddit),
+                auth_token,
+                raw_json(),
+                priority,
+            )
+            .await
+            .map(|page| page.data.content_md);
+        let automoderator = keep("automoderator", automoderator, &mut unavailable);
+
+        let rules = self
+            .get_request_with_priority::<Value, RawJsonRequest>(
+                &format!("{}/r/{}/about/rules", OAUTH_URL, subreddit),
+                auth_token,
+                raw_json(),
+                priority,
+            )
+            .await
+            .map(|response| response.get("rules").cloned().unwrap_or_default());
+        let rules = keep("rules", rules, &mut unavailable);
+
+        let settings = self
+            .get_request_with_priority::<Thing<Value>, RawJsonRequest>(
+                &format!("{}/r/{}/about/edit", OAUTH_URL, subreddit),
+                auth_token,
+                raw_json(),
+                priority,
+            )
+            .await
+            .map(|thing| thing.data);
+        let settings = keep("settings", settings, &mut unavailable);
+
+        let link_flair_templates = self
+            .get_request_with_priority::<Value, RawJsonRequest>(
+                &format!("{}/r/{}/api/link_flair_v2", OAUTH_URL, subreddit),
+                auth_token,
+                raw_json(),
+                priority,
+            )
+            .await;
+        let link_flair_templates = keep(
+            "link_flair_templates",
+            link_flair_templates,
+            &mut unavailable,
+        );
+
+        let user_flair_templates = self
+            .get_request_with_priority::<Value, RawJsonRequest>(
+                &format!("{}/r/{}/api/user_flair_v2", OAUTH_URL, subreddit),
+                auth_token,
+                raw_json(),
+                priority,
+            )
+            .await;
+        let user_flair_templates = keep(
+            "user_flair_templates",
+            user_flair_templates,
+            &mut unavailable,
+        );
+
+        let snapshot = ConfigSnapshot {
+            subreddit: subreddit.to_string(),
+            taken_utc: chrono::Utc::now().timestamp(),
+            automoderator,
+            rules,
+            settings,
+            link_flair_templates,
+            user_flair_templates,
+            unavailable,
+        };
+        if snapshot.automoderator.is_none() && snapshot.settings.is_none() {
+            return Err(RedditError::new(
+                ErrorKind::Forbidden,
+                format!("Could not read the configuration of r/{}", subreddit),
+                format!(
+                    "only moderators of r/{} with config access can back it up",
+                    subreddit
+                ),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Backups of a subreddit, oldest first.
+    fn load_config_backups(&self, subreddit: &str) -> Result<Vec<ConfigSnapshot>, RedditError> {
+        let prefix = format!("{}-", subreddit.to_ascii_lowercase());
+        let mut backups = Vec::new();
+        for key in self
+            .storage
+            .keys(Namespace::Backups)
+            .map_err(RedditError::storage)?
+        {
+            if key
+                .strip_prefix(&prefix)
+                .is_none_or(|taken| taken.parse::<i64>().is_err())
+            {
+                continue;
+            }
+            if let Some(snapshot) = self
+                .storage
+                .read_json::<ConfigSnapshot>(Namespace::Backups, &key)
+                .map_err(RedditError::storage)?
+            {
+                backups.push(snapshot);
+            }
+        }
+        backups.sort_by_key(|snapshot| snapshot.taken_utc);
+        Ok(backups)
+    }
+
+    fn save_config_backup(&self, snapshot: &ConfigSnapshot) -> Result<(), RedditError> {
+        self.storage
+            .write_json(
+                Namespace::Backups,
+                &snapshot_key(&snapshot.subreddit, snapshot.taken_utc),
+                snapshot,
+            )
+            .map_err(RedditError::storage)?;
+
+        let backups = self.load_config_backups(&snapshot.subreddit)?;
+        let excess = backups.len().saturating_sub(MAX_CONFIG_BACKUPS);
+        for old in &backups[..excess] {
+            self.storage
+                .remove(
+                    Namespace::Backups,
+                    &snapshot_key(&old.subreddit, old.taken_utc),
+                )
+                .map_err(RedditError::storage)?;
+        }
+        Ok(())
+    }
+
+    /// Takes the due scheduled configuration backups in the background, checking
+    /// every `CONFIG_BACKUP_CHECK_SECS` seconds (default 900, 0 disables them).
+    pub fn spawn_config_backups(&self) {
+        let interval = env::var("CONFIG_BACKUP_CHECK_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(DEFAULT_BACKUP_CHECK_SECS);
+        if interval == 0 {
+            tracing::info!("Scheduled configuration backups are disabled");
+            return;
+        }
+
+        let cancelled = self.tasks.register(
+            BACKUP_TASK,
+            BACKUP_TASK,
+            format!(
+                "Takes the due scheduled configuration backups, checking every {} seconds",
+                interval
+            ),
+        );
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if client.tasks.is_paused(BACKUP_TASK) {
+                    continue;
+                }
+                let result = client.run_due_config_backups().await;
+                if let Err(e) = &result {
+                    tracing::error!("Scheduled configuration backup failed: {}", e);
+                }
+                client
+                    .tasks
+                    .record_run(BACKUP_TASK, result.err().map(|e| e.to_string()));
+            }
+            tracing::info!("Scheduled configuration backups were cancelled");
+        });
+    }
+
+    async fn run_due_config_backups(&self) -> Result<(), RedditError> {
+        let now = chrono::Utc::now().timestamp();
+        let mut due = Vec::new();
+        for key in self
+            .storage
+            .keys(Namespace::Schedules)
+            .map_err(RedditError::storage)?
+        {
+            if !key.starts_with(SCHEDULE_PREFIX) {
+                continue;
+            }
+            let Some(schedule) = self
+                .storage
+                .read_json::<ConfigBackupSchedule>(Namespace::Schedules, &key)
+                .map_err(RedditError::storage)?
+            else {
+                continue;
+            };
+            let interval = i64::from(schedule.interval_hours) * SECONDS_PER_HOUR;
+            if schedule
+                .last_backup_utc
+                .is_none_or(|last| last + interval <= now)
+            {
+                due.push((key, schedule));
+            }
+        }
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        for (key, mut schedule) in due {
+            tracing::info!("Backing up the configuration of {}", schedule.subreddit);
+            let result = self
+                .as_record_account(&schedule.account, async {
+                    let auth_token = self.auth_token().await?;
+                    let snapshot = self
+                        .fetch_config_snapshot(
+                            &schedule.subreddit,
+                            &auth_token,
+                            Priority::Background,
+                        )
+                        .await?;
+                    self.save_config_backup(&snapshot)
+                })
+                .await;
+            schedule.last_backup_utc = Some(now);
+            schedule.last_error = result.err().map(|e| e.to_string());
+
+            // The schedule may have been cancelled while the backup ran.
+            if self
+                .storage
+                .read_json::<ConfigBackupSchedule>(Namespace::Schedules, &key)
+                .map_err(RedditError::storage)?
+                .is_some()
+            {
+                self.storage
+                    .write_json(Namespace::Schedules, &key, &schedule)
+                    .map_err(RedditError::storage)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[tool(
+        description = "For moderators: back up a subreddit's configuration now (AutoModerator config, rules, settings and flair templates) to the local state directory. Compare later with diff_config."
+    )]
+    pub(super) async fn backup_subreddit_config(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Backing up the configuration of {}", subreddit);
+
+        let deadline = Deadline::for_tool("backup_subreddit_config");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        let snapshot = deadline
+            .run(self.fetch_config_snapshot(&subreddit, &auth_token, Priority::Interactive))
+            .await??;
+        self.save_config_backup(&snapshot)?;
+
+        serde_json::to_string(&summarize(&snapshot)).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: back up a subreddit's configuration periodically in the background, as the account of this call. The first backup is taken within minutes. Pass interval_hours=0 to stop."
+    )]
+    pub(super) async fn schedule_config_backup(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Hours between backups (default 24); 0 stops the schedule")]
+        interval_hours: Option<u32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Scheduling configuration backups of {}", subreddit);
+
+        let deadline = Deadline::for_tool("schedule_config_backup");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let key = schedule_key(&subreddit);
+
+        let interval_hours = interval_hours.unwrap_or(DEFAULT_BACKUP_INTERVAL_HOURS);
+        if interval_hours == 0 {
+            let removed = self
+                .storage
+                .remove(Namespace::Schedules, &key)
+                .map_err(RedditError::storage)?;
+            if !removed {
+                return Err(RedditError::not_found(
+                    format!("No backup schedule for r/{}", subreddit),
+                    "call list_config_backups to see the schedule of a subreddit",
+                ));
+            }
+            return Ok(format!("Stopped scheduled backups of r/{}", subreddit));
+        }
+
+        let previous = self
+            .storage
+            .read_json::<ConfigBackupSchedule>(Namespace::Schedules, &key)
+            .map_err(RedditError::storage)?;
+        let schedule = ConfigBackupSchedule {
+            subreddit,
+            account: self.account().name.clone(),
+            interval_hours,
+            created_utc: chrono::Utc::now().timestamp(),
+            last_backup_utc: previous.and_then(|previous| previous.last_backup_utc),
+            last_error: None,
+        };
+        self.storage
+            .write_json(Namespace::Schedules, &key, &schedule)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&schedule).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "List the stored configuration backups of a subreddit, newest first, and its backup schedule. Reads local state only."
+    )]
+    pub(super) async fn list_config_backups(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        let subreddit = normalize_subreddit(&subreddit)?;
+        let schedule = self
+            .storage
+            .read_json::<ConfigBackupSchedule>(Namespace::Schedules, &schedule_key(&subreddit))
+            .map_err(RedditError::storage)?;
+        let backups = self
+            .load_config_backups(&subreddit)?
+            .iter()
+            .rev()
+            .map(summarize)
+            .collect();
+
+        serde_json::to_string(&ConfigBackupsResponse {
+            subreddit: schedule
+                .as_ref()
+                .map(|schedule| schedule.subreddit.clone())
+                .unwrap_or(subreddit),
+            schedule,
+            backups,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: show what changed in a subreddit's configuration (AutoModerator config, rules, settings, flair templates) since a backup, comparing the newest backup taken at or before the given date with the live configuration."
+    )]
+    pub(super) async fn diff_config(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Use the newest backup taken at or before this time: a unix timestamp, an RFC 3339 time or a YYYY-MM-DD date (meaning the end of that day)"
+        )]
+        date: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!(
+            "Diffing the configuration of {} against {}",
+            subreddit,
+            date
+        );
+
+        let deadline = Deadline::for_tool("diff_config");
+        let auth_token = self.auth_token().await?;
+        let mut cutoff = parse_timestamp(&date, "date")?;
+        if NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").is_ok() {
+            cutoff += 24 * SECONDS_PER_HOUR - 1;
+        }
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        let backups = self.load_config_backups(&subreddit)?;
+        let baseline = backups
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.taken_utc <= cutoff)
+            .ok_or_else(|| {
+                let hint = match backups.first() {
+                    Some(oldest) => format!(
+                        "the oldest backup of r/{} was taken at {}",
+                        subreddit,
+                        format_utc(oldest.taken_utc)
+                    ),
+                    None => format!(
+                        "take one with backup_subreddit_config or schedule_config_backup for r/{}",
+                        subreddit
+                    ),
+                };
+                RedditError::not_found(
+                    format!(
+                        "No backup of r/{} taken at or before {}",
+                        subreddit,
+                        format_utc(cutoff)
+                    ),
+                    hint,
+                )
+            })?;
+
+        let current = deadline
+            .run(self.fetch_config_snapshot(&subreddit, &auth_token, Priority::Interactive))
+            .await??;
+
+        let mut not_compared = Vec::new();
+        let automoderator = match (&baseline.automoderator, &current.automoderator) {
+            (Some(before), Some(after)) => Some(diff_lines(before, after)),
+            _ => {
+                not_compared.push("automoderator".to_string());
+                None
+            }
+        };
+        let mut changes = Vec::new();
+        for ((name, before), (_, after)) in sections(baseline).into_iter().zip(sections(&current)) {
+            match (before, after) {
+                (Some(before), Some(after)) => diff_section(name, before, after, &mut changes),
+                _ => not_compared.push(name.to_string()),
+            }
+        }
+        let unchanged = changes.is_empty()
+            && automoderator
+                .as_ref()
+                .is_none_or(|diff| diff.added_lines.is_empty() && diff.removed_lines.is_empty());
+
+        serde_json::to_string(&ConfigDiffResponse {
+            subreddit,
+            baseline_taken_at: format_utc(baseline.taken_utc),
+            compared_at: format_utc(current.taken_utc),
+            unchanged,
+            automoderator,
+            changes,
+            not_compared,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
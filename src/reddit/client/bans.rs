@@ -0,0 +1,270 @@
+use rmcp::tool;
+
+use super::jobs::{resumable, resume_note};
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    BanListExport, BanListRow, BannedUser, Job, JobSpec, ListingRequest, UserList,
+};
+use crate::reddit::scrub::Scrubber;
+
+const DEFAULT_BAN_SCAN: u32 = 1000;
+const MAX_BAN_SCAN: u32 = 10000;
+const BAN_EXPORT_FORMATS: [&str; 2] = ["json", "csv"];
+const BAN_DURATIONS: [&str; 2] = ["permanent", "temporary"];
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[BanListRow]) -> String {
+    let mut csv = String::from("username,banned_at,banned_utc,permanent,days_left,note\n");
+    for row in rows {
+        let fields = [
+            csv_field(&row.username),
+            row.banned_at.clone(),
+            row.banned_utc.to_string(),
+            row.permanent.to_string(),
+            row.days_left
+                .map(|days| days.to_string())
+                .unwrap_or_default(),
+            csv_field(&row.note),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+impl RedditClient {
+    /// Pages through the ban list into `job`, saving it after every page. A
+    /// job stopped by the deadline or pause_job stays incomplete for resume_job.
+    async fn fetch_ban_list(
+        &self,
+        job: &mut Job,
+        subreddit: &str,
+        auth_token: &str,
+        limit: u32,
+        deadline: &Deadline,
+    ) -> Result<Option<String>, RedditError> {
+        let url = format!("{}/r/{}/about/banned", OAUTH_URL, subreddit);
+
+        while !job.complete {
+            if job.items.len() >= limit as usize {
+                job.complete = true;
+                self.save_job(job)?;
+                return Ok(Some(format!(
+                    "stopped after {} bans; raise limit to read more",
+                    limit
+                )));
+            }
+            if let Some(reason) = self.job_interrupted(job)? {
+                return Ok(Some(reason));
+            }
+            let page = match deadline
+                .run(self.get_request::<UserList<BannedUser>, ListingRequest>(
+                    &url,
+                    auth_token,
+                    ListingRequest {
+                        limit: (limit - job.items.len() as u32).min(MAX_PAGE_SIZE),
+                        after: job.cursor.clone(),
+                        before: None,
+                        raw_json: 1,
+                    },
+                ))
+                .await
+            {
+                Ok(page) => page?,
+                Err(_) => return Ok(Some(deadline.truncation_reason())),
+            };
+
+            let fetched = page.data.children.len();
+            job.scanned += fetched;
+            for ban in page.data.children {
+                if job.processed_ids.contains(&ban.name) {
+                    continue;
+                }
+                job.processed_ids.push(ban.name.clone());
+                job.items
+                    .push(serde_json::to_value(ban).map_err(RedditError::serialize)?);
+            }
+            job.pages += 1;
+            job.cursor = page.data.after;
+            job.complete = job.cursor.is_none() || fetched == 0;
+            self.save_job(job)?;
+        }
+        Ok(None)
+    }
+
+    #[tool(
+        description = "For moderators: export a subreddit's ban list (user, ban date, permanent or days left, note) as JSON or CSV, optionally filtered, e.g. permanent bans older than two years, for ban list audits. Reddit includes the ban reason in the note. With scrub, usernames become stable pseudonyms and emails and phone numbers are removed, for sharing the data."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn export_ban_list(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Output format: json or csv (default json)")]
+        format: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only permanent or only temporary bans")]
+        duration: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only bans made more than this many days ago")]
+        older_than_days: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Only bans whose note contains this text")]
+        note_contains: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of bans to read (default 1000, max 10000)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "Replace usernames with pseudonyms and remove emails and phone numbers (default: [exports] scrub_pii of the server configuration)"
+        )]
+        scrub: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Exporting the ban list of {}", subreddit);
+
+        let format = format.unwrap_or_else(|| "json".to_string());
+        if !BAN_EXPORT_FORMATS.contains(&format.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown format {}", format),
+                "use json or csv",
+            ));
+        }
+        if let Some(duration) = &duration
+            && !BAN_DURATIONS.contains(&duration.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown duration {}", duration),
+                "use permanent or temporary, or omit it for every ban",
+            ));
+        }
+        let limit = limit.unwrap_or(DEFAULT_BAN_SCAN).clamp(1, MAX_BAN_SCAN);
+
+        let deadline = Deadline::for_tool("export_ban_list");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let job = self.start_job(JobSpec::BanListExport {
+            subreddit,
+            format,
+            duration,
+            older_than_days,
+            note_contains: note_contains.map(|text| text.to_lowercase()),
+            limit,
+            scrub: scrub.unwrap_or(self.config.get().exports.scrub_pii),
+        })?;
+        self.run_job(job).await
+    }
+
+    /// Runs or resumes a ban list export job.
+    pub(super) async fn run_ban_list_export(&self, mut job: Job) -> Result<String, RedditError> {
+        let JobSpec::BanListExport {
+            subreddit,
+            format,
+            duration,
+            older_than_days,
+            note_contains,
+            limit,
+            scrub,
+        } = job.spec.clone()
+        else {
+            return Err(RedditError::invalid_input(
+                format!("Job {} is not a ban list export", job.id),
+                "call resume_job with it instead",
+            ));
+        };
+        let scrubber =
+            scrub.then(|| Scrubber::new(self.config.get().exports.pseudonym_key.as_deref()));
+
+        let deadline = Deadline::for_tool("export_ban_list");
+        let auth_token = self.auth_token().await?;
+        let truncated_reason = self
+            .fetch_ban_list(&mut job, &subreddit, &auth_token, limit, &deadline)
+            .await
+            .map_err(|e| match e.kind {
+                ErrorKind::Forbidden => e.with_hint(format!(
+                    "only moderators of r/{} with the access permission can read its ban list",
+                    subreddit
+                )),
+                _ => resumable(e, &job),
+            })?
+            .map(|reason| resume_note(reason, &job));
+        let bans = job
+            .items
+            .iter()
+            .map(|ban| serde_json::from_value::<BannedUser>(ban.clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RedditError::parse)?;
+        let scanned = bans.len();
+        let now = chrono::Utc::now().timestamp();
+
+        let rows: Vec<BanListRow> = bans
+            .into_iter()
+            .map(|ban| {
+                let banned_utc = ban.date as i64;
+                BanListRow {
+                    username: ban.name,
+                    banned_utc,
+                    banned_at: chrono::DateTime::from_timestamp(banned_utc, 0)
+                        .map(|time| time.to_rfc3339())
+                        .unwrap_or_default(),
+                    permanent: ban.days_left.is_none(),
+                    days_left: ban.days_left,
+                    note: ban.note.unwrap_or_default(),
+                }
+            })
+            .filter(|row| match duration.as_deref() {
+                Some("permanent") => row.permanent,
+                Some("temporary") => !row.permanent,
+                _ => true,
+            })
+            .filter(|row| {
+                older_than_days.is_none_or(|days| now - row.banned_utc > days * SECONDS_PER_DAY)
+            })
+            .filter(|row| {
+                note_contains
+                    .as_deref()
+                    .is_none_or(|text| row.note.to_lowercase().contains(text))
+            })
+            .map(|row| match &scrubber {
+                Some(scrubber) => BanListRow {
+                    username: scrubber.pseudonym(&row.username),
+                    note: scrubber.scrub_text(&row.note),
+                    ..row
+                },
+                None => row,
+            })
+            .collect();
+
+        let matched = rows.len();
+        let (format, bans, csv) = match format.as_str() {
+            "csv" => ("csv", None, Some(to_csv(&rows))),
+            _ => ("json", Some(rows), None),
+        };
+        serde_json::to_string(&BanListExport {
+            job_id: job.id,
+            subreddit,
+            format,
+            scanned,
+            matched,
+            bans,
+            csv,
+            scrubbed: scrubber.is_some(),
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+
+use rmcp::tool;
+use serde_json::Value;
+
+use super::{BASE_URL, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    AddRuleRequest, BootstrapConfig, BootstrapFlair, BootstrapResponse, BootstrapRule,
+    BootstrapStep, BootstrapStepStatus, FlairTemplateRequest, RawJsonRequest, StickyRequest,
+    SubmitData, SubmitRequest, Thing,
+};
+
+/// Reddit allows at most this many rules per subreddit.
+const MAX_RULES: usize = 15;
+const RULE_KINDS: [&str; 3] = ["link", "comment", "all"];
+const FLAIR_TEXT_COLORS: [&str; 2] = ["dark", "light"];
+/// `about/edit` names of the settings that `site_admin` expects under another name.
+const SETTING_RENAMES: [(&str, &str); 6] = [
+    ("subreddit_id", "sr"),
+    ("content_options", "link_type"),
+    ("default_set", "allow_top"),
+    ("header_hover_text", "header-title"),
+    ("language", "lang"),
+    ("subreddit_type", "type"),
+];
+const CONFIG_HINT: &str = "pass a TOML or JSON block with any of settings, sidebar, rules, post_flairs, user_flairs and welcome_post";
+
+enum Step<'a> {
+    Settings,
+    Rule(&'a BootstrapRule),
+    PostFlair(&'a BootstrapFlair),
+    UserFlair(&'a BootstrapFlair),
+    WelcomePost,
+    StickyWelcomePost,
+}
+
+impl Step<'_> {
+    fn name(&self) -> String {
+        match self {
+            Step::Settings => "settings".to_string(),
+            Step::Rule(rule) => format!("rule: {}", rule.short_name),
+            Step::PostFlair(flair) => format!("post_flair: {}", flair.text),
+            Step::UserFlair(flair) => format!("user_flair: {}", flair.text),
+            Step::WelcomePost => "welcome_post".to_string(),
+            Step::StickyWelcomePost => "sticky_welcome_post".to_string(),
+        }
+    }
+}
+
+fn parse_config(config: &str) -> Result<BootstrapConfig, RedditError> {
+    let parsed = if config.trim_start().starts_with('{') {
+        serde_json::from_str(config).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(config).map_err(|e| e.to_string())
+    };
+    let config: BootstrapConfig = parsed
+        .map_err(|e| RedditError::invalid_input(format!("Invalid config: {}", e), CONFIG_HINT))?;
+
+    if config.rules.len() > MAX_RULES {
+        return Err(RedditError::invalid_input(
+            format!("{} rules were given", config.rules.len()),
+            format!("Reddit allows at most {} rules per subreddit", MAX_RULES),
+        ));
+    }
+    for rule in &config.rules {
+        if let Some(kind) = &rule.kind
+            && !RULE_KINDS.contains(&kind.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Rule {:?} has kind {:?}", rule.short_name, kind),
+                "use kind link, comment or all",
+            ));
+        }
+    }
+    for flair in config.post_flairs.iter().chain(&config.user_flairs) {
+        if let Some(color) = &flair.text_color
+            && !FLAIR_TEXT_COLORS.contains(&color.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Flair {:?} has text_color {:?}", flair.text, color),
+                "use text_color dark or light",
+            ));
+        }
+    }
+    Ok(config)
+}
+
+fn step_rejected(error: RedditError) -> RedditError {
+    error.with_rejection_hint("fix the config block and run the failed steps again")
+}
+
+fn form_value(value: Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+impl RedditClient {
+    /// Writes the settings and sidebar on top of the current settings, since
+    /// `site_admin` resets every setting that is left out.
+    async fn apply_bootstrap_settings(
+        &self,
+        subreddit: &str,
+        config: &BootstrapConfig,
+        auth_token: &str,
+    ) -> Result<(), RedditError> {
+        let current = self
+            .get_request::<Thing<Value>, RawJsonRequest>(
+                &format!("{}/r/{}/about/edit", OAUTH_URL, subreddit),
+                auth_token,
+                RawJsonRequest { raw_json: 1 },
+            )
+            .await?;
+        let Value::Object(mut settings) = current.data else {
+            return Err(RedditError::parse(
+                "the subreddit settings are not an object",
+            ));
+        };
+        settings.extend(config.settings.clone());
+        if let Some(sidebar) = &config.sidebar {
+            settings.insert("description".to_string(), Value::String(sidebar.clone()));
+        }
+        for (from, to) in SETTING_RENAMES {
+            if let Some(value) = settings.remove(from) {
+                settings.insert(to.to_string(), value);
+            }
+        }
+
+        let form: BTreeMap<String, String> = settings
+            .into_iter()
+            .filter_map(|(key, value)| form_value(value).map(|value| (key, value)))
+            .collect();
+        self.post_api::<Value, BTreeMap<String, String>>(
+            &format!("{}/site_admin", BASE_URL),
+            auth_token,
+            form,
+        )
+        .await
+        .map(|_| ())
+        .map_err(step_rejected)
+    }
+
+    async fn add_bootstrap_flair(
+        &self,
+        subreddit: &str,
+        flair: &BootstrapFlair,
+        flair_type: &'static str,
+        auth_token: &str,
+    ) -> Result<(), RedditError> {
+        self.post_api::<Value, FlairTemplateRequest>(
+            &format!("{}/r/{}/api/flairtemplate_v2", OAUTH_URL, subreddit),
+            auth_token,
+            FlairTemplateRequest {
+                flair_type,
+                text: flair.text.clone(),
+                css_class: flair.css_class.clone(),
+                background_color: flair.background_color.clone(),
+                text_color: flair
+                    .text_color
+                    .clone()
+                    .unwrap_or_else(|| "dark".to_string()),
+                mod_only: flair.mod_only,
+                text_editable: flair.text_editable,
+            },
+        )
+        .await
+        .map(|_| ())
+        .map_err(step_rejected)
+    }
+
+    /// Runs one step, returning the fullname of the welcome post when it was submitted.
+    async fn apply_bootstrap_step(
+        &self,
+        step: &Step<'_>,
+        subreddit: &str,
+        config: &BootstrapConfig,
+        welcome_post: Option<&str>,
+        auth_token: &str,
+    ) -> Result<Option<String>, RedditError> {
+        match step {
+            Step::Settings => {
+                self.apply_bootstrap_settings(subreddit, config, auth_token)
+                    .await?
+            }
+            Step::Rule(rule) => {
+                self.post_api::<Value, AddRuleRequest>(
+                    &format!("{}/add_subreddit_rule", BASE_URL),
+                    auth_token,
+                    AddRuleRequest {
+                        r: subreddit.to_string(),
+                        short_name: rule.short_name.clone(),
+                        description: rule.description.clone(),
+                        violation_reason: rule.violation_reason.clone(),
+                        kind: rule.kind.clone().unwrap_or_else(|| "all".to_string()),
+                    },
+                )
+                .await
+                .map_err(step_rejected)?;
+            }
+            Step::PostFlair(flair) => {
+                self.add_bootstrap_flair(subreddit, flair, "LINK_FLAIR", auth_token)
+                    .await?
+            }
+            Step::UserFlair(flair) => {
+                self.add_bootstrap_flair(subreddit, flair, "USER_FLAIR", auth_token)
+                    .await?
+            }
+            Step::WelcomePost => {
+                let post = config.welcome_post.as_ref().ok_or_else(|| {
+                    RedditError::invalid_input("No welcome_post was configured", CONFIG_HINT)
+                })?;
+                let post = self
+                    .post_api::<SubmitData, SubmitRequest>(
+                        &format!("{}/submit", BASE_URL),
+                        auth_token,
+                        SubmitRequest {
+                            sr: subreddit.to_string(),
+                            kind: "self",
+                            title: post.title.clone(),
+                            text: Some(post.text.clone()),
+                            url: None,
+                            flair_id: None,
+                            flair_text: None,
+                            nsfw: false,
+                            spoiler: false,
+                            crosspost_fullname: None,
+                        },
+                    )
+                    .await
+                    .map_err(step_rejected)?
+                    .ok_or_else(|| RedditError::parse("the submit response has no data"))?;
+                return Ok(Some(post.name));
+            }
+            Step::StickyWelcomePost => {
+                self.post_api::<Value, StickyRequest>(
+                    &format!("{}/set_subreddit_sticky", BASE_URL),
+                    auth_token,
+                    StickyRequest {
+                        id: welcome_post.unwrap_or_default().to_string(),
+                        state: true,
+                    },
+                )
+                .await
+                .map_err(step_rejected)?;
+            }
+        }
+        Ok(None)
+    }
+
+    #[tool(
+        description = "For moderators: set up a new subreddit from one config block, applying settings and sidebar, rules, post flairs, user flairs and a stickied welcome post in that order. Reports the outcome of every step; a failed step does not stop the others. Use dry_run to list the steps first."
+    )]
+    pub(super) async fn bootstrap_subreddit(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "TOML or JSON block with any of: settings (table of about/edit settings such as title, public_description, subreddit_type), sidebar (markdown), rules (list of short_name, description, violation_reason, kind link/comment/all), post_flairs and user_flairs (lists of text, css_class, background_color, text_color dark/light, mod_only, text_editable), welcome_post (title, text)"
+        )]
+        config: String,
+        #[tool(param)]
+        #[schemars(description = "Only list the steps without applying them (default false)")]
+        dry_run: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Bootstrapping {}", subreddit);
+
+        let deadline = Deadline::for_tool("bootstrap_subreddit");
+        let auth_token = self.auth_token().await?;
+        let config = parse_config(&config)?;
+        let dry_run = dry_run.unwrap_or(false);
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        let mut plan = Vec::new();
+        if !config.settings.is_empty() || config.sidebar.is_some() {
+            plan.push(Step::Settings);
+        }
+        plan.extend(config.rules.iter().map(Step::Rule));
+        plan.extend(config.post_flairs.iter().map(Step::PostFlair));
+        plan.extend(config.user_flairs.iter().map(Step::UserFlair));
+        if config.welcome_post.is_some() {
+            plan.extend([Step::WelcomePost, Step::StickyWelcomePost]);
+        }
+        if plan.is_empty() {
+            return Err(RedditError::invalid_input(
+                "The config block has nothing to apply",
+                CONFIG_HINT,
+            ));
+        }
+
+        let mut steps = Vec::new();
+        let mut welcome_post: Option<String> = None;
+        for step in &plan {
+            let name = step.name();
+            let (status, detail) = if dry_run {
+                (BootstrapStepStatus::Planned, None)
+            } else if deadline.is_expired() {
+                (
+                    BootstrapStepStatus::Skipped,
+                    Some(deadline.truncation_reason()),
+                )
+            } else if matches!(step, Step::StickyWelcomePost) && welcome_post.is_none() {
+                (
+                    BootstrapStepStatus::Skipped,
+                    Some("the welcome post was not submitted".to_string()),
+                )
+            } else {
+                tracing::info!("Bootstrapping {}: {}", subreddit, name);
+                let result = deadline
+                    .run(self.apply_bootstrap_step(
+                        step,
+                        &subreddit,
+                        &config,
+                        welcome_post.as_deref(),
+                        &auth_token,
+                    ))
+                    .await
+                    .and_then(|result| result);
+                match result {
+                    Ok(post) => {
+                        welcome_post = post.or(welcome_post);
+                        (BootstrapStepStatus::Done, None)
+                    }
+                    Err(e) => (BootstrapStepStatus::Failed, Some(e.to_string())),
+                }
+            };
+            steps.push(BootstrapStep {
+                step: name,
+                status,
+                detail,
+            });
+        }
+
+        let count = |status| steps.iter().filter(|step| step.status == status).count();
+        serde_json::to_string(&BootstrapResponse {
+            subreddit,
+            dry_run,
+            completed: count(BootstrapStepStatus::Done),
+            failed: count(BootstrapStepStatus::Failed),
+            steps,
+            welcome_post,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,249 @@
+use rmcp::tool;
+use uuid::Uuid;
+
+use super::{OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    Collection, CollectionPostRequest, CollectionRequest, CollectionResponse,
+    CreateCollectionRequest, SubredditCollectionsRequest,
+};
+use crate::reddit::validation::normalize_post_id;
+
+const COLLECTION_LAYOUTS: [&str; 2] = ["TIMELINE", "GALLERY"];
+
+fn normalize_collection_id(input: &str) -> Result<String, RedditError> {
+    // Collection URLs end in /collection/<uuid>.
+    let id = input.trim().trim_end_matches('/');
+    let id = id.rsplit('/').next().unwrap_or(id);
+    Uuid::parse_str(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|_| {
+            RedditError::invalid_input(
+                format!("{:?} is not a valid collection id", input),
+                "pass the collection UUID or its reddit.com/r/<subreddit>/collection/<uuid> URL",
+            )
+        })
+}
+
+/// Collection changes need the posts moderator permission.
+fn moderator_hint(error: RedditError) -> RedditError {
+    if error.kind == ErrorKind::Forbidden {
+        error.with_hint("only moderators with the posts permission can change collections")
+    } else {
+        error
+    }
+}
+
+impl RedditClient {
+    #[tool(
+        description = "Get a Reddit collection (a moderator-curated series of posts, e.g. event megathreads) with its posts."
+    )]
+    pub(super) async fn get_collection(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Collection UUID or reddit.com collection URL")]
+        collection_id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching collection {}", collection_id);
+
+        let collection_id = normalize_collection_id(&collection_id)?;
+        let auth_token = self.auth_token().await?;
+        let url = format!("{}/api/v1/collections/collection", OAUTH_URL);
+        let mut collection = Deadline::for_tool("get_collection")
+            .run(self.get_request::<Collection, CollectionRequest>(
+                &url,
+                &auth_token,
+                CollectionRequest {
+                    collection_id,
+                    include_links: true,
+                },
+            ))
+            .await??;
+
+        let posts = collection
+            .sorted_links
+            .take()
+            .map(|listing| {
+                listing
+                    .data
+                    .children
+                    .into_iter()
+                    .map(|child| child.data)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        serde_json::to_string(&CollectionResponse {
+            collection,
+            posts: self.apply_content_policy(posts),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "List the collections of a subreddit.")]
+    pub(super) async fn list_subreddit_collections(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Listing collections of {}", subreddit);
+
+        let deadline = Deadline::for_tool("list_subreddit_collections");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+            .await??;
+
+        let url = format!("{}/api/v1/collections/subreddit_collections", OAUTH_URL);
+        let collections = deadline
+            .run(
+                self.get_request::<Vec<Collection>, SubredditCollectionsRequest>(
+                    &url,
+                    &auth_token,
+                    SubredditCollectionsRequest {
+                        sr_fullname: about.name,
+                    },
+                ),
+            )
+            .await??;
+
+        serde_json::to_string(&collections).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Create a collection in a subreddit. Requires moderator permissions.")]
+    pub(super) async fn create_collection(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Collection title, at most 300 characters")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Collection description, at most 500 characters")]
+        description: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Display layout: TIMELINE or GALLERY (default TIMELINE)")]
+        display_layout: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Creating collection {:?} in {}", title, subreddit);
+
+        let display_layout = display_layout.map(|layout| layout.to_ascii_uppercase());
+        if let Some(layout) = &display_layout
+            && !COLLECTION_LAYOUTS.contains(&layout.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown display layout {}", layout),
+                "use TIMELINE or GALLERY",
+            ));
+        }
+        if title.trim().is_empty() || title.chars().count() > 300 {
+            return Err(RedditError::invalid_input(
+                "Collection titles must have 1-300 characters",
+                "shorten or fill in the title",
+            ));
+        }
+
+        let deadline = Deadline::for_tool("create_collection");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+            .await??;
+
+        let url = format!("{}/api/v1/collections/create_collection", OAUTH_URL);
+        let collection = deadline
+            .run(self.post_form::<Collection, CreateCollectionRequest>(
+                &url,
+                &auth_token,
+                CreateCollectionRequest {
+                    sr_fullname: about.name,
+                    title,
+                    description: description.unwrap_or_default(),
+                    display_layout,
+                },
+            ))
+            .await?
+            .map_err(moderator_hint)?;
+
+        serde_json::to_string(&collection).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Add a post to a collection. Requires moderator permissions.")]
+    pub(super) async fn add_post_to_collection(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Collection UUID or reddit.com collection URL")]
+        collection_id: String,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+    ) -> Result<String, RedditError> {
+        self.change_collection(true, &collection_id, &post_id).await
+    }
+
+    #[tool(description = "Remove a post from a collection. Requires moderator permissions.")]
+    pub(super) async fn remove_post_from_collection(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Collection UUID or reddit.com collection URL")]
+        collection_id: String,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+    ) -> Result<String, RedditError> {
+        self.change_collection(false, &collection_id, &post_id)
+            .await
+    }
+
+    async fn change_collection(
+        &self,
+        add: bool,
+        collection_id: &str,
+        post_id: &str,
+    ) -> Result<String, RedditError> {
+        let collection_id = normalize_collection_id(collection_id)?;
+        let link_fullname = format!("t3_{}", normalize_post_id(post_id)?);
+        let (tool, endpoint) = if add {
+            ("add_post_to_collection", "add_post_to_collection")
+        } else {
+            ("remove_post_from_collection", "remove_post_in_collection")
+        };
+        tracing::info!(
+            "Calling {} for {} in {}",
+            endpoint,
+            link_fullname,
+            collection_id
+        );
+
+        let url = format!("{}/api/v1/collections/{}", OAUTH_URL, endpoint);
+        let auth_token = self.auth_token().await?;
+        Deadline::for_tool(tool)
+            .run(self.post_form::<serde_json::Value, CollectionPostRequest>(
+                &url,
+                &auth_token,
+                CollectionPostRequest {
+                    collection_id: collection_id.clone(),
+                    link_fullname: link_fullname.clone(),
+                },
+            ))
+            .await?
+            .map_err(moderator_hint)?;
+
+        Ok(if add {
+            format!("Added {} to collection {}", link_fullname, collection_id)
+        } else {
+            format!(
+                "Removed {} from collection {}",
+                link_fullname, collection_id
+            )
+        })
+    }
+}
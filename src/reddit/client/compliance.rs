@@ -0,0 +1,620 @@
+use std::collections::HashMap;
+
+use regex::{Regex, RegexBuilder};
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    CheckStatus, ChecklistItem, Listing, ModAction, ModLogRequest, PostRequirements,
+    RawJsonRequest, RemovalPattern, RulesPrecheckResponse, SubredditRule, UserAbout,
+};
+
+const MAX_REMOVAL_REASONS: usize = 10;
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+/// Below these, many subreddits' AutoModerator silently filters posts.
+const YOUNG_ACCOUNT_DAYS: f64 = 7.0;
+const LOW_KARMA: i64 = 10;
+
+/// The draft post being checked.
+pub(super) struct Draft<'a> {
+    pub(super) title: &'a str,
+    pub(super) body: &'a str,
+    pub(super) url: Option<&'a str>,
+    pub(super) flair: Option<&'a str>,
+}
+
+impl Draft<'_> {
+    fn domain(&self) -> Option<String> {
+        let url = reqwest::Url::parse(self.url?).ok()?;
+        let host = url.host_str()?;
+        Some(
+            host.strip_prefix("www.")
+                .unwrap_or(host)
+                .to_ascii_lowercase(),
+        )
+    }
+
+    fn has_link(&self) -> bool {
+        self.url.is_some() || self.body.contains("http://") || self.body.contains("https://")
+    }
+}
+
+fn item(source: &'static str, check: &str, status: CheckStatus, detail: String) -> ChecklistItem {
+    ChecklistItem {
+        source,
+        check: check.to_string(),
+        status,
+        detail,
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn check_length(
+    checklist: &mut Vec<ChecklistItem>,
+    field: &str,
+    text: &str,
+    min: Option<usize>,
+    max: Option<usize>,
+) {
+    let length = text.chars().count();
+    if let Some(min) = min.filter(|&min| length < min) {
+        checklist.push(item(
+            "post_requirements",
+            &format!("{} length", field),
+            CheckStatus::Conflict,
+            format!(
+                "{} has {} characters, at least {} required",
+                field, length, min
+            ),
+        ));
+    } else if let Some(max) = max.filter(|&max| length > max) {
+        checklist.push(item(
+            "post_requirements",
+            &format!("{} length", field),
+            CheckStatus::Conflict,
+            format!(
+                "{} has {} characters, at most {} allowed",
+                field, length, max
+            ),
+        ));
+    } else if min.is_some() || max.is_some() {
+        checklist.push(item(
+            "post_requirements",
+            &format!("{} length", field),
+            CheckStatus::Pass,
+            format!("{} has {} characters", field, length),
+        ));
+    }
+}
+
+fn check_strings(
+    checklist: &mut Vec<ChecklistItem>,
+    field: &str,
+    text: &str,
+    blacklisted: &[String],
+    required: &[String],
+) {
+    let banned: Vec<&String> = blacklisted
+        .iter()
+        .filter(|banned| contains_ignore_case(text, banned))
+        .collect();
+    if !banned.is_empty() {
+        checklist.push(item(
+            "post_requirements",
+            &format!("{} banned words", field),
+            CheckStatus::Conflict,
+            format!("{} contains {:?}", field, banned),
+        ));
+    }
+    if !required.is_empty() && !required.iter().any(|word| contains_ignore_case(text, word)) {
+        checklist.push(item(
+            "post_requirements",
+            &format!("{} required words", field),
+            CheckStatus::Conflict,
+            format!("{} must contain one of {:?}", field, required),
+        ));
+    }
+}
+
+fn check_requirements(requirements: &PostRequirements, draft: &Draft) -> Vec<ChecklistItem> {
+    let mut checklist = Vec::new();
+
+    check_length(
+        &mut checklist,
+        "title",
+        draft.title,
+        requirements.title_text_min_length,
+        requirements.title_text_max_length,
+    );
+    check_strings(
+        &mut checklist,
+        "title",
+        draft.title,
+        &requirements.title_blacklisted_strings,
+        &requirements.title_required_strings,
+    );
+    if !requirements.title_regexes.is_empty() {
+        let matches = requirements.title_regexes.iter().any(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .is_ok_and(|regex| regex.is_match(draft.title))
+        });
+        checklist.push(item(
+            "post_requirements",
+            "title format",
+            if matches {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Conflict
+            },
+            format!("title must match one of {:?}", requirements.title_regexes),
+        ));
+    }
+
+    match requirements.body_restriction_policy.as_deref() {
+        Some("required") if draft.body.trim().is_empty() => checklist.push(item(
+            "post_requirements",
+            "body",
+            CheckStatus::Conflict,
+            "posts must have a body".to_string(),
+        )),
+        Some("notAllowed") if !draft.body.trim().is_empty() => checklist.push(item(
+            "post_requirements",
+            "body",
+            CheckStatus::Conflict,
+            "posts may not have a body".to_string(),
+        )),
+        _ => {}
+    }
+    if !draft.body.is_empty() {
+        check_length(
+            &mut checklist,
+            "body",
+            draft.body,
+            requirements.body_text_min_length,
+            requirements.body_text_max_length,
+        );
+        check_strings(
+            &mut checklist,
+            "body",
+            draft.body,
+            &requirements.body_blacklisted_strings,
+            &requirements.body_required_strings,
+        );
+    }
+
+    match requirements.link_restriction_policy.as_deref() {
+        Some("required") if draft.url.is_none() => checklist.push(item(
+            "post_requirements",
+            "link",
+            CheckStatus::Conflict,
+            "posts must be link posts".to_string(),
+        )),
+        Some("notAllowed") if draft.url.is_some() => checklist.push(item(
+            "post_requirements",
+            "link",
+            CheckStatus::Conflict,
+            "link posts are not allowed".to_string(),
+        )),
+        _ => {}
+    }
+    if let Some(domain) = draft.domain() {
+        let listed = |domains: &[String]| {
+            domains
+                .iter()
+                .any(|listed| domain == *listed || domain.ends_with(&format!(".{}", listed)))
+        };
+        if listed(&requirements.domain_blacklist) {
+            checklist.push(item(
+                "post_requirements",
+                "link domain",
+                CheckStatus::Conflict,
+                format!("{} is on the domain blacklist", domain),
+            ));
+        } else if !requirements.domain_whitelist.is_empty()
+            && !listed(&requirements.domain_whitelist)
+        {
+            checklist.push(item(
+                "post_requirements",
+                "link domain",
+                CheckStatus::Conflict,
+                format!(
+                    "{} is not one of the allowed domains {:?}",
+                    domain, requirements.domain_whitelist
+                ),
+            ));
+        }
+    }
+
+    if requirements.is_flair_required {
+        checklist.push(item(
+            "post_requirements",
+            "flair",
+            if draft.flair.is_some() {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Conflict
+            },
+            "posts must have a flair".to_string(),
+        ));
+    }
+
+    checklist
+}
+
+/// Karma and account age thresholds stated in a rule or guideline text,
+/// e.g. "at least 50 comment karma" or "accounts must be 30 days old".
+fn stated_thresholds(text: &str) -> (Vec<(Option<&'static str>, i64)>, Vec<f64>) {
+    let karma_before =
+        Regex::new(r"(?i)(\d[\d,]*)\+?\s*(?:(comment|post|link|combined|total)\s+)?karma")
+            .expect("valid karma pattern");
+    let karma_after = Regex::new(
+        r"(?i)(?:(comment|post|link|combined|total)\s+)?karma\s+(?:of\s+)?(?:at\s+least\s+|over\s+|above\s+|>=?\s*)(\d[\d,]*)",
+    )
+    .expect("valid karma pattern");
+    let age =
+        Regex::new(r"(?i)(\d+)\s*(day|week|month|year)s?").expect("valid account age pattern");
+
+    let kind = |word: Option<regex::Match>| match word.map(|w| w.as_str().to_lowercase()) {
+        Some(word) if word == "comment" => Some("comment"),
+        Some(word) if word == "post" || word == "link" => Some("post"),
+        _ => None,
+    };
+    let number = |digits: &str| digits.replace(',', "").parse::<i64>().ok();
+
+    let mut karma = Vec::new();
+    for captures in karma_before.captures_iter(text) {
+        if let Some(minimum) = number(&captures[1]) {
+            karma.push((kind(captures.get(2)), minimum));
+        }
+    }
+    for captures in karma_after.captures_iter(text) {
+        if let Some(minimum) = number(&captures[2]) {
+            karma.push((kind(captures.get(1)), minimum));
+        }
+    }
+
+    let lower = text.to_lowercase();
+    let mut days = Vec::new();
+    if lower.contains("account") || lower.contains(" old") {
+        for captures in age.captures_iter(text) {
+            let Some(count) = number(&captures[1]) else {
+                continue;
+            };
+            let unit = match captures[2].to_lowercase().as_str() {
+                "week" => 7.0,
+                "month" => 30.0,
+                "year" => 365.0,
+                _ => 1.0,
+            };
+            days.push(count as f64 * unit);
+        }
+    }
+    (karma, days)
+}
+
+/// Compares the account's age and karma with common AutoModerator filters and
+/// with thresholds the subreddit's rules or guidelines state.
+pub(super) fn account_gating_checks(
+    me: &UserAbout,
+    rules: &[SubredditRule],
+    guidelines: Option<&str>,
+    now: f64,
+) -> Vec<ChecklistItem> {
+    let mut checklist = Vec::new();
+    let total_karma = me.link_karma + me.comment_karma;
+    let age_days = me
+        .created_utc
+        .map(|created| ((now - created) / SECONDS_PER_DAY).max(0.0));
+
+    let sources = rules
+        .iter()
+        .map(|rule| {
+            (
+                rule.short_name.as_str(),
+                format!("{} {}", rule.short_name, rule.description),
+            )
+        })
+        .chain(guidelines.map(|text| ("posting guidelines", text.to_string())));
+    let mut stated = false;
+    for (name, text) in sources {
+        let (karma, days) = stated_thresholds(&text);
+        for (kind, minimum) in karma {
+            stated = true;
+            let (label, actual) = match kind {
+                Some("comment") => ("comment karma", me.comment_karma),
+                Some(_) => ("post karma", me.link_karma),
+                None => ("karma", total_karma),
+            };
+            let met = actual >= minimum;
+            checklist.push(item(
+                "account",
+                &format!("{} ({})", label, name),
+                if met {
+                    CheckStatus::Pass
+                } else {
+                    CheckStatus::Warning
+                },
+                format!(
+                    "\"{}\" appears to require {} {}; the account has {}",
+                    name, minimum, label, actual
+                ),
+            ));
+        }
+        if let Some(age_days) = age_days {
+            for minimum in days {
+                stated = true;
+                checklist.push(item(
+                    "account",
+                    &format!("account age ({})", name),
+                    if age_days >= minimum {
+                        CheckStatus::Pass
+                    } else {
+                        CheckStatus::Warning
+                    },
+                    format!(
+                        "\"{}\" appears to require an account {:.0} days old; the account is {:.0} days old",
+                        name, minimum, age_days
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Thresholds that are not published are common too; flag accounts below the usual ones.
+    if !stated {
+        if let Some(age_days) = age_days.filter(|days| *days < YOUNG_ACCOUNT_DAYS) {
+            checklist.push(item(
+                "account",
+                "account age",
+                CheckStatus::Warning,
+                format!(
+                    "the account is {:.0} days old; many subreddits silently filter posts from accounts younger than {} days",
+                    age_days, YOUNG_ACCOUNT_DAYS
+                ),
+            ));
+        }
+        if total_karma < LOW_KARMA {
+            checklist.push(item(
+                "account",
+                "karma",
+                CheckStatus::Warning,
+                format!(
+                    "the account has {} karma; many subreddits silently filter posts from accounts with less than {}",
+                    total_karma, LOW_KARMA
+                ),
+            ));
+        }
+    }
+    if me.has_verified_email == Some(false) {
+        checklist.push(item(
+            "account",
+            "verified email",
+            CheckStatus::Review,
+            "the account has no verified email, which some subreddits filter".to_string(),
+        ));
+    }
+    checklist
+}
+
+/// Turns a subreddit rule into a checklist entry. The rule text is left for
+/// the agent to judge, but cheap signals from the draft raise it to a warning.
+fn check_rule(rule: &SubredditRule, draft: &Draft) -> ChecklistItem {
+    let text = format!("{} {}", rule.short_name, rule.description).to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| text.contains(word));
+
+    let mut signals = Vec::new();
+    if mentions(&["promot", "advertis", "spam", "referral"]) && draft.has_link() {
+        signals.push("the draft contains a link");
+    }
+    if mentions(&["flair"]) && draft.flair.is_none() {
+        signals.push("the draft has no flair");
+    }
+    if mentions(&["title"])
+        && draft
+            .title
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .all(|c| c.is_uppercase())
+    {
+        signals.push("the title is all caps");
+    }
+    if mentions(&["english"]) && !draft.title.is_ascii() {
+        signals.push("the title contains non-ASCII text");
+    }
+    if mentions(&["low effort", "low-effort"])
+        && draft.body.trim().is_empty()
+        && draft.url.is_none()
+    {
+        signals.push("the draft has no body or link");
+    }
+
+    let (status, detail) = if signals.is_empty() {
+        (CheckStatus::Review, rule.description.clone())
+    } else {
+        (
+            CheckStatus::Warning,
+            format!("{} ({})", rule.description, signals.join("; ")),
+        )
+    };
+    item("rule", &rule.short_name, status, detail)
+}
+
+impl RedditClient {
+    /// The authenticated account, from `/api/v1/me`.
+    pub(super) async fn fetch_me(&self, auth_token: &str) -> Result<UserAbout, RedditError> {
+        self.get_request::<UserAbout, RawJsonRequest>(
+            &format!("{}/api/v1/me", OAUTH_URL),
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await
+    }
+
+    pub(super) async fn fetch_post_requirements(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+    ) -> Result<PostRequirements, RedditError> {
+        let url = format!("{}/api/v1/{}/post_requirements", OAUTH_URL, subreddit);
+        self.get_request::<PostRequirements, RawJsonRequest>(
+            &url,
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await
+    }
+
+    /// Checks a draft against the subreddit's post type, posting requirements
+    /// and rules and the account's age and karma. Also returns the posting
+    /// requirements, for their guidelines.
+    pub(super) async fn draft_checklist(
+        &self,
+        subreddit: &str,
+        draft: &Draft<'_>,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<(Vec<ChecklistItem>, PostRequirements), RedditError> {
+        let about = deadline
+            .run(self.fetch_subreddit_about(subreddit, auth_token))
+            .await??;
+        let rules = deadline
+            .run(self.fetch_subreddit_rules(subreddit, auth_token))
+            .await?
+            .unwrap_or_default();
+        let requirements = deadline
+            .run(self.fetch_post_requirements(subreddit, auth_token))
+            .await?
+            .unwrap_or_default();
+        let me = deadline.run(self.fetch_me(auth_token)).await?.ok();
+
+        let mut checklist = Vec::new();
+        match about.submission_type.as_deref() {
+            Some("self") if draft.url.is_some() => checklist.push(item(
+                "submission_type",
+                "post type",
+                CheckStatus::Conflict,
+                "the subreddit only allows text posts".to_string(),
+            )),
+            Some("link") if draft.url.is_none() => checklist.push(item(
+                "submission_type",
+                "post type",
+                CheckStatus::Conflict,
+                "the subreddit only allows link posts".to_string(),
+            )),
+            _ => {}
+        }
+        checklist.extend(check_requirements(&requirements, draft));
+        if let Some(me) = &me {
+            checklist.extend(account_gating_checks(
+                me,
+                &rules,
+                requirements.guidelines_text.as_deref(),
+                chrono::Utc::now().timestamp() as f64,
+            ));
+        }
+        checklist.extend(
+            rules
+                .iter()
+                .filter(|rule| rule.kind != "comment")
+                .map(|rule| check_rule(rule, draft)),
+        );
+        Ok((checklist, requirements))
+    }
+
+    #[tool(
+        description = "Check a draft post against a subreddit's rules, posting requirements and, when the account can read the mod log, its recent removal reasons. Also compares the account's age and karma with the thresholds the rules state and with common AutoModerator filters. Returns a checklist of definite conflicts, warnings and rules to review."
+    )]
+    pub(super) async fn check_post_against_rules(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Draft title")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Draft body text, for text posts")]
+        body: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Link URL, for link posts")]
+        url: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Flair the post would be submitted with")]
+        flair: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Checking draft post against the rules of {}", subreddit);
+
+        let deadline = Deadline::for_tool("check_post_against_rules");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let body = body.unwrap_or_default();
+        let draft = Draft {
+            title: &title,
+            body: &body,
+            url: url.as_deref(),
+            flair: flair.as_deref(),
+        };
+
+        let (checklist, requirements) = self
+            .draft_checklist(&subreddit, &draft, &auth_token, &deadline)
+            .await?;
+        // Only moderators can read the mod log; everyone else gets a 403.
+        let mod_log_url = format!("{}/r/{}/about/log", OAUTH_URL, subreddit);
+        let removals = deadline
+            .run(self.get_request::<Listing<ModAction>, ModLogRequest>(
+                &mod_log_url,
+                &auth_token,
+                ModLogRequest {
+                    action: "removelink".to_string(),
+                    limit: MAX_PAGE_SIZE,
+                    raw_json: 1,
+                },
+            ))
+            .await?
+            .ok();
+
+        let mut reasons: HashMap<String, usize> = HashMap::new();
+        for removal in removals.iter().flat_map(|listing| &listing.data.children) {
+            let reason = removal
+                .data
+                .details
+                .clone()
+                .filter(|details| !details.is_empty())
+                .or_else(|| removal.data.description.clone())
+                .unwrap_or_else(|| "unspecified".to_string());
+            *reasons.entry(reason).or_insert(0) += 1;
+        }
+        let mut recent_removal_reasons: Vec<RemovalPattern> = reasons
+            .into_iter()
+            .map(|(reason, count)| RemovalPattern { reason, count })
+            .collect();
+        recent_removal_reasons.sort_by(|a, b| b.count.cmp(&a.count).then(a.reason.cmp(&b.reason)));
+        recent_removal_reasons.truncate(MAX_REMOVAL_REASONS);
+
+        let count = |status| {
+            checklist
+                .iter()
+                .filter(|item| item.status == status)
+                .count()
+        };
+        serde_json::to_string(&RulesPrecheckResponse {
+            subreddit,
+            conflicts: count(CheckStatus::Conflict),
+            warnings: count(CheckStatus::Warning),
+            guidelines: requirements.guidelines_text.clone(),
+            mod_log_available: removals.is_some(),
+            recent_removal_reasons,
+            checklist,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
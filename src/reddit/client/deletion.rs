@@ -0,0 +1,382 @@
+use std::time::Duration;
+
+use rmcp::tool;
+use uuid::Uuid;
+
+use super::jobs::{resumable, resume_note};
+use super::{BASE_URL, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    Comment, DeletionFilters, DeletionItem, DeletionPlan, DeletionPreviewResponse,
+    DeletionProgress, DeletionRunResponse, DeletionStatus, EditUserTextRequest, Job, JobSpec, Post,
+    ThingRequest,
+};
+use crate::reddit::text::snippet;
+use crate::reddit::validation::normalize_subreddit;
+use crate::storage::Namespace;
+
+const HISTORY_LIMIT: u32 = 1000;
+const DELETION_KINDS: [&str; 2] = ["posts", "comments"];
+const DELETION_EXCERPT_CHARS: usize = 120;
+const DEFAULT_OVERWRITE_TEXT: &str = "[deleted]";
+/// Pause between items so a long run stays well inside Reddit's rate limit.
+const DELETE_INTERVAL: Duration = Duration::from_secs(1);
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+fn progress(plan: &DeletionPlan) -> DeletionProgress {
+    let count = |status| {
+        plan.items
+            .iter()
+            .filter(|item| item.status == status)
+            .count()
+    };
+    let pending = count(DeletionStatus::Pending);
+    DeletionProgress {
+        plan_id: plan.id.clone(),
+        total: plan.items.len(),
+        pending,
+        deleted: count(DeletionStatus::Deleted),
+        failed: count(DeletionStatus::Failed),
+        complete: pending == 0,
+    }
+}
+
+fn matches_filters(
+    filters: &DeletionFilters,
+    subreddit: &str,
+    score: i64,
+    created_utc: f64,
+    now: f64,
+) -> bool {
+    let in_list = |list: &[String]| list.iter().any(|name| name.eq_ignore_ascii_case(subreddit));
+    filters
+        .older_than_days
+        .is_none_or(|days| now - created_utc > days as f64 * SECONDS_PER_DAY)
+        && filters.max_score.is_none_or(|max| score <= max)
+        && (filters.subreddits.is_empty() || in_list(&filters.subreddits))
+        && !in_list(&filters.exclude_subreddits)
+}
+
+impl RedditClient {
+    fn load_deletion_plan(&self, plan_id: &str) -> Result<DeletionPlan, RedditError> {
+        self.storage
+            .read_json::<DeletionPlan>(Namespace::Deletions, plan_id)
+            .map_err(RedditError::storage)?
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("No deletion plan {}", plan_id),
+                    "call preview_history_deletion to create a plan",
+                )
+            })
+    }
+
+    fn save_deletion_plan(&self, plan: &DeletionPlan) -> Result<(), RedditError> {
+        self.storage
+            .write_json(Namespace::Deletions, &plan.id, plan)
+            .map_err(RedditError::storage)
+    }
+
+    #[tool(
+        description = "Preview a bulk deletion of the authenticated account's own posts and comments. Filters the history by age, score and subreddit and saves the matches as a plan; nothing is deleted until run_history_deletion is called with the plan_id."
+    )]
+    pub(super) async fn preview_history_deletion(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "What to delete: posts, comments or both (default both)")]
+        kinds: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(description = "Only match items older than this many days")]
+        older_than_days: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Only match items with a score at or below this")]
+        max_score: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Only match items in these subreddits")]
+        subreddits: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(description = "Never match items in these subreddits")]
+        exclude_subreddits: Option<Vec<String>>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Previewing history deletion for {}", self.account().name);
+
+        let kinds = kinds.unwrap_or_else(|| DELETION_KINDS.map(String::from).to_vec());
+        if let Some(unknown) = kinds
+            .iter()
+            .find(|kind| !DELETION_KINDS.contains(&kind.as_str()))
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown kind {}", unknown),
+                "use posts, comments or both",
+            ));
+        }
+        let normalize = |names: Option<Vec<String>>| {
+            names
+                .unwrap_or_default()
+                .iter()
+                .map(|name| normalize_subreddit(name))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let filters = DeletionFilters {
+            kinds,
+            older_than_days,
+            max_score,
+            subreddits: normalize(subreddits)?,
+            exclude_subreddits: normalize(exclude_subreddits)?,
+        };
+
+        let deadline = Deadline::for_tool("preview_history_deletion");
+        let auth_token = self.auth_token().await?;
+        let now = chrono::Utc::now().timestamp() as f64;
+        let mut items = Vec::new();
+        let mut scanned = 0;
+        let mut truncated_reason = None;
+
+        if filters.kinds.iter().any(|kind| kind == "posts") {
+            let url = format!("{}/user/{}/submitted", OAUTH_URL, self.account_username()?);
+            let (posts, reason) = self
+                .collect_listing::<Post>(&url, &auth_token, HISTORY_LIMIT, &deadline)
+                .await?;
+            truncated_reason = truncated_reason.or(reason);
+            scanned += posts.len();
+            items.extend(
+                posts
+                    .into_iter()
+                    .filter(|post| {
+                        matches_filters(
+                            &filters,
+                            &post.subreddit,
+                            post.score,
+                            post.created_utc,
+                            now,
+                        )
+                    })
+                    .map(|post| DeletionItem {
+                        fullname: post.name,
+                        subreddit: post.subreddit,
+                        score: post.score,
+                        created_utc: post.created_utc,
+                        excerpt: snippet(&post.title, DELETION_EXCERPT_CHARS),
+                        editable: post.is_self,
+                        status: DeletionStatus::Pending,
+                        error: None,
+                    }),
+            );
+        }
+        if filters.kinds.iter().any(|kind| kind == "comments") {
+            let url = format!("{}/user/{}/comments", OAUTH_URL, self.account_username()?);
+            let (comments, reason) = self
+                .collect_listing::<Comment>(&url, &auth_token, HISTORY_LIMIT, &deadline)
+                .await?;
+            truncated_reason = truncated_reason.or(reason);
+            scanned += comments.len();
+            items.extend(
+                comments
+                    .into_iter()
+                    .filter(|comment| {
+                        matches_filters(
+                            &filters,
+                            &comment.subreddit,
+                            comment.score,
+                            comment.created_utc,
+                            now,
+                        )
+                    })
+                    .map(|comment| DeletionItem {
+                        fullname: comment.name,
+                        subreddit: comment.subreddit,
+                        score: comment.score,
+                        created_utc: comment.created_utc,
+                        excerpt: snippet(&comment.body, DELETION_EXCERPT_CHARS),
+                        editable: true,
+                        status: DeletionStatus::Pending,
+                        error: None,
+                    }),
+            );
+        }
+
+        let plan = DeletionPlan {
+            id: Uuid::new_v4().to_string(),
+            username: self.account_username()?,
+            created_utc: now as i64,
+            filters,
+            items,
+        };
+        self.save_deletion_plan(&plan)?;
+
+        serde_json::to_string(&DeletionPreviewResponse {
+            plan_id: plan.id,
+            filters: plan.filters,
+            scanned,
+            matched: plan.items.len(),
+            items: plan.items,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Delete the items of a plan made by preview_history_deletion, optionally overwriting text first. Runs paced to respect rate limits and records progress after every item; call resume_job with the returned job_id, or again with the same plan_id, to continue."
+    )]
+    pub(super) async fn run_history_deletion(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "plan_id returned by preview_history_deletion")]
+        plan_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Overwrite comment and text post bodies before deleting them, so the original text does not linger in caches. Defaults to false."
+        )]
+        overwrite: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Text to overwrite with (default [deleted])")]
+        overwrite_text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Must be true; deleting cannot be undone")]
+        confirm: bool,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Running history deletion plan {}", plan_id);
+
+        let plan = self.load_deletion_plan(&plan_id)?;
+        if !confirm {
+            return Err(RedditError::invalid_input(
+                "Deletion was not confirmed",
+                "review the plan from preview_history_deletion and call again with confirm=true",
+            ));
+        }
+        let job = self.start_job(JobSpec::HistoryDeletion {
+            plan_id: plan.id,
+            overwrite: overwrite.unwrap_or(false),
+            overwrite_text: overwrite_text.unwrap_or_else(|| DEFAULT_OVERWRITE_TEXT.to_string()),
+        })?;
+        self.run_job(job).await
+    }
+
+    /// Runs or resumes a history deletion job. Progress is recorded in the
+    /// plan after every item.
+    pub(super) async fn run_deletion_job(&self, mut job: Job) -> Result<String, RedditError> {
+        let JobSpec::HistoryDeletion {
+            plan_id,
+            overwrite,
+            overwrite_text,
+        } = job.spec.clone()
+        else {
+            return Err(RedditError::invalid_input(
+                format!("Job {} is not a history deletion", job.id),
+                "call resume_job with it instead",
+            ));
+        };
+        let mut plan = self.load_deletion_plan(&plan_id)?;
+        if plan.username != self.account_username()? {
+            return Err(RedditError::new(
+                ErrorKind::Forbidden,
+                format!("Plan {} belongs to u/{}", plan.id, plan.username),
+                "create a new plan with preview_history_deletion for this account",
+            ));
+        }
+
+        let deadline = Deadline::for_tool("run_history_deletion");
+        let auth_token = self.auth_token().await?;
+        let mut processed_this_run = 0;
+        let mut stopped_reason = None;
+
+        for index in 0..plan.items.len() {
+            if plan.items[index].status != DeletionStatus::Pending {
+                continue;
+            }
+            if let Some(reason) = self.job_interrupted(&mut job)? {
+                stopped_reason = Some(resume_note(reason, &job));
+                break;
+            }
+            if deadline.is_expired() {
+                stopped_reason = Some(resume_note(deadline.truncation_reason(), &job));
+                break;
+            }
+
+            let item = &plan.items[index];
+            let mut result = Ok(());
+            if overwrite && item.editable {
+                let url = format!("{}/editusertext", BASE_URL);
+                result = self
+                    .post_api_with_priority::<serde_json::Value, EditUserTextRequest>(
+                        &url,
+                        &auth_token,
+                        EditUserTextRequest {
+                            thing_id: item.fullname.clone(),
+                            text: overwrite_text.clone(),
+                        },
+                        Priority::Bulk,
+                    )
+                    .await
+                    .map(|_| ());
+            }
+            if result.is_ok() {
+                let url = format!("{}/del", BASE_URL);
+                result = self
+                    .post_form_with_priority::<serde_json::Value, ThingRequest>(
+                        &url,
+                        &auth_token,
+                        ThingRequest {
+                            id: item.fullname.clone(),
+                        },
+                        Priority::Bulk,
+                    )
+                    .await
+                    .map(|_| ());
+            }
+
+            // Rate limiting and expired tokens affect every remaining item, so
+            // leave them pending for the next run instead of failing them all.
+            if let Err(e) = &result
+                && matches!(e.kind, ErrorKind::RateLimited | ErrorKind::Unauthorized)
+            {
+                stopped_reason = Some(resumable(e.clone(), &job).to_string());
+                break;
+            }
+
+            let item = &mut plan.items[index];
+            match result {
+                Ok(()) => item.status = DeletionStatus::Deleted,
+                Err(e) => {
+                    item.status = DeletionStatus::Failed;
+                    item.error = Some(e.to_string());
+                }
+            }
+            processed_this_run += 1;
+            job.processed_ids.push(item.fullname.clone());
+            self.save_deletion_plan(&plan)?;
+            self.save_job(&job)?;
+            tokio::time::sleep(DELETE_INTERVAL).await;
+        }
+
+        let progress = progress(&plan);
+        job.complete = progress.complete;
+        self.save_job(&job)?;
+        serde_json::to_string(&DeletionRunResponse {
+            progress,
+            job_id: job.id,
+            processed_this_run,
+            overwrite,
+            stopped_reason,
+            failures: plan
+                .items
+                .into_iter()
+                .filter(|item| item.status == DeletionStatus::Failed)
+                .collect(),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Show how far a bulk deletion plan has progressed.")]
+    pub(super) async fn get_deletion_progress(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "plan_id returned by preview_history_deletion")]
+        plan_id: String,
+    ) -> Result<String, RedditError> {
+        let plan = self.load_deletion_plan(&plan_id)?;
+        serde_json::to_string(&progress(&plan)).map_err(RedditError::serialize)
+    }
+}
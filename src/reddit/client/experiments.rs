@@ -0,0 +1,525 @@
+use std::env;
+use std::time::Duration;
+
+use rmcp::model::LoggingLevel;
+use rmcp::tool;
+
+use super::RedditClient;
+use crate::reddit::auth::AuthMode;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    Engagement, Experiment, ExperimentResultsResponse, ExperimentVariant, SubmissionVerification,
+    SubmitRequest, SubmitVariantResponse, VariantResult, VariantState,
+};
+use crate::reddit::validation::parse_timestamp;
+use crate::storage::Namespace;
+
+const DEFAULT_EXPERIMENT_CHECK_SECS: u64 = 300;
+/// Id of the experiment task in list_jobs.
+const EXPERIMENT_TASK: &str = "title_experiments";
+const DEFAULT_OBSERVE_HOURS: u32 = 24;
+const MAX_OBSERVE_HOURS: u32 = 7 * 24;
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
+/// What submitting a variant reported besides its new state.
+#[derive(Default)]
+struct SubmitVariantOutcome {
+    verification: Option<SubmissionVerification>,
+    warnings: Vec<String>,
+}
+
+fn validate_experiment_name(name: &str) -> Result<String, RedditError> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid experiment name", name),
+            "use 1-64 letters, digits, underscores or dashes, e.g. launch-titles",
+        ));
+    }
+    Ok(name)
+}
+
+fn no_experiment(name: &str) -> RedditError {
+    RedditError::not_found(
+        format!("No experiment named {}", name),
+        "submit a variant with submit_title_variant to start one",
+    )
+}
+
+fn submit_request(variant: &ExperimentVariant) -> SubmitRequest {
+    SubmitRequest {
+        sr: variant.subreddit.clone(),
+        kind: if variant.url.is_some() {
+            "link"
+        } else {
+            "self"
+        },
+        title: variant.title.clone(),
+        text: variant.text.clone(),
+        url: variant.url.clone(),
+        flair_id: variant.flair_id.clone(),
+        flair_text: variant.flair_text.clone(),
+        nsfw: false,
+        spoiler: false,
+        crosspost_fullname: None,
+    }
+}
+
+/// The completed variant with the highest score, comments breaking ties.
+fn leader(variants: &[ExperimentVariant]) -> Option<usize> {
+    variants
+        .iter()
+        .enumerate()
+        .filter_map(|(index, variant)| {
+            variant
+                .engagement
+                .as_ref()
+                .filter(|_| variant.state == VariantState::Complete)
+                .map(|engagement| (index, engagement))
+        })
+        .max_by_key(|(_, engagement)| (engagement.score, engagement.num_comments))
+        .map(|(index, _)| index)
+}
+
+impl RedditClient {
+    fn load_experiment(&self, name: &str) -> Result<Option<Experiment>, RedditError> {
+        self.storage
+            .read_json::<Experiment>(Namespace::Experiments, name)
+            .map_err(RedditError::storage)
+    }
+
+    fn save_experiment(&self, experiment: &Experiment) -> Result<(), RedditError> {
+        self.storage
+            .write_json(Namespace::Experiments, &experiment.name, experiment)
+            .map_err(RedditError::storage)
+    }
+
+    async fn fetch_engagement(
+        &self,
+        fullnames: &[String],
+        auth_token: &str,
+    ) -> Result<Vec<(String, Engagement)>, RedditError> {
+        let measured_utc = chrono::Utc::now().timestamp();
+        Ok(self
+            .fetch_info(fullnames, auth_token)
+            .await?
+            .into_values()
+            .map(|item| {
+                let engagement = Engagement {
+                    score: item.score.unwrap_or_default(),
+                    upvote_ratio: item.upvote_ratio,
+                    num_comments: item.num_comments.unwrap_or_default(),
+                    measured_utc,
+                };
+                (item.name, engagement)
+            })
+            .collect())
+    }
+
+    /// Submits a variant, moving it to observing or, when the submission is
+    /// refused, to failed.
+    async fn submit_variant(
+        &self,
+        variant: &mut ExperimentVariant,
+        observe_hours: u32,
+        ignore_cooldown: bool,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> SubmitVariantOutcome {
+        let mut outcome = SubmitVariantOutcome::default();
+        let submitted =
+            match self.check_cooldown(&variant.subreddit, ignore_cooldown, &mut outcome.warnings) {
+                Ok(_) => {
+                    self.submit(submit_request(variant), auth_token, deadline)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+        match submitted {
+            Ok((post, verification)) => {
+                let now = chrono::Utc::now().timestamp();
+                variant.state = VariantState::Observing;
+                variant.post = Some(post);
+                variant.submitted_utc = Some(now);
+                variant.observe_until_utc = Some(now + i64::from(observe_hours) * SECONDS_PER_HOUR);
+                outcome.verification = Some(verification);
+            }
+            Err(e) => {
+                variant.state = VariantState::Failed;
+                variant.error = Some(e.to_string());
+            }
+        }
+        outcome
+    }
+
+    /// Submits the due scheduled variants and measures the variants whose
+    /// observation window ended, every `EXPERIMENT_CHECK_SECS` seconds
+    /// (default 300, 0 disables it).
+    pub fn spawn_experiments(&self) {
+        let interval = env::var("EXPERIMENT_CHECK_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(DEFAULT_EXPERIMENT_CHECK_SECS);
+        if interval == 0 || self.auth_mode == AuthMode::AppOnly {
+            tracing::info!("Title experiments are disabled");
+            return;
+        }
+
+        let cancelled = self.tasks.register(
+            EXPERIMENT_TASK,
+            EXPERIMENT_TASK,
+            format!(
+                "Submits scheduled title variants and measures finished ones, checking every {} seconds",
+                interval
+            ),
+        );
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if client.tasks.is_paused(EXPERIMENT_TASK) {
+                    continue;
+                }
+                let result = client.run_due_experiments().await;
+                if let Err(e) = &result {
+                    tracing::error!("Title experiment check failed: {}", e);
+                }
+                client
+                    .tasks
+                    .record_run(EXPERIMENT_TASK, result.err().map(|e| e.to_string()));
+            }
+            tracing::info!("Title experiments were cancelled");
+        });
+    }
+
+    /// Checks every experiment as the account it was started as. A failing
+    /// experiment does not hold up the others; the last failure is returned.
+    async fn run_due_experiments(&self) -> Result<(), RedditError> {
+        let now = chrono::Utc::now().timestamp();
+        let mut failure = None;
+        for key in self
+            .storage
+            .keys(Namespace::Experiments)
+            .map_err(RedditError::storage)?
+        {
+            let Some(mut experiment) = self.load_experiment(&key)? else {
+                continue;
+            };
+            let due = |variant: &ExperimentVariant| match variant.state {
+                VariantState::Scheduled => variant.submit_at_utc <= now,
+                VariantState::Observing => variant.observe_until_utc.is_some_and(|end| end <= now),
+                _ => false,
+            };
+            if !experiment.variants.iter().any(due) {
+                continue;
+            }
+
+            let account = experiment.account.clone();
+            let finished = match self
+                .as_record_account(&account, self.run_experiment(&mut experiment, now))
+                .await
+            {
+                Ok(finished) => finished,
+                Err(e) => {
+                    tracing::error!("Title experiment {} failed: {}", experiment.name, e);
+                    failure = Some(e);
+                    continue;
+                }
+            };
+
+            for title in finished {
+                self.notify_clients(
+                    LoggingLevel::Info,
+                    "experiments",
+                    serde_json::json!({
+                        "experiment": experiment.name,
+                        "finished_variant": title,
+                        "leader": leader(&experiment.variants)
+                            .map(|index| &experiment.variants[index].title),
+                    }),
+                )
+                .await;
+            }
+        }
+        failure.map_or(Ok(()), Err)
+    }
+
+    /// Submits the due variants of `experiment` and measures the ones whose
+    /// observation ended, as the current account. Returns the titles of the
+    /// variants that finished.
+    async fn run_experiment(
+        &self,
+        experiment: &mut Experiment,
+        now: i64,
+    ) -> Result<Vec<String>, RedditError> {
+        let auth_token = self.auth_token().await?;
+        let deadline = Deadline::for_tool(EXPERIMENT_TASK);
+        let observe_hours = experiment.observe_hours;
+        let mut finished = Vec::new();
+        for variant in experiment
+            .variants
+            .iter_mut()
+            .filter(|variant| variant.state == VariantState::Scheduled)
+            .filter(|variant| variant.submit_at_utc <= now)
+        {
+            tracing::info!("Submitting title variant {:?}", variant.title);
+            self.submit_variant(variant, observe_hours, false, &auth_token, &deadline)
+                .await;
+        }
+        // Saved before measuring so a failed measurement cannot lead to the
+        // variants being submitted again.
+        self.save_experiment(experiment)?;
+        let ended: Vec<String> = experiment
+            .variants
+            .iter()
+            .filter(|variant| variant.state == VariantState::Observing)
+            .filter(|variant| variant.observe_until_utc.is_some_and(|end| end <= now))
+            .filter_map(|variant| variant.post.as_ref().map(|post| post.name.clone()))
+            .collect();
+        if !ended.is_empty() {
+            for (name, engagement) in self.fetch_engagement(&ended, &auth_token).await? {
+                if let Some(variant) = experiment
+                    .variants
+                    .iter_mut()
+                    .find(|variant| variant.post.as_ref().is_some_and(|post| post.name == name))
+                {
+                    variant.state = VariantState::Complete;
+                    variant.engagement = Some(engagement);
+                    finished.push(variant.title.clone());
+                }
+            }
+        }
+        self.save_experiment(experiment)?;
+        Ok(finished)
+    }
+
+    #[tool(
+        description = "Submit one title variant of an A/B title experiment, now or at a scheduled time, so that variants can be trialled in comparable time slots. Each submitted variant is observed for the experiment's window (default 24 hours) and its score, upvote ratio and comment count are then recorded by a background task. Compare the variants with get_experiment_results."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn submit_title_variant(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Experiment name, 1-64 letters, digits, underscores or dashes. The first variant creates the experiment."
+        )]
+        experiment: String,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, e.g. rust or r/rust")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "The title variant")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Markdown body of a text post")]
+        text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "URL of a link post. Leave empty for a text post.")]
+        url: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Flair template id")]
+        flair_id: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Flair text, for editable flair templates")]
+        flair_text: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "When to submit: unix timestamp, RFC 3339 time or YYYY-MM-DD. Omit to submit now."
+        )]
+        submit_at: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Hours to observe each variant before recording its engagement (default 24, at most 168). Fixed by the experiment's first variant."
+        )]
+        observe_hours: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "Submit now even when a blocking posting cooldown is in effect. Defaults to false."
+        )]
+        ignore_cooldown: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let name = validate_experiment_name(&experiment)?;
+        tracing::info!("Adding a title variant to experiment {}", name);
+
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(RedditError::invalid_input(
+                "The title is empty",
+                "pass the title variant to try",
+            ));
+        }
+        let url = url.filter(|url| !url.trim().is_empty());
+        if url.is_some() && text.as_ref().is_some_and(|text| !text.is_empty()) {
+            return Err(RedditError::invalid_input(
+                "Both text and url were given",
+                "pass text for a text post or url for a link post, not both",
+            ));
+        }
+        if observe_hours.is_some_and(|hours| hours == 0 || hours > MAX_OBSERVE_HOURS) {
+            return Err(RedditError::invalid_input(
+                "observe_hours is out of range",
+                format!("pass between 1 and {} hours", MAX_OBSERVE_HOURS),
+            ));
+        }
+        let now = chrono::Utc::now().timestamp();
+        let submit_at = submit_at
+            .map(|time| parse_timestamp(&time, "submit_at"))
+            .transpose()?
+            .unwrap_or(now);
+
+        let account = self.account().name.clone();
+        let mut experiment = match self.load_experiment(&name)? {
+            Some(experiment) => {
+                if experiment.account != account {
+                    return Err(RedditError::new(
+                        ErrorKind::Forbidden,
+                        format!(
+                            "Experiment {} submits as the account {}",
+                            name, experiment.account
+                        ),
+                        format!(
+                            "call again with account={}, or start a new experiment",
+                            experiment.account
+                        ),
+                    ));
+                }
+                if observe_hours.is_some_and(|hours| hours != experiment.observe_hours) {
+                    return Err(RedditError::invalid_input(
+                        format!(
+                            "Experiment {} observes variants for {} hours",
+                            name, experiment.observe_hours
+                        ),
+                        "omit observe_hours so that the variants stay comparable",
+                    ));
+                }
+                experiment
+            }
+            None => Experiment {
+                name: name.clone(),
+                account,
+                observe_hours: observe_hours.unwrap_or(DEFAULT_OBSERVE_HOURS),
+                created_utc: now,
+                variants: Vec::new(),
+            },
+        };
+
+        let deadline = Deadline::for_tool("submit_title_variant");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let mut warnings = Vec::new();
+        let url = match url {
+            Some(url) => Some(self.clean_link(url, &mut warnings).await),
+            None => None,
+        };
+        let mut variant = ExperimentVariant {
+            title,
+            subreddit,
+            text: if url.is_none() { text } else { None },
+            url,
+            flair_id,
+            flair_text,
+            submit_at_utc: submit_at,
+            state: VariantState::Scheduled,
+            post: None,
+            submitted_utc: None,
+            observe_until_utc: None,
+            engagement: None,
+            error: None,
+        };
+
+        let mut outcome = SubmitVariantOutcome::default();
+        if submit_at <= now {
+            outcome = self
+                .submit_variant(
+                    &mut variant,
+                    experiment.observe_hours,
+                    ignore_cooldown.unwrap_or(false),
+                    &auth_token,
+                    &deadline,
+                )
+                .await;
+            if let Some(error) = &variant.error {
+                return Err(RedditError::invalid_input(
+                    format!("The variant could not be submitted: {}", error),
+                    "fix the problem and submit the variant again",
+                ));
+            }
+        }
+        experiment.variants.push(variant.clone());
+        self.save_experiment(&experiment)?;
+
+        serde_json::to_string(&SubmitVariantResponse {
+            experiment: name,
+            variant_index: experiment.variants.len() - 1,
+            variant,
+            verification: outcome.verification,
+            warnings: warnings.into_iter().chain(outcome.warnings).collect(),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Compare the title variants of an A/B title experiment: each variant's state (scheduled, observing, complete or failed), its engagement at the end of the observation window and, for variants still observed, their engagement so far. The leader is the completed variant with the highest score."
+    )]
+    pub(super) async fn get_experiment_results(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Experiment name")]
+        experiment: String,
+    ) -> Result<String, RedditError> {
+        let name = validate_experiment_name(&experiment)?;
+        tracing::info!("Reading the results of experiment {}", name);
+
+        let experiment = self
+            .load_experiment(&name)?
+            .ok_or_else(|| no_experiment(&name))?;
+        let observing: Vec<String> = experiment
+            .variants
+            .iter()
+            .filter(|variant| variant.state == VariantState::Observing)
+            .filter_map(|variant| variant.post.as_ref().map(|post| post.name.clone()))
+            .collect();
+        let mut current = Vec::new();
+        if !observing.is_empty() {
+            let auth_token = self.auth_token().await?;
+            current = Deadline::for_tool("get_experiment_results")
+                .run(self.fetch_engagement(&observing, &auth_token))
+                .await??;
+        }
+
+        let leader = leader(&experiment.variants);
+        let variants = experiment
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| VariantResult {
+                variant_index: index,
+                current: variant.post.as_ref().and_then(|post| {
+                    current
+                        .iter()
+                        .find(|(name, _)| *name == post.name)
+                        .map(|(_, engagement)| engagement.clone())
+                }),
+                variant: variant.clone(),
+            })
+            .collect();
+        serde_json::to_string(&ExperimentResultsResponse {
+            experiment: experiment.name,
+            observe_hours: experiment.observe_hours,
+            leader: leader.map(|index| experiment.variants[index].title.clone()),
+            variants,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
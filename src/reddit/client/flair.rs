@@ -0,0 +1,195 @@
+use rmcp::tool;
+
+use super::{BASE_URL, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    ComposeRequest, FlairEnforcementItem, FlairEnforcementResponse, FlairEnforcementStatus, Post,
+    PostRequirements, RawJsonRequest, RemoveRequest,
+};
+
+const DEFAULT_GRACE_MINUTES: i64 = 30;
+const DEFAULT_FLAIR_SCAN: u32 = 100;
+const MAX_FLAIR_SCAN: u32 = 500;
+const DEFAULT_FLAIR_MESSAGE: &str = "Hi u/{author}, your post \"{title}\" in r/{subreddit} needs a flair. Please add one: {permalink}";
+const FLAIR_MESSAGE_SUBJECT: &str = "Your post needs a flair";
+
+fn render_message(template: &str, post: &Post) -> String {
+    template
+        .replace("{author}", &post.author)
+        .replace("{title}", &post.title)
+        .replace("{subreddit}", &post.subreddit)
+        .replace(
+            "{permalink}",
+            &format!("https://www.reddit.com{}", post.permalink),
+        )
+}
+
+impl RedditClient {
+    #[tool(
+        description = "For moderators: find recent posts without flair in a subreddit and, for posts older than a grace period, message their authors and/or remove them. Runs as a dry run unless dry_run=false, and reports the result for every post."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn enforce_post_flair(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Leave posts younger than this many minutes alone (default 30)")]
+        grace_minutes: Option<i64>,
+        #[tool(param)]
+        #[schemars(description = "Number of newest posts to scan (default 100, max 500)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "Send the author a private message")]
+        message_authors: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Remove the post")]
+        remove_posts: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Message text. {author}, {title}, {subreddit} and {permalink} are replaced with the post's values."
+        )]
+        message: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only report what would be done (default true)")]
+        dry_run: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Enforcing post flair in {}", subreddit);
+
+        let message_authors = message_authors.unwrap_or(false);
+        let remove_posts = remove_posts.unwrap_or(false);
+        let dry_run = dry_run.unwrap_or(true);
+        let grace_minutes = grace_minutes.unwrap_or(DEFAULT_GRACE_MINUTES).max(0);
+        let limit = limit.unwrap_or(DEFAULT_FLAIR_SCAN).clamp(1, MAX_FLAIR_SCAN);
+        let message = message.unwrap_or_else(|| DEFAULT_FLAIR_MESSAGE.to_string());
+
+        let deadline = Deadline::for_tool("enforce_post_flair");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        if (message_authors || remove_posts) && !dry_run {
+            let about = deadline
+                .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+                .await??;
+            if about.user_is_moderator != Some(true) {
+                return Err(RedditError::invalid_input(
+                    format!("The account does not moderate r/{}", subreddit),
+                    "run with dry_run=true to only list posts without flair",
+                ));
+            }
+        }
+
+        let requirements_url = format!("{}/api/v1/{}/post_requirements", OAUTH_URL, subreddit);
+        let flair_required = deadline
+            .run(self.get_request::<PostRequirements, RawJsonRequest>(
+                &requirements_url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await?
+            .map(|requirements| requirements.is_flair_required)
+            .unwrap_or(false);
+
+        let url = format!("{}/r/{}/new", OAUTH_URL, subreddit);
+        let (posts, mut truncated_reason) = self
+            .collect_listing::<Post>(&url, &auth_token, limit, &deadline)
+            .await?;
+        let posts_scanned = posts.len();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut items = Vec::new();
+        for post in posts {
+            let has_flair = post
+                .link_flair_text
+                .as_deref()
+                .is_some_and(|flair| !flair.trim().is_empty());
+            if has_flair {
+                continue;
+            }
+
+            let age_minutes = (now - post.created_utc as i64) / 60;
+            let mut item = FlairEnforcementItem {
+                fullname: post.name.clone(),
+                title: post.title.clone(),
+                author: post.author.clone(),
+                permalink: post.permalink.clone(),
+                age_minutes,
+                status: FlairEnforcementStatus::InGracePeriod,
+                messaged: false,
+                removed: false,
+                errors: Vec::new(),
+            };
+            if age_minutes < grace_minutes || !(message_authors || remove_posts) {
+                items.push(item);
+                continue;
+            }
+            if dry_run {
+                item.status = FlairEnforcementStatus::WouldAction;
+                items.push(item);
+                continue;
+            }
+            if deadline.is_expired() {
+                truncated_reason.get_or_insert_with(|| deadline.truncation_reason());
+                break;
+            }
+
+            if message_authors && post.author != "[deleted]" {
+                let sent = self
+                    .post_api_with_priority::<serde_json::Value, ComposeRequest>(
+                        &format!("{}/compose", BASE_URL),
+                        &auth_token,
+                        ComposeRequest {
+                            to: post.author.clone(),
+                            subject: FLAIR_MESSAGE_SUBJECT.to_string(),
+                            text: render_message(&message, &post),
+                        },
+                        Priority::Bulk,
+                    )
+                    .await;
+                match sent {
+                    Ok(_) => item.messaged = true,
+                    Err(e) => item.errors.push(format!("message failed: {}", e)),
+                }
+            }
+            if remove_posts {
+                let removed = self
+                    .post_form_with_priority::<serde_json::Value, RemoveRequest>(
+                        &format!("{}/remove", BASE_URL),
+                        &auth_token,
+                        RemoveRequest {
+                            id: post.name.clone(),
+                            spam: false,
+                        },
+                        Priority::Bulk,
+                    )
+                    .await;
+                match removed {
+                    Ok(_) => item.removed = true,
+                    Err(e) => item.errors.push(format!("removal failed: {}", e)),
+                }
+            }
+            item.status = if item.errors.is_empty() {
+                FlairEnforcementStatus::Actioned
+            } else {
+                FlairEnforcementStatus::Failed
+            };
+            items.push(item);
+        }
+
+        serde_json::to_string(&FlairEnforcementResponse {
+            subreddit,
+            flair_required,
+            dry_run,
+            posts_scanned,
+            missing_flair: items.len(),
+            items,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
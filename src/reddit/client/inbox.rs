@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::time::Duration;
+
+use rmcp::model::LoggingLevel;
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::config::{InboxConfig, InboxPriority};
+use crate::reddit::accounts::with_account;
+use crate::reddit::auth::AuthMode;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    InboxGroup, InboxItem, InboxRequest, InboxResponse, Listing, PrioritizedInboxItem,
+};
+
+const DEFAULT_INBOX_LIMIT: u32 = 25;
+const DEFAULT_INBOX_POLL_SECS: u64 = 300;
+/// Id of the inbox watch in list_jobs.
+const INBOX_TASK: &str = "inbox_watch";
+
+/// Groups items by priority, high first, keeping Reddit's newest-first order
+/// within a group.
+fn prioritize(config: &InboxConfig, items: Vec<InboxItem>) -> Vec<InboxGroup> {
+    let mut groups: Vec<InboxGroup> = Vec::new();
+    for item in items {
+        let (priority, matched_rule) = config.classify(
+            &item.author,
+            &item.subject,
+            item.subreddit.as_deref(),
+            &item.body,
+        );
+        let item = PrioritizedInboxItem { item, matched_rule };
+        match groups.iter_mut().find(|group| group.priority == priority) {
+            Some(group) => group.items.push(item),
+            None => groups.push(InboxGroup {
+                priority,
+                items: vec![item],
+            }),
+        }
+    }
+    groups.sort_by_key(|group| group.priority);
+    groups
+}
+
+impl RedditClient {
+    async fn fetch_inbox(
+        &self,
+        auth_token: &str,
+        unread_only: bool,
+        limit: u32,
+        after: Option<String>,
+        priority: Priority,
+    ) -> Result<Listing<InboxItem>, RedditError> {
+        let folder = if unread_only { "unread" } else { "inbox" };
+        self.get_request_with_priority::<Listing<InboxItem>, InboxRequest>(
+            &format!("{}/message/{}", OAUTH_URL, folder),
+            auth_token,
+            InboxRequest {
+                mark: false,
+                limit,
+                after,
+                raw_json: 1,
+            },
+            priority,
+        )
+        .await
+    }
+
+    /// Checks the unread inbox of every account in the background every
+    /// `INBOX_POLL_SECS` seconds (default 300, 0 disables it) and notifies
+    /// the connected clients of new high-priority items. Nothing is fetched while no
+    /// `[inbox]` rule can give an item high priority.
+    pub fn spawn_inbox_watch(&self) {
+        let interval = env::var("INBOX_POLL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(DEFAULT_INBOX_POLL_SECS);
+        if interval == 0 || self.auth_mode == AuthMode::AppOnly {
+            tracing::info!("Inbox notifications are disabled");
+            return;
+        }
+
+        let cancelled = self.tasks.register(
+            INBOX_TASK,
+            INBOX_TASK,
+            format!(
+                "Checks the unread inboxes for high-priority items every {} seconds",
+                interval
+            ),
+        );
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+            // High-priority unread items already notified about, by account.
+            let mut notified: HashMap<String, HashSet<String>> = HashMap::new();
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if client.tasks.is_paused(INBOX_TASK) {
+                    continue;
+                }
+                let mut errors = Vec::new();
+                for account in client.accounts.all() {
+                    let name = account.name.clone();
+                    let seen = notified.entry(name.clone()).or_default();
+                    if let Err(e) =
+                        with_account(name.clone(), client.check_inbox(&name, seen)).await
+                    {
+                        tracing::error!("Inbox check of {} failed: {}", name, e);
+                        errors.push(format!("{}: {}", name, e));
+                    }
+                }
+                client
+                    .tasks
+                    .record_run(INBOX_TASK, (!errors.is_empty()).then(|| errors.join("; ")));
+            }
+            tracing::info!("The inbox watch was cancelled");
+        });
+    }
+
+    async fn check_inbox(
+        &self,
+        account: &str,
+        notified: &mut HashSet<String>,
+    ) -> Result<(), RedditError> {
+        let config = self.config.get().inbox;
+        if config.default_priority != InboxPriority::High
+            && config
+                .rules
+                .iter()
+                .all(|rule| rule.priority != InboxPriority::High)
+        {
+            return Ok(());
+        }
+
+        let auth_token = self.auth_token().await?;
+        let unread = self
+            .fetch_inbox(&auth_token, true, MAX_PAGE_SIZE, None, Priority::Background)
+            .await?;
+        let mut high = HashSet::new();
+        for group in prioritize(
+            &config,
+            unread.data.children.into_iter().map(|c| c.data).collect(),
+        ) {
+            if group.priority != InboxPriority::High {
+                continue;
+            }
+            for entry in group.items {
+                high.insert(entry.item.name.clone());
+                if notified.contains(&entry.item.name) {
+                    continue;
+                }
+                self.notify_clients(
+                    LoggingLevel::Notice,
+                    "inbox",
+                    serde_json::json!({
+                        "account": account,
+                        "priority": InboxPriority::High,
+                        "matched_rule": entry.matched_rule,
+                        "item": entry.item,
+                    }),
+                )
+                .await;
+            }
+        }
+        // Items read in the meantime drop out, so the set stays small.
+        *notified = high;
+        Ok(())
+    }
+
+    #[tool(
+        description = "Read the account's inbox (private messages, comment replies and mentions) grouped by priority, high first. Priorities come from the [inbox] rules of the server configuration, matching sender, subject pattern, subreddit or keywords. Does not mark anything as read."
+    )]
+    pub(super) async fn get_inbox(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Only unread items (default true)")]
+        unread_only: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Number of items (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous call, to get older items")]
+        after: Option<String>,
+    ) -> Result<String, RedditError> {
+        let unread_only = unread_only.unwrap_or(true);
+        tracing::info!("Reading the inbox (unread only: {})", unread_only);
+
+        let auth_token = self.auth_token().await?;
+        let listing = Deadline::for_tool("get_inbox")
+            .run(self.fetch_inbox(
+                &auth_token,
+                unread_only,
+                limit.unwrap_or(DEFAULT_INBOX_LIMIT).clamp(1, MAX_PAGE_SIZE),
+                after,
+                Priority::Interactive,
+            ))
+            .await??;
+
+        let items: Vec<InboxItem> = listing.data.children.into_iter().map(|c| c.data).collect();
+        serde_json::to_string(&InboxResponse {
+            unread_only,
+            total: items.len(),
+            groups: prioritize(&self.config.get().inbox, items),
+            after: listing.data.after,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,312 @@
+use rmcp::tool;
+use uuid::Uuid;
+
+use super::RedditClient;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{Job, JobSpec};
+use crate::reddit::tasks::{TaskState, TaskStatus};
+use crate::storage::Namespace;
+
+/// Adds to the hint of an error that stopped `job` how to continue it.
+/// Errors about the request itself would stop a resumed job the same way.
+pub(super) fn resumable(error: RedditError, job: &Job) -> RedditError {
+    match error.kind {
+        ErrorKind::RateLimited
+        | ErrorKind::Timeout
+        | ErrorKind::Network
+        | ErrorKind::Server
+        | ErrorKind::Unauthorized => {
+            let hint = format!(
+                "{}; progress was saved, so resume_job with job_id {} continues from the last checkpoint",
+                error.hint, job.id
+            );
+            error.with_hint(hint)
+        }
+        _ => error,
+    }
+}
+
+/// Adds to the reason a job stopped early how to continue it.
+pub(super) fn resume_note(reason: String, job: &Job) -> String {
+    if job.complete || job.cancelled {
+        reason
+    } else {
+        format!(
+            "{}; resume_job with job_id {} continues from here",
+            reason, job.id
+        )
+    }
+}
+
+/// Kind and description of a bulk job, as listed by list_jobs.
+fn describe(spec: &JobSpec) -> (&'static str, String) {
+    match spec {
+        JobSpec::BanListExport { subreddit, .. } => (
+            "ban_list_export",
+            format!("export_ban_list of r/{}", subreddit),
+        ),
+        JobSpec::PostSearch {
+            query, subreddit, ..
+        } => (
+            "post_search",
+            match subreddit {
+                Some(subreddit) => {
+                    format!("search_posts_between for {:?} in r/{}", query, subreddit)
+                }
+                None => format!("search_posts_between for {:?}", query),
+            },
+        ),
+        JobSpec::HistoryDeletion { plan_id, .. } => (
+            "history_deletion",
+            format!("run_history_deletion of plan {}", plan_id),
+        ),
+    }
+}
+
+/// Status of a bulk job that no call is running. An unfinished job is paused
+/// until resume_job continues it.
+fn stored_status(job: &Job) -> TaskStatus {
+    let (kind, description) = describe(&job.spec);
+    TaskStatus {
+        id: job.id.clone(),
+        kind,
+        description,
+        state: if job.cancelled {
+            TaskState::Cancelled
+        } else if job.complete {
+            TaskState::Finished
+        } else {
+            TaskState::Paused
+        },
+        account: Some(job.account.clone()),
+        started_utc: job.created_utc,
+        last_run_utc: Some(job.updated_utc),
+        last_error: None,
+        items: Some(job.items.len().max(job.processed_ids.len())),
+    }
+}
+
+impl RedditClient {
+    /// Creates and saves a job for `spec`, run as the current account.
+    pub(super) fn start_job(&self, spec: JobSpec) -> Result<Job, RedditError> {
+        let now = chrono::Utc::now().timestamp();
+        let job = Job {
+            id: Uuid::new_v4().to_string(),
+            account: self.account().name.clone(),
+            created_utc: now,
+            updated_utc: now,
+            spec,
+            cursor: None,
+            processed_ids: Vec::new(),
+            items: Vec::new(),
+            pages: 0,
+            scanned: 0,
+            complete: false,
+            cancelled: false,
+        };
+        self.save_job(&job)?;
+        Ok(job)
+    }
+
+    pub(super) fn save_job(&self, job: &Job) -> Result<(), RedditError> {
+        let job = Job {
+            updated_utc: chrono::Utc::now().timestamp(),
+            ..job.clone()
+        };
+        self.storage
+            .write_json(Namespace::Jobs, &job.id, &job)
+            .map_err(RedditError::storage)
+    }
+
+    fn load_job(&self, job_id: &str) -> Result<Job, RedditError> {
+        self.storage
+            .read_json::<Job>(Namespace::Jobs, job_id)
+            .map_err(RedditError::storage)?
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("No job {}", job_id),
+                    "call list_jobs to see the background tasks and the jobs of export_ban_list, search_posts_between and run_history_deletion",
+                )
+            })
+    }
+
+    /// Loads a bulk job of the current account.
+    fn load_own_job(&self, job_id: &str) -> Result<Job, RedditError> {
+        let job = self.load_job(job_id)?;
+        if job.account != self.account().name {
+            return Err(RedditError::new(
+                ErrorKind::Forbidden,
+                format!("Job {} runs as the account {}", job.id, job.account),
+                format!("call again with account={}", job.account),
+            ));
+        }
+        Ok(job)
+    }
+
+    /// Runs a bulk job, registered for list_jobs, pause_job and cancel_job
+    /// while it runs.
+    pub(super) async fn run_job(&self, job: Job) -> Result<String, RedditError> {
+        let (kind, description) = describe(&job.spec);
+        let _tracked = self.tasks.track(&job.id, kind, description, &job.account);
+        match job.spec {
+            JobSpec::BanListExport { .. } => self.run_ban_list_export(job).await,
+            JobSpec::PostSearch { .. } => self.run_post_search(job).await,
+            JobSpec::HistoryDeletion { .. } => self.run_deletion_job(job).await,
+        }
+    }
+
+    /// Why a running job has to stop at this checkpoint, after pause_job or
+    /// cancel_job. A cancelled job is saved as such.
+    pub(super) fn job_interrupted(&self, job: &mut Job) -> Result<Option<String>, RedditError> {
+        match self.tasks.stop_requested(&job.id) {
+            Some(TaskState::Cancelled) => {
+                job.cancelled = true;
+                self.save_job(job)?;
+                Ok(Some("cancelled by cancel_job".to_string()))
+            }
+            Some(_) => Ok(Some("paused by pause_job".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Status of a background task, or of a bulk job of the current account.
+    fn job_status(&self, job_id: &str) -> Result<TaskStatus, RedditError> {
+        match self.tasks.get(job_id) {
+            Some(status) => Ok(status),
+            None => self.load_own_job(job_id).map(|job| stored_status(&job)),
+        }
+    }
+
+    #[tool(
+        description = "List what the server is doing besides answering tool calls: background tasks (monitor polling, scheduled configuration backups, title experiments, the startup credential check, authorization listeners) and the bulk jobs of export_ban_list, search_posts_between and run_history_deletion, with their state (running, paused, cancelled or finished)."
+    )]
+    pub(super) async fn list_jobs(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Also list finished and cancelled bulk jobs (default false)")]
+        include_finished: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let include_finished = include_finished.unwrap_or(false);
+        let mut jobs = self.tasks.list();
+        let account = self.account().name.clone();
+        let mut stored = Vec::new();
+        for key in self
+            .storage
+            .keys(Namespace::Jobs)
+            .map_err(RedditError::storage)?
+        {
+            if jobs.iter().any(|job| job.id == key) {
+                continue;
+            }
+            let Some(job) = self
+                .storage
+                .read_json::<Job>(Namespace::Jobs, &key)
+                .map_err(RedditError::storage)?
+            else {
+                continue;
+            };
+            if job.account == account && (include_finished || !(job.complete || job.cancelled)) {
+                stored.push(stored_status(&job));
+            }
+        }
+        stored.sort_by_key(|job| std::cmp::Reverse(job.last_run_utc));
+        jobs.extend(stored);
+        serde_json::to_string(&jobs).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Show the state and progress of a background task or bulk job.")]
+    pub(super) async fn get_job_status(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Job id from list_jobs or from the response of a bulk tool")]
+        job_id: String,
+    ) -> Result<String, RedditError> {
+        serde_json::to_string(&self.job_status(&job_id)?).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Pause a background task or a running bulk job. A paused background task skips its runs; a bulk job stops at its next checkpoint and returns what it has so far. resume_job continues either."
+    )]
+    pub(super) async fn pause_job(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Job id from list_jobs")]
+        job_id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Pausing job {}", job_id);
+
+        let status = match self.tasks.get(&job_id) {
+            Some(status) => {
+                if status.account.is_some() {
+                    // A running bulk job; the account check happens here.
+                    self.load_own_job(&job_id)?;
+                }
+                self.tasks.set_paused(&job_id, true).unwrap_or(status)
+            }
+            // Bulk jobs that no call is running are already stopped.
+            None => stored_status(&self.load_own_job(&job_id)?),
+        };
+        serde_json::to_string(&status).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Cancel a background task until the server restarts, or a bulk job for good: a running job stops at its next checkpoint and resume_job refuses it afterwards. Work already done, such as deleted items, is not undone."
+    )]
+    pub(super) async fn cancel_job(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Job id from list_jobs")]
+        job_id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Cancelling job {}", job_id);
+
+        let status = match self.tasks.get(&job_id) {
+            Some(status) => {
+                if status.account.is_some() {
+                    self.load_own_job(&job_id)?;
+                }
+                self.tasks.cancel(&job_id).unwrap_or(status)
+            }
+            None => {
+                let mut job = self.load_own_job(&job_id)?;
+                if !job.complete && !job.cancelled {
+                    job.cancelled = true;
+                    self.save_job(&job)?;
+                }
+                stored_status(&job)
+            }
+        };
+        serde_json::to_string(&status).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Continue a bulk job of export_ban_list, search_posts_between or run_history_deletion from its last checkpoint, after it stopped at the time limit, on a rate limit, by pause_job or because the server restarted, instead of paging from the start again. Returns the same result as the original tool; a finished job returns its final result. Also resumes a background task paused by pause_job."
+    )]
+    pub(super) async fn resume_job(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "job_id from the response of the stopped call")]
+        job_id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Resuming job {}", job_id);
+
+        if let Some(status) = self.tasks.get(&job_id) {
+            if status.account.is_some() {
+                return Err(RedditError::invalid_input(
+                    format!("Job {} is already running", job_id),
+                    "wait for the running call to return, or pause it with pause_job first",
+                ));
+            }
+            let status = self.tasks.set_paused(&job_id, false).unwrap_or(status);
+            return serde_json::to_string(&status).map_err(RedditError::serialize);
+        }
+        let job = self.load_own_job(&job_id)?;
+        if job.cancelled {
+            return Err(RedditError::invalid_input(
+                format!("Job {} was cancelled", job.id),
+                "start a new one with the original tool",
+            ));
+        }
+        self.run_job(job).await
+    }
+}
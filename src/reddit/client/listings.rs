@@ -0,0 +1,378 @@
+use rmcp::tool;
+
+use super::{LISTING_SORTS, MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    HomeFeedResponse, Listing, Post, PostSummary, SiteFeedResponse, SortedListingRequest,
+    SubredditPostsResponse,
+};
+use crate::reddit::session::Fullname;
+
+const DEFAULT_LISTING_LIMIT: u32 = 25;
+const LISTING_TIMES: [&str; 6] = ["hour", "day", "week", "month", "year", "all"];
+/// Sorts that take a time filter.
+const TIMED_SORTS: [&str; 2] = ["top", "controversial"];
+const HOME_FEED_SORTS: [&str; 2] = ["best", "hot"];
+const SITE_FEEDS: [&str; 2] = ["popular", "all"];
+
+/// Accepts `GLOBAL`, a country code such as `US` or a US state such as `US_WA`.
+fn validate_geo(geo: &str) -> Result<String, RedditError> {
+    let geo = geo.trim().to_ascii_uppercase();
+    let is_region = |code: &str| code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase());
+    let valid = geo == "GLOBAL"
+        || match geo.split_once('_') {
+            Some((country, state)) => is_region(country) && is_region(state),
+            None => is_region(&geo),
+        };
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid region", geo),
+            "pass GLOBAL, a country code such as US or GB, or a US state such as US_WA",
+        ));
+    }
+    Ok(geo)
+}
+
+/// One page of a post listing, without the posts the content policy hides.
+pub(super) struct PostPage {
+    pub(super) posts: Vec<PostSummary>,
+    pub(super) after: Option<String>,
+    pub(super) before: Option<String>,
+}
+
+pub(super) fn post_summary(post: Post) -> PostSummary {
+    PostSummary {
+        id: post.id,
+        name: post.name,
+        title: post.title,
+        author: post.author,
+        subreddit: post.subreddit,
+        score: post.score,
+        num_comments: post.num_comments,
+        permalink: format!("https://www.reddit.com{}", post.permalink),
+        created_utc: post.created_utc as i64,
+    }
+}
+
+/// Checks `sort` against `sorts` and `time`, returning the time filter to send.
+pub(super) fn validate_sort(
+    sort: &str,
+    sorts: &[&str],
+    time: Option<String>,
+) -> Result<Option<String>, RedditError> {
+    if !sorts.contains(&sort) {
+        return Err(RedditError::invalid_input(
+            format!("Unsupported sort {}", sort),
+            format!("use one of {}", sorts.join(", ")),
+        ));
+    }
+    let Some(time) = time else {
+        return Ok(None);
+    };
+    if !TIMED_SORTS.contains(&sort) {
+        return Err(RedditError::invalid_input(
+            format!("time does not apply to the {} sort", sort),
+            "only top and controversial take a time filter; omit time",
+        ));
+    }
+    if !LISTING_TIMES.contains(&time.as_str()) {
+        return Err(RedditError::invalid_input(
+            format!("Unknown time filter {}", time),
+            format!("use one of {}", LISTING_TIMES.join(", ")),
+        ));
+    }
+    Ok(Some(time))
+}
+
+pub(super) fn check_cursors(
+    after: &Option<String>,
+    before: &Option<String>,
+) -> Result<(), RedditError> {
+    if after.is_some() && before.is_some() {
+        return Err(RedditError::invalid_input(
+            "after and before were both given",
+            "pass only after for the next page or only before for the previous one",
+        ));
+    }
+    Ok(())
+}
+
+impl RedditClient {
+    /// Drops the posts returned earlier in the session when `exclude_seen` is
+    /// set and records the rest as seen. Returns how many were dropped.
+    pub(super) fn filter_seen<T: Fullname>(
+        &self,
+        posts: &mut Vec<T>,
+        exclude_seen: Option<bool>,
+    ) -> usize {
+        let mut excluded = 0;
+        if exclude_seen.unwrap_or(false) {
+            let (unseen, count) = self.seen_items.exclude_seen(std::mem::take(posts));
+            *posts = unseen;
+            excluded = count;
+        }
+        self.seen_items.mark_seen(posts);
+        excluded
+    }
+
+    pub(super) async fn fetch_post_page(
+        &self,
+        url: &str,
+        auth_token: &str,
+        request: SortedListingRequest,
+    ) -> Result<PostPage, RedditError> {
+        let listing = self
+            .get_request::<Listing<Post>, SortedListingRequest>(url, auth_token, request)
+            .await?;
+        let posts = listing
+            .data
+            .children
+            .into_iter()
+            .map(|child| child.data)
+            .collect();
+        Ok(PostPage {
+            posts: self
+                .apply_content_policy(posts)
+                .into_iter()
+                .map(post_summary)
+                .collect(),
+            after: listing.data.after,
+            before: listing.data.before,
+        })
+    }
+
+    #[tool(
+        description = "Get one page of a subreddit's posts sorted by hot, new, top, rising or controversial, as summaries (title, author, score, comment count, permalink, creation time). Pass the returned after or before cursor to get the next or previous page."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn get_subreddit_posts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "hot, new, top, rising or controversial (default hot)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Time filter of top and controversial: hour, day, week, month, year or all (Reddit's default is day)"
+        )]
+        time: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of posts (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "before cursor of a page, to get the page before it")]
+        before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let sort = sort.unwrap_or_else(|| "hot".to_string());
+        tracing::info!("Calling /r/{}/{}", subreddit, sort);
+
+        let time = validate_sort(&sort, &LISTING_SORTS, time)?;
+        check_cursors(&after, &before)?;
+
+        let deadline = Deadline::for_tool("get_subreddit_posts");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let url = format!("{}/r/{}/{}", OAUTH_URL, subreddit, sort);
+        let mut page = deadline
+            .run(
+                self.fetch_post_page(
+                    &url,
+                    &auth_token,
+                    SortedListingRequest {
+                        g: None,
+                        t: time.clone(),
+                        limit: limit
+                            .unwrap_or(DEFAULT_LISTING_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+        let excluded_seen = self.filter_seen(&mut page.posts, exclude_seen);
+
+        serde_json::to_string(&SubredditPostsResponse {
+            subreddit,
+            sort,
+            time,
+            posts: page.posts,
+            excluded_seen,
+            after: page.after,
+            before: page.before,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Browse the site-wide feeds r/popular and r/all, sorted by hot, new, top, rising or controversial, as post summaries. r/popular can be narrowed to a region. Useful for spotting trends without knowing subreddit names. Pass the returned after or before cursor to page."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn get_site_feed(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "popular or all (default popular)")]
+        feed: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "hot, new, top, rising or controversial (default hot)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Time filter of top and controversial: hour, day, week, month, year or all (Reddit's default is day)"
+        )]
+        time: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Region of r/popular: GLOBAL, a country code such as US, GB or DE, or a US state such as US_WA"
+        )]
+        geo: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of posts (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "before cursor of a page, to get the page before it")]
+        before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let feed = feed
+            .map(|feed| feed.trim().trim_start_matches("r/").to_ascii_lowercase())
+            .unwrap_or_else(|| "popular".to_string());
+        let sort = sort.unwrap_or_else(|| "hot".to_string());
+        tracing::info!("Calling /r/{}/{}", feed, sort);
+
+        if !SITE_FEEDS.contains(&feed.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown feed {}", feed),
+                format!("use one of {}", SITE_FEEDS.join(", ")),
+            ));
+        }
+        let time = validate_sort(&sort, &LISTING_SORTS, time)?;
+        let geo = geo.map(|geo| validate_geo(&geo)).transpose()?;
+        if geo.is_some() && feed != "popular" {
+            return Err(RedditError::invalid_input(
+                "geo only applies to r/popular",
+                "omit geo or browse the popular feed",
+            ));
+        }
+        check_cursors(&after, &before)?;
+
+        let auth_token = self.auth_token().await?;
+        let mut page = Deadline::for_tool("get_site_feed")
+            .run(
+                self.fetch_post_page(
+                    &format!("{}/r/{}/{}", OAUTH_URL, feed, sort),
+                    &auth_token,
+                    SortedListingRequest {
+                        g: geo.clone(),
+                        t: time.clone(),
+                        limit: limit
+                            .unwrap_or(DEFAULT_LISTING_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+        let excluded_seen = self.filter_seen(&mut page.posts, exclude_seen);
+
+        serde_json::to_string(&SiteFeedResponse {
+            feed,
+            sort,
+            time,
+            geo,
+            posts: page.posts,
+            excluded_seen,
+            after: page.after,
+            before: page.before,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get one page of the signed-in account's front page: posts from its subscriptions sorted by best (Reddit's personalized ranking) or hot, as summaries. Pass the returned after or before cursor to page."
+    )]
+    pub(super) async fn get_home_feed(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "best or hot (default best)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of posts (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "before cursor of a page, to get the page before it")]
+        before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let sort = sort.unwrap_or_else(|| "best".to_string());
+        tracing::info!("Calling /{}", sort);
+
+        if !HOME_FEED_SORTS.contains(&sort.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unsupported home feed sort {}", sort),
+                format!("use one of {}", HOME_FEED_SORTS.join(", ")),
+            ));
+        }
+        check_cursors(&after, &before)?;
+
+        let auth_token = self.auth_token().await?;
+        let mut page = Deadline::for_tool("get_home_feed")
+            .run(
+                self.fetch_post_page(
+                    &format!("{}/{}", OAUTH_URL, sort),
+                    &auth_token,
+                    SortedListingRequest {
+                        g: None,
+                        t: None,
+                        limit: limit
+                            .unwrap_or(DEFAULT_LISTING_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+        let excluded_seen = self.filter_seen(&mut page.posts, exclude_seen);
+
+        serde_json::to_string(&HomeFeedResponse {
+            sort,
+            posts: page.posts,
+            excluded_seen,
+            after: page.after,
+            before: page.before,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,453 @@
+use rmcp::tool;
+
+use super::{BASE_URL, MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::config::MacroStep;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::idempotency::Claim;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    BanRequest, Comment, DistinguishRequest, Listing, ListingRequest, MacroRunResponse,
+    MacroStepOutcome, MacroStepStatus, MacroSummary, ModNoteRequest, RemoveRequest, ReplyRequest,
+    ThingRequest, UnbanRequest,
+};
+use crate::reddit::text::near_duplicate;
+use crate::reddit::validation::normalize_thing_id;
+
+/// Inverse of a completed step, used to roll a failed macro back.
+enum Undo {
+    Approve,
+    Lock,
+    Unlock,
+    Unban,
+    Delete(String),
+}
+
+struct MacroTarget {
+    fullname: String,
+    author: String,
+    subreddit: String,
+}
+
+impl MacroTarget {
+    fn fill(&self, text: &str) -> String {
+        text.replace("{author}", &self.author)
+            .replace("{subreddit}", &self.subreddit)
+    }
+}
+
+impl RedditClient {
+    async fn apply_macro_step(
+        &self,
+        step: &MacroStep,
+        target: &MacroTarget,
+        allow_duplicate_comment: bool,
+        auth_token: &str,
+    ) -> Result<(Option<String>, Option<Undo>), RedditError> {
+        let thing = || ThingRequest {
+            id: target.fullname.clone(),
+        };
+        match step {
+            MacroStep::Remove { spam } => {
+                self.post_form::<serde_json::Value, RemoveRequest>(
+                    &format!("{}/remove", BASE_URL),
+                    auth_token,
+                    RemoveRequest {
+                        id: target.fullname.clone(),
+                        spam: *spam,
+                    },
+                )
+                .await?;
+                Ok((None, Some(Undo::Approve)))
+            }
+            MacroStep::Approve => {
+                self.post_form::<serde_json::Value, ThingRequest>(
+                    &format!("{}/approve", BASE_URL),
+                    auth_token,
+                    thing(),
+                )
+                .await?;
+                Ok((None, None))
+            }
+            MacroStep::Lock => {
+                self.post_form::<serde_json::Value, ThingRequest>(
+                    &format!("{}/lock", BASE_URL),
+                    auth_token,
+                    thing(),
+                )
+                .await?;
+                Ok((None, Some(Undo::Unlock)))
+            }
+            MacroStep::Unlock => {
+                self.post_form::<serde_json::Value, ThingRequest>(
+                    &format!("{}/unlock", BASE_URL),
+                    auth_token,
+                    thing(),
+                )
+                .await?;
+                Ok((None, Some(Undo::Lock)))
+            }
+            MacroStep::Ban {
+                duration_days,
+                message,
+                reason,
+                note,
+            } => {
+                self.post_api::<serde_json::Value, BanRequest>(
+                    &format!("{}/r/{}/api/friend", OAUTH_URL, target.subreddit),
+                    auth_token,
+                    BanRequest {
+                        name: target.author.clone(),
+                        kind: "banned",
+                        duration: *duration_days,
+                        ban_message: message.as_deref().map(|text| target.fill(text)),
+                        ban_reason: reason.clone(),
+                        note: note.as_deref().map(|text| target.fill(text)),
+                    },
+                )
+                .await
+                .map_err(|e| {
+                    e.with_rejection_hint("check the macro definition in the configuration file")
+                })?;
+                let detail = match duration_days {
+                    Some(days) => format!("banned u/{} for {} days", target.author, days),
+                    None => format!("banned u/{} permanently", target.author),
+                };
+                Ok((Some(detail), Some(Undo::Unban)))
+            }
+            MacroStep::ModNote { note, label } => {
+                let response = self
+                    .post_form::<serde_json::Value, ModNoteRequest>(
+                        &format!("{}/mod/notes", BASE_URL),
+                        auth_token,
+                        ModNoteRequest {
+                            subreddit: target.subreddit.clone(),
+                            user: target.author.clone(),
+                            note: target.fill(note),
+                            label: label.clone(),
+                            reddit_id: target.fullname.clone(),
+                        },
+                    )
+                    .await?;
+                let detail = response
+                    .pointer("/created/id")
+                    .and_then(|id| id.as_str())
+                    .map(|id| format!("note {}", id));
+                Ok((detail, None))
+            }
+            MacroStep::Reply { text, sticky } => {
+                let (reply, distinguish_error) = self
+                    .post_mod_reply(
+                        &target.fullname,
+                        target.fill(text),
+                        *sticky,
+                        allow_duplicate_comment,
+                        auth_token,
+                        Priority::Interactive,
+                    )
+                    .await?;
+                let detail = match distinguish_error {
+                    None => format!("replied with {}", reply),
+                    Some(e) => {
+                        format!("replied with {} but could not distinguish it: {}", reply, e)
+                    }
+                };
+                Ok((Some(detail), Some(Undo::Delete(reply))))
+            }
+        }
+    }
+
+    /// Refuses a reply that nearly repeats one of the account's recent comments
+    /// on the same post, so a retrying agent cannot spam the thread.
+    pub(super) async fn check_duplicate_comment(
+        &self,
+        thing_id: &str,
+        text: &str,
+        auth_token: &str,
+        priority: Priority,
+    ) -> Result<(), RedditError> {
+        let link_id = if thing_id.starts_with("t3_") {
+            thing_id.to_string()
+        } else {
+            match self
+                .fetch_info(&[thing_id.to_string()], auth_token)
+                .await?
+                .remove(thing_id)
+                .and_then(|item| item.link_id)
+            {
+                Some(link_id) => link_id,
+                None => return Ok(()),
+            }
+        };
+
+        let recent = self
+            .get_request_with_priority::<Listing<Comment>, ListingRequest>(
+                &format!("{}/user/{}/comments", OAUTH_URL, self.account_username()?),
+                auth_token,
+                ListingRequest {
+                    limit: MAX_PAGE_SIZE,
+                    after: None,
+                    before: None,
+                    raw_json: 1,
+                },
+                priority,
+            )
+            .await?;
+        let duplicate = recent
+            .data
+            .children
+            .into_iter()
+            .map(|child| child.data)
+            .find(|comment| comment.link_id == link_id && near_duplicate(&comment.body, text));
+        match duplicate {
+            Some(comment) => Err(RedditError::invalid_input(
+                format!(
+                    "The account already posted a near-identical comment on this post: https://www.reddit.com{}",
+                    comment.permalink
+                ),
+                "do not repeat the comment; pass allow_duplicate_comment=true if posting it again is intended",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Replies to a post or comment and distinguishes the reply as a moderator.
+    /// Returns the reply's fullname and, when distinguishing failed, why.
+    pub(super) async fn post_mod_reply(
+        &self,
+        thing_id: &str,
+        text: String,
+        sticky: bool,
+        allow_duplicate: bool,
+        auth_token: &str,
+        priority: Priority,
+    ) -> Result<(String, Option<RedditError>), RedditError> {
+        if !allow_duplicate {
+            self.check_duplicate_comment(thing_id, &text, auth_token, priority)
+                .await?;
+        }
+        let reply = self
+            .post_api_with_priority::<serde_json::Value, ReplyRequest>(
+                &format!("{}/comment", BASE_URL),
+                auth_token,
+                ReplyRequest {
+                    thing_id: thing_id.to_string(),
+                    text,
+                },
+                priority,
+            )
+            .await?
+            .as_ref()
+            .and_then(|data| data.pointer("/things/0/data/name"))
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| RedditError::parse("the comment response has no name"))?;
+
+        // Sticky replies are only possible on posts.
+        let distinguished = self
+            .post_api_with_priority::<serde_json::Value, DistinguishRequest>(
+                &format!("{}/distinguish", BASE_URL),
+                auth_token,
+                DistinguishRequest {
+                    id: reply.clone(),
+                    how: "yes",
+                    sticky: sticky && thing_id.starts_with("t3_"),
+                },
+                priority,
+            )
+            .await;
+        Ok((reply, distinguished.err()))
+    }
+
+    async fn undo_macro_step(
+        &self,
+        undo: &Undo,
+        target: &MacroTarget,
+        auth_token: &str,
+    ) -> Result<(), RedditError> {
+        let (url, id) = match undo {
+            Undo::Approve => (format!("{}/approve", BASE_URL), target.fullname.clone()),
+            Undo::Lock => (format!("{}/lock", BASE_URL), target.fullname.clone()),
+            Undo::Unlock => (format!("{}/unlock", BASE_URL), target.fullname.clone()),
+            Undo::Delete(fullname) => (format!("{}/del", BASE_URL), fullname.clone()),
+            Undo::Unban => {
+                return self
+                    .post_api::<serde_json::Value, UnbanRequest>(
+                        &format!("{}/r/{}/api/unfriend", OAUTH_URL, target.subreddit),
+                        auth_token,
+                        UnbanRequest {
+                            name: target.author.clone(),
+                            kind: "banned",
+                        },
+                    )
+                    .await
+                    .map(|_| ());
+            }
+        };
+        self.post_form::<serde_json::Value, ThingRequest>(&url, auth_token, ThingRequest { id })
+            .await
+            .map(|_| ())
+    }
+
+    #[tool(description = "List the moderation macros defined in the configuration file.")]
+    pub(super) async fn list_macros(&self) -> Result<String, RedditError> {
+        let macros: Vec<MacroSummary> = self
+            .config
+            .get()
+            .macros
+            .into_iter()
+            .map(|(name, config)| MacroSummary { name, config })
+            .collect();
+        serde_json::to_string(&macros).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Run a moderation macro (a named bundle of actions such as remove + ban + mod note, defined in the configuration file) against a post or comment. Steps run in order; if one fails the completed steps are undone where Reddit allows it, and the outcome of every step is reported."
+    )]
+    pub(super) async fn run_macro(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Post or comment to act on: a fullname (t3_/t1_), a permalink or a post id"
+        )]
+        target: String,
+        #[tool(param)]
+        #[schemars(description = "Name of the macro, see list_macros")]
+        macro_name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Post reply steps even when the account already left a near-identical comment on the same post (default false)"
+        )]
+        allow_duplicate_comment: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of running the macro again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Running macro {} on {}", macro_name, target);
+
+        let config = self
+            .config
+            .get()
+            .macros
+            .remove(&macro_name)
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("No macro named {}", macro_name),
+                    "call list_macros to see the configured macros",
+                )
+            })?;
+        let fullname = normalize_thing_id(&target)?;
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "run_macro",
+            idempotency_key.as_deref(),
+            serde_json::json!({ "target": fullname, "macro_name": macro_name }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let deadline = Deadline::for_tool("run_macro");
+        let auth_token = self.auth_token().await?;
+        let info = deadline
+            .run(self.fetch_info(std::slice::from_ref(&fullname), &auth_token))
+            .await??
+            .remove(&fullname)
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("{} was not found", fullname),
+                    "check the target id or permalink",
+                )
+            })?;
+        let author = info.author.unwrap_or_default();
+        let needs_author = config
+            .steps
+            .iter()
+            .any(|step| matches!(step, MacroStep::Ban { .. } | MacroStep::ModNote { .. }));
+        if needs_author && (author.is_empty() || author == "[deleted]") {
+            return Err(RedditError::invalid_input(
+                format!("The author of {} is deleted", fullname),
+                "ban and mod_note steps need a target whose author still exists",
+            ));
+        }
+        let target = MacroTarget {
+            fullname,
+            author,
+            subreddit: info.subreddit.unwrap_or_default(),
+        };
+
+        let mut steps: Vec<MacroStepOutcome> = Vec::new();
+        let mut undos = Vec::new();
+        let mut failed = false;
+        for step in &config.steps {
+            if failed {
+                steps.push(MacroStepOutcome {
+                    action: step.name(),
+                    status: MacroStepStatus::Skipped,
+                    detail: None,
+                });
+                continue;
+            }
+            match deadline
+                .run(self.apply_macro_step(
+                    step,
+                    &target,
+                    allow_duplicate_comment.unwrap_or(false),
+                    &auth_token,
+                ))
+                .await
+                .and_then(|result| result)
+            {
+                Ok((detail, undo)) => {
+                    steps.push(MacroStepOutcome {
+                        action: step.name(),
+                        status: MacroStepStatus::Done,
+                        detail,
+                    });
+                    undos.push(undo);
+                }
+                Err(e) => {
+                    steps.push(MacroStepOutcome {
+                        action: step.name(),
+                        status: MacroStepStatus::Failed,
+                        detail: Some(e.to_string()),
+                    });
+                    failed = true;
+                }
+            }
+        }
+
+        if failed {
+            for (index, undo) in undos.iter().enumerate().rev() {
+                let outcome = &mut steps[index];
+                match undo {
+                    None => outcome.status = MacroStepStatus::NotRolledBack,
+                    Some(undo) => match self.undo_macro_step(undo, &target, &auth_token).await {
+                        Ok(()) => outcome.status = MacroStepStatus::RolledBack,
+                        Err(e) => {
+                            outcome.status = MacroStepStatus::NotRolledBack;
+                            outcome.detail = Some(format!("rollback failed: {}", e));
+                        }
+                    },
+                }
+            }
+        }
+
+        let result = serde_json::to_string(&MacroRunResponse {
+            macro_name,
+            target: target.fullname,
+            author: target.author,
+            subreddit: target.subreddit,
+            success: !failed,
+            steps,
+        })
+        .map_err(RedditError::serialize)?;
+        // A failed run was rolled back, so retrying it with the same key runs it again.
+        if !failed {
+            claim.record(&result);
+        }
+        Ok(result)
+    }
+}
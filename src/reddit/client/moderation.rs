@@ -0,0 +1,819 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rmcp::tool;
+use serde::de::IgnoredAny;
+use tokio::task::JoinSet;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    Comment, Flashpoint, FlashpointsResponse, ItemReport, Listing, ListingRequest, ModAction,
+    ModDashboardResponse, ModTeamActivityResponse, ModeratorActivity, ModmailConversationList,
+    ModmailConversationsRequest, ModqueueChangesResponse, ModqueueEntry, ModqueueItem,
+    ModqueueSnapshot, Post, QueueTotals, RawJsonRequest, RemovalReasonSuggestion,
+    RemovalReasonSuggestionsResponse, RemovalReasonsResponse, SubredditAbout, SubredditQueueCounts,
+    SubredditRule,
+};
+use crate::reddit::text::snippet;
+use crate::reddit::validation::normalize_thing_id;
+use crate::storage::Namespace;
+
+const DEFAULT_FLASHPOINTS: usize = 15;
+const FLASHPOINT_EXCERPT_CHARS: usize = 200;
+/// Posts below this upvote ratio are treated as divisive.
+const DIVISIVE_UPVOTE_RATIO: f64 = 0.65;
+const REPORT_WEIGHT: f64 = 3.0;
+const MAX_MODQUEUE_ITEMS: u32 = 1000;
+const MAX_MODERATED_SUBREDDITS: u32 = 200;
+/// Queues are counted by paging through them, so counting stops here.
+const DASHBOARD_COUNT_CAP: u32 = 500;
+const MODMAIL_ENTITY_CHUNK: usize = 25;
+const DEFAULT_ACTIVITY_DAYS: i64 = 30;
+const DEFAULT_ACTIVITY_ENTRIES: usize = 5000;
+const MAX_ACTIVITY_ENTRIES: usize = 50000;
+const MODQUEUE_EXCERPT_CHARS: usize = 200;
+const DEFAULT_REASON_SUGGESTIONS: usize = 3;
+const MIN_REASON_CONFIDENCE: f64 = 0.2;
+/// Matching a report by text alone is less certain than going through a rule.
+const DIRECT_MATCH_WEIGHT: f64 = 0.8;
+const KEYWORD_STOPWORDS: [&str; 20] = [
+    "the", "and", "for", "not", "are", "with", "your", "you", "this", "that", "from", "all", "any",
+    "must", "will", "have", "has", "our", "please", "rule",
+];
+
+static RULE_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\brule\s*#?\s*(\d+)").expect("valid rule number regex"));
+
+fn post_flashpoint(post: Post, in_modqueue: bool) -> Flashpoint {
+    let mut reasons = Vec::new();
+    let mut flashpoint_score = 0.0;
+
+    let ratio = post.upvote_ratio.unwrap_or(1.0);
+    if ratio < DIVISIVE_UPVOTE_RATIO {
+        // Split votes only matter when people are actually arguing.
+        flashpoint_score += (1.0 - ratio) * 10.0 * (1.0 + post.num_comments as f64).ln();
+        reasons.push(format!(
+            "upvote ratio {:.0}% with {} comments",
+            ratio * 100.0,
+            post.num_comments
+        ));
+    }
+    let reports = post.num_reports.unwrap_or(0).max(0);
+    if reports > 0 {
+        flashpoint_score += reports as f64 * REPORT_WEIGHT;
+        reasons.push(format!("{} reports", reports));
+    }
+    if in_modqueue {
+        flashpoint_score += 1.0;
+        reasons.push("waiting in the mod queue".to_string());
+    }
+
+    Flashpoint {
+        kind: "post",
+        fullname: post.name,
+        author: post.author,
+        permalink: post.permalink,
+        excerpt: snippet(&post.title, FLASHPOINT_EXCERPT_CHARS),
+        score: post.score,
+        upvote_ratio: post.upvote_ratio,
+        reports,
+        in_modqueue,
+        flashpoint_score,
+        reasons,
+    }
+}
+
+fn comment_flashpoint(comment: Comment, in_modqueue: bool) -> Flashpoint {
+    let mut reasons = Vec::new();
+    let mut flashpoint_score = 0.0;
+
+    if comment.controversiality > 0 {
+        flashpoint_score += 3.0;
+        reasons.push("marked controversial by Reddit".to_string());
+    }
+    if comment.score < 0 {
+        flashpoint_score += (-comment.score).min(20) as f64 / 2.0;
+        reasons.push(format!("score {}", comment.score));
+    }
+    let reports = comment.num_reports.unwrap_or(0).max(0);
+    if reports > 0 {
+        flashpoint_score += reports as f64 * REPORT_WEIGHT;
+        reasons.push(format!("{} reports", reports));
+    }
+    if in_modqueue {
+        flashpoint_score += 1.0;
+        reasons.push("waiting in the mod queue".to_string());
+    }
+
+    Flashpoint {
+        kind: "comment",
+        fullname: comment.name,
+        author: comment.author,
+        permalink: comment.permalink,
+        excerpt: snippet(&comment.body, FLASHPOINT_EXCERPT_CHARS),
+        score: comment.score,
+        upvote_ratio: None,
+        reports,
+        in_modqueue,
+        flashpoint_score,
+        reasons,
+    }
+}
+
+fn keywords(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 3 && !KEYWORD_STOPWORDS.contains(word))
+        .map(|word| match word.strip_suffix('s') {
+            Some(stem) if stem.len() >= 3 => stem.to_string(),
+            _ => word.to_string(),
+        })
+        .collect()
+}
+
+/// Share of the smaller keyword set found in the other one.
+fn keyword_overlap(a: &str, b: &str) -> f64 {
+    let (a, b) = (keywords(a), keywords(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.len().min(b.len()) as f64
+}
+
+/// The rule a report most likely refers to, with a confidence. Reports made
+/// through Reddit's report dialog quote the rule's name or violation reason.
+fn match_report_to_rule<'a>(
+    report: &str,
+    rules: &'a [SubredditRule],
+) -> Option<(&'a SubredditRule, f64)> {
+    let quoted = |rule: &&SubredditRule| {
+        rule.short_name.eq_ignore_ascii_case(report.trim())
+            || rule
+                .violation_reason
+                .as_deref()
+                .is_some_and(|reason| reason.eq_ignore_ascii_case(report.trim()))
+    };
+    if let Some(rule) = rules.iter().find(quoted) {
+        return Some((rule, 1.0));
+    }
+    if let Some(rule) = RULE_NUMBER
+        .captures(report)
+        .and_then(|captures| captures[1].parse::<usize>().ok())
+        .and_then(|number| rules.get(number.checked_sub(1)?))
+    {
+        return Some((rule, 0.9));
+    }
+    rules
+        .iter()
+        .map(|rule| {
+            let text = format!(
+                "{} {}",
+                rule.short_name,
+                rule.violation_reason.as_deref().unwrap_or_default()
+            );
+            (rule, keyword_overlap(report, &text))
+        })
+        .filter(|(_, confidence)| *confidence > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn item_reports(
+    user_reports: &[Vec<serde_json::Value>],
+    mod_reports: &[Vec<serde_json::Value>],
+) -> Vec<ItemReport> {
+    let reason = |entry: &[serde_json::Value]| {
+        entry
+            .first()
+            .and_then(|reason| reason.as_str())
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string)
+    };
+    let user = user_reports.iter().filter_map(|entry| {
+        Some(ItemReport {
+            source: "user",
+            reason: reason(entry)?,
+            count: entry.get(1).and_then(|count| count.as_i64()).unwrap_or(1),
+        })
+    });
+    let moderator = mod_reports.iter().filter_map(|entry| {
+        Some(ItemReport {
+            source: "moderator",
+            reason: reason(entry)?,
+            count: 1,
+        })
+    });
+    user.chain(moderator).collect()
+}
+
+impl RedditClient {
+    /// Number of items in a moderation listing, and whether counting stopped early.
+    async fn count_listing(
+        &self,
+        url: &str,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<(usize, bool), RedditError> {
+        let (items, truncated_reason) = self
+            .collect_listing::<IgnoredAny>(url, auth_token, DASHBOARD_COUNT_CAP, deadline)
+            .await?;
+        let capped = truncated_reason.is_some() || items.len() >= DASHBOARD_COUNT_CAP as usize;
+        Ok((items.len(), capped))
+    }
+
+    #[tool(
+        description = "For moderators: rank recent posts and comments of a subreddit that look like flashpoints (divisive votes, controversial or downvoted comments, reports), using the mod queue when the account may read it."
+    )]
+    pub(super) async fn find_flashpoints(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of items to return (default 15)")]
+        limit: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Looking for flashpoints in {}", subreddit);
+
+        let deadline = Deadline::for_tool("find_flashpoints");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let limit = limit.unwrap_or(DEFAULT_FLASHPOINTS).max(1);
+
+        let posts_url = format!("{}/r/{}/new", OAUTH_URL, subreddit);
+        let (posts, _) = self
+            .collect_listing::<Post>(&posts_url, &auth_token, MAX_PAGE_SIZE, &deadline)
+            .await?;
+        let comments_url = format!("{}/r/{}/comments", OAUTH_URL, subreddit);
+        let (comments, _) = self
+            .collect_listing::<Comment>(&comments_url, &auth_token, MAX_PAGE_SIZE, &deadline)
+            .await?;
+
+        // Only moderators can read the mod queue; everyone else gets a 403.
+        let modqueue_url = format!("{}/r/{}/about/modqueue", OAUTH_URL, subreddit);
+        let modqueue = deadline
+            .run(
+                self.get_request::<Listing<serde_json::Value>, ListingRequest>(
+                    &modqueue_url,
+                    &auth_token,
+                    ListingRequest {
+                        limit: MAX_PAGE_SIZE,
+                        after: None,
+                        before: None,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await?
+            .ok();
+        let modqueue_available = modqueue.is_some();
+
+        // Queued items carry the report counts, so they win over the copies
+        // found in the listings.
+        let mut flashpoints = Vec::new();
+        for child in modqueue
+            .into_iter()
+            .flat_map(|listing| listing.data.children)
+        {
+            let flashpoint = match child.kind.as_str() {
+                "t3" => serde_json::from_value(child.data).map(|post| post_flashpoint(post, true)),
+                "t1" => serde_json::from_value(child.data)
+                    .map(|comment| comment_flashpoint(comment, true)),
+                _ => continue,
+            };
+            flashpoints.push(flashpoint.map_err(RedditError::parse)?);
+        }
+        let queued: HashSet<String> = flashpoints
+            .iter()
+            .map(|flashpoint| flashpoint.fullname.clone())
+            .collect();
+
+        let posts_scanned = posts.len();
+        let comments_scanned = comments.len();
+        flashpoints.extend(
+            posts
+                .into_iter()
+                .filter(|post| !queued.contains(&post.name))
+                .map(|post| post_flashpoint(post, false)),
+        );
+        flashpoints.extend(
+            comments
+                .into_iter()
+                .filter(|comment| !queued.contains(&comment.name))
+                .map(|comment| comment_flashpoint(comment, false)),
+        );
+        flashpoints.retain(|flashpoint| flashpoint.flashpoint_score > 0.0);
+        flashpoints.sort_by(|a, b| b.flashpoint_score.total_cmp(&a.flashpoint_score));
+        flashpoints.truncate(limit);
+
+        serde_json::to_string(&FlashpointsResponse {
+            subreddit,
+            posts_scanned,
+            comments_scanned,
+            modqueue_available,
+            flashpoints,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: suggest which of the subreddit's removal reasons fits a reported post or comment, by matching its reports against the subreddit rules and the removal reason texts. Returns the best removal reason ids with a confidence between 0 and 1."
+    )]
+    pub(super) async fn suggest_removal_reasons(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Reported post or comment: a fullname (t3_/t1_), a permalink or a post id"
+        )]
+        target: String,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of suggestions (default 3)")]
+        limit: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Suggesting removal reasons for {}", target);
+
+        let deadline = Deadline::for_tool("suggest_removal_reasons");
+        let auth_token = self.auth_token().await?;
+        let fullname = normalize_thing_id(&target)?;
+        let limit = limit.unwrap_or(DEFAULT_REASON_SUGGESTIONS).max(1);
+
+        let item = deadline
+            .run(self.fetch_info(std::slice::from_ref(&fullname), &auth_token))
+            .await??
+            .remove(&fullname)
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("{} was not found", fullname),
+                    "check the target id or permalink",
+                )
+            })?;
+        let subreddit = item.subreddit.clone().unwrap_or_default();
+        let reports = item_reports(&item.user_reports, &item.mod_reports);
+
+        let rules = deadline
+            .run(self.fetch_subreddit_rules(&subreddit, &auth_token))
+            .await?
+            .unwrap_or_default();
+        let reasons_url = format!("{}/api/v1/{}/removal_reasons", OAUTH_URL, subreddit);
+        let mut removal_reasons = deadline
+            .run(self.get_request::<RemovalReasonsResponse, RawJsonRequest>(
+                &reasons_url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await?
+            .map_err(|e| {
+                e.with_hint(format!(
+                    "only moderators of r/{} can read its removal reasons",
+                    subreddit
+                ))
+            })?;
+        let removal_reasons_total = removal_reasons.data.len();
+
+        let matched_rules: Vec<(&ItemReport, &SubredditRule, f64)> = reports
+            .iter()
+            .filter_map(|report| {
+                match_report_to_rule(&report.reason, &rules)
+                    .map(|(rule, confidence)| (report, rule, confidence))
+            })
+            .collect();
+
+        let mut suggestions = Vec::new();
+        for id in removal_reasons.order.clone() {
+            let Some(reason) = removal_reasons.data.remove(&id) else {
+                continue;
+            };
+            let reason_text = format!("{} {}", reason.title, reason.message);
+            let mut best: (f64, Option<String>, Option<String>) = (0.0, None, None);
+
+            for (report, rule, rule_confidence) in &matched_rules {
+                let rule_fit = if reason.title.eq_ignore_ascii_case(&rule.short_name) {
+                    1.0
+                } else {
+                    keyword_overlap(&rule.short_name, &reason.title)
+                        .max(keyword_overlap(&rule.short_name, &reason_text))
+                };
+                let confidence = rule_confidence * rule_fit;
+                if confidence > best.0 {
+                    best = (
+                        confidence,
+                        Some(rule.short_name.clone()),
+                        Some(report.reason.clone()),
+                    );
+                }
+            }
+            for report in &reports {
+                let confidence =
+                    DIRECT_MATCH_WEIGHT * keyword_overlap(&report.reason, &reason_text);
+                if confidence > best.0 {
+                    best = (confidence, None, Some(report.reason.clone()));
+                }
+            }
+
+            let (confidence, matched_rule, matched_report) = best;
+            if confidence >= MIN_REASON_CONFIDENCE {
+                suggestions.push(RemovalReasonSuggestion {
+                    reason,
+                    confidence: (confidence * 100.0).round() / 100.0,
+                    matched_rule,
+                    matched_report,
+                });
+            }
+        }
+        suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        suggestions.truncate(limit);
+
+        serde_json::to_string(&RemovalReasonSuggestionsResponse {
+            target: fullname,
+            subreddit,
+            reports,
+            suggestions,
+            removal_reasons_total,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: report what changed in a subreddit's mod queue since the previous call: items that were added and items that were resolved (approved, removed or deleted). The first call returns the whole queue as added."
+    )]
+    pub(super) async fn modqueue_changes(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Checking mod queue changes in {}", subreddit);
+
+        let deadline = Deadline::for_tool("modqueue_changes");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        let url = format!("{}/r/{}/about/modqueue", OAUTH_URL, subreddit);
+        let (queue, truncated_reason) = self
+            .collect_listing::<ModqueueItem>(&url, &auth_token, MAX_MODQUEUE_ITEMS, &deadline)
+            .await
+            .map_err(|e| match e.kind {
+                ErrorKind::Forbidden => e.with_hint(format!(
+                    "only moderators of r/{} can read its mod queue",
+                    subreddit
+                )),
+                _ => e,
+            })?;
+        let current: Vec<ModqueueEntry> = queue
+            .into_iter()
+            .map(|item| ModqueueEntry {
+                excerpt: snippet(
+                    item.title
+                        .as_deref()
+                        .or(item.body.as_deref())
+                        .unwrap_or_default(),
+                    MODQUEUE_EXCERPT_CHARS,
+                ),
+                fullname: item.name,
+                author: item.author,
+                permalink: item.permalink,
+                reports: item.num_reports.unwrap_or(0),
+                created_utc: item.created_utc,
+            })
+            .collect();
+
+        let key = subreddit.to_ascii_lowercase();
+        let previous = self
+            .storage
+            .read_json::<ModqueueSnapshot>(Namespace::Modqueues, &key)
+            .map_err(RedditError::storage)?;
+        let previous_check_utc = previous.as_ref().map(|snapshot| snapshot.checked_utc);
+        let previous_items = previous.map(|snapshot| snapshot.items).unwrap_or_default();
+
+        let current_names: HashSet<&str> = current
+            .iter()
+            .map(|entry| entry.fullname.as_str())
+            .collect();
+        let previous_names: HashSet<&str> = previous_items
+            .iter()
+            .map(|entry| entry.fullname.as_str())
+            .collect();
+        let added: Vec<ModqueueEntry> = current
+            .iter()
+            .filter(|entry| !previous_names.contains(entry.fullname.as_str()))
+            .cloned()
+            .collect();
+        // A truncated read cannot tell a resolved item from one it did not reach.
+        let resolved: Vec<ModqueueEntry> = if truncated_reason.is_some() {
+            Vec::new()
+        } else {
+            previous_items
+                .iter()
+                .filter(|entry| !current_names.contains(entry.fullname.as_str()))
+                .cloned()
+                .collect()
+        };
+        let queue_size = current.len();
+        let unchanged = queue_size - added.len();
+
+        self.storage
+            .write_json(
+                Namespace::Modqueues,
+                &key,
+                &ModqueueSnapshot {
+                    checked_utc: chrono::Utc::now().timestamp(),
+                    items: current,
+                },
+            )
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&ModqueueChangesResponse {
+            subreddit,
+            previous_check_utc,
+            queue_size,
+            added,
+            resolved,
+            unchanged,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators of several communities: one dashboard across every subreddit the account moderates, with mod queue, unmoderated and unread modmail counts per subreddit and in total."
+    )]
+    pub(super) async fn get_mod_dashboard(&self) -> Result<String, RedditError> {
+        tracing::info!("Building the moderator dashboard");
+
+        let deadline = Deadline::for_tool("get_mod_dashboard");
+        let auth_token = self.auth_token().await?;
+
+        let url = format!("{}/subreddits/mine/moderator", OAUTH_URL);
+        let (moderated, mut truncated_reason) = self
+            .collect_listing::<SubredditAbout>(
+                &url,
+                &auth_token,
+                MAX_MODERATED_SUBREDDITS,
+                &deadline,
+            )
+            .await?;
+        // Profiles the account owns show up as u_<name> subreddits.
+        let moderated: Vec<SubredditAbout> = moderated
+            .into_iter()
+            .filter(|subreddit| !subreddit.display_name.starts_with("u_"))
+            .collect();
+
+        let mut counts = JoinSet::new();
+        for (index, subreddit) in moderated.iter().enumerate() {
+            let client = self.clone();
+            let auth_token = auth_token.clone();
+            let deadline = deadline.clone();
+            let name = subreddit.display_name.clone();
+            counts.spawn(async move {
+                let modqueue_url = format!("{}/r/{}/about/modqueue", OAUTH_URL, name);
+                let unmoderated_url = format!("{}/r/{}/about/unmoderated", OAUTH_URL, name);
+                let modqueue = client
+                    .count_listing(&modqueue_url, &auth_token, &deadline)
+                    .await;
+                let unmoderated = client
+                    .count_listing(&unmoderated_url, &auth_token, &deadline)
+                    .await;
+                (index, modqueue, unmoderated)
+            });
+        }
+
+        let mut subreddits: Vec<SubredditQueueCounts> = moderated
+            .iter()
+            .map(|subreddit| SubredditQueueCounts {
+                subreddit: subreddit.display_name.clone(),
+                subscribers: subreddit.subscribers,
+                modqueue: 0,
+                unmoderated: 0,
+                unread_modmail: 0,
+                capped: false,
+                error: None,
+            })
+            .collect();
+        while let Some(result) = counts.join_next().await {
+            let Ok((index, modqueue, unmoderated)) = result else {
+                continue;
+            };
+            let entry = &mut subreddits[index];
+            match (modqueue, unmoderated) {
+                (Ok((modqueue, modqueue_capped)), Ok((unmoderated, unmoderated_capped))) => {
+                    entry.modqueue = modqueue;
+                    entry.unmoderated = unmoderated;
+                    entry.capped = modqueue_capped || unmoderated_capped;
+                }
+                // Moderators without the posts permission cannot read the queues.
+                (Err(e), _) | (_, Err(e)) => entry.error = Some(e.to_string()),
+            }
+        }
+
+        let conversations_url = format!("{}/api/mod/conversations", OAUTH_URL);
+        let names: Vec<String> = moderated
+            .iter()
+            .map(|subreddit| subreddit.display_name.clone())
+            .collect();
+        let mut unread: HashMap<String, usize> = HashMap::new();
+        for chunk in names.chunks(MODMAIL_ENTITY_CHUNK) {
+            let conversations = deadline
+                .run(
+                    self.get_request::<ModmailConversationList, ModmailConversationsRequest>(
+                        &conversations_url,
+                        &auth_token,
+                        ModmailConversationsRequest {
+                            entity: chunk.join(","),
+                            state: "all",
+                            sort: "unread",
+                            limit: MAX_PAGE_SIZE,
+                        },
+                    ),
+                )
+                .await;
+            match conversations {
+                Ok(Ok(list)) => {
+                    for conversation in list.conversations.into_values() {
+                        if conversation.last_unread.is_some() {
+                            *unread
+                                .entry(conversation.owner.display_name.to_ascii_lowercase())
+                                .or_insert(0) += 1;
+                        }
+                    }
+                }
+                Ok(Err(e)) => tracing::debug!("Skipping modmail counts: {}", e),
+                Err(e) => {
+                    truncated_reason.get_or_insert(e.to_string());
+                    break;
+                }
+            }
+        }
+        for entry in &mut subreddits {
+            entry.unread_modmail = unread
+                .get(&entry.subreddit.to_ascii_lowercase())
+                .copied()
+                .unwrap_or(0);
+        }
+
+        let unread_count_url = format!("{}/api/mod/conversations/unread/count", OAUTH_URL);
+        let modmail_unread_by_folder = deadline
+            .run(self.get_request::<HashMap<String, i64>, RawJsonRequest>(
+                &unread_count_url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await?
+            .ok();
+        if deadline.is_expired() {
+            truncated_reason.get_or_insert_with(|| deadline.truncation_reason());
+        }
+
+        let totals = subreddits
+            .iter()
+            .fold(QueueTotals::default(), |mut totals, entry| {
+                totals.modqueue += entry.modqueue;
+                totals.unmoderated += entry.unmoderated;
+                totals.unread_modmail += entry.unread_modmail;
+                totals
+            });
+        subreddits.sort_by_key(|entry| {
+            std::cmp::Reverse(entry.modqueue + entry.unmoderated + entry.unread_modmail)
+        });
+
+        serde_json::to_string(&ModDashboardResponse {
+            moderated_subreddits: subreddits.len(),
+            totals,
+            subreddits,
+            modmail_unread_by_folder,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: count mod log actions per moderator and action type over a period (default the last 30 days), to show how moderation work is spread across the team."
+    )]
+    pub(super) async fn get_mod_team_activity(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Number of days to cover, counting back from now (default 30)")]
+        days: Option<i64>,
+        #[tool(param)]
+        #[schemars(
+            description = "Maximum number of mod log entries to read (default 5000, max 50000)"
+        )]
+        max_entries: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Reporting mod team activity in {}", subreddit);
+
+        let deadline = Deadline::for_tool("get_mod_team_activity");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let days = days.unwrap_or(DEFAULT_ACTIVITY_DAYS).max(1);
+        let max_entries = max_entries
+            .unwrap_or(DEFAULT_ACTIVITY_ENTRIES)
+            .clamp(1, MAX_ACTIVITY_ENTRIES);
+        let period_end_utc = chrono::Utc::now().timestamp();
+        let period_start_utc = period_end_utc - days * 24 * 60 * 60;
+
+        // The mod log is newest first, so reading stops at the first entry
+        // older than the period.
+        let url = format!("{}/r/{}/about/log", OAUTH_URL, subreddit);
+        let mut actions: Vec<ModAction> = Vec::new();
+        let mut after = None;
+        let mut truncated_reason = None;
+        loop {
+            if actions.len() >= max_entries {
+                truncated_reason = Some(format!(
+                    "stopped after {} entries; raise max_entries to cover the whole period",
+                    max_entries
+                ));
+                break;
+            }
+            let page = match deadline
+                .run(self.get_request::<Listing<ModAction>, ListingRequest>(
+                    &url,
+                    &auth_token,
+                    ListingRequest {
+                        limit: MAX_PAGE_SIZE,
+                        after: after.clone(),
+                        before: None,
+                        raw_json: 1,
+                    },
+                ))
+                .await
+            {
+                Ok(page) => page.map_err(|e| match e.kind {
+                    ErrorKind::Forbidden => e.with_hint(format!(
+                        "only moderators of r/{} can read its mod log",
+                        subreddit
+                    )),
+                    _ => e,
+                })?,
+                Err(_) => {
+                    truncated_reason = Some(deadline.truncation_reason());
+                    break;
+                }
+            };
+
+            let fetched = page.data.children.len();
+            let mut reached_start = false;
+            for action in page.data.children.into_iter().map(|child| child.data) {
+                if (action.created_utc as i64) < period_start_utc {
+                    reached_start = true;
+                    break;
+                }
+                actions.push(action);
+            }
+            after = page.data.after;
+            if reached_start || after.is_none() || fetched == 0 {
+                break;
+            }
+        }
+        actions.truncate(max_entries);
+
+        let mut totals_by_action: BTreeMap<String, usize> = BTreeMap::new();
+        let mut by_moderator: HashMap<String, ModeratorActivity> = HashMap::new();
+        for action in &actions {
+            *totals_by_action.entry(action.action.clone()).or_insert(0) += 1;
+            let activity = by_moderator
+                .entry(action.moderator.clone())
+                .or_insert_with(|| ModeratorActivity {
+                    moderator: action.moderator.clone(),
+                    total: 0,
+                    share: 0.0,
+                    actions: BTreeMap::new(),
+                    last_action_utc: action.created_utc,
+                });
+            activity.total += 1;
+            *activity.actions.entry(action.action.clone()).or_insert(0) += 1;
+            activity.last_action_utc = activity.last_action_utc.max(action.created_utc);
+        }
+        let mut moderators: Vec<ModeratorActivity> = by_moderator.into_values().collect();
+        for activity in &mut moderators {
+            activity.share =
+                (activity.total as f64 / actions.len() as f64 * 1000.0).round() / 1000.0;
+        }
+        moderators.sort_by(|a, b| b.total.cmp(&a.total).then(a.moderator.cmp(&b.moderator)));
+
+        serde_json::to_string(&ModTeamActivityResponse {
+            subreddit,
+            period_start_utc,
+            period_end_utc,
+            actions_counted: actions.len(),
+            totals_by_action,
+            moderators,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
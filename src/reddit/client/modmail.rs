@@ -0,0 +1,362 @@
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::idempotency::Claim;
+use crate::reddit::models::{
+    Comment, HygieneItem, ModmailContextMessage, ModmailContextResponse,
+    ModmailConversationResponse, ModmailReplyRequest, ModmailReplyResponse, ModmailRequest,
+    ModmailUserContext, Post, ReplyTarget,
+};
+use crate::reddit::text::snippet;
+
+const DEFAULT_MODMAIL_HISTORY: u32 = 10;
+const MODMAIL_EXCERPT_CHARS: usize = 200;
+
+/// Accepts a conversation id or a mod.reddit.com/mail URL.
+fn normalize_conversation_id(input: &str) -> Result<String, RedditError> {
+    let id = input
+        .trim()
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let valid = (1..=12).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid modmail conversation id", input),
+            "pass the conversation id, e.g. 2abcd, or its mod.reddit.com/mail URL",
+        ));
+    }
+    Ok(id.to_string())
+}
+
+impl RedditClient {
+    async fn fetch_modmail_conversation(
+        &self,
+        conversation_id: &str,
+        auth_token: &str,
+    ) -> Result<ModmailConversationResponse, RedditError> {
+        let url = format!("{}/api/mod/conversations/{}", OAUTH_URL, conversation_id);
+        self.get_request::<ModmailConversationResponse, ModmailRequest>(
+            &url,
+            auth_token,
+            ModmailRequest { mark_read: false },
+        )
+        .await
+        .map_err(|e| {
+            e.with_hint(
+                "check the conversation id; only moderators of the subreddit can read its modmail",
+            )
+        })
+    }
+
+    #[tool(
+        description = "For moderators: gather everything needed to draft a modmail reply in one object: the conversation's messages in order, the participant's ban/mute status and recent posts and comments, the subreddit rules and notes for the reply. Send the reply with send_modmail_reply."
+    )]
+    pub(super) async fn get_modmail_context(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Modmail conversation id or mod.reddit.com/mail URL")]
+        conversation_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Number of the participant's recent posts and comments to include (default 10, max 100)"
+        )]
+        history_limit: Option<u32>,
+    ) -> Result<String, RedditError> {
+        let conversation_id = normalize_conversation_id(&conversation_id)?;
+        tracing::info!("Assembling modmail context for {}", conversation_id);
+
+        let deadline = Deadline::for_tool("get_modmail_context");
+        let auth_token = self.auth_token().await?;
+        let history_limit = history_limit
+            .unwrap_or(DEFAULT_MODMAIL_HISTORY)
+            .clamp(1, MAX_PAGE_SIZE);
+
+        let mut response = deadline
+            .run(self.fetch_modmail_conversation(&conversation_id, &auth_token))
+            .await??;
+        let conversation = response.conversation;
+        let subreddit = conversation.owner.display_name.clone();
+
+        let mut message_ids: Vec<String> = conversation
+            .obj_ids
+            .iter()
+            .filter(|object| object.key == "messages")
+            .map(|object| object.id.clone())
+            .collect();
+        if message_ids.is_empty() {
+            message_ids = response.messages.keys().cloned().collect();
+            message_ids.sort_by_key(|id| response.messages[id].date.clone());
+        }
+        let messages: Vec<ModmailContextMessage> = message_ids
+            .iter()
+            .filter_map(|id| response.messages.remove(id))
+            .map(|message| ModmailContextMessage {
+                role: if message.author.is_admin {
+                    "admin"
+                } else if message.author.is_mod {
+                    "moderator"
+                } else {
+                    "user"
+                },
+                author: message.author.name,
+                date: message.date,
+                internal: message.is_internal,
+                body: message.body_markdown,
+            })
+            .collect();
+
+        let rules = deadline
+            .run(self.fetch_subreddit_rules(&subreddit, &auth_token))
+            .await?
+            .unwrap_or_default();
+
+        let mut drafting_notes = Vec::new();
+        if conversation.is_internal {
+            drafting_notes.push(
+                "This is a moderator-only discussion; replies are only visible to moderators."
+                    .to_string(),
+            );
+        }
+        if messages
+            .last()
+            .is_some_and(|message| message.role != "user")
+        {
+            drafting_notes.push(
+                "The last message is already from the mod team; a reply may not be needed."
+                    .to_string(),
+            );
+        }
+
+        let participant_deleted = conversation
+            .participant
+            .as_ref()
+            .is_some_and(|participant| participant.is_deleted);
+        let user = match response.user.filter(|_| !participant_deleted) {
+            Some(user) => {
+                let posts_url = format!("{}/user/{}/submitted", OAUTH_URL, user.name);
+                let recent_posts = self
+                    .collect_listing::<Post>(&posts_url, &auth_token, history_limit, &deadline)
+                    .await
+                    .map(|(posts, _)| posts);
+                let comments_url = format!("{}/user/{}/comments", OAUTH_URL, user.name);
+                let recent_comments = self
+                    .collect_listing::<Comment>(
+                        &comments_url,
+                        &auth_token,
+                        history_limit,
+                        &deadline,
+                    )
+                    .await
+                    .map(|(comments, _)| comments);
+                if recent_posts.is_err() || recent_comments.is_err() {
+                    drafting_notes.push(format!(
+                        "The post history of u/{} could not be read; the account may be suspended or its profile hidden.",
+                        user.name
+                    ));
+                }
+
+                let ban = user.ban_status.filter(|ban| ban.active);
+                if let Some(ban) = &ban {
+                    drafting_notes.push(match &ban.end_date {
+                        Some(end) if !ban.is_permanent => {
+                            format!(
+                                "u/{} is banned from r/{} until {}.",
+                                user.name, subreddit, end
+                            )
+                        }
+                        _ => format!(
+                            "u/{} is permanently banned from r/{}.",
+                            user.name, subreddit
+                        ),
+                    });
+                }
+                let mute = user.mute_status.filter(|mute| mute.active);
+                if mute.is_some() {
+                    drafting_notes.push(format!(
+                        "u/{} is muted and cannot reply to modmail until the mute ends.",
+                        user.name
+                    ));
+                }
+
+                let mut posts_in_subreddit: Vec<_> = user.recent_posts.into_values().collect();
+                posts_in_subreddit.sort_by(|a, b| b.date.cmp(&a.date));
+                let mut comments_in_subreddit: Vec<_> =
+                    user.recent_comments.into_values().collect();
+                comments_in_subreddit.sort_by(|a, b| b.date.cmp(&a.date));
+
+                Some(ModmailUserContext {
+                    name: user.name,
+                    account_created: user.created,
+                    ban,
+                    mute,
+                    posts_in_subreddit,
+                    comments_in_subreddit,
+                    recent_posts: recent_posts
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|post| HygieneItem {
+                            fullname: post.name,
+                            subreddit: post.subreddit,
+                            score: post.score,
+                            created_utc: post.created_utc,
+                            permalink: post.permalink,
+                            excerpt: snippet(&post.title, MODMAIL_EXCERPT_CHARS),
+                        })
+                        .collect(),
+                    recent_comments: recent_comments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|comment| HygieneItem {
+                            fullname: comment.name,
+                            subreddit: comment.subreddit,
+                            score: comment.score,
+                            created_utc: comment.created_utc,
+                            permalink: comment.permalink,
+                            excerpt: snippet(&comment.body, MODMAIL_EXCERPT_CHARS),
+                        })
+                        .collect(),
+                })
+            }
+            None => None,
+        };
+        drafting_notes.push(format!(
+            "Send the reply with send_modmail_reply using conversation_id {}; it is signed as r/{} unless as_subreddit is false.",
+            conversation.id, subreddit
+        ));
+
+        serde_json::to_string(&ModmailContextResponse {
+            conversation_id: conversation.id,
+            subreddit,
+            subject: conversation.subject,
+            internal_conversation: conversation.is_internal,
+            messages,
+            user,
+            rules,
+            drafting_notes,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    pub(super) async fn send_modmail(
+        &self,
+        conversation_id: &str,
+        body: String,
+        as_subreddit: bool,
+        internal: bool,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<ModmailReplyResponse, RedditError> {
+        let url = format!("{}/api/mod/conversations/{}", OAUTH_URL, conversation_id);
+        let response = deadline
+            .run(
+                self.post_form::<ModmailConversationResponse, ModmailReplyRequest>(
+                    &url,
+                    auth_token,
+                    ModmailReplyRequest {
+                        body,
+                        is_author_hidden: as_subreddit,
+                        is_internal: internal,
+                    },
+                ),
+            )
+            .await??;
+        Ok(ModmailReplyResponse {
+            conversation_id: response.conversation.id,
+            sent: true,
+            as_subreddit,
+            internal,
+            message_count: response.messages.len(),
+        })
+    }
+
+    #[tool(
+        description = "For moderators: reply to a modmail conversation, e.g. with a reply drafted from get_modmail_context. When the server requires approval ([replies] require_approval), the reply is queued for approve_reply instead of being sent."
+    )]
+    pub(super) async fn send_modmail_reply(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Modmail conversation id or mod.reddit.com/mail URL")]
+        conversation_id: String,
+        #[tool(param)]
+        #[schemars(description = "Reply text in markdown")]
+        body: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Sign the reply as the subreddit instead of the moderator account (default true)"
+        )]
+        as_subreddit: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Post a private moderator note visible only to the mod team (default false)"
+        )]
+        internal: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of sending the reply again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        let conversation_id = normalize_conversation_id(&conversation_id)?;
+        tracing::info!("Replying to modmail conversation {}", conversation_id);
+
+        if body.trim().is_empty() {
+            return Err(RedditError::invalid_input(
+                "The reply body is empty",
+                "pass the text of the reply",
+            ));
+        }
+        let as_subreddit = as_subreddit.unwrap_or(true);
+        let internal = internal.unwrap_or(false);
+
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "send_modmail_reply",
+            idempotency_key.as_deref(),
+            serde_json::json!({
+                "conversation_id": conversation_id,
+                "body": body,
+                "as_subreddit": as_subreddit,
+                "internal": internal,
+            }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let result = if self.config.get().replies.require_approval {
+            self.send_or_queue(
+                ReplyTarget::Modmail {
+                    conversation_id,
+                    as_subreddit,
+                    internal,
+                },
+                body,
+                "send_modmail_reply",
+            )
+            .await?
+        } else {
+            let deadline = Deadline::for_tool("send_modmail_reply");
+            let auth_token = self.auth_token().await?;
+            let response = self
+                .send_modmail(
+                    &conversation_id,
+                    body,
+                    as_subreddit,
+                    internal,
+                    &auth_token,
+                    &deadline,
+                )
+                .await?;
+            serde_json::to_string(&response).map_err(RedditError::serialize)?
+        };
+        claim.record(&result);
+        Ok(result)
+    }
+}
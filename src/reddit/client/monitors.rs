@@ -0,0 +1,943 @@
+use std::env;
+use std::time::Duration;
+
+use regex::{Regex, RegexBuilder};
+use rmcp::tool;
+
+use rmcp::model::LoggingLevel;
+
+use super::{BASE_URL, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    AnomalyStat, ComposeRequest, Listing, ListingRequest, Monitor, MonitorEvent,
+    MonitorPreviewItem, MonitorPreviewResponse, MonitorRule, MonitorSummary, Post, PostFilter,
+    RawJsonRequest, TrafficPreviewResponse, TrafficResponse,
+};
+use crate::reddit::validation::normalize_username;
+use crate::storage::Namespace;
+
+const DEFAULT_MONITOR_POLL_SECS: u64 = 120;
+/// Id of the polling task in list_jobs.
+const MONITOR_TASK: &str = "monitor_polling";
+const MONITOR_POSTS_PER_POLL: u32 = 25;
+const MAX_MONITOR_EVENTS: usize = 50;
+const POST_KINDS: [&str; 2] = ["link", "self"];
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+const DEFAULT_THRESHOLD_SIGMA: f64 = 3.0;
+const ANOMALY_BASELINE_HOURS: i64 = 72;
+const MIN_BASELINE_HOURS: usize = 6;
+const VELOCITY_POSTS: u32 = 100;
+
+fn validate_monitor_name(name: &str) -> Result<String, RedditError> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid monitor name", name),
+            "use 1-64 letters, digits, underscores or dashes, e.g. source-reminder",
+        ));
+    }
+    Ok(name)
+}
+
+fn no_monitor(name: &str) -> RedditError {
+    RedditError::not_found(
+        format!("No monitor named {}", name),
+        "call list_monitors to see the configured monitors",
+    )
+}
+
+fn compile_title_pattern(filter: &PostFilter) -> Result<Option<Regex>, RedditError> {
+    filter
+        .title_pattern
+        .as_deref()
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| {
+                    RedditError::invalid_input(
+                        format!("Invalid title_pattern: {}", e),
+                        "pass a regular expression such as (?:study|paper)",
+                    )
+                })
+        })
+        .transpose()
+}
+
+fn post_domain(post: &Post) -> Option<String> {
+    let url = reqwest::Url::parse(&post.url).ok()?;
+    let host = url.host_str()?;
+    Some(
+        host.strip_prefix("www.")
+            .unwrap_or(host)
+            .to_ascii_lowercase(),
+    )
+}
+
+fn post_matches(filter: &PostFilter, title_pattern: Option<&Regex>, post: &Post) -> bool {
+    let kind_matches = match filter.post_kind.as_deref() {
+        Some("link") => !post.is_self,
+        Some("self") => post.is_self,
+        _ => true,
+    };
+    let flair_matches = filter.flairs.is_empty()
+        || post.link_flair_text.as_deref().is_some_and(|flair| {
+            filter
+                .flairs
+                .iter()
+                .any(|wanted| wanted.eq_ignore_ascii_case(flair))
+        });
+    let domain_matches = filter.domains.is_empty()
+        || post_domain(post).is_some_and(|domain| {
+            filter.domains.iter().any(|wanted| {
+                let wanted = wanted.trim_start_matches("www.").to_ascii_lowercase();
+                domain == wanted || domain.ends_with(&format!(".{}", wanted))
+            })
+        });
+    kind_matches
+        && flair_matches
+        && domain_matches
+        && title_pattern.is_none_or(|pattern| pattern.is_match(&post.title))
+}
+
+/// Fills the `{author}`, `{title}`, `{subreddit}` and `{permalink}` placeholders.
+fn render_template(template: &str, post: &Post) -> String {
+    template
+        .replace("{author}", &post.author)
+        .replace("{title}", &post.title)
+        .replace("{subreddit}", &post.subreddit)
+        .replace(
+            "{permalink}",
+            &format!("https://www.reddit.com{}", post.permalink),
+        )
+}
+
+/// Compares one hour of a metric with the hours before it, or returns `None` when
+/// there are too few of them. The standard deviation is taken as at least 1 so
+/// a quiet subreddit does not alert on a single extra post.
+fn anomaly_stat(
+    metric: &str,
+    hour_utc: i64,
+    value: f64,
+    baseline: &[f64],
+    threshold_sigma: f64,
+) -> Option<AnomalyStat> {
+    if baseline.len() < MIN_BASELINE_HOURS {
+        return None;
+    }
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+    let mean = baseline.iter().sum::<f64>() / baseline.len() as f64;
+    let variance = baseline
+        .iter()
+        .map(|sample| (sample - mean).powi(2))
+        .sum::<f64>()
+        / baseline.len() as f64;
+    let std_dev = variance.sqrt();
+    let sigma = (value - mean) / std_dev.max(1.0);
+    Some(AnomalyStat {
+        metric: metric.to_string(),
+        hour_utc,
+        value,
+        baseline_mean: round(mean),
+        baseline_std_dev: round(std_dev),
+        baseline_hours: baseline.len(),
+        sigma: round(sigma),
+        spike: sigma >= threshold_sigma,
+    })
+}
+
+/// Unique visitors and pageviews of the newest complete hour of traffic.
+fn traffic_stats(traffic: &TrafficResponse, now: i64, threshold_sigma: f64) -> Vec<AnomalyStat> {
+    let mut hours: Vec<&Vec<f64>> = traffic
+        .hour
+        .iter()
+        .filter(|entry| entry.len() >= 3)
+        .collect();
+    hours.sort_by(|a, b| b[0].total_cmp(&a[0]));
+    let Some(latest) = hours
+        .iter()
+        .position(|entry| entry[0] as i64 + SECONDS_PER_HOUR <= now)
+    else {
+        return Vec::new();
+    };
+    let hour_utc = hours[latest][0] as i64;
+    let baseline = &hours[latest + 1..];
+    let baseline = &baseline[..baseline.len().min(ANOMALY_BASELINE_HOURS as usize)];
+
+    [("uniques", 1), ("pageviews", 2)]
+        .into_iter()
+        .filter_map(|(metric, index)| {
+            let samples: Vec<f64> = baseline.iter().map(|entry| entry[index]).collect();
+            anomaly_stat(
+                metric,
+                hour_utc,
+                hours[latest][index],
+                &samples,
+                threshold_sigma,
+            )
+        })
+        .collect()
+}
+
+/// New posts in the newest complete hour, against the earlier hours the
+/// listing fully covers.
+fn velocity_stat(posts: &[Post], now: i64, threshold_sigma: f64) -> Option<AnomalyStat> {
+    let hour_utc = now / SECONDS_PER_HOUR * SECONDS_PER_HOUR - SECONDS_PER_HOUR;
+    let oldest = posts.iter().map(|post| post.created_utc as i64).min()?;
+    let covered_from = if posts.len() < VELOCITY_POSTS as usize {
+        hour_utc - ANOMALY_BASELINE_HOURS * SECONDS_PER_HOUR
+    } else {
+        (oldest / SECONDS_PER_HOUR + 1) * SECONDS_PER_HOUR
+    };
+    let count = |start: i64| {
+        posts
+            .iter()
+            .filter(|post| (start..start + SECONDS_PER_HOUR).contains(&(post.created_utc as i64)))
+            .count() as f64
+    };
+    let baseline: Vec<f64> = (1..=ANOMALY_BASELINE_HOURS)
+        .map(|hours_back| hour_utc - hours_back * SECONDS_PER_HOUR)
+        .take_while(|start| *start >= covered_from)
+        .map(count)
+        .collect();
+    anomaly_stat(
+        "new_posts",
+        hour_utc,
+        count(hour_utc),
+        &baseline,
+        threshold_sigma,
+    )
+}
+
+fn spike_message(subreddit: &str, stat: &AnomalyStat) -> String {
+    let hour = chrono::DateTime::from_timestamp(stat.hour_utc, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| stat.hour_utc.to_string());
+    format!(
+        "r/{}: {} {} in the hour from {}, {:.1} standard deviations above the {}-hour mean of {:.1}. This may be brigading or a post going viral.",
+        subreddit,
+        stat.value,
+        stat.metric,
+        hour,
+        stat.sigma,
+        stat.baseline_hours,
+        stat.baseline_mean
+    )
+}
+
+fn summarize(monitor: &Monitor) -> MonitorSummary {
+    MonitorSummary {
+        name: monitor.name.clone(),
+        account: monitor.account.clone(),
+        subreddit: monitor.subreddit.clone(),
+        kind: match monitor.rule {
+            MonitorRule::StickyComment { .. } => "sticky_comment",
+            MonitorRule::TrafficAnomaly { .. } => "traffic_anomaly",
+        },
+        dry_run: monitor.dry_run,
+        last_checked_utc: monitor.last_checked_utc,
+        last_error: monitor.last_error.clone(),
+        events: monitor.events.len(),
+    }
+}
+
+impl RedditClient {
+    fn load_monitor(&self, name: &str) -> Result<Monitor, RedditError> {
+        self.storage
+            .read_json::<Monitor>(Namespace::Monitors, name)
+            .map_err(RedditError::storage)?
+            .ok_or_else(|| no_monitor(name))
+    }
+
+    /// Polls every stored monitor in the background, every `MONITOR_POLL_SECS`
+    /// seconds (default 120, 0 disables polling).
+    pub fn spawn_monitors(&self) {
+        let interval = env::var("MONITOR_POLL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(DEFAULT_MONITOR_POLL_SECS);
+        if interval == 0 {
+            tracing::info!("Monitor polling is disabled");
+            return;
+        }
+
+        let cancelled = self.tasks.register(
+            MONITOR_TASK,
+            MONITOR_TASK,
+            format!("Checks every monitor every {} seconds", interval),
+        );
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if client.tasks.is_paused(MONITOR_TASK) {
+                    continue;
+                }
+                let result = client.poll_monitors().await;
+                if let Err(e) = &result {
+                    tracing::error!("Monitor poll failed: {}", e);
+                }
+                client
+                    .tasks
+                    .record_run(MONITOR_TASK, result.err().map(|e| e.to_string()));
+            }
+            tracing::info!("Monitor polling was cancelled");
+        });
+    }
+
+    async fn poll_monitors(&self) -> Result<(), RedditError> {
+        let names = self
+            .storage
+            .keys(Namespace::Monitors)
+            .map_err(RedditError::storage)?;
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        for name in names {
+            let mut monitor = match self.load_monitor(&name) {
+                Ok(monitor) => monitor,
+                Err(e) => {
+                    tracing::error!("Skipping monitor {}: {}", name, e);
+                    continue;
+                }
+            };
+            let created_utc = monitor.created_utc;
+            let account = monitor.account.clone();
+            let checked = self
+                .as_record_account(&account, async {
+                    let auth_token = self.auth_token().await?;
+                    self.check_monitor(&mut monitor, &auth_token).await;
+                    Ok(())
+                })
+                .await;
+            if let Err(e) = checked {
+                tracing::error!("Monitor {} could not be checked: {}", name, e);
+                monitor.last_checked_utc = Some(chrono::Utc::now().timestamp());
+                monitor.last_error = Some(e.to_string());
+            }
+
+            // The monitor may have been deleted or replaced while it was checked.
+            let current = self
+                .storage
+                .read_json::<Monitor>(Namespace::Monitors, &name)
+                .map_err(RedditError::storage)?;
+            if current.is_some_and(|current| current.created_utc == created_utc) {
+                self.storage
+                    .write_json(Namespace::Monitors, &name, &monitor)
+                    .map_err(RedditError::storage)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_monitor(&self, monitor: &mut Monitor, auth_token: &str) {
+        let now = chrono::Utc::now().timestamp();
+        monitor.last_checked_utc = Some(now);
+
+        let events = match monitor.rule.clone() {
+            MonitorRule::StickyComment { filter, template } => {
+                self.check_sticky_comment_monitor(monitor, &filter, &template, auth_token, now)
+                    .await
+            }
+            MonitorRule::TrafficAnomaly {
+                threshold_sigma,
+                notify_user,
+            } => {
+                self.check_traffic_monitor(
+                    monitor,
+                    threshold_sigma,
+                    notify_user.as_deref(),
+                    auth_token,
+                    now,
+                )
+                .await
+            }
+        };
+
+        monitor.events.extend(events);
+        let excess = monitor.events.len().saturating_sub(MAX_MONITOR_EVENTS);
+        monitor.events.drain(..excess);
+    }
+
+    async fn check_sticky_comment_monitor(
+        &self,
+        monitor: &mut Monitor,
+        filter: &PostFilter,
+        template: &str,
+        auth_token: &str,
+        now: i64,
+    ) -> Vec<MonitorEvent> {
+        let url = format!("{}/r/{}/new", OAUTH_URL, monitor.subreddit);
+        let listing = self
+            .get_request_with_priority::<Listing<Post>, ListingRequest>(
+                &url,
+                auth_token,
+                ListingRequest {
+                    limit: MONITOR_POSTS_PER_POLL,
+                    after: None,
+                    before: None,
+                    raw_json: 1,
+                },
+                Priority::Background,
+            )
+            .await;
+        let mut posts: Vec<Post> = match listing {
+            Ok(listing) => listing
+                .data
+                .children
+                .into_iter()
+                .map(|child| child.data)
+                .filter(|post| post.created_utc > monitor.watermark_utc)
+                .collect(),
+            Err(e) => {
+                monitor.last_error = Some(e.to_string());
+                return Vec::new();
+            }
+        };
+        monitor.last_error = None;
+        posts.sort_by(|a, b| a.created_utc.total_cmp(&b.created_utc));
+
+        let title_pattern = match compile_title_pattern(filter) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                monitor.last_error = Some(e.to_string());
+                return Vec::new();
+            }
+        };
+
+        let mut events = Vec::new();
+        for post in posts {
+            monitor.watermark_utc = monitor.watermark_utc.max(post.created_utc);
+            if !post_matches(filter, title_pattern.as_ref(), &post) {
+                continue;
+            }
+            let comment = render_template(template, &post);
+            let (outcome, detail) = if monitor.dry_run {
+                ("would_comment", Some(comment))
+            } else {
+                match self
+                    .post_mod_reply(
+                        &post.name,
+                        comment,
+                        true,
+                        false,
+                        auth_token,
+                        Priority::Background,
+                    )
+                    .await
+                {
+                    Ok((reply, None)) => ("commented", Some(reply)),
+                    Ok((reply, Some(e))) => (
+                        "commented",
+                        Some(format!("{} (not stickied: {})", reply, e)),
+                    ),
+                    Err(e) => ("failed", Some(e.to_string())),
+                }
+            };
+            tracing::info!("Monitor {} {} on {}", monitor.name, outcome, post.name);
+            events.push(MonitorEvent {
+                checked_utc: now,
+                post: post.name,
+                title: post.title,
+                anomaly: None,
+                outcome: outcome.to_string(),
+                detail,
+            });
+        }
+        events
+    }
+
+    /// Hourly traffic and new-post velocity compared with their baselines, and
+    /// the reasons for the metrics that could not be computed.
+    async fn fetch_anomaly_stats(
+        &self,
+        subreddit: &str,
+        threshold_sigma: f64,
+        auth_token: &str,
+        priority: Priority,
+    ) -> (Vec<AnomalyStat>, Vec<String>) {
+        let now = chrono::Utc::now().timestamp();
+        let mut stats = Vec::new();
+        let mut unavailable = Vec::new();
+
+        let traffic = self
+            .get_request_with_priority::<TrafficResponse, RawJsonRequest>(
+                &format!("{}/r/{}/about/traffic", OAUTH_URL, subreddit),
+                auth_token,
+                RawJsonRequest { raw_json: 1 },
+                priority,
+            )
+            .await;
+        match traffic {
+            Ok(traffic) => {
+                let traffic = traffic_stats(&traffic, now, threshold_sigma);
+                if traffic.is_empty() {
+                    unavailable.push("traffic: not enough hourly traffic data yet".to_string());
+                }
+                stats.extend(traffic);
+            }
+            Err(e) => unavailable.push(format!("traffic: {}", e.error)),
+        }
+
+        let posts = self
+            .get_request_with_priority::<Listing<Post>, ListingRequest>(
+                &format!("{}/r/{}/new", OAUTH_URL, subreddit),
+                auth_token,
+                ListingRequest {
+                    limit: VELOCITY_POSTS,
+                    after: None,
+                    before: None,
+                    raw_json: 1,
+                },
+                priority,
+            )
+            .await;
+        match posts {
+            Ok(listing) => {
+                let posts: Vec<Post> = listing
+                    .data
+                    .children
+                    .into_iter()
+                    .map(|child| child.data)
+                    .collect();
+                match velocity_stat(&posts, now, threshold_sigma) {
+                    Some(stat) => stats.push(stat),
+                    None => unavailable.push(format!(
+                        "new_posts: the newest {} posts cover fewer than {} earlier hours",
+                        VELOCITY_POSTS, MIN_BASELINE_HOURS
+                    )),
+                }
+            }
+            Err(e) => unavailable.push(format!("new_posts: {}", e.error)),
+        }
+
+        (stats, unavailable)
+    }
+
+    async fn check_traffic_monitor(
+        &self,
+        monitor: &mut Monitor,
+        threshold_sigma: f64,
+        notify_user: Option<&str>,
+        auth_token: &str,
+        now: i64,
+    ) -> Vec<MonitorEvent> {
+        let (stats, unavailable) = self
+            .fetch_anomaly_stats(
+                &monitor.subreddit,
+                threshold_sigma,
+                auth_token,
+                Priority::Background,
+            )
+            .await;
+        monitor.last_error = (!unavailable.is_empty()).then(|| unavailable.join("; "));
+
+        // Every hour is checked once, even when a later poll sees it again.
+        let traffic_watermark = monitor.traffic_watermark_utc.unwrap_or(0.0);
+        let posts_watermark = monitor.watermark_utc;
+        let mut events = Vec::new();
+        for stat in stats {
+            let hour = stat.hour_utc as f64;
+            if stat.metric == "new_posts" {
+                if hour <= posts_watermark {
+                    continue;
+                }
+                monitor.watermark_utc = hour;
+            } else {
+                if hour <= traffic_watermark {
+                    continue;
+                }
+                monitor.traffic_watermark_utc = Some(hour);
+            }
+            if !stat.spike {
+                continue;
+            }
+
+            let message = spike_message(&monitor.subreddit, &stat);
+            let (outcome, detail) = if monitor.dry_run {
+                ("would_notify", Some(message))
+            } else {
+                self.notify_spike(monitor, &stat, message, notify_user, auth_token)
+                    .await
+            };
+            tracing::info!(
+                "Monitor {} {} on {} spike",
+                monitor.name,
+                outcome,
+                stat.metric
+            );
+            events.push(MonitorEvent {
+                checked_utc: now,
+                post: String::new(),
+                title: String::new(),
+                anomaly: Some(stat),
+                outcome: outcome.to_string(),
+                detail,
+            });
+        }
+        events
+    }
+
+    /// Tells the connected clients about a spike and messages `notify_user`.
+    async fn notify_spike(
+        &self,
+        monitor: &Monitor,
+        stat: &AnomalyStat,
+        message: String,
+        notify_user: Option<&str>,
+        auth_token: &str,
+    ) -> (&'static str, Option<String>) {
+        self.notify_clients(
+            LoggingLevel::Warning,
+            &format!("monitor:{}", monitor.name),
+            serde_json::json!({
+                "monitor": monitor.name,
+                "subreddit": monitor.subreddit,
+                "message": message,
+                "anomaly": stat,
+            }),
+        )
+        .await;
+
+        let Some(user) = notify_user else {
+            return ("notified", Some(message));
+        };
+        let sent = self
+            .post_api_with_priority::<serde_json::Value, ComposeRequest>(
+                &format!("{}/compose", BASE_URL),
+                auth_token,
+                ComposeRequest {
+                    to: user.to_string(),
+                    subject: format!("Activity spike in r/{}", monitor.subreddit),
+                    text: message.clone(),
+                },
+                Priority::Background,
+            )
+            .await;
+        match sent {
+            Ok(_) => (
+                "notified",
+                Some(format!("{} (messaged u/{})", message, user)),
+            ),
+            Err(e) => (
+                "failed",
+                Some(format!("message to u/{} failed: {}", user, e)),
+            ),
+        }
+    }
+
+    /// Resolves a subreddit the account moderates.
+    async fn moderated_subreddit(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+        deadline: &Deadline,
+        purpose: &str,
+    ) -> Result<String, RedditError> {
+        let subreddit = deadline
+            .run(self.resolve_subreddit(subreddit, auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, auth_token))
+            .await??;
+        if about.user_is_moderator != Some(true) {
+            return Err(RedditError::invalid_input(
+                format!("The account does not moderate r/{}", subreddit),
+                format!("{} need a subreddit the account moderates", purpose),
+            ));
+        }
+        Ok(subreddit)
+    }
+
+    #[tool(
+        description = "For moderators: create a monitor that watches a subreddit for new posts matching a filter and replies to each with a templated comment, distinguished and stickied (e.g. a source-required reminder). Monitors start in dry-run mode, which only records what would be posted; check with get_monitor or preview_monitor, then recreate with dry_run=false. Creating a monitor under an existing name replaces it."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn create_sticky_comment_monitor(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the monitor, e.g. source-reminder")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Moderated subreddit to watch")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Comment in markdown. {author}, {title}, {subreddit} and {permalink} are replaced with the post's values."
+        )]
+        template: String,
+        #[tool(param)]
+        #[schemars(description = "Only posts whose title matches this regular expression")]
+        title_pattern: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only posts with one of these flairs")]
+        flairs: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(description = "Only link posts to these domains, e.g. youtube.com")]
+        domains: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(description = "Only link or self posts")]
+        post_kind: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Record what would be posted without commenting (default true)")]
+        dry_run: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let name = validate_monitor_name(&name)?;
+        tracing::info!("Creating sticky comment monitor {}", name);
+
+        if template.trim().is_empty() {
+            return Err(RedditError::invalid_input(
+                "The comment template is empty",
+                "pass the markdown text of the comment",
+            ));
+        }
+        if let Some(kind) = &post_kind
+            && !POST_KINDS.contains(&kind.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown post_kind {}", kind),
+                "use link or self, or omit it to match both",
+            ));
+        }
+        let filter = PostFilter {
+            title_pattern,
+            flairs: flairs.unwrap_or_default(),
+            domains: domains.unwrap_or_default(),
+            post_kind,
+        };
+        compile_title_pattern(&filter)?;
+
+        let deadline = Deadline::for_tool("create_sticky_comment_monitor");
+        let auth_token = self.auth_token().await?;
+        let subreddit = self
+            .moderated_subreddit(
+                &subreddit,
+                &auth_token,
+                &deadline,
+                "sticky comment monitors",
+            )
+            .await?;
+
+        let now = chrono::Utc::now().timestamp();
+        let monitor = Monitor {
+            name: name.clone(),
+            account: self.account().name.clone(),
+            subreddit,
+            created_utc: now,
+            dry_run: dry_run.unwrap_or(true),
+            rule: MonitorRule::StickyComment { filter, template },
+            // Only posts made after the monitor was created are handled.
+            watermark_utc: now as f64,
+            traffic_watermark_utc: None,
+            last_checked_utc: None,
+            last_error: None,
+            events: Vec::new(),
+        };
+        self.storage
+            .write_json(Namespace::Monitors, &name, &monitor)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&summarize(&monitor)).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: create a monitor that watches a subreddit's hourly traffic (/about/traffic) and new-post velocity, and alerts when the newest complete hour is more than threshold_sigma standard deviations above the previous 72 hours, hinting at brigading or a post going viral. Alerts are recorded as monitor events, pushed to connected clients as log notifications and optionally sent as a private message. Monitors start in dry-run mode, which only records the alerts. Creating a monitor under an existing name replaces it."
+    )]
+    pub(super) async fn create_traffic_anomaly_monitor(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the monitor, e.g. brigade-watch")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Moderated subreddit to watch")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Alert when an hour is this many standard deviations above the baseline (default 3)"
+        )]
+        threshold_sigma: Option<f64>,
+        #[tool(param)]
+        #[schemars(description = "Username to send a private message to for every alert")]
+        notify_user: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Record alerts without notifying anyone (default true)")]
+        dry_run: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let name = validate_monitor_name(&name)?;
+        tracing::info!("Creating traffic anomaly monitor {}", name);
+
+        let threshold_sigma = threshold_sigma.unwrap_or(DEFAULT_THRESHOLD_SIGMA);
+        if !(threshold_sigma.is_finite() && threshold_sigma > 0.0) {
+            return Err(RedditError::invalid_input(
+                format!("threshold_sigma {} is not positive", threshold_sigma),
+                "pass a number of standard deviations such as 3",
+            ));
+        }
+        let notify_user = notify_user
+            .map(|user| normalize_username(&user))
+            .transpose()?;
+
+        let deadline = Deadline::for_tool("create_traffic_anomaly_monitor");
+        let auth_token = self.auth_token().await?;
+        let subreddit = self
+            .moderated_subreddit(
+                &subreddit,
+                &auth_token,
+                &deadline,
+                "traffic anomaly monitors",
+            )
+            .await?;
+
+        let monitor = Monitor {
+            name: name.clone(),
+            account: self.account().name.clone(),
+            subreddit,
+            created_utc: chrono::Utc::now().timestamp(),
+            dry_run: dry_run.unwrap_or(true),
+            rule: MonitorRule::TrafficAnomaly {
+                threshold_sigma,
+                notify_user,
+            },
+            // The newest complete hour is checked on the first poll.
+            watermark_utc: 0.0,
+            traffic_watermark_utc: None,
+            last_checked_utc: None,
+            last_error: None,
+            events: Vec::new(),
+        };
+        self.storage
+            .write_json(Namespace::Monitors, &name, &monitor)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&summarize(&monitor)).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "List the background monitors and when each last ran.")]
+    pub(super) async fn list_monitors(&self) -> Result<String, RedditError> {
+        let mut monitors = Vec::new();
+        for name in self
+            .storage
+            .keys(Namespace::Monitors)
+            .map_err(RedditError::storage)?
+        {
+            monitors.push(summarize(&self.load_monitor(&name)?));
+        }
+        serde_json::to_string(&monitors).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Show a monitor's definition and its recent events, including what a dry-run monitor would have posted."
+    )]
+    pub(super) async fn get_monitor(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the monitor")]
+        name: String,
+    ) -> Result<String, RedditError> {
+        let monitor = self.load_monitor(&validate_monitor_name(&name)?)?;
+        serde_json::to_string(&monitor).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Delete a background monitor.")]
+    pub(super) async fn delete_monitor(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the monitor")]
+        name: String,
+    ) -> Result<String, RedditError> {
+        let name = validate_monitor_name(&name)?;
+        tracing::info!("Deleting monitor {}", name);
+
+        if !self
+            .storage
+            .remove(Namespace::Monitors, &name)
+            .map_err(RedditError::storage)?
+        {
+            return Err(no_monitor(&name));
+        }
+        Ok(format!("Deleted monitor {}", name))
+    }
+
+    #[tool(
+        description = "Preview a monitor without acting: for a sticky comment monitor, which of the subreddit's newest posts its filter matches and the comment each would get; for a traffic anomaly monitor, the current hourly statistics and whether they count as a spike."
+    )]
+    pub(super) async fn preview_monitor(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the monitor")]
+        name: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Number of newest posts to check, 1-100 (default 25); sticky comment monitors only"
+        )]
+        limit: Option<u32>,
+    ) -> Result<String, RedditError> {
+        let monitor = self.load_monitor(&validate_monitor_name(&name)?)?;
+        tracing::info!("Previewing monitor {}", monitor.name);
+
+        let deadline = Deadline::for_tool("preview_monitor");
+        // Read as the monitor's account, which may see what the caller cannot.
+        let auth_token = self
+            .as_record_account(&monitor.account, self.auth_token())
+            .await?;
+        let (filter, template) = match &monitor.rule {
+            MonitorRule::StickyComment { filter, template } => (filter, template),
+            MonitorRule::TrafficAnomaly {
+                threshold_sigma, ..
+            } => {
+                let (stats, unavailable) = deadline
+                    .run(self.fetch_anomaly_stats(
+                        &monitor.subreddit,
+                        *threshold_sigma,
+                        &auth_token,
+                        Priority::Interactive,
+                    ))
+                    .await?;
+                return serde_json::to_string(&TrafficPreviewResponse {
+                    name: monitor.name.clone(),
+                    subreddit: monitor.subreddit.clone(),
+                    threshold_sigma: *threshold_sigma,
+                    stats,
+                    unavailable,
+                })
+                .map_err(RedditError::serialize);
+            }
+        };
+        let limit = limit.unwrap_or(MONITOR_POSTS_PER_POLL).clamp(1, 100);
+        let url = format!("{}/r/{}/new", OAUTH_URL, monitor.subreddit);
+        let (posts, _) = self
+            .collect_listing::<Post>(&url, &auth_token, limit, &deadline)
+            .await?;
+
+        let title_pattern = compile_title_pattern(filter)?;
+        let matches = posts
+            .iter()
+            .filter(|post| post_matches(filter, title_pattern.as_ref(), post))
+            .map(|post| MonitorPreviewItem {
+                post: post.name.clone(),
+                title: post.title.clone(),
+                permalink: post.permalink.clone(),
+                comment: render_template(template, post),
+            })
+            .collect();
+
+        serde_json::to_string(&MonitorPreviewResponse {
+            name: monitor.name,
+            subreddit: monitor.subreddit,
+            posts_checked: posts.len(),
+            matches,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
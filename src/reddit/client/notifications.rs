@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use rmcp::tool;
+
+use super::RedditClient;
+use super::threads::{MAX_THREAD_DEPTH, THREAD_COMMENT_LIMIT};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    NotificationOptOuts, NotifiedUsers, OptOut, OptOutChangedResponse, OptOutsResponse,
+    ParticipantNotice, ReplyTarget, ThreadNotificationResponse, TreeComment,
+};
+use crate::reddit::validation::{normalize_post_id, normalize_username};
+use crate::storage::Namespace;
+
+const OPT_OUTS_KEY: &str = "opt-outs";
+const NOTIFIED_KEY: &str = "notified";
+/// Accounts that are never messaged.
+const SKIPPED_AUTHORS: [&str; 2] = ["[deleted]", "AutoModerator"];
+
+/// Adds the authors of `comments` and their replies in thread order, once each.
+fn collect_authors(
+    comments: &[TreeComment],
+    seen: &mut HashSet<String>,
+    authors: &mut Vec<String>,
+) {
+    for comment in comments {
+        if seen.insert(comment.author.to_lowercase()) {
+            authors.push(comment.author.clone());
+        }
+        collect_authors(&comment.replies.0, seen, authors);
+    }
+}
+
+fn notice(username: String, status: &'static str) -> ParticipantNotice {
+    ParticipantNotice {
+        username,
+        status,
+        reply_id: None,
+        error: None,
+    }
+}
+
+impl RedditClient {
+    fn load_opt_outs(&self) -> Result<NotificationOptOuts, RedditError> {
+        self.storage
+            .read_json::<NotificationOptOuts>(Namespace::Notifications, OPT_OUTS_KEY)
+            .map_err(RedditError::storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    #[tool(
+        description = "Send a private message to the participants of a thread, e.g. to notify the people who RSVPed to an event. The post's author and every commenter get the message once, up to a cap; users on the opt-out list and users notified recently are skipped, and messages are spaced out. With [replies] require_approval the messages are queued instead. Use dry_run to see who would be messaged."
+    )]
+    pub(super) async fn notify_thread_participants(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+        #[tool(param)]
+        #[schemars(description = "Message subject")]
+        subject: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Message text in markdown; {username}, {title}, {permalink} and {subreddit} are filled in for each recipient"
+        )]
+        template: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Most users to message; defaults to and cannot exceed [notifications] max_recipients"
+        )]
+        max_recipients: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "Only report who would be messaged (default false)")]
+        dry_run: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Notifying the participants of {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let subject = subject.trim().to_string();
+        if subject.is_empty() || template.trim().is_empty() {
+            return Err(RedditError::invalid_input(
+                "The subject or the template is empty",
+                "pass a subject and the message text",
+            ));
+        }
+        let config = self.config.get();
+        let max_recipients = max_recipients
+            .unwrap_or(config.notifications.max_recipients)
+            .min(config.notifications.max_recipients) as usize;
+        let dry_run = dry_run.unwrap_or(false);
+        let require_approval = config.replies.require_approval;
+
+        let deadline = Deadline::for_tool("notify_thread_participants");
+        let auth_token = self.auth_token().await?;
+        let (post, tree) = deadline
+            .run(self.fetch_thread(
+                &post_id,
+                "old",
+                MAX_THREAD_DEPTH,
+                THREAD_COMMENT_LIMIT,
+                &auth_token,
+            ))
+            .await??;
+
+        let mut seen = HashSet::from([self.account().name.to_lowercase()]);
+        seen.extend(SKIPPED_AUTHORS.iter().map(|author| author.to_lowercase()));
+        let mut participants = Vec::new();
+        if seen.insert(post.author.to_lowercase()) {
+            participants.push(post.author.clone());
+        }
+        collect_authors(&tree.0, &mut seen, &mut participants);
+
+        let opt_outs = self.load_opt_outs()?;
+        let mut notified = self
+            .storage
+            .read_json::<NotifiedUsers>(Namespace::Notifications, NOTIFIED_KEY)
+            .map_err(RedditError::storage)?
+            .unwrap_or_default();
+        let now = chrono::Utc::now().timestamp();
+        let cooldown = i64::from(config.notifications.cooldown_hours) * 60 * 60;
+        let interval = Duration::from_secs(config.notifications.send_interval_seconds);
+        let permalink = format!("https://www.reddit.com{}", post.permalink);
+
+        let participant_count = participants.len();
+        let mut recipients = Vec::new();
+        let mut sent = 0;
+        let mut truncated_reason = None;
+        for username in participants {
+            let key = username.to_lowercase();
+            if opt_outs.users.contains_key(&key) {
+                recipients.push(notice(username, "opted_out"));
+                continue;
+            }
+            if notified
+                .last_notified_utc
+                .get(&key)
+                .is_some_and(|last| now - last < cooldown)
+            {
+                recipients.push(notice(username, "notified_recently"));
+                continue;
+            }
+            if sent >= max_recipients {
+                recipients.push(notice(username, "over_cap"));
+                continue;
+            }
+            if dry_run {
+                sent += 1;
+                recipients.push(notice(username, "would_send"));
+                continue;
+            }
+            if truncated_reason.is_some() || deadline.is_expired() {
+                truncated_reason.get_or_insert_with(|| deadline.truncation_reason());
+                recipients.push(notice(username, "not_reached"));
+                continue;
+            }
+
+            let body = template
+                .replace("{username}", &username)
+                .replace("{title}", &post.title)
+                .replace("{permalink}", &permalink)
+                .replace("{subreddit}", &post.subreddit);
+            let target = ReplyTarget::Message {
+                to: username.clone(),
+                subject: subject.clone(),
+            };
+            let mut entry = notice(username, "sent");
+            let result = if require_approval {
+                self.queue_reply(target, body).map(|reply| {
+                    entry.status = "queued";
+                    entry.reply_id = Some(reply.id);
+                })
+            } else {
+                if sent > 0 {
+                    // Bounded so a long interval cannot outlast the call.
+                    let _ = deadline.run(tokio::time::sleep(interval)).await;
+                }
+                self.send_reply(&target, body, &auth_token, &deadline)
+                    .await
+                    .map(|_| ())
+            };
+            match result {
+                Ok(()) => {
+                    sent += 1;
+                    notified.last_notified_utc.insert(key, now);
+                    // Saved after every message so a failure later on cannot
+                    // lead to the same users being messaged twice.
+                    self.storage
+                        .write_json(Namespace::Notifications, NOTIFIED_KEY, &notified)
+                        .map_err(RedditError::storage)?;
+                }
+                Err(e) => {
+                    entry.status = "failed";
+                    entry.error = Some(e.to_string());
+                }
+            }
+            recipients.push(entry);
+        }
+
+        serde_json::to_string(&ThreadNotificationResponse {
+            post_id,
+            title: post.title,
+            dry_run,
+            require_approval,
+            participants: participant_count,
+            notified: sent,
+            recipients,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Add a user to the opt-out list of notify_thread_participants, so they are never messaged by it."
+    )]
+    pub(super) async fn add_notification_opt_out(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "Why or when the user opted out")]
+        note: Option<String>,
+    ) -> Result<String, RedditError> {
+        let username = normalize_username(&username)?;
+        tracing::info!("Opting u/{} out of notifications", username);
+
+        let mut opt_outs = self.load_opt_outs()?;
+        let key = username.to_lowercase();
+        let changed = !opt_outs.users.contains_key(&key);
+        opt_outs.users.insert(
+            key,
+            OptOut {
+                username: username.clone(),
+                added_utc: chrono::Utc::now().timestamp(),
+                note,
+            },
+        );
+        self.storage
+            .write_json(Namespace::Notifications, OPT_OUTS_KEY, &opt_outs)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&OptOutChangedResponse {
+            username,
+            opted_out: true,
+            changed,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Remove a user from the opt-out list of notify_thread_participants.")]
+    pub(super) async fn remove_notification_opt_out(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+    ) -> Result<String, RedditError> {
+        let username = normalize_username(&username)?;
+        tracing::info!("Opting u/{} back into notifications", username);
+
+        let mut opt_outs = self.load_opt_outs()?;
+        let changed = opt_outs.users.remove(&username.to_lowercase()).is_some();
+        if changed {
+            self.storage
+                .write_json(Namespace::Notifications, OPT_OUTS_KEY, &opt_outs)
+                .map_err(RedditError::storage)?;
+        }
+
+        serde_json::to_string(&OptOutChangedResponse {
+            username,
+            opted_out: false,
+            changed,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "List the users who opted out of notify_thread_participants.")]
+    pub(super) async fn list_notification_opt_outs(&self) -> Result<String, RedditError> {
+        tracing::info!("Listing notification opt-outs");
+
+        let opt_outs = self.load_opt_outs()?;
+        serde_json::to_string(&OptOutsResponse {
+            opt_outs: opt_outs.users.into_values().collect(),
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,311 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rmcp::tool;
+
+use super::RedditClient;
+use super::threads::{MAX_THREAD_DEPTH, THREAD_COMMENT_LIMIT};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    CommentPollResponse, CommentTree, PollOptionTally, TreeComment, UnparsedPollAnswer,
+};
+use crate::reddit::validation::normalize_post_id;
+
+/// Longer free text answers are taken for discussion rather than a vote.
+const MAX_FREE_TEXT_CHARS: usize = 80;
+const UNPARSED_SAMPLES: usize = 10;
+/// Accounts whose comments are never counted.
+const SKIPPED_AUTHORS: [&str; 2] = ["[deleted]", "AutoModerator"];
+
+/// A numbered line of a post, e.g. `1. Pizza` or `2) Tacos`.
+static NUMBERED_OPTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:[-*]\s+)?(\d{1,2})\\?[.)]\s+(.+?)\s*$")
+        .expect("valid numbered option regex")
+});
+
+/// An answer that starts with an option number, e.g. `2`, `#2` or `Option 2.`.
+static OPTION_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:#|option\s*)?(\d{1,3})(?:[.):\s]|$)").expect("valid option number regex")
+});
+
+/// The first run of options numbered 1, 2, 3... in `text`.
+fn numbered_options(text: &str) -> Vec<String> {
+    let mut options = Vec::new();
+    for captures in NUMBERED_OPTION.captures_iter(text) {
+        let number: usize = captures[1].parse().unwrap_or_default();
+        if number == options.len() + 1 {
+            options.push(captures[2].trim_matches('*').trim().to_string());
+        } else if !options.is_empty() {
+            break;
+        }
+    }
+    options
+}
+
+/// The value of the `field: value` line of `body`, allowing for bold markup.
+fn field_value<'a>(body: &'a str, field: &str) -> Option<&'a str> {
+    body.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches(['-', '*', ' ']);
+        let name = line.get(..field.len())?;
+        if !name.eq_ignore_ascii_case(field) {
+            return None;
+        }
+        let value = line[field.len()..]
+            .trim_start_matches('*')
+            .strip_prefix(':')?
+            .trim_start_matches(['*', ' '])
+            .trim();
+        (!value.is_empty()).then_some(value)
+    })
+}
+
+/// The first line of `body` that is not quoting someone else.
+fn first_line(body: &str) -> Option<&str> {
+    body.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('>'))
+}
+
+/// Whether `needle` occurs in `haystack` between non-alphanumeric characters.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Index of the option `answer` picks, by number or by naming it. When the
+/// answer names several options the longest name wins, so `dark red` is not
+/// counted for `red`; a tie is left unparsed.
+fn match_option(answer: &str, options: &[String]) -> Option<usize> {
+    let answer = answer.trim_matches(['*', '_', ' ']);
+    if let Some(captures) = OPTION_NUMBER.captures(answer) {
+        let number: usize = captures[1].parse().unwrap_or_default();
+        if (1..=options.len()).contains(&number) {
+            return Some(number - 1);
+        }
+    }
+
+    let answer = answer.to_lowercase();
+    let mut named: Vec<(usize, usize)> = options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| !option.is_empty())
+        .filter(|(_, option)| contains_word(&answer, &option.to_lowercase()))
+        .map(|(index, option)| (option.len(), index))
+        .collect();
+    named.sort_unstable_by(|a, b| b.cmp(a));
+    match named.as_slice() {
+        [] => None,
+        [(_, index)] => Some(*index),
+        [(longest, index), (next, _), ..] => (longest > next).then_some(*index),
+    }
+}
+
+/// Free text answers are grouped case-insensitively, ignoring trailing punctuation.
+fn free_text_key(answer: &str) -> Option<String> {
+    let answer = answer
+        .trim_matches(['*', '_', ' '])
+        .trim_end_matches(['.', '!', '?']);
+    if answer.is_empty() || answer.chars().count() > MAX_FREE_TEXT_CHARS {
+        return None;
+    }
+    Some(
+        answer
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase(),
+    )
+}
+
+fn collect_comments<'a>(
+    tree: &'a CommentTree,
+    include_replies: bool,
+    comments: &mut Vec<&'a TreeComment>,
+    hidden: &mut i64,
+) {
+    *hidden += tree.1.iter().map(|more| more.count).sum::<i64>();
+    for comment in &tree.0 {
+        comments.push(comment);
+        if include_replies {
+            collect_comments(&comment.replies, include_replies, comments, hidden);
+        }
+    }
+}
+
+fn share(votes: usize, counted: usize) -> f64 {
+    if counted == 0 {
+        0.0
+    } else {
+        (votes as f64 / counted as f64 * 1000.0).round() / 1000.0
+    }
+}
+
+impl RedditClient {
+    #[tool(
+        description = "Tally a poll run in a thread's comments. Each comment is read as a vote: an option number (\"2\", \"#2\"), an option name, or with field the value of a templated \"Field: value\" line. Options default to the numbered list in the post; without options the answers are tallied as short free text. Only a user's first answer counts. Stickied and distinguished comments are ignored."
+    )]
+    pub(super) async fn tally_comment_poll(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "The poll's options in order, so option n can also be voted for as n; defaults to the numbered list in the post's text"
+        )]
+        options: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(
+            description = "Read the answer from the comment line starting with this field name and a colon, e.g. Vote for \"Vote: 2\""
+        )]
+        field: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Also count replies, not just top-level comments (default false)"
+        )]
+        include_replies: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Tallying the comment poll of {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let field = field
+            .map(|field| field.trim().trim_end_matches(':').trim().to_string())
+            .filter(|field| !field.is_empty());
+        let include_replies = include_replies.unwrap_or(false);
+
+        let deadline = Deadline::for_tool("tally_comment_poll");
+        let auth_token = self.auth_token().await?;
+        let depth = if include_replies { MAX_THREAD_DEPTH } else { 1 };
+        let (post, tree) = deadline
+            .run(self.fetch_thread(&post_id, "old", depth, THREAD_COMMENT_LIMIT, &auth_token))
+            .await??;
+
+        let given: Vec<String> = options
+            .unwrap_or_default()
+            .into_iter()
+            .map(|option| option.trim().to_string())
+            .filter(|option| !option.is_empty())
+            .collect();
+        let (options, options_source) = if !given.is_empty() {
+            (given, "given")
+        } else {
+            let options = numbered_options(&post.selftext);
+            if options.len() >= 2 {
+                (options, "post")
+            } else {
+                (Vec::new(), "free_text")
+            }
+        };
+
+        let mut comments = Vec::new();
+        let mut hidden_comments = 0;
+        collect_comments(&tree, include_replies, &mut comments, &mut hidden_comments);
+        comments.retain(|comment| {
+            !comment.stickied
+                && comment.distinguished.is_none()
+                && !SKIPPED_AUTHORS.contains(&comment.author.as_str())
+        });
+        comments.sort_by(|a, b| a.created_utc.total_cmp(&b.created_utc));
+
+        let mut voted = HashSet::new();
+        let mut option_voters: Vec<Vec<String>> = vec![Vec::new(); options.len()];
+        let mut free_text: Vec<(String, Vec<String>)> = Vec::new();
+        let mut free_text_index: HashMap<String, usize> = HashMap::new();
+        let mut duplicates = 0;
+        let mut unparsed = 0;
+        let mut unparsed_samples = Vec::new();
+        for comment in &comments {
+            let answer = match &field {
+                Some(field) => field_value(&comment.body, field),
+                None => first_line(&comment.body),
+            };
+            let choice = answer.and_then(|answer| {
+                if options.is_empty() {
+                    free_text_key(answer).map(|key| Err((key, answer)))
+                } else {
+                    match_option(answer, &options).map(Ok)
+                }
+            });
+            let Some(choice) = choice else {
+                unparsed += 1;
+                if unparsed_samples.len() < UNPARSED_SAMPLES {
+                    unparsed_samples.push(UnparsedPollAnswer {
+                        comment_id: comment.id.clone(),
+                        author: comment.author.clone(),
+                        text: answer
+                            .or_else(|| first_line(&comment.body))
+                            .unwrap_or_default()
+                            .chars()
+                            .take(MAX_FREE_TEXT_CHARS)
+                            .collect(),
+                    });
+                }
+                continue;
+            };
+            if !voted.insert(comment.author.to_lowercase()) {
+                duplicates += 1;
+                continue;
+            }
+            match choice {
+                Ok(index) => option_voters[index].push(comment.author.clone()),
+                Err((key, answer)) => {
+                    let index = *free_text_index.entry(key).or_insert_with(|| {
+                        free_text
+                            .push((answer.trim_matches(['*', '_', ' ']).to_string(), Vec::new()));
+                        free_text.len() - 1
+                    });
+                    free_text[index].1.push(comment.author.clone());
+                }
+            }
+        }
+
+        let responses = voted.len();
+        let mut results: Vec<PollOptionTally> = if options.is_empty() {
+            free_text
+                .into_iter()
+                .map(|(option, voters)| PollOptionTally {
+                    number: None,
+                    option,
+                    votes: voters.len(),
+                    share: share(voters.len(), responses),
+                    voters,
+                })
+                .collect()
+        } else {
+            options
+                .into_iter()
+                .zip(option_voters)
+                .enumerate()
+                .map(|(index, (option, voters))| PollOptionTally {
+                    number: Some(index + 1),
+                    option,
+                    votes: voters.len(),
+                    share: share(voters.len(), responses),
+                    voters,
+                })
+                .collect()
+        };
+        results.sort_by_key(|result| Reverse(result.votes));
+
+        serde_json::to_string(&CommentPollResponse {
+            post_id,
+            title: post.title,
+            options_source,
+            field,
+            comments_scanned: comments.len(),
+            responses,
+            duplicates,
+            unparsed,
+            results,
+            unparsed_samples,
+            hidden_comments,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,136 @@
+use std::cmp::Reverse;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use reqwest::Url;
+use rmcp::model::{Content, IntoContents};
+use rmcp::tool;
+
+use super::RedditClient;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{InlineImage, PostDetails};
+use crate::reddit::validation::normalize_post_id;
+
+/// Largest image attached inline; base64 makes it a third bigger again.
+const MAX_INLINE_IMAGE_BYTES: usize = 1024 * 1024;
+const IMAGE_EXTENSIONS: [&str; 5] = [".jpg", ".jpeg", ".png", ".gif", ".webp"];
+
+/// The JSON of a tool followed by an image for vision-capable hosts.
+pub(super) struct WithImage {
+    text: String,
+    image: Option<(Vec<u8>, String)>,
+}
+
+impl IntoContents for WithImage {
+    fn into_contents(self) -> Vec<Content> {
+        let mut contents = vec![Content::text(self.text)];
+        if let Some((bytes, mime_type)) = self.image {
+            contents.push(Content::image(BASE64.encode(bytes), mime_type));
+        }
+        contents
+    }
+}
+
+/// The post's own image first, then Reddit's resized copies, largest first.
+fn image_candidates(details: &PostDetails) -> Vec<String> {
+    let post = &details.post;
+    let path = post.url.split(['?', '#']).next().unwrap_or_default();
+    let is_image = post.post_hint.as_deref() == Some("image")
+        || IMAGE_EXTENSIONS
+            .iter()
+            .any(|extension| path.to_ascii_lowercase().ends_with(extension));
+    let mut candidates = Vec::new();
+    if is_image {
+        candidates.push(post.url.clone());
+    }
+    for image in details.preview.iter().flat_map(|preview| &preview.images) {
+        candidates.push(image.source.url.clone());
+        let mut resolutions: Vec<_> = image.resolutions.iter().collect();
+        resolutions.sort_by_key(|resolution| Reverse(resolution.width));
+        candidates.extend(resolutions.iter().map(|resolution| resolution.url.clone()));
+    }
+    candidates.dedup();
+    candidates
+}
+
+impl RedditClient {
+    #[tool(
+        description = "Get the complete details of one post: title, self text, link URL and domain, flair, score and upvote ratio, awards, lock/sticky/spoiler state and crosspost information. For image posts it can also attach the image itself, e.g. to read the text of a screenshot."
+    )]
+    pub(super) async fn get_post(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Post id (1abcde), t3_ fullname, reddit.com or redd.it URL of the post"
+        )]
+        post: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Attach the post's image as inline image content, downscaled to Reddit's preview when it is over 1 MiB (default false)"
+        )]
+        include_image: Option<bool>,
+    ) -> Result<WithImage, RedditError> {
+        tracing::info!("Fetching post {}", post);
+
+        let post_id = normalize_post_id(&post)?;
+        let deadline = Deadline::for_tool("get_post");
+        let auth_token = self.auth_token().await?;
+        let mut details: PostDetails = deadline
+            .run(self.fetch_post(&post_id, &auth_token))
+            .await??;
+        details.awards = details.post.all_awardings.clone();
+
+        let mut image = None;
+        if include_image.unwrap_or(false) {
+            let candidates = image_candidates(&details);
+            let mut inline_image = InlineImage {
+                attached: false,
+                url: None,
+                mime_type: None,
+                bytes: None,
+                reason: None,
+            };
+            if details.post.over_18 && !self.config.get().content.allow_nsfw {
+                inline_image.reason = Some("the post is NSFW".to_string());
+            } else if candidates.is_empty() {
+                inline_image.reason = Some("the post has no image".to_string());
+            } else {
+                for candidate in candidates {
+                    let Ok(url) = Url::parse(&candidate) else {
+                        continue;
+                    };
+                    let Ok(downloaded) = deadline
+                        .run(self.unfurler.download_image(url, MAX_INLINE_IMAGE_BYTES))
+                        .await
+                    else {
+                        break;
+                    };
+                    if let Some((bytes, mime_type)) = downloaded {
+                        inline_image = InlineImage {
+                            attached: true,
+                            url: Some(candidate),
+                            mime_type: Some(mime_type.clone()),
+                            bytes: Some(bytes.len()),
+                            reason: None,
+                        };
+                        image = Some((bytes, mime_type));
+                        break;
+                    }
+                }
+                if image.is_none() {
+                    inline_image.reason = Some(format!(
+                        "no copy of the image under {} bytes could be downloaded",
+                        MAX_INLINE_IMAGE_BYTES
+                    ));
+                }
+            }
+            details.inline_image = Some(inline_image);
+        }
+
+        Ok(WithImage {
+            text: serde_json::to_string(&details).map_err(RedditError::serialize)?,
+            image,
+        })
+    }
+}
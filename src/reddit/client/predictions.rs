@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{Post, PredictionPost, PredictionsResponse};
+use crate::reddit::validation::normalize_post_id;
+
+impl RedditClient {
+    #[tool(
+        description = "Get prediction tournament data (questions, options, stakes, resolution) from a post, or from the recent hot and new posts of a subreddit that runs predictions."
+    )]
+    pub(super) async fn get_predictions(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Subreddit whose recent posts to scan, used when post_id is omitted"
+        )]
+        subreddit: Option<String>,
+    ) -> Result<String, RedditError> {
+        let deadline = Deadline::for_tool("get_predictions");
+        let auth_token = self.auth_token().await?;
+
+        let posts: Vec<Post> = match (post_id, subreddit) {
+            (Some(post_id), _) => {
+                tracing::info!("Fetching predictions of post {}", post_id);
+                let post_id = normalize_post_id(&post_id)?;
+                vec![
+                    deadline
+                        .run(self.fetch_post(&post_id, &auth_token))
+                        .await??,
+                ]
+            }
+            (None, Some(subreddit)) => {
+                tracing::info!("Scanning {} for predictions", subreddit);
+                let subreddit = deadline
+                    .run(self.resolve_subreddit(&subreddit, &auth_token))
+                    .await??;
+                let mut fullnames = HashSet::new();
+                let mut posts = Vec::new();
+                for listing in ["hot", "new"] {
+                    let url = format!("{}/r/{}/{}", OAUTH_URL, subreddit, listing);
+                    let (page, _) = self
+                        .collect_listing::<Post>(&url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                        .await?;
+                    posts.extend(
+                        page.into_iter()
+                            .filter(|post| fullnames.insert(post.name.clone())),
+                    );
+                }
+                posts
+            }
+            (None, None) => {
+                return Err(RedditError::invalid_input(
+                    "Neither post_id nor subreddit was given",
+                    "pass the post_id of a prediction post or a subreddit to scan",
+                ));
+            }
+        };
+
+        let posts_scanned = posts.len();
+        let posts = posts
+            .into_iter()
+            .filter_map(|post| {
+                Some(PredictionPost {
+                    tournament: post.tournament_data?,
+                    post_id: post.id,
+                    title: post.title,
+                    subreddit: post.subreddit,
+                    permalink: post.permalink,
+                })
+            })
+            .collect();
+
+        serde_json::to_string(&PredictionsResponse {
+            posts_scanned,
+            posts,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
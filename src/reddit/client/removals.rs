@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+
+use rmcp::tool;
+
+use super::{BASE_URL, MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    ModAction, RecentRemovalsResponse, RemovalSource, RemovedItem, RestoreOutcome, RestoreResponse,
+    SpamQueueItem, ThingRequest,
+};
+use crate::reddit::text::snippet;
+use crate::reddit::validation::normalize_thing_id;
+
+const DEFAULT_REMOVALS: usize = 25;
+const MOD_LOG_SCAN: u32 = 500;
+const REMOVAL_EXCERPT_CHARS: usize = 200;
+const MAX_RESTORE_ITEMS: usize = 100;
+const REMOVAL_ACTIONS: [&str; 4] = ["removelink", "removecomment", "spamlink", "spamcomment"];
+/// Mod log details that only repeat the action instead of giving a reason.
+const GENERIC_DETAILS: [&str; 4] = ["remove", "spam", "confirm_spam", "unspam"];
+
+fn thing_kind(fullname: &str) -> &'static str {
+    if fullname.starts_with("t1_") {
+        "comment"
+    } else {
+        "post"
+    }
+}
+
+fn spam_queue_item(item: SpamQueueItem) -> RemovedItem {
+    let removed_by = match &item.banned_by {
+        Some(serde_json::Value::String(moderator)) => moderator.clone(),
+        _ => "reddit".to_string(),
+    };
+    let spam = matches!(item.banned_by, Some(serde_json::Value::Bool(true)));
+    RemovedItem {
+        kind: thing_kind(&item.name),
+        fullname: item.name,
+        author: item.author,
+        excerpt: snippet(
+            item.body
+                .as_deref()
+                .or(item.title.as_deref())
+                .unwrap_or_default(),
+            REMOVAL_EXCERPT_CHARS,
+        ),
+        title: item.title,
+        permalink: format!("https://www.reddit.com{}", item.permalink),
+        removed_by,
+        removal_reason: item.mod_reason_title,
+        spam,
+        removed_utc: item.banned_at_utc.unwrap_or(item.created_utc),
+        still_removed: true,
+        source: RemovalSource::SpamQueue,
+    }
+}
+
+impl RedditClient {
+    #[tool(
+        description = "For moderators: list recently removed posts and comments of a subreddit from the mod log and spam queue, newest first, with who removed them and why. Pass the fullnames to restore_removed_items to approve them again, e.g. after an AutoModerator misfire."
+    )]
+    pub(super) async fn list_recent_removals(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Only list items removed by this moderator, e.g. AutoModerator (case-insensitive)"
+        )]
+        removed_by: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Also list items that were approved again or deleted since (default false)"
+        )]
+        include_restored: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of items to return (default 25)")]
+        limit: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Listing recent removals in {}", subreddit);
+
+        let deadline = Deadline::for_tool("list_recent_removals");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let include_restored = include_restored.unwrap_or(false);
+        let limit = limit.unwrap_or(DEFAULT_REMOVALS).max(1);
+
+        let log_url = format!("{}/r/{}/about/log", OAUTH_URL, subreddit);
+        let (log, mut truncated_reason) = self
+            .collect_listing::<ModAction>(&log_url, &auth_token, MOD_LOG_SCAN, &deadline)
+            .await
+            .map_err(|e| match e.kind {
+                ErrorKind::Forbidden => e.with_hint(format!(
+                    "only moderators of r/{} can read its mod log",
+                    subreddit
+                )),
+                _ => e,
+            })?;
+        let mod_log_entries_scanned = log.len();
+
+        // The log is newest first, so the first entry per target is its latest
+        // removal or removal reason.
+        let mut reasons: HashMap<String, String> = HashMap::new();
+        for action in &log {
+            if action.action == "addremovalreason"
+                && let (Some(target), Some(reason)) = (&action.target_fullname, &action.description)
+            {
+                reasons
+                    .entry(target.clone())
+                    .or_insert_with(|| reason.clone());
+            }
+        }
+        let mut seen = HashSet::new();
+        let removals: Vec<&ModAction> = log
+            .iter()
+            .filter(|action| REMOVAL_ACTIONS.contains(&action.action.as_str()))
+            .filter(|action| {
+                action
+                    .target_fullname
+                    .as_ref()
+                    .is_some_and(|target| seen.insert(target.clone()))
+            })
+            .collect();
+
+        let fullnames: Vec<String> = removals
+            .iter()
+            .filter_map(|action| action.target_fullname.clone())
+            .collect();
+        let mut info = deadline
+            .run(self.fetch_info(&fullnames, &auth_token))
+            .await??;
+
+        let mut items: Vec<RemovedItem> = removals
+            .into_iter()
+            .map(|action| {
+                let fullname = action.target_fullname.clone().unwrap_or_default();
+                let current = info.remove(&fullname);
+                let details = action
+                    .details
+                    .clone()
+                    .filter(|details| !GENERIC_DETAILS.contains(&details.as_str()));
+                RemovedItem {
+                    kind: thing_kind(&fullname),
+                    author: current
+                        .as_ref()
+                        .and_then(|item| item.author.clone())
+                        .or_else(|| action.target_author.clone())
+                        .unwrap_or_default(),
+                    title: current
+                        .as_ref()
+                        .and_then(|item| item.title.clone())
+                        .or_else(|| action.target_title.clone()),
+                    excerpt: snippet(
+                        current
+                            .as_ref()
+                            .and_then(|item| item.body.as_deref().or(item.title.as_deref()))
+                            .unwrap_or_default(),
+                        REMOVAL_EXCERPT_CHARS,
+                    ),
+                    permalink: current
+                        .as_ref()
+                        .and_then(|item| item.permalink.as_ref())
+                        .map(|permalink| format!("https://www.reddit.com{}", permalink))
+                        .unwrap_or_default(),
+                    removed_by: action.moderator.clone(),
+                    removal_reason: reasons.get(&fullname).cloned().or(details),
+                    spam: action.action.starts_with("spam"),
+                    removed_utc: action.created_utc,
+                    still_removed: current.as_ref().is_none_or(|item| {
+                        item.removed_by_category
+                            .as_deref()
+                            .is_some_and(|category| category != "deleted")
+                    }),
+                    source: RemovalSource::ModLog,
+                    fullname,
+                }
+            })
+            .collect();
+
+        // Items the spam filter caught never show up in the mod log.
+        let spam_url = format!("{}/r/{}/about/spam", OAUTH_URL, subreddit);
+        let spam_queue = match self
+            .collect_listing::<SpamQueueItem>(&spam_url, &auth_token, MAX_PAGE_SIZE, &deadline)
+            .await
+        {
+            Ok((queue, spam_truncated)) => {
+                truncated_reason = truncated_reason.or(spam_truncated);
+                Some(queue)
+            }
+            Err(e) if e.kind == ErrorKind::Forbidden => None,
+            Err(e) => return Err(e),
+        };
+        let spam_queue_available = spam_queue.is_some();
+        items.extend(
+            spam_queue
+                .into_iter()
+                .flatten()
+                .filter(|item| seen.insert(item.name.clone()))
+                .map(spam_queue_item),
+        );
+
+        if let Some(removed_by) = &removed_by {
+            items.retain(|item| item.removed_by.eq_ignore_ascii_case(removed_by));
+        }
+        if !include_restored {
+            items.retain(|item| item.still_removed);
+        }
+        items.sort_by(|a, b| b.removed_utc.total_cmp(&a.removed_utc));
+        items.truncate(limit);
+
+        serde_json::to_string(&RecentRemovalsResponse {
+            subreddit,
+            mod_log_entries_scanned,
+            spam_queue_available,
+            items,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: approve removed posts or comments again, e.g. the ones listed by list_recent_removals. Reports for every item whether it was restored."
+    )]
+    pub(super) async fn restore_removed_items(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Posts or comments to approve: fullnames (t3_/t1_), permalinks or post ids, at most 100"
+        )]
+        items: Vec<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Restoring {} removed items", items.len());
+
+        let deadline = Deadline::for_tool("restore_removed_items");
+        let auth_token = self.auth_token().await?;
+        if items.is_empty() || items.len() > MAX_RESTORE_ITEMS {
+            return Err(RedditError::invalid_input(
+                format!("{} items were given", items.len()),
+                format!("pass between 1 and {} items", MAX_RESTORE_ITEMS),
+            ));
+        }
+        let mut fullnames = Vec::new();
+        for item in &items {
+            let fullname = normalize_thing_id(item)?;
+            if !fullnames.contains(&fullname) {
+                fullnames.push(fullname);
+            }
+        }
+
+        let mut outcomes = Vec::new();
+        for fullname in fullnames {
+            let result = deadline
+                .run(self.post_form::<serde_json::Value, ThingRequest>(
+                    &format!("{}/approve", BASE_URL),
+                    &auth_token,
+                    ThingRequest {
+                        id: fullname.clone(),
+                    },
+                ))
+                .await
+                .and_then(|result| result);
+            outcomes.push(RestoreOutcome {
+                fullname,
+                restored: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        let restored = outcomes.iter().filter(|outcome| outcome.restored).count();
+
+        serde_json::to_string(&RestoreResponse {
+            restored,
+            failed: outcomes.len() - restored,
+            items: outcomes,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,377 @@
+use rmcp::tool;
+use uuid::Uuid;
+
+use super::{BASE_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::idempotency::Claim;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{
+    ComposeRequest, PendingRepliesResponse, PendingReply, ReplyQueuedResponse,
+    ReplyRejectedResponse, ReplyRequest, ReplySentResponse, ReplyTarget,
+};
+use crate::reddit::validation::{normalize_thing_id, normalize_username};
+use crate::storage::Namespace;
+
+fn no_pending_reply(id: &str) -> RedditError {
+    RedditError::not_found(
+        format!("No pending reply {}", id),
+        "call list_pending_replies to see the queue",
+    )
+}
+
+fn check_body(body: &str) -> Result<(), RedditError> {
+    if body.trim().is_empty() {
+        return Err(RedditError::invalid_input(
+            "The body is empty",
+            "pass the text to send",
+        ));
+    }
+    Ok(())
+}
+
+impl RedditClient {
+    fn load_pending_reply(&self, id: &str) -> Result<PendingReply, RedditError> {
+        // Ids are UUIDs; anything else cannot name a stored reply.
+        let id = Uuid::parse_str(id.trim()).map_err(|_| no_pending_reply(id))?;
+        self.storage
+            .read_json::<PendingReply>(Namespace::Replies, &id.to_string())
+            .map_err(RedditError::storage)?
+            .ok_or_else(|| no_pending_reply(&id.to_string()))
+    }
+
+    /// Stores a reply in the queue that approve_reply releases.
+    pub(super) fn queue_reply(
+        &self,
+        target: ReplyTarget,
+        body: String,
+    ) -> Result<PendingReply, RedditError> {
+        let reply = PendingReply {
+            id: Uuid::new_v4().to_string(),
+            target,
+            body,
+            account: self.account().name.clone(),
+            drafted_utc: chrono::Utc::now().timestamp(),
+            last_error: None,
+        };
+        self.storage
+            .write_json(Namespace::Replies, &reply.id, &reply)
+            .map_err(RedditError::storage)?;
+        tracing::info!("Queued reply {} for approval", reply.id);
+        Ok(reply)
+    }
+
+    /// Sends a reply, or queues it for approve_reply when `[replies]
+    /// require_approval` is set.
+    pub(super) async fn send_or_queue(
+        &self,
+        target: ReplyTarget,
+        body: String,
+        tool: &str,
+    ) -> Result<String, RedditError> {
+        if self.config.get().replies.require_approval {
+            let reply = self.queue_reply(target, body)?;
+            return serde_json::to_string(&ReplyQueuedResponse {
+                queued: true,
+                reply,
+            })
+            .map_err(RedditError::serialize);
+        }
+
+        let deadline = Deadline::for_tool(tool);
+        let auth_token = self.auth_token().await?;
+        let name = self
+            .send_reply(&target, body, &auth_token, &deadline)
+            .await?;
+        serde_json::to_string(&ReplySentResponse {
+            id: None,
+            target,
+            name,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    /// Sends a reply and returns the fullname of a new comment.
+    pub(super) async fn send_reply(
+        &self,
+        target: &ReplyTarget,
+        body: String,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<Option<String>, RedditError> {
+        match target {
+            ReplyTarget::Comment {
+                parent,
+                allow_duplicate,
+            } => {
+                if !allow_duplicate {
+                    deadline
+                        .run(self.check_duplicate_comment(
+                            parent,
+                            &body,
+                            auth_token,
+                            Priority::Interactive,
+                        ))
+                        .await??;
+                }
+                deadline
+                    .run(self.post_api::<serde_json::Value, ReplyRequest>(
+                        &format!("{}/comment", BASE_URL),
+                        auth_token,
+                        ReplyRequest {
+                            thing_id: parent.clone(),
+                            text: body,
+                        },
+                    ))
+                    .await??
+                    .as_ref()
+                    .and_then(|data| data.pointer("/things/0/data/name"))
+                    .and_then(|name| name.as_str())
+                    .map(|name| Some(name.to_string()))
+                    .ok_or_else(|| RedditError::parse("the comment response has no name"))
+            }
+            ReplyTarget::Message { to, subject } => {
+                deadline
+                    .run(self.post_api::<serde_json::Value, ComposeRequest>(
+                        &format!("{}/compose", BASE_URL),
+                        auth_token,
+                        ComposeRequest {
+                            to: to.clone(),
+                            subject: subject.clone(),
+                            text: body,
+                        },
+                    ))
+                    .await??;
+                Ok(None)
+            }
+            ReplyTarget::Modmail {
+                conversation_id,
+                as_subreddit,
+                internal,
+            } => {
+                self.send_modmail(
+                    conversation_id,
+                    body,
+                    *as_subreddit,
+                    *internal,
+                    auth_token,
+                    deadline,
+                )
+                .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    #[tool(
+        description = "Reply to a post or comment. A near-identical comment the account already left on the same post is refused unless allow_duplicate_comment is true. When the server requires approval ([replies] require_approval), the reply is queued for a human to release with approve_reply instead of being posted."
+    )]
+    pub(super) async fn post_comment(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post or comment to reply to: fullname or permalink")]
+        parent: String,
+        #[tool(param)]
+        #[schemars(description = "Comment text in markdown")]
+        body: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Post even when the account already left a near-identical comment on the same post. Defaults to false."
+        )]
+        allow_duplicate_comment: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of commenting again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Replying to {}", parent);
+
+        let parent = normalize_thing_id(&parent)?;
+        check_body(&body)?;
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "post_comment",
+            idempotency_key.as_deref(),
+            serde_json::json!({
+                "parent": parent,
+                "body": body,
+            }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let result = self
+            .send_or_queue(
+                ReplyTarget::Comment {
+                    parent,
+                    allow_duplicate: allow_duplicate_comment.unwrap_or(false),
+                },
+                body,
+                "post_comment",
+            )
+            .await?;
+        claim.record(&result);
+        Ok(result)
+    }
+
+    #[tool(
+        description = "Send a private message to a user. When the server requires approval ([replies] require_approval), the message is queued for a human to release with approve_reply instead of being sent."
+    )]
+    pub(super) async fn send_message(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Recipient username, e.g. spez or u/spez")]
+        to: String,
+        #[tool(param)]
+        #[schemars(description = "Message subject")]
+        subject: String,
+        #[tool(param)]
+        #[schemars(description = "Message text in markdown")]
+        body: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of sending the message again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        let to = normalize_username(&to)?;
+        tracing::info!("Messaging u/{}", to);
+
+        let subject = subject.trim().to_string();
+        if subject.is_empty() {
+            return Err(RedditError::invalid_input(
+                "The subject is empty",
+                "pass a subject for the message",
+            ));
+        }
+        check_body(&body)?;
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "send_message",
+            idempotency_key.as_deref(),
+            serde_json::json!({
+                "to": to,
+                "subject": subject,
+                "body": body,
+            }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let result = self
+            .send_or_queue(ReplyTarget::Message { to, subject }, body, "send_message")
+            .await?;
+        claim.record(&result);
+        Ok(result)
+    }
+
+    #[tool(
+        description = "List the comments and messages waiting for approval, oldest first, with the account each was drafted as."
+    )]
+    pub(super) async fn list_pending_replies(&self) -> Result<String, RedditError> {
+        tracing::info!("Listing pending replies");
+
+        let mut replies = Vec::new();
+        for key in self
+            .storage
+            .keys(Namespace::Replies)
+            .map_err(RedditError::storage)?
+        {
+            if let Some(reply) = self
+                .storage
+                .read_json::<PendingReply>(Namespace::Replies, &key)
+                .map_err(RedditError::storage)?
+            {
+                replies.push(reply);
+            }
+        }
+        replies.sort_by_key(|reply| reply.drafted_utc);
+
+        serde_json::to_string(&PendingRepliesResponse {
+            require_approval: self.config.get().replies.require_approval,
+            replies,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Release a queued comment or message: it is sent as the account it was drafted as, optionally with an edited body, and leaves the queue. If sending fails it stays queued with the error."
+    )]
+    pub(super) async fn approve_reply(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Id of the pending reply")]
+        id: String,
+        #[tool(param)]
+        #[schemars(description = "Replacement body, to send an edited version")]
+        body: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Approving reply {}", id);
+
+        let mut reply = self.load_pending_reply(&id)?;
+        if reply.account != self.account().name {
+            return Err(RedditError::new(
+                ErrorKind::Forbidden,
+                format!(
+                    "Reply {} was drafted as the account {}",
+                    reply.id, reply.account
+                ),
+                format!("call again with account={}", reply.account),
+            ));
+        }
+        if let Some(body) = body {
+            check_body(&body)?;
+            reply.body = body;
+        }
+
+        let deadline = Deadline::for_tool("approve_reply");
+        let auth_token = self.auth_token().await?;
+        match self
+            .send_reply(&reply.target, reply.body.clone(), &auth_token, &deadline)
+            .await
+        {
+            Ok(name) => {
+                self.storage
+                    .remove(Namespace::Replies, &reply.id)
+                    .map_err(RedditError::storage)?;
+                serde_json::to_string(&ReplySentResponse {
+                    id: Some(reply.id),
+                    target: reply.target,
+                    name,
+                })
+                .map_err(RedditError::serialize)
+            }
+            Err(e) => {
+                reply.last_error = Some(e.to_string());
+                self.storage
+                    .write_json(Namespace::Replies, &reply.id, &reply)
+                    .map_err(RedditError::storage)?;
+                Err(e)
+            }
+        }
+    }
+
+    #[tool(description = "Drop a queued comment or message without sending it.")]
+    pub(super) async fn reject_reply(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Id of the pending reply")]
+        id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Rejecting reply {}", id);
+
+        let reply = self.load_pending_reply(&id)?;
+        self.storage
+            .remove(Namespace::Replies, &reply.id)
+            .map_err(RedditError::storage)?;
+        serde_json::to_string(&ReplyRejectedResponse {
+            id: reply.id,
+            rejected: true,
+            target: reply.target,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,128 @@
+use reqwest::header;
+use rmcp::tool;
+
+use super::RedditClient;
+use crate::config::env_or_file;
+use crate::reddit::error::RedditError;
+use crate::reddit::limiter::Priority;
+use crate::reddit::models::{RevokeTokenRequest, RevokeTokenResponse};
+
+const REVOKE_URL: &str = "https://www.reddit.com/api/v1/revoke_token";
+
+impl RedditClient {
+    /// Asks Reddit to invalidate `token`, an access or refresh token as told
+    /// by `token_type_hint`.
+    async fn revoke(
+        &self,
+        token: String,
+        token_type_hint: &'static str,
+    ) -> Result<(), RedditError> {
+        let _permit = self.limiter.acquire(Priority::Interactive).await;
+        tracing::info!("Revoking a token ({})", token_type_hint);
+
+        let (client_id, client_secret) = self.app_credentials()?;
+        let response = self
+            .client
+            .post(REVOKE_URL)
+            .basic_auth(client_id, Some(client_secret))
+            .header(header::USER_AGENT, &self.user_agent)
+            .form(&RevokeTokenRequest {
+                token,
+                token_type_hint,
+            })
+            .send()
+            .await
+            .map_err(RedditError::network)?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(RedditError::from_status(
+                response.status(),
+                response.headers(),
+                REVOKE_URL,
+            ))
+        }
+    }
+
+    /// Removes the tokens of `account` from the cache and the token store and
+    /// returns its access token and, with `refresh_token`, its refresh token.
+    async fn forget_tokens(
+        &self,
+        account: &str,
+        refresh_token: bool,
+    ) -> Result<(Option<String>, Option<String>), RedditError> {
+        let mut cached = self.token.lock().await;
+        let cached_token = cached
+            .remove(account)
+            .and_then(|token| token.auth_token.strip_prefix("Bearer ").map(str::to_string));
+        let Some(mut stored) = self.tokens.load(account)? else {
+            return Ok((cached_token, None));
+        };
+        let access_token = cached_token.or(stored.access_token.take());
+        stored.access_token_expires_utc = 0;
+        let refresh_token = if refresh_token {
+            stored.refresh_token.take()
+        } else {
+            None
+        };
+        self.tokens.save(account, &stored)?;
+        Ok((access_token, refresh_token))
+    }
+
+    /// Revokes the access token of every account when
+    /// `REVOKE_TOKENS_ON_SHUTDOWN` is set, so none stays valid after the
+    /// server exits. Refresh tokens are kept for the next run.
+    pub async fn revoke_tokens_on_shutdown(&self) {
+        if env_or_file("REVOKE_TOKENS_ON_SHUTDOWN").is_none_or(|value| value != "true") {
+            return;
+        }
+        for account in self.accounts.all() {
+            match self.forget_tokens(&account.name, false).await {
+                Ok((Some(access_token), _)) => {
+                    match self.revoke(access_token, "access_token").await {
+                        Ok(()) => tracing::info!("Revoked the access token of {}", account.name),
+                        Err(e) => tracing::warn!(
+                            "Failed to revoke the access token of {}: {}",
+                            account.name,
+                            e
+                        ),
+                    }
+                }
+                Ok((None, _)) => {}
+                Err(e) => tracing::warn!("Failed to read the tokens of {}: {}", account.name, e),
+            }
+        }
+    }
+
+    #[tool(
+        description = "Revoke the account's access token at Reddit so it can no longer be used; the next tool call signs in again. With include_refresh_token the refresh token stored by authorize_account is revoked as well, and the account has to be authorized again unless it has a password."
+    )]
+    pub(super) async fn revoke_token(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Also revoke the stored refresh token (default false)")]
+        include_refresh_token: Option<bool>,
+    ) -> Result<String, RedditError> {
+        let account = self.account().name.clone();
+        tracing::info!("Revoking the tokens of {}", account);
+
+        let (access_token, refresh_token) = self
+            .forget_tokens(&account, include_refresh_token.unwrap_or(false))
+            .await?;
+        let revoked_access_token = access_token.is_some();
+        let revoked_refresh_token = refresh_token.is_some();
+        if let Some(refresh_token) = refresh_token {
+            // Revoking the refresh token revokes the access tokens issued with it.
+            self.revoke(refresh_token, "refresh_token").await?;
+        } else if let Some(access_token) = access_token {
+            self.revoke(access_token, "access_token").await?;
+        }
+
+        serde_json::to_string(&RevokeTokenResponse {
+            account,
+            revoked_access_token,
+            revoked_refresh_token,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use rmcp::tool;
+
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    Listing, Post, SavedSearch, SavedSearchRunResponse, SavedSearchSummary, SearchDefinition,
+    SearchRequest,
+};
+use crate::reddit::validation::normalize_subreddit;
+use crate::storage::Namespace;
+
+const SEARCH_SORTS: [&str; 5] = ["relevance", "hot", "top", "new", "comments"];
+const SEARCH_TIMES: [&str; 6] = ["hour", "day", "week", "month", "year", "all"];
+
+fn validate_search_name(name: &str) -> Result<String, RedditError> {
+    let name = name.trim().to_ascii_lowercase();
+    let valid = (1..=64).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid saved search name", name),
+            "use 1-64 letters, digits, underscores or dashes, e.g. rust-jobs",
+        ));
+    }
+    Ok(name)
+}
+
+fn not_saved(name: &str) -> RedditError {
+    RedditError::not_found(
+        format!("No saved search named {}", name),
+        "call list_saved_searches to see the saved names or save_search to create one",
+    )
+}
+
+impl RedditClient {
+    fn load_saved_search(&self, name: &str) -> Result<SavedSearch, RedditError> {
+        self.storage
+            .read_json::<SavedSearch>(Namespace::Searches, name)
+            .map_err(RedditError::storage)?
+            .ok_or_else(|| not_saved(name))
+    }
+
+    #[tool(
+        description = "Save a named search (query, subreddits and filters) so it can be re-run with run_saved_search. Saving under an existing name replaces that search."
+    )]
+    pub(super) async fn save_search(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the saved search, e.g. rust-jobs")]
+        name: String,
+        #[tool(param)]
+        #[schemars(description = "Search query")]
+        query: String,
+        #[tool(param)]
+        #[schemars(description = "Subreddits to search. Omit to search all of Reddit.")]
+        subreddits: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(description = "Sort: relevance, hot, top, new or comments (default new)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Time window: hour, day, week, month, year or all (default week)"
+        )]
+        time: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only report posts with at least this score")]
+        min_score: Option<i64>,
+    ) -> Result<String, RedditError> {
+        let name = validate_search_name(&name)?;
+        tracing::info!("Saving search {}", name);
+
+        let sort = sort.unwrap_or_else(|| "new".to_string());
+        if !SEARCH_SORTS.contains(&sort.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown search sort {}", sort),
+                format!("use one of {}", SEARCH_SORTS.join(", ")),
+            ));
+        }
+        let time = time.unwrap_or_else(|| "week".to_string());
+        if !SEARCH_TIMES.contains(&time.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown time window {}", time),
+                format!("use one of {}", SEARCH_TIMES.join(", ")),
+            ));
+        }
+        let subreddits = subreddits
+            .unwrap_or_default()
+            .iter()
+            .map(|subreddit| normalize_subreddit(subreddit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let search = SavedSearch {
+            definition: SearchDefinition {
+                name: name.clone(),
+                query,
+                subreddits,
+                sort,
+                time,
+                min_score,
+            },
+            created_utc: chrono::Utc::now().timestamp(),
+            last_run_utc: None,
+            last_results: Vec::new(),
+        };
+        self.storage
+            .write_json(Namespace::Searches, &name, &search)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&search.definition).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "List the saved searches and when each was last run.")]
+    pub(super) async fn list_saved_searches(&self) -> Result<String, RedditError> {
+        let mut searches = Vec::new();
+        for name in self
+            .storage
+            .keys(Namespace::Searches)
+            .map_err(RedditError::storage)?
+        {
+            let search = self.load_saved_search(&name)?;
+            searches.push(SavedSearchSummary {
+                last_run_utc: search.last_run_utc,
+                last_result_count: search.last_results.len(),
+                definition: search.definition,
+            });
+        }
+
+        serde_json::to_string(&searches).map_err(RedditError::serialize)
+    }
+
+    #[tool(description = "Delete a saved search.")]
+    pub(super) async fn delete_saved_search(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the saved search")]
+        name: String,
+    ) -> Result<String, RedditError> {
+        let name = validate_search_name(&name)?;
+        tracing::info!("Deleting saved search {}", name);
+
+        if !self
+            .storage
+            .remove(Namespace::Searches, &name)
+            .map_err(RedditError::storage)?
+        {
+            return Err(not_saved(&name));
+        }
+        Ok(format!("Deleted saved search {}", name))
+    }
+
+    #[tool(
+        description = "Run a saved search and return only the posts that were not part of the previous run's results."
+    )]
+    pub(super) async fn run_saved_search(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Name of the saved search")]
+        name: String,
+    ) -> Result<String, RedditError> {
+        let name = validate_search_name(&name)?;
+        tracing::info!("Running saved search {}", name);
+
+        let mut search = self.load_saved_search(&name)?;
+        let definition = &search.definition;
+        let auth_token = self.auth_token().await?;
+        let url = if definition.subreddits.is_empty() {
+            format!("{}/search", OAUTH_URL)
+        } else {
+            format!("{}/r/{}/search", OAUTH_URL, definition.subreddits.join("+"))
+        };
+
+        let listing = Deadline::for_tool("run_saved_search")
+            .run(self.get_request::<Listing<Post>, SearchRequest>(
+                &url,
+                &auth_token,
+                SearchRequest {
+                    q: definition.query.clone(),
+                    sort: definition.sort.clone(),
+                    t: definition.time.clone(),
+                    restrict_sr: !definition.subreddits.is_empty(),
+                    kind: "link".to_string(),
+                    limit: MAX_PAGE_SIZE,
+                    after: None,
+                    raw_json: 1,
+                },
+            ))
+            .await??;
+        let matches: Vec<Post> = listing
+            .data
+            .children
+            .into_iter()
+            .map(|child| child.data)
+            .filter(|post| definition.min_score.is_none_or(|min| post.score >= min))
+            .collect();
+
+        let first_run = search.last_run_utc.is_none();
+        let previous: HashSet<&String> = search.last_results.iter().collect();
+        let new_posts: Vec<Post> = matches
+            .iter()
+            .filter(|post| !previous.contains(&post.name))
+            .cloned()
+            .collect();
+
+        let response = SavedSearchRunResponse {
+            name: name.clone(),
+            first_run,
+            matches: matches.len(),
+            new_posts: self.apply_content_policy(new_posts),
+        };
+
+        search.last_run_utc = Some(chrono::Utc::now().timestamp());
+        search.last_results = matches.into_iter().map(|post| post.name).collect();
+        self.storage
+            .write_json(Namespace::Searches, &name, &search)
+            .map_err(RedditError::storage)?;
+
+        serde_json::to_string(&response).map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,428 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate};
+use rmcp::tool;
+
+use super::jobs::{resumable, resume_note};
+use super::listings::post_summary;
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    Job, JobSpec, Listing, Post, SearchBetweenResponse, SearchRequest, TimelineBucket,
+    TopicTimelineResponse,
+};
+use crate::reddit::validation::parse_timestamp;
+
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+const MAX_SEARCH_LIMIT: usize = 500;
+/// Reddit stops paginating search results after roughly a thousand hits.
+const MAX_SEARCH_PAGES: usize = 10;
+const TIMELINE_BUCKETS: [&str; 2] = ["day", "week"];
+const MAX_TIMELINE_BUCKETS: usize = 400;
+
+/// Smallest `t=` window that still reaches back to `start`.
+fn search_window(start: i64, now: i64) -> &'static str {
+    const HOUR: i64 = 60 * 60;
+    const DAY: i64 = 24 * HOUR;
+    match now - start {
+        age if age <= HOUR => "hour",
+        age if age <= DAY => "day",
+        age if age <= 7 * DAY => "week",
+        age if age <= 31 * DAY => "month",
+        age if age <= 365 * DAY => "year",
+        _ => "all",
+    }
+}
+
+fn utc_date(timestamp: i64) -> NaiveDate {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// First day of the bucket holding `date`; weeks start on Monday.
+fn bucket_start(date: NaiveDate, bucket: &str) -> NaiveDate {
+    if bucket == "week" {
+        date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+    } else {
+        date
+    }
+}
+
+impl RedditClient {
+    #[tool(
+        description = "Search posts created between two points in time. Combines Reddit's coarse time windows with newest-first pagination and exact timestamp filtering."
+    )]
+    pub(super) async fn search_posts_between(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Search query")]
+        query: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Start of the range: unix timestamp, RFC 3339 time or YYYY-MM-DD date (UTC)"
+        )]
+        start: String,
+        #[tool(param)]
+        #[schemars(
+            description = "End of the range: unix timestamp, RFC 3339 time or YYYY-MM-DD date (UTC). Defaults to now."
+        )]
+        end: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only search this subreddit. Omit to search all of Reddit.")]
+        subreddit: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of posts to return, 1-500 (default 100)")]
+        limit: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Searching {:?} between {} and {:?}", query, start, end);
+
+        let now = chrono::Utc::now().timestamp();
+        let start = parse_timestamp(&start, "start")?;
+        let end = match end {
+            Some(end) => parse_timestamp(&end, "end")?,
+            None => now,
+        };
+        if start >= end {
+            return Err(RedditError::invalid_input(
+                "start must be before end",
+                "swap start and end or widen the range",
+            ));
+        }
+
+        let deadline = Deadline::for_tool("search_posts_between");
+        let auth_token = self.auth_token().await?;
+        let subreddit = match subreddit {
+            Some(subreddit) => Some(
+                deadline
+                    .run(self.resolve_subreddit(&subreddit, &auth_token))
+                    .await??,
+            ),
+            None => None,
+        };
+        let job = self.start_job(JobSpec::PostSearch {
+            query,
+            subreddit,
+            start,
+            end,
+            window: search_window(start, now).to_string(),
+            limit: limit
+                .unwrap_or(DEFAULT_SEARCH_LIMIT)
+                .clamp(1, MAX_SEARCH_LIMIT),
+            exclude_seen: exclude_seen.unwrap_or(false),
+        })?;
+        self.run_job(job).await
+    }
+
+    #[tool(
+        description = "Build a timeline of how discussion of a topic evolved: searches posts between two points in time and buckets them by day or week, with the number of posts, their total score and comments, and the top post of each bucket. Empty buckets are included."
+    )]
+    pub(super) async fn get_topic_timeline(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Search query")]
+        query: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Start of the range: unix timestamp, RFC 3339 time or YYYY-MM-DD date (UTC)"
+        )]
+        start: String,
+        #[tool(param)]
+        #[schemars(
+            description = "End of the range: unix timestamp, RFC 3339 time or YYYY-MM-DD date (UTC). Defaults to now."
+        )]
+        end: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Only search this subreddit. Omit to search all of Reddit.")]
+        subreddit: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Bucket size: day or week (default day)")]
+        bucket: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Building timeline of {:?} from {}", query, start);
+
+        let now = chrono::Utc::now().timestamp();
+        let start = parse_timestamp(&start, "start")?;
+        let end = match end {
+            Some(end) => parse_timestamp(&end, "end")?,
+            None => now,
+        };
+        if start >= end {
+            return Err(RedditError::invalid_input(
+                "start must be before end",
+                "swap start and end or widen the range",
+            ));
+        }
+        let bucket = bucket.unwrap_or_else(|| "day".to_string());
+        if !TIMELINE_BUCKETS.contains(&bucket.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown bucket {}", bucket),
+                format!("use one of {}", TIMELINE_BUCKETS.join(", ")),
+            ));
+        }
+
+        let step = if bucket == "week" { 7 } else { 1 };
+        let first = bucket_start(utc_date(start), &bucket);
+        let mut buckets = Vec::new();
+        let mut day = first;
+        let last = utc_date(end - 1);
+        while day <= last {
+            if buckets.len() == MAX_TIMELINE_BUCKETS {
+                return Err(RedditError::invalid_input(
+                    format!("The range spans more than {} buckets", MAX_TIMELINE_BUCKETS),
+                    "narrow the range or use bucket=week",
+                ));
+            }
+            buckets.push(TimelineBucket {
+                start: day.to_string(),
+                posts: 0,
+                total_score: 0,
+                total_comments: 0,
+                top_post: None,
+            });
+            day = day + Days::new(step);
+        }
+
+        let deadline = Deadline::for_tool("get_topic_timeline");
+        let auth_token = self.auth_token().await?;
+        let subreddit = match subreddit {
+            Some(subreddit) => Some(
+                deadline
+                    .run(self.resolve_subreddit(&subreddit, &auth_token))
+                    .await??,
+            ),
+            None => None,
+        };
+        let url = match &subreddit {
+            Some(subreddit) => format!("{}/r/{}/search", OAUTH_URL, subreddit),
+            None => format!("{}/search", OAUTH_URL),
+        };
+        let window = search_window(start, now);
+
+        let mut cursor = None;
+        let mut posts = Vec::new();
+        let mut scanned = 0;
+        let mut pages = 0;
+        let mut reached_start = false;
+        let mut truncated_reason = None;
+        loop {
+            if deadline.is_expired() {
+                truncated_reason = Some(deadline.truncation_reason());
+                break;
+            }
+            let search_request = SearchRequest {
+                q: query.clone(),
+                sort: "new".to_string(),
+                t: window.to_string(),
+                restrict_sr: subreddit.is_some(),
+                kind: "link".to_string(),
+                limit: MAX_PAGE_SIZE,
+                after: cursor.clone(),
+                raw_json: 1,
+            };
+            let Ok(page) = deadline
+                .run(self.get_request::<Listing<Post>, SearchRequest>(
+                    &url,
+                    &auth_token,
+                    search_request,
+                ))
+                .await
+            else {
+                truncated_reason = Some(deadline.truncation_reason());
+                break;
+            };
+            let page = page?;
+
+            let fetched = page.data.children.len();
+            scanned += fetched;
+            cursor = page.data.after.filter(|_| fetched > 0);
+            for post in page.data.children.into_iter().map(|child| child.data) {
+                let created = post.created_utc as i64;
+                if created < start {
+                    cursor = None;
+                    break;
+                }
+                if created < end {
+                    posts.push(post);
+                }
+            }
+            pages += 1;
+            if cursor.is_none() {
+                reached_start = true;
+                break;
+            }
+            if pages >= MAX_SEARCH_PAGES {
+                truncated_reason = Some(format!(
+                    "Reddit stopped returning results after {} posts; the earliest buckets are incomplete, narrow the range or the query to reach the start",
+                    scanned
+                ));
+                break;
+            }
+        }
+
+        let posts = self.apply_content_policy(posts);
+        let total_posts = posts.len();
+        for post in posts {
+            let days = (bucket_start(utc_date(post.created_utc as i64), &bucket) - first)
+                .num_days()
+                .max(0) as usize;
+            let Some(slot) = buckets.get_mut(days / step as usize) else {
+                continue;
+            };
+            slot.posts += 1;
+            slot.total_score += post.score;
+            slot.total_comments += post.num_comments;
+            if slot
+                .top_post
+                .as_ref()
+                .is_none_or(|top| post.score > top.score)
+            {
+                slot.top_post = Some(post_summary(post));
+            }
+        }
+
+        serde_json::to_string(&TopicTimelineResponse {
+            query,
+            subreddit,
+            start,
+            end,
+            bucket,
+            total_posts,
+            buckets,
+            scanned,
+            reached_start,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    /// Runs or resumes a search_posts_between job.
+    pub(super) async fn run_post_search(&self, mut job: Job) -> Result<String, RedditError> {
+        let JobSpec::PostSearch {
+            query,
+            subreddit,
+            start,
+            end,
+            window,
+            limit,
+            exclude_seen,
+        } = job.spec.clone()
+        else {
+            return Err(RedditError::invalid_input(
+                format!("Job {} is not a post search", job.id),
+                "call resume_job with it instead",
+            ));
+        };
+        let url = match &subreddit {
+            Some(subreddit) => format!("{}/r/{}/search", OAUTH_URL, subreddit),
+            None => format!("{}/search", OAUTH_URL),
+        };
+
+        let deadline = Deadline::for_tool("search_posts_between");
+        let auth_token = self.auth_token().await?;
+        let mut truncated_reason = None;
+        while !job.complete {
+            if let Some(reason) = self.job_interrupted(&mut job)? {
+                truncated_reason = Some(reason);
+                break;
+            }
+            if deadline.is_expired() {
+                truncated_reason = Some(deadline.truncation_reason());
+                break;
+            }
+
+            let search_request = SearchRequest {
+                q: query.clone(),
+                sort: "new".to_string(),
+                t: window.clone(),
+                restrict_sr: subreddit.is_some(),
+                kind: "link".to_string(),
+                limit: MAX_PAGE_SIZE,
+                after: job.cursor.clone(),
+                raw_json: 1,
+            };
+            let page = match deadline
+                .run(self.get_request::<Listing<Post>, SearchRequest>(
+                    &url,
+                    &auth_token,
+                    search_request,
+                ))
+                .await
+            {
+                Ok(page) => page.map_err(|e| resumable(e, &job))?,
+                Err(_) => {
+                    truncated_reason = Some(deadline.truncation_reason());
+                    break;
+                }
+            };
+
+            let fetched = page.data.children.len();
+            job.scanned += fetched;
+            job.cursor = page.data.after.filter(|_| fetched > 0);
+            for post in page.data.children.into_iter().map(|child| child.data) {
+                let created = post.created_utc as i64;
+                if created < start {
+                    job.cursor = None;
+                    break;
+                }
+                if created < end && !job.processed_ids.contains(&post.name) {
+                    job.processed_ids.push(post.name.clone());
+                    job.items
+                        .push(serde_json::to_value(post).map_err(RedditError::serialize)?);
+                }
+            }
+            job.pages += 1;
+            // Reddit stops paginating search results after about a thousand
+            // hits, so a resumed job could not get further either.
+            job.complete =
+                job.cursor.is_none() || job.items.len() >= limit || job.pages >= MAX_SEARCH_PAGES;
+            self.save_job(&job)?;
+        }
+
+        let mut posts = job
+            .items
+            .iter()
+            .map(|post| serde_json::from_value::<Post>(post.clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RedditError::parse)?;
+        let reached_start = job.complete && job.cursor.is_none();
+        if !reached_start && truncated_reason.is_none() {
+            truncated_reason = Some(if posts.len() >= limit {
+                format!(
+                    "limit of {} posts reached before the start of the range",
+                    limit
+                )
+            } else {
+                format!(
+                    "Reddit stopped returning results after {} posts; narrow the range or the query to reach the start",
+                    job.scanned
+                )
+            });
+        }
+        posts.truncate(limit);
+        let mut posts = self.apply_content_policy(posts);
+        let excluded_seen = self.filter_seen(&mut posts, Some(exclude_seen));
+
+        let truncated_reason = truncated_reason.map(|reason| resume_note(reason, &job));
+        serde_json::to_string(&SearchBetweenResponse {
+            job_id: job.id,
+            query,
+            subreddit,
+            start,
+            end,
+            window,
+            posts,
+            excluded_seen,
+            scanned: job.scanned,
+            reached_start,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,763 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use reqwest::Url;
+use rmcp::tool;
+
+use super::compliance::{Draft, account_gating_checks};
+use super::{BASE_URL, RedditClient};
+use crate::config::CooldownMode;
+use crate::reddit::accounts::DEFAULT_ACCOUNT;
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::idempotency::Claim;
+use crate::reddit::links;
+use crate::reddit::models::{
+    CheckStatus, CooldownStatus, InfoItem, SubmissionRecord, SubmissionStatus,
+    SubmissionVerification, SubmitData, SubmitPostResponse, SubmitRequest, SubmitTargetResult,
+    SubmitToMultipleResponse, TargetOutcome,
+};
+use crate::reddit::validation::normalize_subreddit;
+use crate::storage::Namespace;
+
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+/// AutoModerator and the spam filter act within a few seconds of a submission.
+const VERIFY_DELAY: Duration = Duration::from_secs(3);
+/// Submissions are kept at least this long, even when every window is shorter.
+const SUBMISSION_LOG_HOURS: i64 = 30 * 24;
+/// Pause between the subreddits of submit_to_multiple, so that Reddit's
+/// posting rate limit does not reject the later ones.
+const MULTI_SUBMIT_INTERVAL: Duration = Duration::from_secs(10);
+/// Time a subreddit of submit_to_multiple needs at least: the checks, the
+/// submission and its verification.
+const MULTI_SUBMIT_TARGET_TIME: Duration = Duration::from_secs(15);
+const MAX_MULTI_SUBMIT_TARGETS: usize = 10;
+
+fn verification_of(item: &InfoItem) -> SubmissionVerification {
+    let removed_by_automod = item
+        .banned_by
+        .as_ref()
+        .and_then(|banned_by| banned_by.as_str())
+        .is_some_and(|moderator| moderator.eq_ignore_ascii_case("AutoModerator"));
+    let (status, detail) = match item.removed_by_category.as_deref() {
+        None => (SubmissionStatus::Live, None),
+        Some("automod_filtered") => (
+            SubmissionStatus::HeldForReview,
+            Some("AutoModerator filtered it; it is invisible until a moderator approves it"),
+        ),
+        Some("reddit") => (
+            SubmissionStatus::FilteredAsSpam,
+            Some("Reddit's spam filter caught it; messaging the moderators may get it approved"),
+        ),
+        Some("moderator") if removed_by_automod => (
+            SubmissionStatus::RemovedByAutomod,
+            Some("AutoModerator removed it; check the subreddit rules"),
+        ),
+        Some("moderator") => (SubmissionStatus::RemovedByModerator, None),
+        Some("deleted") | Some("author") => (SubmissionStatus::Deleted, None),
+        Some(_) => (SubmissionStatus::Removed, None),
+    };
+    SubmissionVerification {
+        status,
+        removed_by_category: item.removed_by_category.clone(),
+        detail: detail.map(str::to_string),
+    }
+}
+
+/// Keys a per-subreddit map by the bare, lowercased subreddit name.
+fn by_subreddit(
+    map: Option<HashMap<String, String>>,
+) -> Result<HashMap<String, String>, RedditError> {
+    map.unwrap_or_default()
+        .into_iter()
+        .map(|(subreddit, value)| {
+            Ok((normalize_subreddit(&subreddit)?.to_ascii_lowercase(), value))
+        })
+        .collect()
+}
+
+/// Submissions are logged per account and subreddit. The default account
+/// keeps the key used before there were several accounts.
+fn submissions_key(account: &str, subreddit: &str) -> String {
+    let subreddit = subreddit.to_ascii_lowercase();
+    if account == DEFAULT_ACCOUNT {
+        subreddit
+    } else {
+        format!("{}-{}", account, subreddit)
+    }
+}
+
+fn format_utc(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+impl RedditClient {
+    /// The running account's recent submissions to `subreddit`.
+    fn load_submissions(&self, subreddit: &str) -> Result<Vec<SubmissionRecord>, RedditError> {
+        self.storage
+            .read_json::<Vec<SubmissionRecord>>(
+                Namespace::Submissions,
+                &submissions_key(&self.account().name, subreddit),
+            )
+            .map(Option::unwrap_or_default)
+            .map_err(RedditError::storage)
+    }
+
+    fn cooldown_status(&self, subreddit: &str, now: i64) -> Result<CooldownStatus, RedditError> {
+        let rule = self.config.get().cooldowns.rule_for(subreddit);
+        let window_start = now - i64::from(rule.window_hours) * SECONDS_PER_HOUR;
+        let mut recent: Vec<SubmissionRecord> = self
+            .load_submissions(subreddit)?
+            .into_iter()
+            .filter(|record| record.created_utc > window_start)
+            .collect();
+        recent.sort_by_key(|record| record.created_utc);
+
+        let max_posts = rule.max_posts as usize;
+        let allowed = recent.len() < max_posts;
+        // The window has room again once enough of the oldest posts age out of it.
+        let next_allowed_utc = match max_posts {
+            0 => None,
+            _ if allowed => None,
+            _ => Some(
+                recent[recent.len() - max_posts].created_utc
+                    + i64::from(rule.window_hours) * SECONDS_PER_HOUR,
+            ),
+        };
+
+        Ok(CooldownStatus {
+            subreddit: subreddit.to_string(),
+            rule,
+            recent_submissions: recent,
+            allowed,
+            next_allowed_utc,
+        })
+    }
+
+    fn record_submission(
+        &self,
+        subreddit: &str,
+        record: SubmissionRecord,
+    ) -> Result<(), RedditError> {
+        let window_hours = self.config.get().cooldowns.rule_for(subreddit).window_hours;
+        let keep_after =
+            record.created_utc - SUBMISSION_LOG_HOURS.max(window_hours.into()) * SECONDS_PER_HOUR;
+        let mut records = self.load_submissions(subreddit)?;
+        records.retain(|existing| existing.created_utc > keep_after);
+        records.push(record);
+        self.storage
+            .write_json(
+                Namespace::Submissions,
+                &submissions_key(&self.account().name, subreddit),
+                &records,
+            )
+            .map_err(RedditError::storage)
+    }
+
+    /// Fetches a new post or comment again after a short wait, to report
+    /// whether it is actually visible.
+    async fn verify_submission(
+        &self,
+        fullname: &str,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> SubmissionVerification {
+        let unknown = |detail: String| SubmissionVerification {
+            status: SubmissionStatus::Unknown,
+            removed_by_category: None,
+            detail: Some(detail),
+        };
+        let fetched = deadline
+            .run(async {
+                tokio::time::sleep(VERIFY_DELAY).await;
+                self.fetch_info(&[fullname.to_string()], auth_token).await
+            })
+            .await
+            .and_then(|result| result);
+        match fetched {
+            Ok(mut items) => match items.remove(fullname) {
+                Some(item) => verification_of(&item),
+                None => unknown(format!("{} could not be found again", fullname)),
+            },
+            Err(e) => unknown(format!("the check failed: {}", e)),
+        }
+    }
+
+    /// Checks the subreddit's posting cooldown before a submission. A
+    /// violation fails in block mode unless `ignore_cooldown` and is added to
+    /// `warnings` otherwise. Returns whether a violation was overridden.
+    pub(super) fn check_cooldown(
+        &self,
+        subreddit: &str,
+        ignore_cooldown: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<bool, RedditError> {
+        let status = self.cooldown_status(subreddit, chrono::Utc::now().timestamp())?;
+        if status.allowed {
+            return Ok(false);
+        }
+        let violation = format!(
+            "r/{} allows {} post(s) per {} hours and {} were already submitted in that window",
+            subreddit,
+            status.rule.max_posts,
+            status.rule.window_hours,
+            status.recent_submissions.len()
+        );
+        if status.rule.mode == CooldownMode::Block && !ignore_cooldown {
+            let wait = match status.next_allowed_utc {
+                Some(next) => format!("wait until {}", format_utc(next)),
+                None => "raise the limit in the cooldowns configuration".to_string(),
+            };
+            return Err(RedditError::invalid_input(
+                violation,
+                format!("{} or pass ignore_cooldown=true to submit anyway", wait),
+            ));
+        }
+        warnings.push(violation);
+        Ok(ignore_cooldown)
+    }
+
+    /// Submits a post, logs it for the cooldowns and verifies it.
+    pub(super) async fn submit(
+        &self,
+        request: SubmitRequest,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<(SubmitData, SubmissionVerification), RedditError> {
+        let subreddit = request.sr.clone();
+        let title = request.title.clone();
+        let post = deadline
+            .run(
+                self.post_api::<SubmitData, SubmitRequest>(
+                    &format!("{}/submit", BASE_URL),
+                    auth_token,
+                    request,
+                ),
+            )
+            .await?
+            .map_err(|e| {
+                e.with_rejection_hint(
+                    "fix the reported problem; check_post_against_rules shows the subreddit's requirements",
+                )
+            })?
+            .ok_or_else(|| RedditError::parse("the submit response has no data"))?;
+
+        // The post exists now, so a failure to log it must not turn into an
+        // error the caller would retry.
+        if let Err(e) = self.record_submission(
+            &subreddit,
+            SubmissionRecord {
+                name: post.name.clone(),
+                title,
+                created_utc: chrono::Utc::now().timestamp(),
+            },
+        ) {
+            tracing::warn!(
+                "Failed to log post {} for the posting cooldown: {}",
+                post.name,
+                e
+            );
+        }
+
+        let verification = self
+            .verify_submission(&post.name, auth_token, deadline)
+            .await;
+        if verification.status != SubmissionStatus::Live {
+            tracing::warn!("Post {} is {:?}", post.name, verification.status);
+        }
+        Ok((post, verification))
+    }
+
+    /// Applies the `[links]` policy to the URL of a link post: shortened links
+    /// are resolved and tracking parameters removed. What changed is added to
+    /// `notes`.
+    pub(super) async fn clean_link(&self, url: String, notes: &mut Vec<String>) -> String {
+        let config = self.config.get().links;
+        let Ok(mut cleaned) = Url::parse(url.trim()) else {
+            return url;
+        };
+        let mut changed = false;
+        if config.resolve_shorteners && links::is_shortened(&cleaned, &config) {
+            match self.unfurler.resolve(cleaned.clone()).await {
+                Some(resolved) if resolved != cleaned => {
+                    notes.push(format!(
+                        "the shortened link {} was replaced with {}",
+                        cleaned, resolved
+                    ));
+                    cleaned = resolved;
+                    changed = true;
+                }
+                _ => notes.push(format!(
+                    "the shortened link {} could not be resolved; many subreddits remove shortened links",
+                    cleaned
+                )),
+            }
+        }
+        if config.strip_tracking {
+            let removed = links::strip_tracking(&mut cleaned, &config.tracking_parameters);
+            if !removed.is_empty() {
+                notes.push(format!(
+                    "tracking parameters were removed from the link: {}",
+                    removed.join(", ")
+                ));
+                changed = true;
+            }
+        }
+        if changed { cleaned.to_string() } else { url }
+    }
+
+    /// Warnings that the subreddit may silently filter a post from this
+    /// account because of its age or karma. Lookups that fail are skipped.
+    async fn account_gating_warnings(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Vec<String> {
+        let Ok(Ok(me)) = deadline.run(self.fetch_me(auth_token)).await else {
+            return Vec::new();
+        };
+        let rules = deadline
+            .run(self.fetch_subreddit_rules(subreddit, auth_token))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .unwrap_or_default();
+        let guidelines = deadline
+            .run(self.fetch_post_requirements(subreddit, auth_token))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .and_then(|requirements| requirements.guidelines_text);
+
+        account_gating_checks(
+            &me,
+            &rules,
+            guidelines.as_deref(),
+            chrono::Utc::now().timestamp() as f64,
+        )
+        .into_iter()
+        .filter(|item| item.status == CheckStatus::Warning)
+        .map(|item| format!("the post may be auto-filtered: {}", item.detail))
+        .collect()
+    }
+
+    #[tool(
+        description = "Check whether another post to a subreddit would break its configured posting cadence (e.g. at most 1 post per 24 hours), based on the account's submissions made through this server."
+    )]
+    pub(super) async fn check_posting_cooldown(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, e.g. rust or r/rust")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        let subreddit = normalize_subreddit(&subreddit)?;
+        tracing::info!("Checking posting cooldown for r/{}", subreddit);
+
+        let status = self.cooldown_status(&subreddit, chrono::Utc::now().timestamp())?;
+        serde_json::to_string(&status).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Submit a text or link post. The subreddit's posting cooldown is checked first: depending on configuration a violation is reported as a warning or blocks the submission unless ignore_cooldown is true. Warnings also report when the account's age or karma may get the post silently filtered, and the post is fetched again afterwards to report whether it is live, held for review or removed."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn submit_post(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, e.g. rust or r/rust")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Post title")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Markdown body of a text post")]
+        text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "URL of a link post. Leave empty for a text post.")]
+        url: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Flair template id")]
+        flair_id: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Flair text, for editable flair templates")]
+        flair_text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Mark the post NSFW")]
+        nsfw: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Mark the post as a spoiler")]
+        spoiler: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Submit even when a blocking posting cooldown is in effect. Defaults to false."
+        )]
+        ignore_cooldown: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of posting again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Submitting a post to {}", subreddit);
+
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(RedditError::invalid_input(
+                "The title is empty",
+                "pass a title for the post",
+            ));
+        }
+        let url = url.filter(|url| !url.trim().is_empty());
+        if url.is_some() && text.as_ref().is_some_and(|text| !text.is_empty()) {
+            return Err(RedditError::invalid_input(
+                "Both text and url were given",
+                "pass text for a text post or url for a link post, not both",
+            ));
+        }
+
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "submit_post",
+            idempotency_key.as_deref(),
+            serde_json::json!({
+                "subreddit": subreddit,
+                "title": title,
+                "text": text,
+                "url": url,
+                "flair_id": flair_id,
+                "flair_text": flair_text,
+                "nsfw": nsfw,
+                "spoiler": spoiler,
+            }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let deadline = Deadline::for_tool("submit_post");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        let mut warnings = Vec::new();
+        let url = match url {
+            Some(url) => Some(self.clean_link(url, &mut warnings).await),
+            None => None,
+        };
+        let cooldown_overridden =
+            self.check_cooldown(&subreddit, ignore_cooldown.unwrap_or(false), &mut warnings)?;
+        warnings.extend(
+            self.account_gating_warnings(&subreddit, &auth_token, &deadline)
+                .await,
+        );
+
+        let kind = if url.is_some() { "link" } else { "self" };
+        let request = SubmitRequest {
+            sr: subreddit,
+            kind,
+            title,
+            text: if url.is_none() { text } else { None },
+            url,
+            flair_id,
+            flair_text,
+            nsfw: nsfw.unwrap_or(false),
+            spoiler: spoiler.unwrap_or(false),
+            crosspost_fullname: None,
+        };
+        let (post, verification) = self.submit(request, &auth_token, &deadline).await?;
+
+        let result = serde_json::to_string(&SubmitPostResponse {
+            verification,
+            post,
+            cooldown_overridden,
+            warnings,
+        })
+        .map_err(RedditError::serialize)?;
+        claim.record(&result);
+        Ok(result)
+    }
+
+    /// Checks and submits one target of submit_to_multiple.
+    #[allow(clippy::too_many_arguments)]
+    async fn submit_target(
+        &self,
+        subreddit: &str,
+        request: SubmitRequest,
+        draft_url: Option<&str>,
+        ignore_cooldown: bool,
+        ignore_conflicts: bool,
+        auth_token: &str,
+        deadline: &Deadline,
+        result: &mut SubmitTargetResult,
+    ) -> Result<Option<(SubmitData, SubmissionVerification)>, RedditError> {
+        let subreddit = deadline
+            .run(self.resolve_subreddit(subreddit, auth_token))
+            .await??;
+        result.subreddit = subreddit.clone();
+
+        if let Err(e) = self.check_cooldown(&subreddit, ignore_cooldown, &mut result.warnings) {
+            result.outcome = TargetOutcome::Skipped;
+            result.error = Some(e.to_string());
+            return Ok(None);
+        }
+
+        let body = request.text.clone().unwrap_or_default();
+        let draft = Draft {
+            title: &request.title,
+            body: &body,
+            url: draft_url,
+            flair: request
+                .flair_text
+                .as_deref()
+                .or(request.flair_id.as_deref()),
+        };
+        let (checklist, _) = self
+            .draft_checklist(&subreddit, &draft, auth_token, deadline)
+            .await?;
+        for item in checklist {
+            let detail = format!("{}: {}", item.check, item.detail);
+            match item.status {
+                CheckStatus::Conflict => result.conflicts.push(detail),
+                CheckStatus::Warning => result.warnings.push(detail),
+                _ => {}
+            }
+        }
+        if !result.conflicts.is_empty() && !ignore_conflicts {
+            result.outcome = TargetOutcome::Skipped;
+            result.error = Some(
+                "the post conflicts with the subreddit's requirements; pass ignore_conflicts=true to submit anyway"
+                    .to_string(),
+            );
+            return Ok(None);
+        }
+
+        let request = SubmitRequest {
+            sr: subreddit,
+            ..request
+        };
+        self.submit(request, auth_token, deadline).await.map(Some)
+    }
+
+    #[tool(
+        description = "Submit the same text or link post to several subreddits, one after another with a pause between them to respect Reddit's posting rate limit. In crosspost mode only the first subreddit gets the post and the others crosspost it. Each subreddit is checked first against its posting cooldown and its post type and posting requirements; subreddits with a definite conflict are skipped. Flair can be chosen per subreddit. Returns a per-subreddit report of submitted, skipped and failed posts; a failure does not stop the remaining subreddits. Subreddits that no longer fit in the call's time budget are reported as not attempted."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn submit_to_multiple(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "Subreddits in posting order, e.g. [\"rust\", \"r/programming\"] (at most 10)"
+        )]
+        subreddits: Vec<String>,
+        #[tool(param)]
+        #[schemars(description = "Post title")]
+        title: String,
+        #[tool(param)]
+        #[schemars(description = "Markdown body of a text post")]
+        text: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "URL of a link post. Leave empty for a text post.")]
+        url: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Flair template id per subreddit, e.g. {\"rust\": \"a1b2c3...\"}. Subreddits not listed get no flair."
+        )]
+        flair_ids: Option<HashMap<String, String>>,
+        #[tool(param)]
+        #[schemars(description = "Flair text per subreddit, for editable flair templates")]
+        flair_texts: Option<HashMap<String, String>>,
+        #[tool(param)]
+        #[schemars(
+            description = "Submit to the first subreddit and crosspost that post to the others. Defaults to false."
+        )]
+        crosspost: Option<bool>,
+        #[tool(param)]
+        #[schemars(description = "Mark the posts NSFW")]
+        nsfw: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Submit even where a blocking posting cooldown is in effect. Defaults to false."
+        )]
+        ignore_cooldown: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Submit even where the post conflicts with the subreddit's post type or posting requirements. Defaults to false."
+        )]
+        ignore_conflicts: Option<bool>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optional key of up to 64 letters, digits, - or _ (e.g. a UUID). Retrying with the same key within 24 hours returns the first call's result instead of posting again."
+        )]
+        idempotency_key: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Submitting a post to {} subreddits", subreddits.len());
+
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(RedditError::invalid_input(
+                "The title is empty",
+                "pass a title for the post",
+            ));
+        }
+        if subreddits.is_empty() || subreddits.len() > MAX_MULTI_SUBMIT_TARGETS {
+            return Err(RedditError::invalid_input(
+                format!("{} subreddits were given", subreddits.len()),
+                format!("pass between 1 and {} subreddits", MAX_MULTI_SUBMIT_TARGETS),
+            ));
+        }
+        let url = url.filter(|url| !url.trim().is_empty());
+        if url.is_some() && text.as_ref().is_some_and(|text| !text.is_empty()) {
+            return Err(RedditError::invalid_input(
+                "Both text and url were given",
+                "pass text for a text post or url for a link post, not both",
+            ));
+        }
+        let flair_ids = by_subreddit(flair_ids)?;
+        let flair_texts = by_subreddit(flair_texts)?;
+        let crosspost = crosspost.unwrap_or(false);
+        let ignore_cooldown = ignore_cooldown.unwrap_or(false);
+        let ignore_conflicts = ignore_conflicts.unwrap_or(false);
+
+        let claim = match self.idempotency.claim(
+            &self.account().name,
+            "submit_to_multiple",
+            idempotency_key.as_deref(),
+            serde_json::json!({
+                "subreddits": subreddits,
+                "title": title,
+                "text": text,
+                "url": url,
+                "flair_ids": flair_ids,
+                "flair_texts": flair_texts,
+                "crosspost": crosspost,
+                "nsfw": nsfw,
+            }),
+        )? {
+            Claim::Replay(result) => return Ok(result),
+            Claim::Fresh(claim) => claim,
+        };
+
+        let deadline = Deadline::for_tool("submit_to_multiple");
+        let mut link_notes = Vec::new();
+        let url = match url {
+            Some(url) => Some(self.clean_link(url, &mut link_notes).await),
+            None => None,
+        };
+        let auth_token = self.auth_token().await?;
+        let mut crosspost_of: Option<SubmitData> = None;
+        let mut targets = Vec::new();
+        let mut truncated_reason = None;
+        for (index, subreddit) in subreddits.iter().enumerate() {
+            let pause = if index > 0 {
+                MULTI_SUBMIT_INTERVAL
+            } else {
+                Duration::ZERO
+            };
+            if truncated_reason.is_some() || deadline.remaining() < pause + MULTI_SUBMIT_TARGET_TIME
+            {
+                truncated_reason.get_or_insert_with(|| deadline.truncation_reason());
+                targets.push(SubmitTargetResult {
+                    subreddit: subreddit.clone(),
+                    outcome: TargetOutcome::NotAttempted,
+                    post: None,
+                    verification: None,
+                    conflicts: Vec::new(),
+                    warnings: Vec::new(),
+                    error: None,
+                });
+                continue;
+            }
+            tokio::time::sleep(pause).await;
+            let key = normalize_subreddit(subreddit)
+                .map(|name| name.to_ascii_lowercase())
+                .unwrap_or_default();
+            let (kind, request_text, request_url, crosspost_fullname) = match &crosspost_of {
+                Some(source) => ("crosspost", None, None, Some(source.name.clone())),
+                None if url.is_some() => ("link", None, url.clone(), None),
+                None => ("self", text.clone(), None, None),
+            };
+            let draft_url = match &crosspost_of {
+                Some(source) => Some(source.url.clone()),
+                None => url.clone(),
+            };
+            let request = SubmitRequest {
+                sr: subreddit.clone(),
+                kind,
+                title: title.clone(),
+                text: request_text,
+                url: request_url,
+                flair_id: flair_ids.get(&key).cloned(),
+                flair_text: flair_texts.get(&key).cloned(),
+                nsfw: nsfw.unwrap_or(false),
+                spoiler: false,
+                crosspost_fullname,
+            };
+
+            let mut result = SubmitTargetResult {
+                subreddit: subreddit.clone(),
+                outcome: TargetOutcome::Failed,
+                post: None,
+                verification: None,
+                conflicts: Vec::new(),
+                warnings: link_notes.clone(),
+                error: None,
+            };
+            match self
+                .submit_target(
+                    subreddit,
+                    request,
+                    draft_url.as_deref(),
+                    ignore_cooldown,
+                    ignore_conflicts,
+                    &auth_token,
+                    &deadline,
+                    &mut result,
+                )
+                .await
+            {
+                Ok(Some((post, verification))) => {
+                    result.outcome = if crosspost_of.is_some() {
+                        TargetOutcome::Crossposted
+                    } else {
+                        TargetOutcome::Submitted
+                    };
+                    if crosspost && crosspost_of.is_none() {
+                        crosspost_of = Some(post.clone());
+                    }
+                    result.post = Some(post);
+                    result.verification = Some(verification);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Submitting to {} failed: {}", subreddit, e);
+                    result.outcome = TargetOutcome::Failed;
+                    result.error = Some(e.to_string());
+                }
+            }
+            targets.push(result);
+        }
+
+        let count = |outcome: &[TargetOutcome]| {
+            targets
+                .iter()
+                .filter(|target| outcome.contains(&target.outcome))
+                .count()
+        };
+        let result = serde_json::to_string(&SubmitToMultipleResponse {
+            submitted: count(&[TargetOutcome::Submitted, TargetOutcome::Crossposted]),
+            skipped: count(&[TargetOutcome::Skipped]),
+            failed: count(&[TargetOutcome::Failed]),
+            not_attempted: count(&[TargetOutcome::NotAttempted]),
+            truncated_reason,
+            crosspost_of: crosspost_of.map(|source| source.name),
+            targets,
+        })
+        .map_err(RedditError::serialize)?;
+        claim.record(&result);
+        Ok(result)
+    }
+}
@@ -0,0 +1,834 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rmcp::tool;
+
+use super::listings::post_summary;
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    AwardCount, AwardSummary, AwardedItem, AwardedThing, CommunityListItem, EmojiEntry, Listing,
+    ListingRequest, MostAwardedResponse, Post, PostDetails, RawJsonRequest, RuleEntry,
+    SidebarResponse, SimilarSubreddit, SimilarSubredditsResponse, StickyPost, StickyPostsResponse,
+    SubredditAbout, SubredditAboutResponse, SubredditAutocompleteRequest, SubredditAwardsResponse,
+    SubredditComparison, SubredditEmoji, SubredditEmojisResponse, SubredditRule,
+    SubredditRulesListing, SubredditRulesResponse, Thing, WidgetsResponse, WikiPage,
+};
+use crate::reddit::text::{clean_markdown, snippet};
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+const AWARD_TIMEFRAMES: [&str; 4] = ["week", "month", "year", "all"];
+const DEFAULT_AWARD_SAMPLE: u32 = 300;
+const MAX_AWARD_SAMPLE: u32 = 1000;
+const DEFAULT_AWARDED_LIMIT: usize = 10;
+const MAX_AWARDED_LIMIT: usize = 50;
+const AWARDED_BODY_CHARS: usize = 300;
+/// Reddit lets moderators sticky at most this many posts.
+const STICKY_SLOTS: usize = 2;
+const STICKY_TEXT_CHARS: usize = 3000;
+/// Names of the `gildings` ids that predate `all_awardings`.
+const GILDINGS: [(&str, &str); 3] = [
+    ("gid_1", "Silver"),
+    ("gid_2", "Gold"),
+    ("gid_3", "Platinum"),
+];
+const DEFAULT_SIMILAR_LIMIT: u32 = 10;
+const MAX_SIMILAR_LIMIT: u32 = 25;
+const MAX_TOPICS: usize = 3;
+const AUTOCOMPLETE_LIMIT: u32 = 10;
+// Moderators' own picks count for more than a passing sidebar link, which
+// counts for more than a name that merely matches a topic.
+const WIDGET_WEIGHT: u32 = 3;
+const SIDEBAR_WEIGHT: u32 = 2;
+const AUTOCOMPLETE_WEIGHT: u32 = 1;
+const TOPIC_STOPWORDS: [&str; 12] = [
+    "about",
+    "community",
+    "discuss",
+    "discussion",
+    "everything",
+    "place",
+    "reddit",
+    "related",
+    "subreddit",
+    "that",
+    "this",
+    "with",
+];
+
+static SUBREDDIT_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w/])/?[rR]/([A-Za-z0-9_]{2,21})").expect("valid subreddit link regex")
+});
+
+/// Buckets a post into text, image, video, gallery or link.
+fn content_type(post: &Post) -> &'static str {
+    if post.is_self {
+        return "text";
+    }
+    match post.post_hint.as_deref() {
+        Some("image") => "image",
+        Some("hosted:video") | Some("rich:video") => "video",
+        _ if post.url.contains("reddit.com/gallery/") => "gallery",
+        _ => "link",
+    }
+}
+
+/// Picks autocomplete queries from a subreddit's title and description: its
+/// longest distinct words.
+fn topic_words(about: &SubredditAbout) -> Vec<String> {
+    let mut words: Vec<String> = format!("{} {}", about.title, about.public_description)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 4)
+        .map(str::to_lowercase)
+        .filter(|word| {
+            !TOPIC_STOPWORDS.contains(&word.as_str()) && *word != about.display_name.to_lowercase()
+        })
+        .collect();
+    words.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    words.dedup();
+    words.truncate(MAX_TOPICS);
+    words
+}
+
+/// Tallies the awards of a post or comment, falling back to the older
+/// gildings when Reddit sent no awardings. Returns None when it has none.
+fn awarded_item(thing: AwardedThing) -> Option<AwardedItem> {
+    let awards: Vec<(String, i64, i64)> = if thing.all_awardings.is_empty() {
+        GILDINGS
+            .iter()
+            .filter_map(|(id, name)| {
+                let count = thing.gildings.get(*id).copied().unwrap_or_default();
+                (count > 0).then(|| (name.to_string(), count, 0))
+            })
+            .collect()
+    } else {
+        thing
+            .all_awardings
+            .into_iter()
+            .map(|awarding| (awarding.name, awarding.count, awarding.coin_price))
+            .collect()
+    };
+    let total_awards: i64 = awards.iter().map(|(_, count, _)| count).sum();
+    if total_awards == 0 {
+        return None;
+    }
+
+    Some(AwardedItem {
+        kind: if thing.name.starts_with("t1_") {
+            "comment"
+        } else {
+            "post"
+        },
+        name: thing.name,
+        author: thing.author,
+        subreddit: thing.subreddit,
+        score: thing.score,
+        title: thing.title,
+        body: thing.body.map(|body| snippet(&body, AWARDED_BODY_CHARS)),
+        permalink: format!("https://www.reddit.com{}", thing.permalink),
+        created_utc: thing.created_utc as i64,
+        total_awards,
+        coin_value: awards.iter().map(|(_, count, price)| count * price).sum(),
+        awards: awards
+            .into_iter()
+            .map(|(name, count, _)| AwardCount { name, count })
+            .collect(),
+    })
+}
+
+/// Records that `name` was found by `source`, keyed by lowercase name.
+fn add_candidate<'a>(
+    candidates: &'a mut HashMap<String, SimilarSubreddit>,
+    name: &str,
+    source: &'static str,
+    weight: u32,
+) -> &'a mut SimilarSubreddit {
+    let candidate = candidates
+        .entry(name.to_lowercase())
+        .or_insert_with(|| SimilarSubreddit {
+            name: name.to_string(),
+            title: String::new(),
+            subscribers: None,
+            nsfw: false,
+            score: 0,
+            sources: Vec::new(),
+        });
+    if !candidate.sources.contains(&source) {
+        candidate.sources.push(source);
+        candidate.score += weight;
+    }
+    candidate
+}
+
+fn add_linked(candidates: &mut HashMap<String, SimilarSubreddit>, text: &str) {
+    for captures in SUBREDDIT_LINK.captures_iter(text) {
+        add_candidate(candidates, &captures[1], "sidebar", SIDEBAR_WEIGHT);
+    }
+}
+
+impl RedditClient {
+    pub(super) async fn fetch_subreddit_about(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+    ) -> Result<SubredditAbout, RedditError> {
+        let url = format!("{}/r/{}/about", OAUTH_URL, subreddit);
+        self.get_request::<Thing<SubredditAbout>, RawJsonRequest>(
+            &url,
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await
+        .map(|thing| thing.data)
+    }
+
+    pub(super) async fn fetch_subreddit_rules(
+        &self,
+        subreddit: &str,
+        auth_token: &str,
+    ) -> Result<Vec<SubredditRule>, RedditError> {
+        let url = format!("{}/r/{}/about/rules", OAUTH_URL, subreddit);
+        self.get_request::<SubredditRulesResponse, RawJsonRequest>(
+            &url,
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await
+        .map(|response| response.rules)
+    }
+
+    #[tool(
+        description = "Get a subreddit's metadata: subscribers, active users, creation date, public description, NSFW flag, whether it is public or restricted, and which kinds of posts it accepts. Check it before deciding whether or what to post there."
+    )]
+    pub(super) async fn get_subreddit_about(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching about of {}", subreddit);
+
+        let deadline = Deadline::for_tool("get_subreddit_about");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+            .await??;
+        let now = chrono::Utc::now().timestamp() as f64;
+
+        serde_json::to_string(&SubredditAboutResponse {
+            subreddit: about.display_name,
+            fullname: about.name,
+            title: about.title,
+            public_description: about.public_description,
+            subscribers: about.subscribers,
+            active_users: about.accounts_active,
+            created_utc: about.created_utc,
+            age_days: ((now - about.created_utc) / SECONDS_PER_DAY) as i64,
+            nsfw: about.over18,
+            subreddit_type: about.subreddit_type,
+            submission_type: about.submission_type,
+            allow_images: about.allow_images,
+            allow_videos: about.allow_videos,
+            allow_galleries: about.allow_galleries,
+            allow_polls: about.allow_polls,
+            approved_posters_only: about.restrict_posting,
+            language: about.lang.filter(|lang| !lang.is_empty()),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get a subreddit's old-Reddit sidebar as clean markdown, falling back to the index page of its wiki when the sidebar is empty. Much posting guidance only lives there."
+    )]
+    pub(super) async fn get_subreddit_sidebar(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching sidebar of {}", subreddit);
+
+        let deadline = Deadline::for_tool("get_subreddit_sidebar");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+            .await??;
+
+        let mut source = "sidebar";
+        let mut markdown = clean_markdown(&about.description);
+        if markdown.is_empty() {
+            // Subreddits without a wiki, or with a private one, answer with an error.
+            let url = format!("{}/r/{}/wiki/index", OAUTH_URL, subreddit);
+            let wiki = deadline
+                .run(self.get_request::<Thing<WikiPage>, RawJsonRequest>(
+                    &url,
+                    &auth_token,
+                    RawJsonRequest { raw_json: 1 },
+                ))
+                .await?
+                .map(|page| clean_markdown(&page.data.content_md))
+                .unwrap_or_default();
+            (source, markdown) = if wiki.is_empty() {
+                ("none", wiki)
+            } else {
+                ("wiki", wiki)
+            };
+        }
+
+        serde_json::to_string(&SidebarResponse {
+            subreddit,
+            source,
+            markdown,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get the posts pinned to the top of a subreddit (sticky slots 1 and 2), with their text. Rules clarifications, announcements and megathreads usually live there, so check them before posting."
+    )]
+    pub(super) async fn get_sticky_posts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching sticky posts of {}", subreddit);
+
+        let deadline = Deadline::for_tool("get_sticky_posts");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        // Stickied posts lead the hot listing, in slot order.
+        let url = format!("{}/r/{}/hot", OAUTH_URL, subreddit);
+        let listing = deadline
+            .run(self.get_request::<Listing<PostDetails>, ListingRequest>(
+                &url,
+                &auth_token,
+                ListingRequest {
+                    limit: STICKY_SLOTS as u32,
+                    after: None,
+                    before: None,
+                    raw_json: 1,
+                },
+            ))
+            .await??;
+
+        let allow_nsfw = self.config.get().content.allow_nsfw;
+        let posts = listing
+            .data
+            .children
+            .into_iter()
+            .map(|child| child.data)
+            .take_while(|details| details.stickied)
+            .enumerate()
+            .filter(|(_, details)| allow_nsfw || !details.post.over_18)
+            .map(|(index, details)| {
+                let selftext = snippet(&details.post.selftext, STICKY_TEXT_CHARS);
+                let url = (!details.post.is_self).then(|| details.post.url.clone());
+                StickyPost {
+                    slot: index + 1,
+                    link_flair_text: details.post.link_flair_text.clone(),
+                    locked: details.locked,
+                    url,
+                    selftext,
+                    post: post_summary(details.post),
+                }
+            })
+            .collect();
+
+        serde_json::to_string(&StickyPostsResponse { subreddit, posts })
+            .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "List a subreddit's rules in order, each with its short name, full description, the violation reason moderators cite and whether it covers posts, comments or both. Read them before drafting a post or comment there."
+    )]
+    pub(super) async fn get_subreddit_rules(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching rules of {}", subreddit);
+
+        let deadline = Deadline::for_tool("get_subreddit_rules");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let rules = deadline
+            .run(self.fetch_subreddit_rules(&subreddit, &auth_token))
+            .await??;
+
+        let rules = rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| RuleEntry {
+                number: index + 1,
+                // Reddit falls back to the short name when no reason was set.
+                violation_reason: rule
+                    .violation_reason
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or_else(|| rule.short_name.clone()),
+                short_name: rule.short_name,
+                description: rule.description,
+                applies_to: match rule.kind.as_str() {
+                    "link" => "posts",
+                    "comment" => "comments",
+                    _ => "both",
+                },
+            })
+            .collect();
+
+        serde_json::to_string(&SubredditRulesListing { subreddit, rules })
+            .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Compare 2-5 subreddits side by side: subscribers, active users, posting activity, number of rules, NSFW status and the mix of content types. Useful when deciding where to post."
+    )]
+    pub(super) async fn compare_subreddits(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "2-5 subreddit names, r/ names or reddit.com URLs")]
+        subreddits: Vec<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Comparing subreddits {:?}", subreddits);
+
+        if !(2..=5).contains(&subreddits.len()) {
+            return Err(RedditError::invalid_input(
+                format!("Got {} subreddits to compare", subreddits.len()),
+                "pass between 2 and 5 subreddits",
+            ));
+        }
+
+        let deadline = Deadline::for_tool("compare_subreddits");
+        let auth_token = self.auth_token().await?;
+        let now = chrono::Utc::now().timestamp() as f64;
+        let mut comparisons = Vec::new();
+
+        for subreddit in subreddits {
+            let subreddit = deadline
+                .run(self.resolve_subreddit(&subreddit, &auth_token))
+                .await??;
+            let about = deadline
+                .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+                .await??;
+            // Moderators can hide the rules page; treat that as no published rules.
+            let rule_count = deadline
+                .run(self.fetch_subreddit_rules(&subreddit, &auth_token))
+                .await?
+                .map(|rules| rules.len())
+                .unwrap_or_default();
+            let url = format!("{}/r/{}/new", OAUTH_URL, subreddit);
+            let (sample, _) = self
+                .collect_listing::<Post>(&url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                .await?;
+
+            let sampled_posts = sample.len();
+            let oldest = sample.last().map(|post| post.created_utc).unwrap_or(now);
+            let days = ((now - oldest) / SECONDS_PER_DAY).max(1.0 / 24.0);
+            let average = |value: fn(&Post) -> i64| {
+                if sample.is_empty() {
+                    0.0
+                } else {
+                    sample.iter().map(value).sum::<i64>() as f64 / sampled_posts as f64
+                }
+            };
+            let mut content_types = BTreeMap::new();
+            for post in &sample {
+                *content_types
+                    .entry(content_type(post).to_string())
+                    .or_insert(0) += 1;
+            }
+
+            comparisons.push(SubredditComparison {
+                subreddit,
+                title: about.title,
+                subscribers: about.subscribers,
+                active_users: about.accounts_active,
+                nsfw: about.over18,
+                subreddit_type: about.subreddit_type,
+                submission_type: about.submission_type,
+                created_utc: about.created_utc,
+                rule_count,
+                sampled_posts,
+                posts_per_day: sampled_posts as f64 / days,
+                average_score: average(|post| post.score),
+                average_comments: average(|post| post.num_comments),
+                content_types,
+            });
+        }
+
+        serde_json::to_string(&comparisons).map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "List a subreddit's custom emoji and the Reddit-wide snoomojis, with the :code: to use in flair text and where each may be used."
+    )]
+    pub(super) async fn list_subreddit_emojis(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Listing emojis of {}", subreddit);
+
+        let deadline = Deadline::for_tool("list_subreddit_emojis");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+
+        // The response groups emoji by owner: "snoomojis" for the Reddit-wide
+        // set and the subreddit's t5_ fullname for its own.
+        let url = format!("{}/api/v1/{}/emojis/all", OAUTH_URL, subreddit);
+        let groups = deadline
+            .run(
+                self.get_request::<HashMap<String, HashMap<String, EmojiEntry>>, RawJsonRequest>(
+                    &url,
+                    &auth_token,
+                    RawJsonRequest { raw_json: 1 },
+                ),
+            )
+            .await??;
+
+        let mut emojis: Vec<SubredditEmoji> = groups
+            .into_iter()
+            .flat_map(|(owner, entries)| {
+                let source = if owner == "snoomojis" {
+                    "snoomoji"
+                } else {
+                    "subreddit"
+                };
+                entries
+                    .into_iter()
+                    .map(move |(name, entry)| SubredditEmoji {
+                        code: format!(":{}:", name),
+                        name,
+                        url: entry.url,
+                        source,
+                        mod_flair_only: entry.mod_flair_only,
+                        post_flair_allowed: entry.post_flair_allowed,
+                        user_flair_allowed: entry.user_flair_allowed,
+                    })
+            })
+            .collect();
+        emojis.sort_by(|a, b| b.source.cmp(a.source).then(a.name.cmp(&b.name)));
+
+        serde_json::to_string(&SubredditEmojisResponse { subreddit, emojis })
+            .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Report the awards given in a subreddit. Reddit has no public award catalogue, so this samples the subreddit's top posts and tallies the awards found on them, with coin value and frequency."
+    )]
+    pub(super) async fn list_subreddit_awards(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Top posts timeframe: week, month, year or all (default month)")]
+        timeframe: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Tallying awards of {}", subreddit);
+
+        let timeframe = timeframe.unwrap_or_else(|| "month".to_string());
+        if !AWARD_TIMEFRAMES.contains(&timeframe.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown timeframe {}", timeframe),
+                format!("use one of {}", AWARD_TIMEFRAMES.join(", ")),
+            ));
+        }
+
+        let deadline = Deadline::for_tool("list_subreddit_awards");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let url = format!("{}/r/{}/top?t={}", OAUTH_URL, subreddit, timeframe);
+        let (posts, _) = self
+            .collect_listing::<Post>(&url, &auth_token, DEFAULT_AWARD_SAMPLE, &deadline)
+            .await?;
+
+        let mut awards: HashMap<String, AwardSummary> = HashMap::new();
+        for awarding in posts.iter().flat_map(|post| &post.all_awardings) {
+            let summary = awards
+                .entry(awarding.id.clone())
+                .or_insert_with(|| AwardSummary {
+                    id: awarding.id.clone(),
+                    name: awarding.name.clone(),
+                    icon_url: awarding.icon_url.clone(),
+                    coin_price: awarding.coin_price,
+                    times_given: 0,
+                    posts: 0,
+                });
+            summary.times_given += awarding.count;
+            summary.posts += 1;
+        }
+        let mut awards: Vec<AwardSummary> = awards.into_values().collect();
+        awards.sort_by(|a, b| b.times_given.cmp(&a.times_given).then(a.name.cmp(&b.name)));
+
+        serde_json::to_string(&SubredditAwardsResponse {
+            subreddit,
+            timeframe,
+            sampled_posts: posts.len(),
+            awarded_posts: posts
+                .iter()
+                .filter(|post| !post.all_awardings.is_empty())
+                .count(),
+            total_awards: awards.iter().map(|award| award.times_given).sum(),
+            total_coin_value: awards
+                .iter()
+                .map(|award| award.times_given * award.coin_price)
+                .sum(),
+            awards,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Rank a subreddit's or a user's recent posts and comments by the awards they received, counting every award type rather than only gold. Pass either subreddit or username."
+    )]
+    pub(super) async fn get_most_awarded(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Recent posts and comments to scan (default 300, max 1000); a subreddit's are split between its posts and comments"
+        )]
+        sample: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "Number of items to return (default 10, max 50)")]
+        limit: Option<usize>,
+    ) -> Result<String, RedditError> {
+        let sample = sample
+            .unwrap_or(DEFAULT_AWARD_SAMPLE)
+            .clamp(1, MAX_AWARD_SAMPLE);
+        let limit = limit
+            .unwrap_or(DEFAULT_AWARDED_LIMIT)
+            .clamp(1, MAX_AWARDED_LIMIT);
+        let deadline = Deadline::for_tool("get_most_awarded");
+
+        let (source, things, truncated_reason) = match (subreddit, username) {
+            (Some(subreddit), None) => {
+                tracing::info!("Ranking awarded content of {}", subreddit);
+                let auth_token = self.auth_token().await?;
+                let subreddit = deadline
+                    .run(self.resolve_subreddit(&subreddit, &auth_token))
+                    .await??;
+                let url = format!("{}/r/{}/new", OAUTH_URL, subreddit);
+                let (mut things, posts_truncated) = self
+                    .collect_listing::<AwardedThing>(
+                        &url,
+                        &auth_token,
+                        sample.div_ceil(2),
+                        &deadline,
+                    )
+                    .await?;
+                let url = format!("{}/r/{}/comments", OAUTH_URL, subreddit);
+                let (comments, comments_truncated) = self
+                    .collect_listing::<AwardedThing>(&url, &auth_token, sample / 2, &deadline)
+                    .await?;
+                things.extend(comments);
+                (
+                    format!("r/{}", subreddit),
+                    things,
+                    posts_truncated.or(comments_truncated),
+                )
+            }
+            (None, Some(username)) => {
+                tracing::info!("Ranking awarded content of {}", username);
+                let auth_token = self.auth_token().await?;
+                let username = deadline
+                    .run(self.resolve_username(&username, &auth_token))
+                    .await??;
+                let url = format!("{}/user/{}/overview?sort=new", OAUTH_URL, username);
+                let (things, truncated) = self
+                    .collect_listing::<AwardedThing>(&url, &auth_token, sample, &deadline)
+                    .await?;
+                (format!("u/{}", username), things, truncated)
+            }
+            _ => {
+                return Err(RedditError::invalid_input(
+                    "Pass either subreddit or username",
+                    "set exactly one of subreddit and username",
+                ));
+            }
+        };
+
+        let allow_nsfw = self.config.get().content.allow_nsfw;
+        let scanned = things.len();
+        let mut items: Vec<AwardedItem> = things
+            .into_iter()
+            .filter(|thing| allow_nsfw || !thing.over_18)
+            .filter_map(awarded_item)
+            .collect();
+        let awarded = items.len();
+        items.sort_by(|a, b| {
+            b.total_awards
+                .cmp(&a.total_awards)
+                .then(b.coin_value.cmp(&a.coin_value))
+                .then(b.score.cmp(&a.score))
+        });
+        items.truncate(limit);
+
+        serde_json::to_string(&MostAwardedResponse {
+            source,
+            scanned,
+            awarded,
+            items,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Find communities similar to a subreddit, for 'what other subs are like r/X'. Combines the related-community widgets its moderators set up, subreddits linked from its sidebar and autocomplete matches for the topics in its title and description. Results are ranked by how many of those sources agree."
+    )]
+    pub(super) async fn find_similar_subreddits(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of communities to return (default 10, max 25)")]
+        limit: Option<u32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Finding subreddits similar to {}", subreddit);
+
+        let limit = limit
+            .unwrap_or(DEFAULT_SIMILAR_LIMIT)
+            .clamp(1, MAX_SIMILAR_LIMIT) as usize;
+        let deadline = Deadline::for_tool("find_similar_subreddits");
+        let auth_token = self.auth_token().await?;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_subreddit_about(&subreddit, &auth_token))
+            .await??;
+
+        let mut candidates = HashMap::new();
+
+        // Subreddits without widgets, or on old Reddit only, answer with an error.
+        let url = format!("{}/r/{}/api/widgets", OAUTH_URL, subreddit);
+        let widgets = deadline
+            .run(self.get_request::<WidgetsResponse, RawJsonRequest>(
+                &url,
+                &auth_token,
+                RawJsonRequest { raw_json: 1 },
+            ))
+            .await?
+            .unwrap_or_default();
+        for widget in widgets.items.into_values() {
+            match widget.kind.as_str() {
+                "community-list" => {
+                    let items: Vec<CommunityListItem> =
+                        serde_json::from_value(widget.data).unwrap_or_default();
+                    for item in items {
+                        let candidate =
+                            add_candidate(&mut candidates, &item.name, "widget", WIDGET_WEIGHT);
+                        candidate.subscribers = candidate.subscribers.or(item.subscribers);
+                        candidate.nsfw |= item.is_nsfw;
+                    }
+                }
+                "textarea" => add_linked(&mut candidates, widget.text.as_deref().unwrap_or("")),
+                _ => {}
+            }
+        }
+        add_linked(&mut candidates, &about.description);
+
+        let topics = topic_words(&about);
+        let url = format!("{}/api/subreddit_autocomplete_v2", OAUTH_URL);
+        for topic in &topics {
+            let Ok(listing) = deadline
+                .run(
+                    self.get_request::<Listing<SubredditAbout>, SubredditAutocompleteRequest>(
+                        &url,
+                        &auth_token,
+                        SubredditAutocompleteRequest {
+                            query: topic.clone(),
+                            include_over_18: true,
+                            include_profiles: false,
+                            limit: AUTOCOMPLETE_LIMIT,
+                            raw_json: 1,
+                        },
+                    ),
+                )
+                .await?
+            else {
+                continue;
+            };
+            for found in listing.data.children.into_iter().map(|thing| thing.data) {
+                let candidate = add_candidate(
+                    &mut candidates,
+                    &found.display_name,
+                    "autocomplete",
+                    AUTOCOMPLETE_WEIGHT,
+                );
+                candidate.title = found.title;
+                candidate.subscribers = found.subscribers;
+                candidate.nsfw = found.over18;
+            }
+        }
+
+        candidates.remove(&subreddit.to_lowercase());
+        let mut ranked: Vec<SimilarSubreddit> = candidates.into_values().collect();
+        ranked.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.subscribers.cmp(&a.subscribers))
+                .then(a.name.cmp(&b.name))
+        });
+
+        let allow_nsfw = self.config.get().content.allow_nsfw;
+        let mut similar = Vec::new();
+        for mut candidate in ranked {
+            if similar.len() == limit {
+                break;
+            }
+            // Sidebar links carry no details and may name private or banned
+            // subreddits, which are dropped.
+            if candidate.subscribers.is_none() {
+                let Ok(found) = deadline
+                    .run(self.fetch_subreddit_about(&candidate.name, &auth_token))
+                    .await?
+                else {
+                    continue;
+                };
+                candidate.name = found.display_name;
+                candidate.title = found.title;
+                candidate.subscribers = found.subscribers;
+                candidate.nsfw = found.over18;
+            }
+            if candidate.nsfw && !allow_nsfw {
+                continue;
+            }
+            similar.push(candidate);
+        }
+
+        serde_json::to_string(&SimilarSubredditsResponse {
+            subreddit,
+            topics,
+            similar,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,945 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rmcp::tool;
+
+use super::listings::post_summary;
+use super::{MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::RedditError;
+use crate::reddit::models::{
+    ApiJsonResponse, CommentContextRequest, CommentContextResponse, CommentNode,
+    CommentSortComparisonResponse, CommentTree, CommentsRequest, CommentsResponse,
+    ContextBundleResponse, ContextComment, ExpandedCommentsResponse, HighlightReply, Listing,
+    Mention, MentionContext, MoreChildrenData, MoreChildrenRequest, MoreComments, Post,
+    SortedComment, ThreadHighlight, ThreadHighlightsResponse, ThreadMentionsResponse, TreeComment,
+};
+use crate::reddit::text::{excerpt, snippet};
+use crate::reddit::validation::{normalize_post_id, normalize_thing_id};
+
+const DEFAULT_HIGHLIGHTS: usize = 5;
+const MAX_HIGHLIGHTS: usize = 25;
+const DEFAULT_CHAR_BUDGET: usize = 4000;
+const MAX_REPLY_DEPTH: u32 = 5;
+pub(super) const THREAD_COMMENT_LIMIT: u32 = 500;
+pub(super) const MAX_THREAD_DEPTH: u32 = 10;
+const MAX_MENTION_CONTEXTS: usize = 5;
+const MENTION_CONTEXT_CHARS: usize = 60;
+const COMMENT_SORTS: [&str; 6] = ["confidence", "top", "new", "controversial", "old", "qa"];
+const COMMENT_FILTERS: [&str; 2] = ["stickied", "op"];
+const DEFAULT_COMPARED_SORTS: [&str; 4] = ["top", "controversial", "new", "qa"];
+const DEFAULT_PER_SORT: usize = 20;
+const MAX_PER_SORT: usize = 100;
+const SORTED_COMMENT_CHARS: usize = 200;
+/// Most parent comments Reddit returns above a comment in one request.
+const MAX_CONTEXT_LEVELS: u32 = 8;
+const CONTEXT_REPLY_LIMIT: u32 = 100;
+const DEFAULT_CONTEXT_LEVELS: u32 = 3;
+const DEFAULT_COMMENT_DEPTH: u32 = 5;
+const DEFAULT_COMMENT_LIMIT: u32 = 200;
+/// Most ids of `more` stubs one expand_more_comments call loads.
+const MAX_MORE_CHILDREN: usize = 500;
+
+/// `u/name` and `r/name` references, optionally with a leading slash. Links
+/// such as `reddit.com/r/name` are not counted.
+static MENTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w/])/?([uUrR])/([A-Za-z0-9_-]{2,21})").expect("valid mention regex")
+});
+
+/// Mentions of one kind, keyed by lowercase name.
+#[derive(Default)]
+struct MentionIndex {
+    mentions: HashMap<String, Mention>,
+}
+
+impl MentionIndex {
+    fn record(&mut self, kind: &'static str, name: &str, context: MentionContext) {
+        let mention = self
+            .mentions
+            .entry(name.to_lowercase())
+            .or_insert_with(|| Mention {
+                kind,
+                name: name.to_string(),
+                count: 0,
+                contexts: Vec::new(),
+            });
+        mention.count += 1;
+        if mention.contexts.len() < MAX_MENTION_CONTEXTS {
+            mention.contexts.push(context);
+        }
+    }
+
+    fn into_ranked(self) -> Vec<Mention> {
+        let mut mentions: Vec<Mention> = self.mentions.into_values().collect();
+        mentions.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+        mentions
+    }
+}
+
+#[derive(Default)]
+struct MentionScan {
+    users: MentionIndex,
+    subreddits: MentionIndex,
+    comments_scanned: usize,
+}
+
+impl MentionScan {
+    fn scan_text(&mut self, id: &str, author: &str, text: &str) {
+        for captures in MENTION.captures_iter(text) {
+            let (Some(prefix), Some(name)) = (captures.get(1), captures.get(2)) else {
+                continue;
+            };
+            let context = MentionContext {
+                id: id.to_string(),
+                author: author.to_string(),
+                excerpt: excerpt(text, prefix.start(), name.end(), MENTION_CONTEXT_CHARS),
+            };
+            if prefix.as_str().eq_ignore_ascii_case("u") {
+                self.users.record("user", name.as_str(), context);
+            } else {
+                self.subreddits.record("subreddit", name.as_str(), context);
+            }
+        }
+    }
+
+    fn scan_tree(&mut self, comments: &[TreeComment]) {
+        for comment in comments {
+            self.comments_scanned += 1;
+            self.scan_text(&comment.id, &comment.author, &comment.body);
+            self.scan_tree(&comment.replies.0);
+        }
+    }
+}
+
+fn is_removed(comment: &TreeComment) -> bool {
+    comment.body == "[deleted]" || comment.body == "[removed]"
+}
+
+/// Spends up to `remaining` characters of the budget on `body`.
+fn spend(body: &str, remaining: &mut usize, trimmed: &mut bool) -> Option<String> {
+    if *remaining == 0 {
+        *trimmed = true;
+        return None;
+    }
+    let length = body.chars().count();
+    if length > *remaining {
+        *trimmed = true;
+        let body = snippet(body, *remaining);
+        *remaining = 0;
+        Some(body)
+    } else {
+        *remaining -= length;
+        Some(body.to_string())
+    }
+}
+
+/// Follows the highest-scored reply at every level.
+fn best_reply_chain(comment: &TreeComment) -> Vec<&TreeComment> {
+    let mut chain = Vec::new();
+    let mut current = comment;
+    while let Some(reply) = current
+        .replies
+        .0
+        .iter()
+        .filter(|reply| !is_removed(reply))
+        .max_by_key(|reply| reply.score)
+    {
+        chain.push(reply);
+        current = reply;
+    }
+    chain
+}
+
+/// The comments from the top of `tree` down to the comment `id`.
+fn path_to<'a>(tree: &'a [TreeComment], id: &str) -> Option<Vec<&'a TreeComment>> {
+    tree.iter().find_map(|comment| {
+        if comment.id == id {
+            return Some(vec![comment]);
+        }
+        let mut path = path_to(&comment.replies.0, id)?;
+        path.insert(0, comment);
+        Some(path)
+    })
+}
+
+fn comment_node(comment: TreeComment, depth: u32) -> CommentNode {
+    let replies: Vec<CommentNode> = comment
+        .replies
+        .0
+        .into_iter()
+        .map(|reply| comment_node(reply, depth + 1))
+        .collect();
+    let more = comment.replies.1;
+    let reply_count = replies
+        .iter()
+        .map(|reply| 1 + reply.reply_count)
+        .sum::<i64>()
+        + more.iter().map(|more| more.count).sum::<i64>();
+    CommentNode {
+        name: format!("t1_{}", comment.id),
+        id: comment.id,
+        author: comment.author,
+        score: comment.score,
+        body: comment.body,
+        created_utc: comment.created_utc as i64,
+        depth,
+        parent_id: None,
+        stickied: comment.stickied,
+        distinguished: comment.distinguished,
+        reply_count,
+        replies,
+        more,
+    }
+}
+
+/// Nests the flat `/api/morechildren` list under the parents it contains.
+/// Comments and stubs whose parent is not in the list are the roots.
+fn stitch(comments: Vec<TreeComment>, more: Vec<MoreComments>) -> CommentTree {
+    let ids: HashSet<String> = comments
+        .iter()
+        .map(|comment| format!("t1_{}", comment.id))
+        .collect();
+    let mut children: HashMap<String, CommentTree> = HashMap::new();
+    let mut roots = CommentTree::default();
+    for comment in comments {
+        if ids.contains(&comment.parent_id) {
+            let parent = children.entry(comment.parent_id.clone()).or_default();
+            parent.0.push(comment);
+        } else {
+            roots.0.push(comment);
+        }
+    }
+    for stub in more {
+        if ids.contains(&stub.parent_id) {
+            let parent = children.entry(stub.parent_id.clone()).or_default();
+            parent.1.push(stub);
+        } else {
+            roots.1.push(stub);
+        }
+    }
+
+    fn attach(comment: &mut TreeComment, children: &mut HashMap<String, CommentTree>) {
+        if let Some(replies) = children.remove(&format!("t1_{}", comment.id)) {
+            comment.replies = replies;
+        }
+        for reply in &mut comment.replies.0 {
+            attach(reply, children);
+        }
+    }
+    for comment in &mut roots.0 {
+        attach(comment, &mut children);
+    }
+    roots
+}
+
+/// Collects the comments at any depth that pass `keep`, each without its
+/// replies but with the parent it answers.
+fn filter_nodes(
+    nodes: Vec<CommentNode>,
+    parent_id: &str,
+    keep: &dyn Fn(&CommentNode) -> bool,
+    found: &mut Vec<CommentNode>,
+) {
+    for mut node in nodes {
+        let replies = std::mem::take(&mut node.replies);
+        filter_nodes(replies, &node.name, keep, found);
+        if keep(&node) {
+            node.parent_id = Some(parent_id.to_string());
+            node.more.clear();
+            found.push(node);
+        }
+    }
+}
+
+fn count_nodes(nodes: &[CommentNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| 1 + count_nodes(&node.replies))
+        .sum()
+}
+
+fn context_comment(comment: &TreeComment) -> ContextComment {
+    ContextComment {
+        id: comment.id.clone(),
+        name: format!("t1_{}", comment.id),
+        author: comment.author.clone(),
+        score: comment.score,
+        body: comment.body.clone(),
+        created_utc: comment.created_utc as i64,
+        permalink: format!("https://www.reddit.com{}", comment.permalink),
+    }
+}
+
+impl RedditClient {
+    /// Fetches a post and the thread around one of its comments: up to
+    /// `context` (at most eight) parent comments above it and its direct replies.
+    async fn fetch_comment_context(
+        &self,
+        post_id: &str,
+        comment_id: &str,
+        context: u32,
+        auth_token: &str,
+    ) -> Result<(Post, CommentTree), RedditError> {
+        let url = format!("{}/comments/{}/_/{}", OAUTH_URL, post_id, comment_id);
+        let (post, comments) = self
+            .get_request::<(Listing<Post>, CommentTree), CommentContextRequest>(
+                &url,
+                auth_token,
+                CommentContextRequest {
+                    context,
+                    // The parents, the comment and one level of replies.
+                    depth: context + 2,
+                    limit: CONTEXT_REPLY_LIMIT,
+                    raw_json: 1,
+                },
+            )
+            .await?;
+        let post = post
+            .data
+            .children
+            .into_iter()
+            .next()
+            .map(|child| child.data)
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("Post {} was not found", post_id),
+                    "check the comment's permalink",
+                )
+            })?;
+        Ok((post, comments))
+    }
+
+    /// The id of the post a comment belongs to, from its permalink or, for a
+    /// bare fullname, from `/api/info`.
+    async fn comment_post_id(
+        &self,
+        permalink: &str,
+        fullname: &str,
+        auth_token: &str,
+        deadline: &Deadline,
+    ) -> Result<String, RedditError> {
+        if permalink.contains("/comments/") {
+            return normalize_post_id(permalink);
+        }
+        let info = deadline
+            .run(self.fetch_info(&[fullname.to_string()], auth_token))
+            .await??;
+        let link_id = info
+            .get(fullname)
+            .and_then(|comment| comment.link_id.clone())
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("Comment {} was not found", fullname),
+                    "check the fullname or pass the comment's permalink",
+                )
+            })?;
+        normalize_post_id(&link_id)
+    }
+
+    /// Fetches a post and as much of its comment tree as one request returns,
+    /// up to `limit` comments.
+    pub(super) async fn fetch_thread(
+        &self,
+        post_id: &str,
+        sort: &str,
+        depth: u32,
+        limit: u32,
+        auth_token: &str,
+    ) -> Result<(Post, CommentTree), RedditError> {
+        let url = format!("{}/comments/{}", OAUTH_URL, post_id);
+        let (post, comments) = self
+            .get_request::<(Listing<Post>, CommentTree), CommentsRequest>(
+                &url,
+                auth_token,
+                CommentsRequest {
+                    sort: sort.to_string(),
+                    limit,
+                    depth,
+                    raw_json: 1,
+                },
+            )
+            .await?;
+        let post = post
+            .data
+            .children
+            .into_iter()
+            .next()
+            .map(|child| child.data)
+            .ok_or_else(|| {
+                RedditError::not_found(
+                    format!("Post {} was not found", post_id),
+                    "check the post id or pass the post's reddit.com URL",
+                )
+            })?;
+
+        Ok((post, comments))
+    }
+
+    #[tool(
+        description = "Get the highest-scored top-level comments of a post with their best reply chains, trimmed to a character budget. Meant as compact input for summarizing a thread."
+    )]
+    pub(super) async fn get_thread_highlights(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+        #[tool(param)]
+        #[schemars(description = "Number of top-level comments to return, 1-25 (default 5)")]
+        top_k: Option<usize>,
+        #[tool(param)]
+        #[schemars(
+            description = "Maximum number of comment characters in the response (default 4000)"
+        )]
+        char_budget: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching thread highlights for {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let top_k = top_k.unwrap_or(DEFAULT_HIGHLIGHTS).clamp(1, MAX_HIGHLIGHTS);
+        let char_budget = char_budget.unwrap_or(DEFAULT_CHAR_BUDGET);
+        let auth_token = self.auth_token().await?;
+
+        let (post, comments) = Deadline::for_tool("get_thread_highlights")
+            .run(self.fetch_thread(
+                &post_id,
+                "top",
+                MAX_REPLY_DEPTH + 1,
+                THREAD_COMMENT_LIMIT,
+                &auth_token,
+            ))
+            .await??;
+
+        let top_level_comments = comments.0.len();
+        let mut top: Vec<&TreeComment> = comments
+            .0
+            .iter()
+            .filter(|comment| !is_removed(comment))
+            .collect();
+        top.sort_by_key(|comment| std::cmp::Reverse(comment.score));
+        top.truncate(top_k);
+
+        // Every highlight gets an equal share so one long comment cannot
+        // crowd out the rest.
+        let share = char_budget / top.len().max(1);
+        let mut trimmed = false;
+        let mut chars_used = 0;
+        let mut highlights = Vec::new();
+
+        for comment in top {
+            let mut remaining = share;
+            let Some(body) = spend(&comment.body, &mut remaining, &mut trimmed) else {
+                break;
+            };
+            let mut best_replies = Vec::new();
+            for reply in best_reply_chain(comment) {
+                match spend(&reply.body, &mut remaining, &mut trimmed) {
+                    Some(body) => best_replies.push(HighlightReply {
+                        id: reply.id.clone(),
+                        author: reply.author.clone(),
+                        score: reply.score,
+                        body,
+                    }),
+                    None => break,
+                }
+            }
+            chars_used += share - remaining;
+            highlights.push(ThreadHighlight {
+                id: comment.id.clone(),
+                author: comment.author.clone(),
+                score: comment.score,
+                body,
+                best_replies,
+            });
+        }
+
+        serde_json::to_string(&ThreadHighlightsResponse {
+            post,
+            top_level_comments,
+            highlights,
+            chars_used,
+            trimmed,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Fetch a thread under several comment sorts (e.g. top, controversial, new, qa) and return one merged view labelling where each top-level comment ranks under each sort."
+    )]
+    pub(super) async fn compare_comment_sorts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Sorts to compare: confidence, top, new, controversial, old or qa (default top, controversial, new, qa)"
+        )]
+        sorts: Option<Vec<String>>,
+        #[tool(param)]
+        #[schemars(
+            description = "Number of top-level comments to take from each sort, 1-100 (default 20)"
+        )]
+        per_sort: Option<usize>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Comparing comment sorts of {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let mut sorts: Vec<String> = match sorts {
+            Some(sorts) => sorts.iter().map(|sort| sort.to_ascii_lowercase()).collect(),
+            None => DEFAULT_COMPARED_SORTS
+                .iter()
+                .map(|sort| sort.to_string())
+                .collect(),
+        };
+        let mut seen = HashSet::new();
+        sorts.retain(|sort| seen.insert(sort.clone()));
+        if let Some(unknown) = sorts
+            .iter()
+            .find(|sort| !COMMENT_SORTS.contains(&sort.as_str()))
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown comment sort {}", unknown),
+                format!("use any of {}", COMMENT_SORTS.join(", ")),
+            ));
+        }
+        if sorts.len() < 2 {
+            return Err(RedditError::invalid_input(
+                "At least two sorts are needed for a comparison",
+                "pass two or more sorts or omit sorts to use the defaults",
+            ));
+        }
+        let per_sort = per_sort.unwrap_or(DEFAULT_PER_SORT).clamp(1, MAX_PER_SORT);
+
+        let deadline = Deadline::for_tool("compare_comment_sorts");
+        let auth_token = self.auth_token().await?;
+        let mut title = String::new();
+        let mut comments: HashMap<String, SortedComment> = HashMap::new();
+
+        for sort in &sorts {
+            let (post, tree) = deadline
+                .run(self.fetch_thread(&post_id, sort, 1, THREAD_COMMENT_LIMIT, &auth_token))
+                .await??;
+            title = post.title;
+            for (position, comment) in tree.0.into_iter().take(per_sort).enumerate() {
+                comments
+                    .entry(comment.id.clone())
+                    .or_insert_with(|| SortedComment {
+                        excerpt: snippet(&comment.body, SORTED_COMMENT_CHARS),
+                        id: comment.id,
+                        author: comment.author,
+                        score: comment.score,
+                        positions: BTreeMap::new(),
+                    })
+                    .positions
+                    .insert(sort.clone(), position + 1);
+            }
+        }
+
+        let in_all_sorts = comments
+            .values()
+            .filter(|comment| comment.positions.len() == sorts.len())
+            .count();
+        let mut exclusive: BTreeMap<String, usize> =
+            sorts.iter().map(|sort| (sort.clone(), 0)).collect();
+        for comment in comments.values() {
+            if comment.positions.len() == 1
+                && let Some(sort) = comment.positions.keys().next()
+            {
+                *exclusive.entry(sort.clone()).or_insert(0) += 1;
+            }
+        }
+
+        // Comments that rank well under many sorts first.
+        let mut comments: Vec<SortedComment> = comments.into_values().collect();
+        comments.sort_by_key(|comment| {
+            (
+                std::cmp::Reverse(comment.positions.len()),
+                comment
+                    .positions
+                    .values()
+                    .min()
+                    .copied()
+                    .unwrap_or(usize::MAX),
+            )
+        });
+
+        serde_json::to_string(&CommentSortComparisonResponse {
+            post_id,
+            title,
+            sorts,
+            per_sort,
+            in_all_sorts,
+            exclusive,
+            comments,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Extract every u/ user mention and r/ subreddit reference in a thread, with counts and the surrounding text of each, to map who and what a discussion is about."
+    )]
+    pub(super) async fn extract_thread_mentions(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Extracting mentions from thread {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let auth_token = self.auth_token().await?;
+        let (post, comments) = Deadline::for_tool("extract_thread_mentions")
+            .run(self.fetch_thread(
+                &post_id,
+                "top",
+                MAX_THREAD_DEPTH,
+                THREAD_COMMENT_LIMIT,
+                &auth_token,
+            ))
+            .await??;
+
+        let mut scan = MentionScan::default();
+        scan.scan_text(&post.id, &post.author, &post.title);
+        scan.scan_text(&post.id, &post.author, &post.selftext);
+        scan.scan_tree(&comments.0);
+
+        serde_json::to_string(&ThreadMentionsResponse {
+            post_id,
+            title: post.title,
+            comments_scanned: scan.comments_scanned,
+            users: scan.users.into_ranked(),
+            subreddits: scan.subreddits.into_ranked(),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Read the discussion of a post as a nested comment tree: each comment with author, score, body, depth and reply count. Comments Reddit did not load are listed as more stubs, with the ids expand_more_comments takes. Can instead return just the stickied comment or just the replies of the post's author."
+    )]
+    pub(super) async fn get_comments(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ fullname or reddit.com post URL")]
+        post_id: String,
+        #[tool(param)]
+        #[schemars(
+            description = "confidence (Reddit's best), top, new, controversial, old or qa (default confidence)"
+        )]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Reply levels to load, 1-10 (default 5)")]
+        depth: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "Maximum number of comments to load, 1-500 (default 200)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(
+            description = "Return only some comments as a flat list: stickied for the pinned AutoModerator or moderator comment, op for the replies of the post's author"
+        )]
+        only: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching the comments of {}", post_id);
+
+        let post_id = normalize_post_id(&post_id)?;
+        let sort = sort.unwrap_or_else(|| "confidence".to_string());
+        if !COMMENT_SORTS.contains(&sort.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown comment sort {}", sort),
+                format!("use one of {}", COMMENT_SORTS.join(", ")),
+            ));
+        }
+        if let Some(only) = &only
+            && !COMMENT_FILTERS.contains(&only.as_str())
+        {
+            return Err(RedditError::invalid_input(
+                format!("Unknown comment filter {}", only),
+                format!("use one of {}", COMMENT_FILTERS.join(", ")),
+            ));
+        }
+        // Only top-level comments can be stickied, so one level is enough.
+        let depth = match only.as_deref() {
+            Some("stickied") => 1,
+            _ => depth
+                .unwrap_or(DEFAULT_COMMENT_DEPTH)
+                .clamp(1, MAX_THREAD_DEPTH),
+        };
+        let limit = limit
+            .unwrap_or(DEFAULT_COMMENT_LIMIT)
+            .clamp(1, THREAD_COMMENT_LIMIT);
+        let auth_token = self.auth_token().await?;
+
+        let (post, tree) = Deadline::for_tool("get_comments")
+            .run(self.fetch_thread(&post_id, &sort, depth, limit, &auth_token))
+            .await??;
+        let mut comments: Vec<CommentNode> = tree
+            .0
+            .into_iter()
+            .map(|comment| comment_node(comment, 0))
+            .collect();
+        if let Some(only) = &only {
+            let keep: &dyn Fn(&CommentNode) -> bool = match only.as_str() {
+                "stickied" => &|node| node.stickied,
+                _ => &|node| node.author == post.author && post.author != "[deleted]",
+            };
+            let mut found = Vec::new();
+            filter_nodes(comments, &post.name, keep, &mut found);
+            found.sort_by_key(|node| node.created_utc);
+            comments = found;
+        }
+
+        serde_json::to_string(&CommentsResponse {
+            post,
+            sort,
+            only,
+            loaded: count_nodes(&comments),
+            comments,
+            more: tree.1,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Load the comments hidden behind more stubs of get_comments, with their replies nested under the right parents. Pass the post and the children ids of one or more stubs."
+    )]
+    pub(super) async fn expand_more_comments(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Post id, t3_ link id or reddit.com post URL")]
+        link_id: String,
+        #[tool(param)]
+        #[schemars(description = "Comment ids from the children of more stubs (at most 500)")]
+        children: Vec<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "confidence (Reddit's best), top, new, controversial, old or qa (default confidence)"
+        )]
+        sort: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Expanding {} more comments of {}", children.len(), link_id);
+
+        let link_id = format!("t3_{}", normalize_post_id(&link_id)?);
+        let sort = sort.unwrap_or_else(|| "confidence".to_string());
+        if !COMMENT_SORTS.contains(&sort.as_str()) {
+            return Err(RedditError::invalid_input(
+                format!("Unknown comment sort {}", sort),
+                format!("use one of {}", COMMENT_SORTS.join(", ")),
+            ));
+        }
+        let children: Vec<String> = children
+            .iter()
+            .map(|id| id.trim().trim_start_matches("t1_").to_string())
+            .filter(|id| !id.is_empty())
+            .collect();
+        if children.is_empty() || children.len() > MAX_MORE_CHILDREN {
+            return Err(RedditError::invalid_input(
+                format!("{} comment ids were given", children.len()),
+                format!(
+                    "pass between 1 and {} ids from the children of a more stub",
+                    MAX_MORE_CHILDREN
+                ),
+            ));
+        }
+
+        let deadline = Deadline::for_tool("expand_more_comments");
+        let auth_token = self.auth_token().await?;
+        let url = format!("{}/api/morechildren", OAUTH_URL);
+        let mut comments = Vec::new();
+        let mut more = Vec::new();
+        for chunk in children.chunks(MAX_PAGE_SIZE as usize) {
+            let response = deadline
+                .run(
+                    self.get_request::<ApiJsonResponse<MoreChildrenData>, MoreChildrenRequest>(
+                        &url,
+                        &auth_token,
+                        MoreChildrenRequest {
+                            link_id: link_id.clone(),
+                            children: chunk.join(","),
+                            sort: sort.clone(),
+                            limit_children: false,
+                            api_type: "json",
+                            raw_json: 1,
+                        },
+                    ),
+                )
+                .await??;
+            if let Some(error) = response.json.errors.first() {
+                return Err(RedditError::from_api(error));
+            }
+            for thing in response
+                .json
+                .data
+                .map(|data| data.things)
+                .unwrap_or_default()
+            {
+                match thing.kind.as_str() {
+                    "t1" => comments.push(
+                        serde_json::from_value::<TreeComment>(thing.data)
+                            .map_err(RedditError::parse)?,
+                    ),
+                    "more" => more.push(
+                        serde_json::from_value::<MoreComments>(thing.data)
+                            .map_err(RedditError::parse)?,
+                    ),
+                    _ => {}
+                }
+            }
+        }
+
+        let tree = stitch(comments, more);
+        let comments: Vec<CommentNode> = tree
+            .0
+            .into_iter()
+            .map(|comment| {
+                let depth = comment.depth.unwrap_or(0);
+                comment_node(comment, depth)
+            })
+            .collect();
+        serde_json::to_string(&ExpandedCommentsResponse {
+            link_id,
+            requested: children.len(),
+            loaded: count_nodes(&comments),
+            comments,
+            more: tree.1,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get a comment together with the parent comments above it, up to a number of levels, and a summary of its post. Shows what a reply responds to without loading the whole thread; get_context_bundle also follows the chain to the top and adds the replies."
+    )]
+    pub(super) async fn get_comment_context(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Permalink of the comment, its id or its t1_ fullname")]
+        comment: String,
+        #[tool(param)]
+        #[schemars(description = "Parent levels to include above the comment, 0-8 (default 3)")]
+        levels: Option<u32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching the context of comment {}", comment);
+
+        let fullname = if comment.contains('/') || comment.contains('_') {
+            normalize_thing_id(&comment)?
+        } else {
+            format!("t1_{}", comment.trim())
+        };
+        let Some(comment_id) = fullname.strip_prefix("t1_") else {
+            return Err(RedditError::invalid_input(
+                format!("{} is a post, not a comment", comment),
+                "pass the permalink or id of a comment; get_post shows a post",
+            ));
+        };
+        let levels = levels
+            .unwrap_or(DEFAULT_CONTEXT_LEVELS)
+            .min(MAX_CONTEXT_LEVELS);
+
+        let deadline = Deadline::for_tool("get_comment_context");
+        let auth_token = self.auth_token().await?;
+        let post_id = self
+            .comment_post_id(&comment, &fullname, &auth_token, &deadline)
+            .await?;
+        let (post, tree) = deadline
+            .run(self.fetch_comment_context(&post_id, comment_id, levels, &auth_token))
+            .await??;
+        let path = path_to(&tree.0, comment_id).ok_or_else(|| {
+            RedditError::not_found(
+                format!("Comment {} was not found in post {}", fullname, post_id),
+                "check the permalink; the comment may have been deleted",
+            )
+        })?;
+        let (target, parents) = path.split_last().expect("path ends at the comment");
+
+        serde_json::to_string(&CommentContextResponse {
+            post: post_summary(post),
+            levels,
+            more_parents: path[0].parent_id.starts_with("t1_"),
+            parents: parents
+                .iter()
+                .map(|parent| context_comment(parent))
+                .collect(),
+            comment: context_comment(target),
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get everything needed to reply to a comment in one call: the post, every parent comment from the top-level comment down, the comment itself and its direct replies."
+    )]
+    pub(super) async fn get_context_bundle(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Permalink of the comment, or its t1_ fullname")]
+        permalink: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching the context bundle of {}", permalink);
+
+        let fullname = normalize_thing_id(&permalink)?;
+        let Some(comment_id) = fullname.strip_prefix("t1_") else {
+            return Err(RedditError::invalid_input(
+                format!("{} is a post, not a comment", permalink),
+                "pass the permalink of a comment; get_thread_highlights summarizes a whole post",
+            ));
+        };
+
+        let deadline = Deadline::for_tool("get_context_bundle");
+        let auth_token = self.auth_token().await?;
+        let post_id = self
+            .comment_post_id(&permalink, &fullname, &auth_token, &deadline)
+            .await?;
+
+        let not_found = |id: &str| {
+            RedditError::not_found(
+                format!("Comment t1_{} was not found in post {}", id, post_id),
+                "check the permalink; the comment may have been deleted",
+            )
+        };
+        let (post, tree) = deadline
+            .run(self.fetch_comment_context(&post_id, comment_id, MAX_CONTEXT_LEVELS, &auth_token))
+            .await??;
+        let path = path_to(&tree.0, comment_id).ok_or_else(|| not_found(comment_id))?;
+        let (comment, parents) = path.split_last().expect("path ends at the comment");
+        let mut ancestors: Vec<ContextComment> = parents
+            .iter()
+            .map(|parent| context_comment(parent))
+            .collect();
+        let replies = comment.replies.0.iter().map(context_comment).collect();
+        let comment = context_comment(comment);
+        let mut top_parent = path[0].parent_id.clone();
+
+        // Threads deeper than one request reaches continue from the topmost
+        // parent fetched so far.
+        let mut truncated_reason = None;
+        while let Some(parent_id) = top_parent.strip_prefix("t1_") {
+            let parent_id = parent_id.to_string();
+            let tree = match deadline
+                .run(self.fetch_comment_context(
+                    &post_id,
+                    &parent_id,
+                    MAX_CONTEXT_LEVELS,
+                    &auth_token,
+                ))
+                .await
+            {
+                Ok(result) => result?.1,
+                Err(_) => {
+                    truncated_reason = Some(deadline.truncation_reason());
+                    break;
+                }
+            };
+            let path = path_to(&tree.0, &parent_id).ok_or_else(|| not_found(&parent_id))?;
+            top_parent = path[0].parent_id.clone();
+            ancestors.splice(0..0, path.into_iter().map(context_comment));
+        }
+
+        serde_json::to_string(&ContextBundleResponse {
+            post,
+            ancestors,
+            comment,
+            replies,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
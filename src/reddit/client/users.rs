@@ -0,0 +1,715 @@
+use std::collections::HashSet;
+
+use rmcp::tool;
+
+use super::listings::{check_cursors, validate_sort};
+use super::{BASE_URL, MAX_PAGE_SIZE, OAUTH_URL, RedditClient};
+use crate::reddit::deadline::Deadline;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::{
+    AccountRisk, ActivityRisk, BannedUser, BannedUserRequest, Comment, CommentWithContext, Listing,
+    ListingRequest, ModAction, ModActionSummary, ModNoteSummary, ModNotesRequest, ModNotesResponse,
+    ModerationHistory, OverviewItem, ParentComment, Post, RawJsonRequest, RiskLevel,
+    SortedListingRequest, Thing, UserAbout, UserAboutResponse, UserCommentListResponse,
+    UserCommentSummary, UserCommentsResponse, UserList, UserOverviewResponse, UserPostsResponse,
+    UserRiskSummary,
+};
+use crate::reddit::text::snippet;
+
+const DEFAULT_COMMENT_LIMIT: u32 = 25;
+const MAX_COMMENT_LIMIT: u32 = 100;
+const PARENT_SNIPPET_CHARS: usize = 200;
+const USER_SORTS: [&str; 4] = ["new", "hot", "top", "controversial"];
+const OVERVIEW_BODY_CHARS: usize = 300;
+const DEFAULT_MOD_LOG_SCAN: u32 = 500;
+const MAX_MOD_LOG_SCAN: u32 = 1000;
+const MAX_RECENT_ACTIONS: usize = 10;
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+/// Mod note labels that record earlier trouble with the user.
+const WARNING_NOTE_LABELS: [&str; 5] = [
+    "SPAM_WATCH",
+    "SPAM_WARNING",
+    "ABUSE_WARNING",
+    "BAN",
+    "PERMA_BAN",
+];
+
+impl RedditClient {
+    pub(super) async fn fetch_user_about(
+        &self,
+        username: &str,
+        auth_token: &str,
+    ) -> Result<UserAbout, RedditError> {
+        let url = format!("{}/user/{}/about", OAUTH_URL, username);
+        self.get_request::<Thing<UserAbout>, RawJsonRequest>(
+            &url,
+            auth_token,
+            RawJsonRequest { raw_json: 1 },
+        )
+        .await
+        .map(|thing| thing.data)
+    }
+
+    #[tool(
+        description = "Get a user's profile: account age, link and comment karma, verified and premium status, and profile description. Useful to vet an author before trusting their post."
+    )]
+    pub(super) async fn get_user_about(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching profile of {}", username);
+
+        let deadline = Deadline::for_tool("get_user_about");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let about = deadline
+            .run(self.fetch_user_about(&username, &auth_token))
+            .await??;
+        let now = chrono::Utc::now().timestamp() as f64;
+        let profile = about.subreddit.unwrap_or_default();
+
+        serde_json::to_string(&UserAboutResponse {
+            name: about.name,
+            created_utc: about.created_utc,
+            age_days: about
+                .created_utc
+                .map(|created| ((now - created) / SECONDS_PER_DAY) as i64),
+            link_karma: about.link_karma,
+            comment_karma: about.comment_karma,
+            total_karma: about.total_karma,
+            verified: about.verified,
+            verified_email: about.has_verified_email,
+            premium: about.is_gold,
+            moderator: about.is_mod,
+            employee: about.is_employee,
+            suspended: about.is_suspended,
+            profile_title: profile.title,
+            profile_description: profile.public_description,
+            profile_nsfw: profile.over_18,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get a user's recent posts and comments interleaved, each with its subreddit, score and permalink. Useful for a quick look at what a user has been up to."
+    )]
+    pub(super) async fn get_user_overview(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "new, hot, top or controversial (default new)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of items (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching overview of {}", username);
+
+        let sort = sort.unwrap_or_else(|| "new".to_string());
+        validate_sort(&sort, &USER_SORTS, None)?;
+
+        let deadline = Deadline::for_tool("get_user_overview");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let url = format!("{}/user/{}/overview?sort={}", OAUTH_URL, username, sort);
+        let listing = deadline
+            .run(
+                self.get_request::<Listing<serde_json::Value>, ListingRequest>(
+                    &url,
+                    &auth_token,
+                    ListingRequest {
+                        limit: limit
+                            .unwrap_or(DEFAULT_COMMENT_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before: None,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+
+        let allow_nsfw = self.config.get().content.allow_nsfw;
+        let mut items = Vec::new();
+        for child in listing.data.children {
+            let item = match child.kind.as_str() {
+                "t3" => {
+                    let post: Post =
+                        serde_json::from_value(child.data).map_err(RedditError::parse)?;
+                    if post.over_18 && !allow_nsfw {
+                        continue;
+                    }
+                    OverviewItem {
+                        kind: "post",
+                        name: post.name,
+                        subreddit: post.subreddit,
+                        score: post.score,
+                        title: Some(post.title),
+                        body: None,
+                        num_comments: Some(post.num_comments),
+                        permalink: format!("https://www.reddit.com{}", post.permalink),
+                        created_utc: post.created_utc as i64,
+                    }
+                }
+                "t1" => {
+                    let comment: Comment =
+                        serde_json::from_value(child.data).map_err(RedditError::parse)?;
+                    OverviewItem {
+                        kind: "comment",
+                        name: comment.name,
+                        subreddit: comment.subreddit,
+                        score: comment.score,
+                        title: comment.link_title,
+                        body: Some(snippet(&comment.body, OVERVIEW_BODY_CHARS)),
+                        num_comments: None,
+                        permalink: format!("https://www.reddit.com{}", comment.permalink),
+                        created_utc: comment.created_utc as i64,
+                    }
+                }
+                _ => continue,
+            };
+            items.push(item);
+        }
+
+        serde_json::to_string(&UserOverviewResponse {
+            username,
+            sort,
+            items,
+            after: listing.data.after,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "List the posts a user submitted, sorted by new, hot, top or controversial, one page at a time."
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn get_user_posts(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "new, hot, top or controversial (default new)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Time filter for top and controversial: hour, day, week, month, year or all"
+        )]
+        time: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of posts (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "before cursor of a page, to get the page before it")]
+        before: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "If exclude_seen is true, posts already returned earlier in this session are filtered out. Defaults to false."
+        )]
+        exclude_seen: Option<bool>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching posts of {}", username);
+
+        let sort = sort.unwrap_or_else(|| "new".to_string());
+        let time = validate_sort(&sort, &USER_SORTS, time)?;
+        check_cursors(&after, &before)?;
+
+        let deadline = Deadline::for_tool("get_user_posts");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let url = format!("{}/user/{}/submitted?sort={}", OAUTH_URL, username, sort);
+        let mut page = deadline
+            .run(
+                self.fetch_post_page(
+                    &url,
+                    &auth_token,
+                    SortedListingRequest {
+                        g: None,
+                        t: time.clone(),
+                        limit: limit
+                            .unwrap_or(DEFAULT_COMMENT_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+        let excluded_seen = self.filter_seen(&mut page.posts, exclude_seen);
+
+        serde_json::to_string(&UserPostsResponse {
+            username,
+            sort,
+            time,
+            posts: page.posts,
+            excluded_seen,
+            after: page.after,
+            before: page.before,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "List the comments a user wrote, sorted by new, hot, top or controversial, one page at a time. get_user_comments_with_context also shows what each comment replies to."
+    )]
+    pub(super) async fn get_user_comments(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "new, hot, top or controversial (default new)")]
+        sort: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Time filter for top and controversial: hour, day, week, month, year or all"
+        )]
+        time: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Number of comments (default 25, at most 100)")]
+        limit: Option<u32>,
+        #[tool(param)]
+        #[schemars(description = "after cursor of the previous page, to get the next page")]
+        after: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "before cursor of a page, to get the page before it")]
+        before: Option<String>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching comments of {}", username);
+
+        let sort = sort.unwrap_or_else(|| "new".to_string());
+        let time = validate_sort(&sort, &USER_SORTS, time)?;
+        check_cursors(&after, &before)?;
+
+        let deadline = Deadline::for_tool("get_user_comments");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let url = format!("{}/user/{}/comments?sort={}", OAUTH_URL, username, sort);
+        let listing = deadline
+            .run(
+                self.get_request::<Listing<Comment>, SortedListingRequest>(
+                    &url,
+                    &auth_token,
+                    SortedListingRequest {
+                        g: None,
+                        t: time.clone(),
+                        limit: limit
+                            .unwrap_or(DEFAULT_COMMENT_LIMIT)
+                            .clamp(1, MAX_PAGE_SIZE),
+                        after,
+                        before,
+                        raw_json: 1,
+                    },
+                ),
+            )
+            .await??;
+        let comments = listing
+            .data
+            .children
+            .into_iter()
+            .map(|child| {
+                let comment = child.data;
+                UserCommentSummary {
+                    id: comment.id,
+                    name: comment.name,
+                    subreddit: comment.subreddit,
+                    score: comment.score,
+                    body: comment.body,
+                    link_title: comment.link_title,
+                    link_id: comment.link_id,
+                    parent_id: comment.parent_id,
+                    permalink: format!("https://www.reddit.com{}", comment.permalink),
+                    created_utc: comment.created_utc as i64,
+                }
+            })
+            .collect();
+
+        serde_json::to_string(&UserCommentListResponse {
+            username,
+            sort,
+            time,
+            comments,
+            after: listing.data.after,
+            before: listing.data.before,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "Get a user's recent comments, each with the title of its post and a snippet of the comment it replies to. Useful to see what a user has been discussing."
+    )]
+    pub(super) async fn get_user_comments_with_context(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "Number of recent comments to fetch, 1-100 (default 25)")]
+        limit: Option<u32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Fetching comments with context for {}", username);
+
+        let deadline = Deadline::for_tool("get_user_comments_with_context");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let limit = limit
+            .unwrap_or(DEFAULT_COMMENT_LIMIT)
+            .clamp(1, MAX_COMMENT_LIMIT);
+
+        let url = format!("{}/user/{}/comments", OAUTH_URL, username);
+        let (comments, mut truncated_reason) = self
+            .collect_listing::<Comment>(&url, &auth_token, limit, &deadline)
+            .await?;
+
+        // Post titles usually come with the listing; only parent comments and
+        // the odd missing title need a lookup.
+        let mut wanted = HashSet::new();
+        for comment in &comments {
+            if comment.parent_id.starts_with("t1_") {
+                wanted.insert(comment.parent_id.clone());
+            }
+            if comment.link_title.is_none() {
+                wanted.insert(comment.link_id.clone());
+            }
+        }
+        let wanted: Vec<String> = wanted.into_iter().collect();
+
+        let parents = if truncated_reason.is_some() || wanted.is_empty() {
+            Default::default()
+        } else {
+            match deadline.run(self.fetch_info(&wanted, &auth_token)).await {
+                Ok(parents) => parents?,
+                Err(_) => {
+                    truncated_reason = Some(deadline.truncation_reason());
+                    Default::default()
+                }
+            }
+        };
+
+        let comments = comments
+            .into_iter()
+            .map(|mut comment| {
+                if comment.link_title.is_none() {
+                    comment.link_title = parents
+                        .get(&comment.link_id)
+                        .and_then(|post| post.title.clone());
+                }
+                let parent_comment = parents.get(&comment.parent_id).and_then(|parent| {
+                    Some(ParentComment {
+                        author: parent.author.clone()?,
+                        snippet: snippet(parent.body.as_deref()?, PARENT_SNIPPET_CHARS),
+                    })
+                });
+                CommentWithContext {
+                    comment,
+                    parent_comment,
+                }
+            })
+            .collect();
+
+        serde_json::to_string(&UserCommentsResponse {
+            username,
+            comments,
+            truncated: truncated_reason.is_some(),
+            truncated_reason,
+        })
+        .map_err(RedditError::serialize)
+    }
+
+    #[tool(
+        description = "For moderators: summarize the risk a user poses in a subreddit before acting on them: account age and karma, posting frequency, prior removals and bans from the mod log, current ban and mod notes, with a low/medium/high rating and the signals behind it."
+    )]
+    pub(super) async fn get_user_risk_summary(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "Username, u/ name or reddit.com profile URL")]
+        username: String,
+        #[tool(param)]
+        #[schemars(description = "Subreddit name, r/ name or reddit.com URL")]
+        subreddit: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Number of recent mod log entries to search for actions on the user (default 500, max 1000)"
+        )]
+        mod_log_limit: Option<u32>,
+    ) -> Result<String, RedditError> {
+        tracing::info!("Summarizing risk of {} in {}", username, subreddit);
+
+        let deadline = Deadline::for_tool("get_user_risk_summary");
+        let auth_token = self.auth_token().await?;
+        let username = deadline
+            .run(self.resolve_username(&username, &auth_token))
+            .await??;
+        let subreddit = deadline
+            .run(self.resolve_subreddit(&subreddit, &auth_token))
+            .await??;
+        let mod_log_limit = mod_log_limit
+            .unwrap_or(DEFAULT_MOD_LOG_SCAN)
+            .clamp(1, MAX_MOD_LOG_SCAN);
+        let now = chrono::Utc::now().timestamp() as f64;
+
+        let about = deadline
+            .run(self.fetch_user_about(&username, &auth_token))
+            .await??;
+
+        let (posts, comments) = if about.is_suspended {
+            (Vec::new(), Vec::new())
+        } else {
+            let posts_url = format!("{}/user/{}/submitted", OAUTH_URL, username);
+            let (posts, _) = self
+                .collect_listing::<Post>(&posts_url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                .await?;
+            let comments_url = format!("{}/user/{}/comments", OAUTH_URL, username);
+            let (comments, _) = self
+                .collect_listing::<Comment>(&comments_url, &auth_token, MAX_PAGE_SIZE, &deadline)
+                .await?;
+            (posts, comments)
+        };
+
+        // The mod log, ban list and mod notes are only readable by moderators.
+        let mod_log_url = format!("{}/r/{}/about/log", OAUTH_URL, subreddit);
+        let mod_log = match self
+            .collect_listing::<ModAction>(&mod_log_url, &auth_token, mod_log_limit, &deadline)
+            .await
+        {
+            Ok((actions, _)) => Some(actions),
+            Err(e) if e.kind == ErrorKind::Forbidden => None,
+            Err(e) => return Err(e),
+        };
+        let banned_url = format!("{}/r/{}/about/banned", OAUTH_URL, subreddit);
+        let current_ban = deadline
+            .run(self.get_request::<UserList<BannedUser>, BannedUserRequest>(
+                &banned_url,
+                &auth_token,
+                BannedUserRequest {
+                    user: username.clone(),
+                    raw_json: 1,
+                },
+            ))
+            .await?
+            .ok()
+            .and_then(|list| {
+                list.data
+                    .children
+                    .into_iter()
+                    .find(|banned| banned.name.eq_ignore_ascii_case(&username))
+            });
+        let notes_url = format!("{}/mod/notes", BASE_URL);
+        let notes = deadline
+            .run(self.get_request::<ModNotesResponse, ModNotesRequest>(
+                &notes_url,
+                &auth_token,
+                ModNotesRequest {
+                    subreddit: subreddit.clone(),
+                    user: username.clone(),
+                    limit: MAX_PAGE_SIZE,
+                },
+            ))
+            .await?
+            .ok();
+
+        let account = AccountRisk {
+            created_utc: about.created_utc,
+            age_days: about
+                .created_utc
+                .map(|created| ((now - created) / SECONDS_PER_DAY) as i64),
+            link_karma: about.link_karma,
+            comment_karma: about.comment_karma,
+            verified_email: about.has_verified_email,
+            suspended: about.is_suspended,
+        };
+
+        let activity_times: Vec<(f64, &str)> = posts
+            .iter()
+            .map(|post| (post.created_utc, post.subreddit.as_str()))
+            .chain(
+                comments
+                    .iter()
+                    .map(|comment| (comment.created_utc, comment.subreddit.as_str())),
+            )
+            .collect();
+        let within = |days: f64| {
+            activity_times
+                .iter()
+                .filter(|(created, _)| now - created <= days * SECONDS_PER_DAY)
+                .count()
+        };
+        let in_subreddit = activity_times
+            .iter()
+            .filter(|(_, name)| name.eq_ignore_ascii_case(&subreddit))
+            .count();
+        let activity = ActivityRisk {
+            posts_sampled: posts.len(),
+            comments_sampled: comments.len(),
+            items_last_day: within(1.0),
+            items_per_day_last_30: (within(30.0) as f64 / 30.0 * 10.0).round() / 10.0,
+            share_in_subreddit: if activity_times.is_empty() {
+                0.0
+            } else {
+                (in_subreddit as f64 / activity_times.len() as f64 * 100.0).round() / 100.0
+            },
+            distinct_subreddits: activity_times
+                .iter()
+                .map(|(_, name)| name.to_ascii_lowercase())
+                .collect::<HashSet<_>>()
+                .len(),
+            last_active_utc: activity_times
+                .iter()
+                .map(|(created, _)| *created)
+                .max_by(f64::total_cmp),
+        };
+
+        let mod_log_entries_scanned = mod_log.as_ref().map_or(0, Vec::len);
+        let actions: Vec<ModAction> = mod_log
+            .as_ref()
+            .into_iter()
+            .flatten()
+            .filter(|action| {
+                action
+                    .target_author
+                    .as_deref()
+                    .is_some_and(|author| author.eq_ignore_ascii_case(&username))
+            })
+            .cloned()
+            .collect();
+        let count = |names: &[&str]| {
+            actions
+                .iter()
+                .filter(|action| names.contains(&action.action.as_str()))
+                .count()
+        };
+        let moderation = ModerationHistory {
+            mod_log_available: mod_log.is_some(),
+            mod_log_entries_scanned,
+            removals: count(&["removelink", "removecomment"]),
+            spam_removals: count(&["spamlink", "spamcomment"]),
+            bans: count(&["banuser"]),
+            approvals: count(&["approvelink", "approvecomment"]),
+            recent_actions: actions
+                .iter()
+                .take(MAX_RECENT_ACTIONS)
+                .map(|action| ModActionSummary {
+                    action: action.action.clone(),
+                    moderator: action.moderator.clone(),
+                    created_utc: action.created_utc,
+                    details: action.details.clone().filter(|details| !details.is_empty()),
+                })
+                .collect(),
+            current_ban,
+            notes_available: notes.is_some(),
+            notes: notes
+                .into_iter()
+                .flat_map(|response| response.mod_notes)
+                .filter(|note| note.kind == "NOTE")
+                .map(|note| {
+                    let data = note.user_note_data.unwrap_or_default();
+                    ModNoteSummary {
+                        created_at: note.created_at,
+                        moderator: note.operator,
+                        label: data.label,
+                        note: data.note.unwrap_or_default(),
+                    }
+                })
+                .collect(),
+        };
+
+        let mut points = 0;
+        let mut signals = Vec::new();
+        let mut signal = |weight: u32, text: String| {
+            points += weight;
+            signals.push(text);
+        };
+        if account.suspended {
+            signal(4, "the account is suspended".to_string());
+        }
+        match account.age_days {
+            Some(days) if days < 7 => signal(2, format!("the account is {} days old", days)),
+            Some(days) if days < 30 => signal(1, format!("the account is {} days old", days)),
+            _ => {}
+        }
+        let karma = account.link_karma + account.comment_karma;
+        if karma < 0 {
+            signal(2, format!("negative karma ({})", karma));
+        } else if karma < 10 && !account.suspended {
+            signal(1, format!("very low karma ({})", karma));
+        }
+        if activity.items_last_day >= 20 {
+            signal(
+                1,
+                format!(
+                    "{} posts and comments in the last 24 hours",
+                    activity.items_last_day
+                ),
+            );
+        }
+        if moderation.spam_removals > 0 {
+            signal(
+                2,
+                format!("{} earlier spam removals", moderation.spam_removals),
+            );
+        }
+        match moderation.removals {
+            0 => {}
+            removals @ 1..=2 => signal(1, format!("{} earlier removals", removals)),
+            removals => signal(2, format!("{} earlier removals", removals)),
+        }
+        if moderation.current_ban.is_some() {
+            signal(3, format!("currently banned from r/{}", subreddit));
+        } else if moderation.bans > 0 {
+            signal(2, format!("banned {} times before", moderation.bans));
+        }
+        let warning_notes = moderation
+            .notes
+            .iter()
+            .filter(|note| {
+                note.label
+                    .as_deref()
+                    .is_some_and(|label| WARNING_NOTE_LABELS.contains(&label))
+            })
+            .count();
+        if warning_notes > 0 {
+            signal(
+                warning_notes.min(2) as u32,
+                format!("{} warning mod notes", warning_notes),
+            );
+        }
+        let risk_level = match points {
+            0..=1 => RiskLevel::Low,
+            2..=3 => RiskLevel::Medium,
+            _ => RiskLevel::High,
+        };
+
+        serde_json::to_string(&UserRiskSummary {
+            username,
+            subreddit,
+            risk_level,
+            signals,
+            account,
+            activity,
+            moderation,
+        })
+        .map_err(RedditError::serialize)
+    }
+}
@@ -0,0 +1,103 @@
+use std::env;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::reddit::error::RedditError;
+
+const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 30;
+/// Tools that pause between several budgeted steps get a longer default:
+/// submit_to_multiple waits 10s between up to 10 subreddits.
+const LONG_TOOL_TIMEOUT_SECS: [(&str, u64); 1] = [("submit_to_multiple", 180)];
+/// Extra time a whole tool call gets over its budget, so handlers that stop
+/// at their own deadline can still return what they have.
+const CALL_GRACE: Duration = Duration::from_secs(2);
+
+/// Time budget of a single tool call.
+///
+/// `call_tool` aborts every call that runs past it; handlers that page through
+/// listings also check it themselves to return partial results in time.
+///
+/// The budget defaults to `TOOL_TIMEOUT_SECS` and can be overridden per tool with
+/// `TOOL_TIMEOUT_<TOOL_NAME>_SECS`, e.g. `TOOL_TIMEOUT_SEARCH_SUBREDDIT_NAMES_SECS=10`.
+#[derive(Debug, Clone)]
+pub struct Deadline {
+    tool: String,
+    budget: Duration,
+    expires_at: Instant,
+}
+
+impl Deadline {
+    pub fn for_tool(tool: &str) -> Self {
+        let budget = Duration::from_secs(Self::configured_secs(tool));
+
+        Self {
+            tool: tool.to_string(),
+            budget,
+            expires_at: Instant::now() + budget,
+        }
+    }
+
+    fn configured_secs(tool: &str) -> u64 {
+        let per_tool = format!("TOOL_TIMEOUT_{}_SECS", tool.to_ascii_uppercase());
+
+        let secs = |key: &str| env::var(key).ok().and_then(|value| value.parse().ok());
+        let long = LONG_TOOL_TIMEOUT_SECS
+            .iter()
+            .find(|(name, _)| *name == tool)
+            .map(|(_, secs)| *secs);
+
+        secs(&per_tool)
+            .or(long)
+            .or_else(|| secs("TOOL_TIMEOUT_SECS"))
+            .unwrap_or(DEFAULT_TOOL_TIMEOUT_SECS)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+
+    pub fn truncation_reason(&self) -> String {
+        format!(
+            "truncated due to timeout: {} exceeded its {}s budget",
+            self.tool,
+            self.budget.as_secs()
+        )
+    }
+
+    /// Runs `future` until the deadline, aborting (dropping) it once the budget is spent.
+    pub async fn run<F>(&self, future: F) -> Result<F::Output, RedditError>
+    where
+        F: Future,
+    {
+        self.run_until(self.expires_at, future).await
+    }
+
+    /// Runs a whole tool call, which is aborted shortly after the deadline.
+    pub async fn run_call<F>(&self, future: F) -> Result<F::Output, RedditError>
+    where
+        F: Future,
+    {
+        self.run_until(self.expires_at + CALL_GRACE, future).await
+    }
+
+    async fn run_until<F>(&self, expires_at: Instant, future: F) -> Result<F::Output, RedditError>
+    where
+        F: Future,
+    {
+        tokio::time::timeout_at(expires_at, future)
+            .await
+            .map_err(|_| {
+                tracing::warn!("Tool {} timed out", self.tool);
+                RedditError::timeout(
+                    format!("{} timed out after {}s", self.tool, self.budget.as_secs()),
+                    &self.tool,
+                )
+            })
+    }
+}
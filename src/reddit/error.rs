@@ -0,0 +1,244 @@
+use reqwest::{StatusCode, header::HeaderMap};
+use rmcp::model::{Content, IntoContents};
+
+use crate::reddit::models::ApiError;
+use crate::reddit::quota::{CategoryUsage, ToolCategory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    InvalidInput,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    RateLimited,
+    Timeout,
+    Network,
+    Parse,
+    Server,
+    Storage,
+    /// A setting the tool needs is missing from the server configuration.
+    Configuration,
+    Other,
+}
+
+/// Error returned by every tool. Besides the message it always carries a
+/// `hint` telling the agent what to change before retrying.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RedditError {
+    pub kind: ErrorKind,
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub hint: String,
+}
+
+impl RedditError {
+    pub fn new(kind: ErrorKind, error: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            kind,
+            error: error.into(),
+            status: None,
+            hint: hint.into(),
+        }
+    }
+
+    pub fn invalid_input(error: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, error, hint)
+    }
+
+    pub fn not_found(error: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, error, hint)
+    }
+
+    pub fn timeout(error: impl Into<String>, tool: &str) -> Self {
+        Self::new(
+            ErrorKind::Timeout,
+            error,
+            format!(
+                "request fewer items or raise TOOL_TIMEOUT_{}_SECS",
+                tool.to_ascii_uppercase()
+            ),
+        )
+    }
+
+    pub fn quota_exceeded(tool: &str, usage: &CategoryUsage) -> Self {
+        let category = match usage.category {
+            ToolCategory::Read => "read",
+            ToolCategory::Write => "write",
+            ToolCategory::Local => "local",
+        };
+        Self::new(
+            ErrorKind::RateLimited,
+            format!(
+                "{} was refused: the {} quota of {} calls is used up",
+                tool,
+                category,
+                usage.limit.unwrap_or_default()
+            ),
+            format!(
+                "retry in {}s or raise [quotas].{} in the server configuration; get_quota_usage shows the current usage",
+                usage.next_slot_in_secs.unwrap_or_default(),
+                category
+            ),
+        )
+    }
+
+    pub fn network(error: reqwest::Error) -> Self {
+        Self::new(
+            ErrorKind::Network,
+            format!("Request failed: {}", error),
+            "Reddit could not be reached; check the network connection and retry",
+        )
+    }
+
+    pub fn parse(error: impl std::fmt::Display) -> Self {
+        Self::new(
+            ErrorKind::Parse,
+            format!("Failed to parse the response: {}", error),
+            "Reddit returned an unexpected payload; the endpoint may have changed or the item type is not supported",
+        )
+    }
+
+    pub fn storage(error: std::io::Error) -> Self {
+        Self::new(
+            ErrorKind::Storage,
+            format!("State directory error: {}", error),
+            "check that the state directory is writable or point REDDIT_MCP_STATE_DIR elsewhere",
+        )
+    }
+
+    /// The setting `key` is not set. `config_key` is its name in the
+    /// `[reddit]` section of the config file.
+    pub fn missing_setting(key: &str, config_key: &str, what: &str) -> Self {
+        Self::new(
+            ErrorKind::Configuration,
+            format!("{} is not set", key),
+            format!(
+                "set {} (or {}_FILE, or {} in the [reddit] section of the config file) to {} and restart the server",
+                key, key, config_key, what
+            ),
+        )
+    }
+
+    pub fn serialize(error: serde_json::Error) -> Self {
+        Self::new(
+            ErrorKind::Other,
+            format!("Failed to serialize the response: {}", error),
+            "this is a bug in reddit-mcp; please report it",
+        )
+    }
+
+    /// Maps a non-success HTTP status to an error with a remediation hint.
+    pub fn from_status(status: StatusCode, headers: &HeaderMap, url: &str) -> Self {
+        let (kind, hint) = match status {
+            StatusCode::UNAUTHORIZED => (
+                ErrorKind::Unauthorized,
+                "Reddit rejected the access token; a new one is fetched on the next call, so retry".to_string(),
+            ),
+            StatusCode::FORBIDDEN if Self::insufficient_scope(headers) => (
+                ErrorKind::Forbidden,
+                "your token lacks the OAuth scope this endpoint needs; re-authenticate with the required scopes".to_string(),
+            ),
+            StatusCode::FORBIDDEN => (
+                ErrorKind::Forbidden,
+                "the account may not access this resource; the subreddit may be private, quarantined or banned".to_string(),
+            ),
+            StatusCode::NOT_FOUND => (
+                ErrorKind::NotFound,
+                "check the subreddit, username or id; use search_subreddit_names or check_username to find the right name".to_string(),
+            ),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let reset = headers
+                    .get("x-ratelimit-reset")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("a few");
+                (
+                    ErrorKind::RateLimited,
+                    format!("Reddit rate limit reached; wait {} seconds before retrying", reset),
+                )
+            }
+            status if status.is_server_error() => (
+                ErrorKind::Server,
+                "Reddit is having problems; retry in a minute".to_string(),
+            ),
+            _ => (
+                ErrorKind::Other,
+                "check the tool arguments; Reddit rejected the request".to_string(),
+            ),
+        };
+
+        Self {
+            kind,
+            error: format!("Request to {} failed with status: {}", url, status),
+            status: Some(status.as_u16()),
+            hint,
+        }
+    }
+
+    /// Maps an error Reddit reported inside an `api_type=json` response.
+    pub fn from_api(error: &ApiError) -> Self {
+        let (kind, hint) = match error.code.as_str() {
+            "RATELIMIT" => (
+                ErrorKind::RateLimited,
+                "Reddit limits how often the account may do this; wait the time it names before retrying",
+            ),
+            "USER_REQUIRED" | "NOT_AUTHOR" | "MOD_REQUIRED" => (
+                ErrorKind::Forbidden,
+                "the account is not allowed to do this; check that it moderates the subreddit or owns the item",
+            ),
+            _ => (
+                ErrorKind::InvalidInput,
+                "fix the reported problem and retry",
+            ),
+        };
+        let error = match &error.field {
+            Some(field) => format!(
+                "Reddit rejected the request: {} {} (field {})",
+                error.code, error.message, field
+            ),
+            None => format!(
+                "Reddit rejected the request: {} {}",
+                error.code, error.message
+            ),
+        };
+        Self::new(kind, error, hint)
+    }
+
+    fn insufficient_scope(headers: &HeaderMap) -> bool {
+        headers
+            .get(reqwest::header::WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("insufficient_scope"))
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = hint.into();
+        self
+    }
+
+    /// Replaces the hint of an error Reddit reported for the request's
+    /// contents, keeping the hints of network, permission and rate limit errors.
+    pub fn with_rejection_hint(self, hint: impl Into<String>) -> Self {
+        if self.kind == ErrorKind::InvalidInput {
+            self.with_hint(hint)
+        } else {
+            self
+        }
+    }
+}
+
+impl std::fmt::Display for RedditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (hint: {})", self.error, self.hint)
+    }
+}
+
+impl std::error::Error for RedditError {}
+
+impl IntoContents for RedditError {
+    fn into_contents(self) -> Vec<Content> {
+        let text = serde_json::to_string(&self).unwrap_or_else(|_| self.to_string());
+        vec![Content::text(text)]
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::storage::{Namespace, Storage};
+
+const MAX_KEY_LENGTH: usize = 64;
+/// How long a key is remembered after the call it belongs to.
+const KEY_RETENTION_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct IdempotencyRecord {
+    tool: String,
+    key: String,
+    /// The call's parameters, to refuse reusing a key for a different request.
+    request: serde_json::Value,
+    result: String,
+    created_utc: i64,
+}
+
+/// Results of mutating tool calls made with an `idempotency_key`, kept in the
+/// state directory so a retried call returns the earlier result instead of
+/// posting twice, even across restarts.
+#[derive(Debug, Clone)]
+pub struct IdempotencyStore {
+    storage: Storage,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Outcome of claiming a key.
+#[derive(Debug)]
+pub enum Claim {
+    /// The call was made before; this is its result.
+    Replay(String),
+    /// The call has to be performed. Its result is recorded with
+    /// [`IdempotencyClaim::record`]; dropping the claim without recording,
+    /// e.g. on error, lets the caller retry.
+    Fresh(IdempotencyClaim),
+}
+
+#[derive(Debug)]
+pub struct IdempotencyClaim {
+    store: Option<IdempotencyStore>,
+    entry: String,
+    tool: String,
+    key: String,
+    request: serde_json::Value,
+}
+
+fn validate_key(key: &str) -> Result<(), RedditError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LENGTH
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(RedditError::invalid_input(
+            format!("{:?} is not a valid idempotency_key", key),
+            format!(
+                "use up to {} letters, digits, - or _, e.g. a UUID",
+                MAX_KEY_LENGTH
+            ),
+        ))
+    }
+}
+
+/// Marks a replayed result so the agent can tell nothing was done this time.
+fn mark_replay(result: String) -> String {
+    match serde_json::from_str::<serde_json::Value>(&result) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert("idempotent_replay".to_string(), true.into());
+            serde_json::Value::Object(object).to_string()
+        }
+        _ => result,
+    }
+}
+
+impl IdempotencyStore {
+    pub fn new(storage: Storage) -> Self {
+        Self {
+            storage,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Claims `key` for a call of `tool` with the given parameters, made as
+    /// `account`. Keys are per account, so the same key used by two accounts
+    /// names two calls. Without a key every call is fresh.
+    pub fn claim(
+        &self,
+        account: &str,
+        tool: &str,
+        key: Option<&str>,
+        request: serde_json::Value,
+    ) -> Result<Claim, RedditError> {
+        let request = serde_json::json!({ "account": account, "request": request });
+        let Some(key) = key else {
+            return Ok(Claim::Fresh(IdempotencyClaim {
+                store: None,
+                entry: String::new(),
+                tool: tool.to_string(),
+                key: String::new(),
+                request,
+            }));
+        };
+        validate_key(key)?;
+        let entry = format!("{}-{}-{}", tool, account, key);
+
+        if !self
+            .in_flight
+            .lock()
+            .expect("idempotency lock poisoned")
+            .insert(entry.clone())
+        {
+            return Err(RedditError::new(
+                ErrorKind::Other,
+                format!("A {} call with this idempotency_key is still running", tool),
+                "wait for it to finish, then retry with the same key to get its result",
+            ));
+        }
+        let claim = IdempotencyClaim {
+            store: Some(self.clone()),
+            entry,
+            tool: tool.to_string(),
+            key: key.to_string(),
+            request,
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let record = self
+            .storage
+            .read_json::<IdempotencyRecord>(Namespace::Idempotency, &claim.entry)
+            .map_err(RedditError::storage)?
+            .filter(|record| record.created_utc + KEY_RETENTION_SECS > now);
+        match record {
+            Some(record) if record.request != claim.request => Err(RedditError::invalid_input(
+                format!(
+                    "idempotency_key {} was already used for a different {} call",
+                    key, tool
+                ),
+                "use a new key for every distinct action and repeat the key only when retrying the same call",
+            )),
+            Some(record) => {
+                tracing::info!("Replaying {} for idempotency_key {}", tool, key);
+                Ok(Claim::Replay(mark_replay(record.result)))
+            }
+            None => Ok(Claim::Fresh(claim)),
+        }
+    }
+
+    fn prune(&self, now: i64) {
+        let Ok(keys) = self.storage.keys(Namespace::Idempotency) else {
+            return;
+        };
+        for key in keys {
+            let expired = self
+                .storage
+                .read_json::<IdempotencyRecord>(Namespace::Idempotency, &key)
+                .ok()
+                .flatten()
+                .is_none_or(|record| record.created_utc + KEY_RETENTION_SECS <= now);
+            if expired && let Err(e) = self.storage.remove(Namespace::Idempotency, &key) {
+                tracing::warn!("Failed to remove idempotency record {}: {}", key, e);
+            }
+        }
+    }
+}
+
+impl IdempotencyClaim {
+    /// Remembers the result of the performed call. A failure is only logged:
+    /// the action already happened, so the result must still reach the caller.
+    pub fn record(mut self, result: &str) {
+        let Some(store) = self.store.take() else {
+            return;
+        };
+        let now = chrono::Utc::now().timestamp();
+        store.prune(now);
+        let record = IdempotencyRecord {
+            tool: std::mem::take(&mut self.tool),
+            key: std::mem::take(&mut self.key),
+            request: self.request.take(),
+            result: result.to_string(),
+            created_utc: now,
+        };
+        if let Err(e) = store
+            .storage
+            .write_json(Namespace::Idempotency, &self.entry, &record)
+        {
+            tracing::error!("Failed to record idempotency_key {}: {}", record.key, e);
+        }
+        store
+            .in_flight
+            .lock()
+            .expect("idempotency lock poisoned")
+            .remove(&self.entry);
+    }
+}
+
+impl Drop for IdempotencyClaim {
+    fn drop(&mut self) {
+        if let Some(store) = self.store.take() {
+            store
+                .in_flight
+                .lock()
+                .expect("idempotency lock poisoned")
+                .remove(&self.entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> IdempotencyStore {
+        let root = std::env::temp_dir().join(format!("reddit-mcp-test-{}", uuid::Uuid::new_v4()));
+        IdempotencyStore::new(Storage::open(root).expect("open the test state directory"))
+    }
+
+    fn fresh(claim: Claim) -> IdempotencyClaim {
+        match claim {
+            Claim::Fresh(claim) => claim,
+            Claim::Replay(result) => panic!("unexpected replay of {}", result),
+        }
+    }
+
+    #[test]
+    fn replays_a_recorded_call() {
+        let store = store();
+        let request = serde_json::json!({ "title": "hello" });
+        fresh(
+            store
+                .claim("a", "submit_post", Some("k1"), request.clone())
+                .unwrap(),
+        )
+        .record(r#"{"name":"t3_1"}"#);
+
+        match store
+            .claim("a", "submit_post", Some("k1"), request)
+            .unwrap()
+        {
+            Claim::Replay(result) => assert!(result.contains("t3_1")),
+            Claim::Fresh(_) => panic!("the recorded call was not replayed"),
+        }
+    }
+
+    #[test]
+    fn same_key_of_another_account_is_a_new_call() {
+        let store = store();
+        let request = serde_json::json!({ "title": "hello" });
+        fresh(
+            store
+                .claim("a", "submit_post", Some("k1"), request.clone())
+                .unwrap(),
+        )
+        .record(r#"{"name":"t3_1"}"#);
+
+        let claim = fresh(
+            store
+                .claim("b", "submit_post", Some("k1"), request)
+                .unwrap(),
+        );
+        claim.record(r#"{"name":"t3_2"}"#);
+    }
+}
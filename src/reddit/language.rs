@@ -0,0 +1,145 @@
+use rmcp::model::{CallToolRequestParam, CallToolResult, Tool};
+use serde_json::Value;
+use whatlang::Lang;
+
+use crate::reddit::error::RedditError;
+use crate::reddit::projection::map_json_result;
+
+/// Listing and search tools whose posts can be filtered by language.
+const LANGUAGE_TOOLS: [&str; 8] = [
+    "find_rising_posts",
+    "get_home_feed",
+    "get_new_posts",
+    "get_posts_page",
+    "get_site_feed",
+    "get_subreddit_posts",
+    "run_saved_search",
+    "search_posts_between",
+];
+
+/// Which languages a tool call keeps, from its `language` argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageFilter {
+    languages: Vec<Lang>,
+    /// Drop the listed languages instead of keeping only them.
+    exclude: bool,
+}
+
+impl LanguageFilter {
+    fn parse(argument: &str) -> Result<Self, RedditError> {
+        let (exclude, list) = match argument.trim().strip_prefix('!') {
+            Some(list) => (true, list),
+            None => (false, argument),
+        };
+        let languages = list
+            .split(',')
+            .map(str::trim)
+            .filter(|language| !language.is_empty())
+            .map(|language| {
+                Lang::from_code(language.to_ascii_lowercase())
+                    .or_else(|| {
+                        Lang::all()
+                            .iter()
+                            .copied()
+                            .find(|lang| lang.eng_name().eq_ignore_ascii_case(language))
+                    })
+                    .ok_or_else(|| {
+                        RedditError::invalid_input(
+                            format!("Unknown language {:?}", language),
+                            "use ISO 639-3 codes or English names, e.g. eng,deu or !eng",
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if languages.is_empty() {
+            return Err(RedditError::invalid_input(
+                "language names no languages",
+                "use ISO 639-3 codes or English names, e.g. eng,deu or !eng",
+            ));
+        }
+        Ok(Self { languages, exclude })
+    }
+
+    /// Whether a post with this text is kept. Text whose language cannot be
+    /// detected is only kept when excluding.
+    fn keeps(&self, text: &str) -> bool {
+        match whatlang::detect_lang(text) {
+            Some(lang) => self.languages.contains(&lang) != self.exclude,
+            None => self.exclude,
+        }
+    }
+
+    /// Drops the posts of `value` in other languages. Posts are the objects
+    /// with a `title`, detected from the title and the self text.
+    fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Array(items) => items
+                .into_iter()
+                .filter(|item| match item.get("title").and_then(Value::as_str) {
+                    Some(title) => {
+                        let body = item.get("selftext").and_then(Value::as_str);
+                        self.keeps(&format!("{} {}", title, body.unwrap_or_default()))
+                    }
+                    None => true,
+                })
+                .map(|item| self.apply(item))
+                .collect(),
+            Value::Object(object) => object
+                .into_iter()
+                .map(|(key, value)| (key, self.apply(value)))
+                .collect(),
+            other => other,
+        }
+    }
+}
+
+/// Adds the optional `language` parameter to listing and search tools.
+pub fn with_language_parameter(mut tool: Tool) -> Tool {
+    if !LANGUAGE_TOOLS.contains(&tool.name.as_ref()) {
+        return tool;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(properties) = properties.as_object_mut() {
+        properties.insert(
+            "language".to_string(),
+            serde_json::json!({
+                "type": ["string", "null"],
+                "description": "Comma-separated languages of the posts to keep, as ISO 639-3 codes or English names, e.g. eng,deu; prefix with ! to drop them instead, e.g. !eng. Detected from the title and body; posts too short to tell are only kept with !",
+            }),
+        );
+    }
+    tool.input_schema = schema.into();
+    tool
+}
+
+/// Takes the `language` argument out of a call of a listing or search tool.
+pub fn take_language(
+    request: &mut CallToolRequestParam,
+) -> Result<Option<LanguageFilter>, RedditError> {
+    if !LANGUAGE_TOOLS.contains(&request.name.as_ref()) {
+        return Ok(None);
+    }
+    let argument = request
+        .arguments
+        .as_mut()
+        .and_then(|arguments| arguments.remove("language"));
+    match argument {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(language)) => LanguageFilter::parse(&language).map(Some),
+        Some(other) => Err(RedditError::invalid_input(
+            format!("language must be a string, got {}", other),
+            "use ISO 639-3 codes or English names, e.g. eng,deu or !eng",
+        )),
+    }
+}
+
+/// Drops the posts of a successful tool result that the filter does not keep.
+pub fn filter_result(result: CallToolResult, filter: Option<&LanguageFilter>) -> CallToolResult {
+    match filter {
+        Some(filter) => map_json_result(result, |value| filter.apply(value)),
+        None => result,
+    }
+}
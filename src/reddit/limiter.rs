@@ -0,0 +1,284 @@
+use std::collections::VecDeque;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+const DEFAULT_INTERACTIVE_RESERVED_REQUESTS: usize = 1;
+
+/// Priority class of an outgoing Reddit request. Lower variants are served first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Interactive,
+    Background,
+    Bulk,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [Priority::Interactive, Priority::Background, Priority::Bulk];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Concurrency limiter for the HTTP layer.
+///
+/// A fixed number of request slots is shared by all priority classes, but a
+/// part of it is reserved for interactive tool calls: background and bulk work
+/// together may only use what remains, and bulk work alone only half of it.
+/// Freed slots are always handed to the highest priority waiter first, so
+/// background polling can never starve an interactive request.
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    limits: [usize; 3],
+    in_flight: usize,
+    in_flight_by_class: [usize; 3],
+    waiters: [VecDeque<oneshot::Sender<LimiterPermit>>; 3],
+}
+
+impl LimiterState {
+    fn can_grant(&self, priority: Priority) -> bool {
+        self.in_flight < self.limits[Priority::Interactive.index()]
+            && self.in_flight_by_class[priority.index()] < self.limits[priority.index()]
+            && (priority == Priority::Interactive
+                || self.non_interactive_in_flight() < self.limits[Priority::Background.index()])
+    }
+
+    /// Background and bulk requests together never take the reserved slots.
+    fn non_interactive_in_flight(&self) -> usize {
+        self.in_flight_by_class[Priority::Background.index()]
+            + self.in_flight_by_class[Priority::Bulk.index()]
+    }
+
+    fn has_waiters_up_to(&self, priority: Priority) -> bool {
+        Priority::ALL
+            .iter()
+            .filter(|p| **p <= priority)
+            .any(|p| !self.waiters[p.index()].is_empty())
+    }
+
+    fn take(&mut self, priority: Priority) {
+        self.in_flight += 1;
+        self.in_flight_by_class[priority.index()] += 1;
+    }
+
+    fn give_back(&mut self, priority: Priority) {
+        self.in_flight -= 1;
+        self.in_flight_by_class[priority.index()] -= 1;
+    }
+
+    /// Hands free slots to waiters, highest priority first.
+    fn dispatch(&mut self, shared: &Arc<Mutex<LimiterState>>) {
+        for priority in Priority::ALL {
+            while self.can_grant(priority) {
+                let Some(waiter) = self.waiters[priority.index()].pop_front() else {
+                    break;
+                };
+                self.take(priority);
+                let permit = LimiterPermit {
+                    state: Some(shared.clone()),
+                    priority,
+                };
+                if let Err(mut permit) = waiter.send(permit) {
+                    // The waiting request was cancelled; reclaim its slot without re-entering drop.
+                    permit.state = None;
+                    self.give_back(priority);
+                }
+            }
+        }
+    }
+}
+
+fn class_limits(max_concurrent: usize, interactive_reserved: usize) -> [usize; 3] {
+    let max_concurrent = max_concurrent.max(1);
+    let shared = max_concurrent.saturating_sub(interactive_reserved).max(1);
+    let bulk = (shared / 2).max(1);
+    [max_concurrent, shared, bulk]
+}
+
+/// A held request slot. The slot is released (and handed to the next waiter) on drop.
+#[derive(Debug)]
+pub struct LimiterPermit {
+    state: Option<Arc<Mutex<LimiterState>>>,
+    priority: Priority,
+}
+
+impl RequestLimiter {
+    pub fn new(max_concurrent: usize, interactive_reserved: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LimiterState {
+                limits: class_limits(max_concurrent, interactive_reserved),
+                in_flight: 0,
+                in_flight_by_class: [0; 3],
+                waiters: Default::default(),
+            })),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let (max_concurrent, interactive_reserved) = Self::env_limits();
+        Self::new(max_concurrent, interactive_reserved)
+    }
+
+    fn env_limits() -> (usize, usize) {
+        let max_concurrent = env::var("MAX_CONCURRENT_REQUESTS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS);
+        let interactive_reserved = env::var("INTERACTIVE_RESERVED_REQUESTS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_INTERACTIVE_RESERVED_REQUESTS);
+        (max_concurrent, interactive_reserved)
+    }
+
+    /// Applies new limits at runtime. Unset values fall back to the environment defaults.
+    /// Requests already in flight keep their slots; lowering a limit only delays new ones.
+    pub fn reconfigure(&self, max_concurrent: Option<usize>, interactive_reserved: Option<usize>) {
+        let (env_max, env_reserved) = Self::env_limits();
+        let limits = class_limits(
+            max_concurrent.unwrap_or(env_max),
+            interactive_reserved.unwrap_or(env_reserved),
+        );
+
+        let mut state = self.state.lock().expect("limiter lock poisoned");
+        if state.limits != limits {
+            tracing::info!("Request limits changed to {:?}", limits);
+            state.limits = limits;
+            state.dispatch(&self.state);
+        }
+    }
+
+    pub async fn acquire(&self, priority: Priority) -> LimiterPermit {
+        let receiver = {
+            let mut state = self.state.lock().expect("limiter lock poisoned");
+            if state.can_grant(priority) && !state.has_waiters_up_to(priority) {
+                state.take(priority);
+                return LimiterPermit {
+                    state: Some(self.state.clone()),
+                    priority,
+                };
+            }
+
+            tracing::debug!("Waiting for a {:?} request slot", priority);
+            let (sender, receiver) = oneshot::channel();
+            state.waiters[priority.index()].push_back(sender);
+            receiver
+        };
+
+        // The sender is only dropped together with the limiter, which outlives all callers.
+        receiver.await.expect("request limiter dropped")
+    }
+}
+
+impl Drop for LimiterPermit {
+    fn drop(&mut self) {
+        let Some(shared) = self.state.take() else {
+            return;
+        };
+        let mut state = shared.lock().expect("limiter lock poisoned");
+        state.give_back(self.priority);
+        state.dispatch(&shared);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// Acquires a slot in a task and reports it once granted, keeping the permit alive.
+    fn spawn_waiter(
+        limiter: &RequestLimiter,
+        priority: Priority,
+        granted: &mpsc::UnboundedSender<(Priority, LimiterPermit)>,
+    ) {
+        let limiter = limiter.clone();
+        let granted = granted.clone();
+        tokio::spawn(async move {
+            let permit = limiter.acquire(priority).await;
+            let _ = granted.send((priority, permit));
+        });
+    }
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn background_and_bulk_leave_the_reserved_slot_free() {
+        // 4 slots, 1 reserved: background and bulk share 3, bulk alone gets 1.
+        let limiter = RequestLimiter::new(4, 1);
+        let _held = [
+            limiter.acquire(Priority::Background).await,
+            limiter.acquire(Priority::Background).await,
+            limiter.acquire(Priority::Bulk).await,
+        ];
+
+        let (sender, mut granted) = mpsc::unbounded_channel();
+        spawn_waiter(&limiter, Priority::Background, &sender);
+        spawn_waiter(&limiter, Priority::Bulk, &sender);
+        settle().await;
+        assert!(granted.try_recv().is_err());
+
+        let interactive = tokio::time::timeout(
+            Duration::from_secs(1),
+            limiter.acquire(Priority::Interactive),
+        )
+        .await;
+        assert!(interactive.is_ok());
+    }
+
+    #[tokio::test]
+    async fn bulk_alone_only_gets_half_of_the_shared_slots() {
+        let limiter = RequestLimiter::new(4, 1);
+        let _bulk = limiter.acquire(Priority::Bulk).await;
+
+        let (sender, mut granted) = mpsc::unbounded_channel();
+        spawn_waiter(&limiter, Priority::Bulk, &sender);
+        settle().await;
+        assert!(granted.try_recv().is_err());
+
+        let background = tokio::time::timeout(
+            Duration::from_secs(1),
+            limiter.acquire(Priority::Background),
+        )
+        .await;
+        assert!(background.is_ok());
+    }
+
+    #[tokio::test]
+    async fn released_slot_goes_to_interactive_first() {
+        // 2 slots, 1 reserved: one interactive and one background fill it.
+        let limiter = RequestLimiter::new(2, 1);
+        let _interactive = limiter.acquire(Priority::Interactive).await;
+        let background = limiter.acquire(Priority::Background).await;
+
+        let (sender, mut granted) = mpsc::unbounded_channel();
+        // The background waiter queues up first.
+        spawn_waiter(&limiter, Priority::Background, &sender);
+        settle().await;
+        spawn_waiter(&limiter, Priority::Interactive, &sender);
+        settle().await;
+        assert!(granted.try_recv().is_err());
+
+        drop(background);
+        let (priority, _permit) = tokio::time::timeout(Duration::from_secs(1), granted.recv())
+            .await
+            .expect("a waiter is granted the released slot")
+            .expect("the waiter reports its permit");
+        assert_eq!(priority, Priority::Interactive);
+        settle().await;
+        assert!(granted.try_recv().is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use reqwest::Url;
+
+use crate::config::LinkConfig;
+
+/// Whether a link points to one of the configured shorteners.
+pub fn is_shortened(url: &Url, config: &LinkConfig) -> bool {
+    url.host_str().is_some_and(|host| {
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        config
+            .shorteners
+            .iter()
+            .any(|shortener| shortener.eq_ignore_ascii_case(host))
+    })
+}
+
+fn is_tracking(name: &str, parameters: &[String]) -> bool {
+    let name = name.to_ascii_lowercase();
+    parameters
+        .iter()
+        .map(|parameter| parameter.to_ascii_lowercase())
+        .any(|parameter| match parameter.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == parameter,
+        })
+}
+
+/// Removes the tracking parameters from the query of `url` and returns the
+/// names of those removed.
+pub fn strip_tracking(url: &mut Url, parameters: &[String]) -> Vec<String> {
+    let (kept, removed): (Vec<_>, Vec<_>) = url
+        .query_pairs()
+        .into_owned()
+        .partition(|(name, _)| !is_tracking(name, parameters));
+    if removed.is_empty() {
+        return Vec::new();
+    }
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    removed.into_iter().map(|(name, _)| name).collect()
+}
@@ -1,3 +1,25 @@
+pub mod accounts;
+pub mod auth;
+pub mod automod;
+pub mod cache;
 pub mod client;
+pub mod deadline;
+pub mod error;
+pub mod idempotency;
+pub mod language;
+pub mod limiter;
+pub mod links;
 pub mod models;
-
+pub mod pager;
+pub mod projection;
+pub mod quota;
+pub mod scopes;
+pub mod scrub;
+pub mod session;
+pub mod tables;
+pub mod tasks;
+pub mod text;
+pub mod token_store;
+pub mod totp;
+pub mod unfurl;
+pub mod validation;
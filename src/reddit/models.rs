@@ -1,3 +1,13 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::config::{CooldownRule, InboxPriority, MacroConfig};
+use crate::reddit::accounts::DEFAULT_ACCOUNT;
+use crate::reddit::quota::{CategoryUsage, ToolCategory};
+use crate::reddit::session::Fullname;
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct AccessTokenRequest {
     pub grant_type: String,
@@ -11,6 +21,117 @@ pub struct AccessTokenResponse {
     pub expires_in: i32,
     pub scope: String,
     pub token_type: String,
+    /// Only issued by the authorization code flow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AuthorizationCodeRequest {
+    pub grant_type: &'static str,
+    pub code: String,
+    pub redirect_uri: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ClientCredentialsRequest {
+    pub grant_type: &'static str,
+    /// Only for the `installed_client` grant of installed apps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RefreshTokenRequest {
+    pub grant_type: &'static str,
+    pub refresh_token: String,
+}
+
+/// Query of the redirect back from Reddit's authorize page.
+#[derive(Debug, serde::Deserialize)]
+pub struct AuthorizationCallback {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Tokens kept across restarts by the token store.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StoredTokens {
+    /// Issued by authorize_account; used instead of the password when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(default)]
+    pub access_token_expires_utc: i64,
+    /// Whether the access token is an application-only one.
+    #[serde(default)]
+    pub app_only: bool,
+    #[serde(default)]
+    pub scope: String,
+    /// Username of an account signed in through authorize_account, which
+    /// has none configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RevokeTokenRequest {
+    pub token: String,
+    pub token_type_hint: &'static str,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RevokeTokenResponse {
+    pub account: String,
+    pub revoked_access_token: bool,
+    pub revoked_refresh_token: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AccountSummary {
+    pub name: String,
+    pub username: String,
+    pub default: bool,
+    pub has_password: bool,
+    /// Signed in through authorize_account.
+    pub has_refresh_token: bool,
+    /// Whether an access token was fetched since the server started.
+    pub signed_in: bool,
+}
+
+/// What get_access_token reports about a token, which itself is never
+/// returned.
+#[derive(Debug, serde::Serialize)]
+pub struct AccessTokenStatusResponse {
+    pub account: String,
+    pub expires_in_secs: u64,
+    /// OAuth scopes of the token, `*` for all of them.
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WhoAmIResponse {
+    pub account: String,
+    pub username: String,
+    pub link_karma: i64,
+    pub comment_karma: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_utc: Option<f64>,
+    /// OAuth scopes of the token, `*` for all of them.
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AuthorizationResponse {
+    pub authorize_url: String,
+    pub redirect_url: String,
+    pub scopes: Vec<String>,
+    pub expires_in_secs: u64,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -22,3 +143,3353 @@ pub struct SearchSubredditNameRequest {
     pub search_query_id: String,
     pub typeahead_active: bool,
 }
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct SearchSubredditNamesResponse {
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UsernameAvailableRequest {
+    pub user: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UserSearchRequest {
+    pub q: String,
+    pub limit: u32,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct UserSummary {
+    pub name: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UsernameCheckResponse {
+    pub username: String,
+    pub exists: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct QuotaUsageResponse {
+    pub window_minutes: u32,
+    pub categories: Vec<CategoryUsage>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UnavailableTool {
+    pub tool: String,
+    pub reason: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ToolGroupCapabilities {
+    pub group: ToolCategory,
+    /// Whether `[tools] groups` lets the group through.
+    pub enabled: bool,
+    pub available: Vec<String>,
+    pub unavailable: Vec<UnavailableTool>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AccountCapabilities {
+    pub name: String,
+    pub signed_in: bool,
+    /// Granted OAuth scopes, known once the account has signed in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ServerCapabilitiesResponse {
+    pub version: &'static str,
+    /// `stdio` or `http`.
+    pub transport: &'static str,
+    /// `user` or `app_only`.
+    pub auth_mode: &'static str,
+    /// `confidential` (script and web apps) or `installed`.
+    pub app_type: &'static str,
+    pub accounts: Vec<AccountCapabilities>,
+    /// No tool that changes something on Reddit can be called.
+    pub read_only: bool,
+    /// Comments and messages are queued for approve_reply instead of sent.
+    pub require_approval: bool,
+    pub allow_nsfw: bool,
+    pub groups: Vec<ToolGroupCapabilities>,
+    pub state_dir: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ListingRequest {
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    pub raw_json: u8,
+}
+
+/// Query of a sorted listing; `t` is the time filter of top and controversial.
+#[derive(Debug, serde::Serialize)]
+pub struct SortedListingRequest {
+    /// Region of r/popular, e.g. `GLOBAL` or `US`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub g: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    pub raw_json: u8,
+}
+
+/// Query for endpoints that take no parameters besides `raw_json`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct RawJsonRequest {
+    pub raw_json: u8,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Listing<T> {
+    pub kind: String,
+    pub data: ListingData<T>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct ListingData<T> {
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub children: Vec<Thing<T>>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Thing<T> {
+    pub kind: String,
+    pub data: T,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Post {
+    pub id: String,
+    pub name: String,
+    pub title: String,
+    pub author: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub num_comments: i64,
+    pub permalink: String,
+    pub url: String,
+    pub created_utc: f64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub selftext: String,
+    #[serde(default)]
+    pub over_18: bool,
+    #[serde(default)]
+    pub is_self: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upvote_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_reports: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_hint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_flair_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_preview: Option<LinkPreview>,
+    #[serde(default, deserialize_with = "null_as_default", skip_serializing)]
+    pub all_awardings: Vec<Awarding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tournament_data: Option<TournamentData>,
+}
+
+/// Prediction tournament embedded in a post. Reddit does not document the
+/// format, so every field is optional and unknown fields are kept in `extra`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct TournamentData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tournament_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_participants: Option<i64>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub predictions: Vec<Prediction>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct Prediction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_option_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_stake_amount: Option<i64>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub options: Vec<PredictionOption>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct PredictionOption {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vote_count: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_stake_amount: Option<i64>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PredictionPost {
+    pub post_id: String,
+    pub title: String,
+    pub subreddit: String,
+    pub permalink: String,
+    pub tournament: TournamentData,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PredictionsResponse {
+    pub posts_scanned: usize,
+    pub posts: Vec<PredictionPost>,
+}
+
+/// Reddit sends `null` for some fields it has retired; read those as empty.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Account of the background records saved before they recorded one.
+fn default_account() -> String {
+    DEFAULT_ACCOUNT.to_string()
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Awarding {
+    pub id: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub name: String,
+    #[serde(default)]
+    pub icon_url: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub coin_price: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub count: i64,
+}
+
+/// A post with the details only get_post returns.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct PostDetails {
+    #[serde(flatten)]
+    pub post: Post,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_flair_css_class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_flair_text: Option<String>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub stickied: bool,
+    #[serde(default)]
+    pub spoiler: bool,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub total_awards_received: i64,
+    /// Copied from the post's awardings, which listings leave out.
+    #[serde(skip_deserializing)]
+    pub awards: Vec<Awarding>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub num_crossposts: i64,
+    /// Fullname of the post this one crossposts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crosspost_parent: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "null_as_default",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub crosspost_parent_list: Vec<Post>,
+    #[serde(default, skip_serializing)]
+    pub preview: Option<PostPreview>,
+    /// Set when get_post was asked to attach the image.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub inline_image: Option<InlineImage>,
+}
+
+/// The resized copies Reddit keeps of a post's image.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct PostPreview {
+    #[serde(default)]
+    pub images: Vec<PreviewImage>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PreviewImage {
+    pub source: PreviewSource,
+    #[serde(default)]
+    pub resolutions: Vec<PreviewSource>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PreviewSource {
+    pub url: String,
+    #[serde(default)]
+    pub width: u32,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InlineImage {
+    pub attached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Title, description and image of the page a link post points to.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct LinkPreview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+}
+
+impl Fullname for Post {
+    fn fullname(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SubredditAbout {
+    pub name: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub public_description: String,
+    #[serde(default)]
+    pub subscribers: Option<i64>,
+    #[serde(default)]
+    pub accounts_active: Option<i64>,
+    #[serde(default)]
+    pub over18: bool,
+    #[serde(default)]
+    pub subreddit_type: String,
+    #[serde(default)]
+    pub submission_type: Option<String>,
+    pub created_utc: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_is_moderator: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub allow_images: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub allow_videos: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub allow_galleries: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub allow_polls: Option<bool>,
+    /// Only approved users may post.
+    #[serde(default, skip_serializing)]
+    pub restrict_posting: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub lang: Option<String>,
+    /// The old-Reddit sidebar markdown.
+    #[serde(default, skip_serializing)]
+    pub description: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SidebarResponse {
+    pub subreddit: String,
+    /// sidebar, wiki, or none when the subreddit has neither.
+    pub source: &'static str,
+    pub markdown: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct StickyPost {
+    /// Sticky slot, 1 or 2, counted from the top of the subreddit.
+    pub slot: usize,
+    #[serde(flatten)]
+    pub post: PostSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_flair_text: Option<String>,
+    pub locked: bool,
+    /// Link of a link post; self posts have their text instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub selftext: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct StickyPostsResponse {
+    pub subreddit: String,
+    pub posts: Vec<StickyPost>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditAboutResponse {
+    pub subreddit: String,
+    pub fullname: String,
+    pub title: String,
+    pub public_description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribers: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_users: Option<i64>,
+    pub created_utc: f64,
+    pub age_days: i64,
+    pub nsfw: bool,
+    /// public, restricted, private, archived, ...
+    pub subreddit_type: String,
+    /// any, link or self: which kind of posts may be submitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_images: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_videos: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_galleries: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_polls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_posters_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SubredditRule {
+    pub short_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violation_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct SubredditRulesResponse {
+    pub rules: Vec<SubredditRule>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RuleEntry {
+    pub number: usize,
+    pub short_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// The removal reason moderators pick for breaking the rule.
+    pub violation_reason: String,
+    /// posts, comments or both.
+    pub applies_to: &'static str,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditRulesListing {
+    pub subreddit: String,
+    pub rules: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct PostRequirements {
+    pub title_regexes: Vec<String>,
+    pub title_blacklisted_strings: Vec<String>,
+    pub title_required_strings: Vec<String>,
+    pub title_text_min_length: Option<usize>,
+    pub title_text_max_length: Option<usize>,
+    pub body_blacklisted_strings: Vec<String>,
+    pub body_required_strings: Vec<String>,
+    pub body_text_min_length: Option<usize>,
+    pub body_text_max_length: Option<usize>,
+    pub body_restriction_policy: Option<String>,
+    pub link_restriction_policy: Option<String>,
+    pub domain_blacklist: Vec<String>,
+    pub domain_whitelist: Vec<String>,
+    pub is_flair_required: bool,
+    pub guidelines_text: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ModLogRequest {
+    #[serde(rename = "type")]
+    pub action: String,
+    pub limit: u32,
+    pub raw_json: u8,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ModAction {
+    pub action: String,
+    #[serde(rename = "mod", default)]
+    pub moderator: String,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub target_fullname: Option<String>,
+    #[serde(default)]
+    pub target_title: Option<String>,
+    #[serde(default)]
+    pub target_author: Option<String>,
+    pub created_utc: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Conflict,
+    Warning,
+    Review,
+    Pass,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ChecklistItem {
+    pub source: &'static str,
+    pub check: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RemovalPattern {
+    pub reason: String,
+    pub count: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RulesPrecheckResponse {
+    pub subreddit: String,
+    pub conflicts: usize,
+    pub warnings: usize,
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guidelines: Option<String>,
+    pub mod_log_available: bool,
+    pub recent_removal_reasons: Vec<RemovalPattern>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct EmojiEntry {
+    pub url: String,
+    #[serde(default)]
+    pub mod_flair_only: bool,
+    #[serde(default)]
+    pub post_flair_allowed: bool,
+    #[serde(default)]
+    pub user_flair_allowed: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditEmoji {
+    pub name: String,
+    /// What to write in flair text to show the emoji, e.g. `:party:`.
+    pub code: String,
+    pub url: String,
+    pub source: &'static str,
+    pub mod_flair_only: bool,
+    pub post_flair_allowed: bool,
+    pub user_flair_allowed: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditEmojisResponse {
+    pub subreddit: String,
+    pub emojis: Vec<SubredditEmoji>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AwardSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    pub coin_price: i64,
+    pub times_given: i64,
+    pub posts: usize,
+}
+
+/// A post or comment of a listing, read only for its awards.
+#[derive(Debug, serde::Deserialize)]
+pub struct AwardedThing {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub subreddit: String,
+    #[serde(default)]
+    pub score: i64,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub created_utc: f64,
+    #[serde(default)]
+    pub over_18: bool,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub all_awardings: Vec<Awarding>,
+    /// Silver, gold and platinum counts by `gid_` id, from before awards.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub gildings: HashMap<String, i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AwardCount {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AwardedItem {
+    /// post or comment.
+    pub kind: &'static str,
+    pub name: String,
+    pub author: String,
+    pub subreddit: String,
+    pub score: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    pub permalink: String,
+    pub created_utc: i64,
+    pub total_awards: i64,
+    pub coin_value: i64,
+    pub awards: Vec<AwardCount>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MostAwardedResponse {
+    /// The r/ or u/ name that was scanned.
+    pub source: String,
+    pub scanned: usize,
+    pub awarded: usize,
+    pub items: Vec<AwardedItem>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditAwardsResponse {
+    pub subreddit: String,
+    pub timeframe: String,
+    pub sampled_posts: usize,
+    pub awarded_posts: usize,
+    pub total_awards: i64,
+    pub total_coin_value: i64,
+    pub awards: Vec<AwardSummary>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct WidgetsResponse {
+    #[serde(default)]
+    pub items: HashMap<String, Widget>,
+}
+
+/// A sidebar widget. Only community lists and text areas are read, so the
+/// kind-specific `data` is left untyped.
+#[derive(Debug, serde::Deserialize)]
+pub struct Widget {
+    pub kind: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct CommunityListItem {
+    pub name: String,
+    #[serde(default)]
+    pub subscribers: Option<i64>,
+    #[serde(default, rename = "isNSFW")]
+    pub is_nsfw: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SubredditAutocompleteRequest {
+    pub query: String,
+    pub include_over_18: bool,
+    pub include_profiles: bool,
+    pub limit: u32,
+    pub raw_json: u8,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SimilarSubreddit {
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribers: Option<i64>,
+    pub nsfw: bool,
+    pub score: u32,
+    /// Where the community was found: widget, sidebar or autocomplete.
+    pub sources: Vec<&'static str>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SimilarSubredditsResponse {
+    pub subreddit: String,
+    pub topics: Vec<String>,
+    pub similar: Vec<SimilarSubreddit>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CollectionRequest {
+    pub collection_id: String,
+    pub include_links: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SubredditCollectionsRequest {
+    pub sr_fullname: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CreateCollectionRequest {
+    pub sr_fullname: String,
+    pub title: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_layout: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CollectionPostRequest {
+    pub collection_id: String,
+    pub link_fullname: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Collection {
+    pub collection_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author_name: String,
+    #[serde(default)]
+    pub subreddit_id: String,
+    #[serde(default)]
+    pub display_layout: Option<String>,
+    #[serde(default)]
+    pub created_at_utc: f64,
+    #[serde(default)]
+    pub last_update_utc: f64,
+    #[serde(default)]
+    pub link_ids: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub sorted_links: Option<Listing<Post>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CollectionResponse {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub posts: Vec<Post>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct Flashpoint {
+    pub kind: &'static str,
+    pub fullname: String,
+    pub author: String,
+    pub permalink: String,
+    pub excerpt: String,
+    pub score: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upvote_ratio: Option<f64>,
+    pub reports: i64,
+    pub in_modqueue: bool,
+    pub flashpoint_score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FlashpointsResponse {
+    pub subreddit: String,
+    pub posts_scanned: usize,
+    pub comments_scanned: usize,
+    pub modqueue_available: bool,
+    pub flashpoints: Vec<Flashpoint>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditComparison {
+    pub subreddit: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribers: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_users: Option<i64>,
+    pub nsfw: bool,
+    pub subreddit_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_type: Option<String>,
+    pub created_utc: f64,
+    pub rule_count: usize,
+    pub sampled_posts: usize,
+    pub posts_per_day: f64,
+    pub average_score: f64,
+    pub average_comments: f64,
+    pub content_types: std::collections::BTreeMap<String, usize>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PostingSlot {
+    pub day: String,
+    pub hour: u32,
+    pub posts: u32,
+    pub average_score: f64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PostingTimesResponse {
+    pub subreddit: String,
+    pub timeframe: String,
+    pub utc_offset_hours: i32,
+    pub sampled_posts: usize,
+    /// Seven rows, Monday first, of 24 hourly post counts.
+    pub heatmap: Vec<Vec<u32>>,
+    pub best_slots: Vec<PostingSlot>,
+    pub best_days: Vec<String>,
+    pub best_hours: Vec<u32>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RisingPost {
+    #[serde(flatten)]
+    pub post: Post,
+    pub age_minutes: f64,
+    pub score_per_minute: f64,
+    pub comments_per_minute: f64,
+    pub likely_to_trend: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RisingPostsResponse {
+    pub subreddit: String,
+    pub sampled_posts: usize,
+    pub median_score_per_minute: f64,
+    pub posts: Vec<RisingPost>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditListingResponse {
+    pub subreddit: String,
+    pub posts: Vec<Post>,
+    pub new_since_last_fetch: usize,
+    pub served_from_cache: bool,
+    pub excluded_seen: usize,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+/// Query of /message/inbox and /message/unread.
+#[derive(Debug, serde::Serialize)]
+pub struct InboxRequest {
+    /// Reddit marks fetched messages as read unless this is false.
+    pub mark: bool,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    pub raw_json: u8,
+}
+
+/// A private message (t4) or a comment reply or mention (t1) in the inbox.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct InboxItem {
+    pub id: String,
+    pub name: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub author: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub subject: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subreddit: Option<String>,
+    pub created_utc: f64,
+    /// Unread.
+    #[serde(default)]
+    pub new: bool,
+    #[serde(default)]
+    pub was_comment: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub context: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PrioritizedInboxItem {
+    #[serde(flatten)]
+    pub item: InboxItem,
+    /// Name or `#<position>` of the rule that set the priority.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InboxGroup {
+    pub priority: InboxPriority,
+    pub items: Vec<PrioritizedInboxItem>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InboxResponse {
+    pub unread_only: bool,
+    pub total: usize,
+    /// High priority first; empty groups are left out.
+    pub groups: Vec<InboxGroup>,
+    pub after: Option<String>,
+}
+
+/// The fields of a post that listings return.
+#[derive(Debug, serde::Serialize)]
+pub struct PostSummary {
+    pub id: String,
+    pub name: String,
+    pub title: String,
+    pub author: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub num_comments: i64,
+    pub permalink: String,
+    pub created_utc: i64,
+}
+
+impl Fullname for PostSummary {
+    fn fullname(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditPostsResponse {
+    pub subreddit: String,
+    pub sort: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    pub posts: Vec<PostSummary>,
+    pub excluded_seen: usize,
+    /// Cursor for the next page, passed as `after`.
+    pub after: Option<String>,
+    /// Cursor for the previous page, passed as `before`.
+    pub before: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SiteFeedResponse {
+    pub feed: String,
+    pub sort: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo: Option<String>,
+    pub posts: Vec<PostSummary>,
+    pub excluded_seen: usize,
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HomeFeedResponse {
+    pub sort: String,
+    pub posts: Vec<PostSummary>,
+    pub excluded_seen: usize,
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Comment {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub body: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub link_id: String,
+    pub parent_id: String,
+    pub permalink: String,
+    pub created_utc: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_title: Option<String>,
+    #[serde(default)]
+    pub controversiality: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_reports: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct InfoRequest {
+    pub id: String,
+    pub raw_json: u8,
+}
+
+/// A post or comment returned by `/api/info`; only the fields both share are required.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct InfoItem {
+    pub name: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub subreddit: Option<String>,
+    #[serde(default)]
+    pub permalink: Option<String>,
+    /// The post a comment belongs to.
+    #[serde(default)]
+    pub link_id: Option<String>,
+    /// Set while the item is removed, e.g. `moderator`, `automod_filtered` or `deleted`.
+    #[serde(default)]
+    pub removed_by_category: Option<String>,
+    /// Who removed the item: a moderator's name, or `true` for the spam filter.
+    #[serde(default)]
+    pub banned_by: Option<serde_json::Value>,
+    /// `[reason, count, ...]` entries.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub user_reports: Vec<Vec<serde_json::Value>>,
+    /// `[reason, moderator, ...]` entries.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub mod_reports: Vec<Vec<serde_json::Value>>,
+    #[serde(default)]
+    pub score: Option<i64>,
+    #[serde(default)]
+    pub upvote_ratio: Option<f64>,
+    #[serde(default)]
+    pub num_comments: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ParentComment {
+    pub author: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentWithContext {
+    #[serde(flatten)]
+    pub comment: Comment,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_comment: Option<ParentComment>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserCommentsResponse {
+    pub username: String,
+    pub comments: Vec<CommentWithContext>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+/// A post or comment of a user's overview, newest activity first by default.
+#[derive(Debug, serde::Serialize)]
+pub struct OverviewItem {
+    /// post or comment.
+    pub kind: &'static str,
+    pub name: String,
+    pub subreddit: String,
+    pub score: i64,
+    /// The post's title, or for a comment the title of its post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_comments: Option<i64>,
+    pub permalink: String,
+    pub created_utc: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserCommentSummary {
+    pub id: String,
+    pub name: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_title: Option<String>,
+    pub link_id: String,
+    pub parent_id: String,
+    pub permalink: String,
+    pub created_utc: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserPostsResponse {
+    pub username: String,
+    pub sort: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    pub posts: Vec<PostSummary>,
+    pub excluded_seen: usize,
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserCommentListResponse {
+    pub username: String,
+    pub sort: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    pub comments: Vec<UserCommentSummary>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserOverviewResponse {
+    pub username: String,
+    pub sort: String,
+    pub items: Vec<OverviewItem>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SearchRequest {
+    pub q: String,
+    pub sort: String,
+    pub t: String,
+    pub restrict_sr: bool,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    pub raw_json: u8,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SearchBetweenResponse {
+    pub job_id: String,
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subreddit: Option<String>,
+    pub start: i64,
+    pub end: i64,
+    pub window: String,
+    pub posts: Vec<Post>,
+    pub excluded_seen: usize,
+    pub scanned: usize,
+    pub reached_start: bool,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TimelineBucket {
+    /// First day of the bucket, YYYY-MM-DD.
+    pub start: String,
+    pub posts: usize,
+    pub total_score: i64,
+    pub total_comments: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_post: Option<PostSummary>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TopicTimelineResponse {
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subreddit: Option<String>,
+    pub start: i64,
+    pub end: i64,
+    pub bucket: String,
+    pub total_posts: usize,
+    pub buckets: Vec<TimelineBucket>,
+    pub scanned: usize,
+    pub reached_start: bool,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SearchDefinition {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub subreddits: Vec<String>,
+    pub sort: String,
+    pub time: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<i64>,
+}
+
+/// A saved search as stored in the state directory, with the fullnames of the
+/// previous run's results to diff the next run against.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SavedSearch {
+    #[serde(flatten)]
+    pub definition: SearchDefinition,
+    pub created_utc: i64,
+    #[serde(default)]
+    pub last_run_utc: Option<i64>,
+    #[serde(default)]
+    pub last_results: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SavedSearchSummary {
+    #[serde(flatten)]
+    pub definition: SearchDefinition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_utc: Option<i64>,
+    pub last_result_count: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SavedSearchRunResponse {
+    pub name: String,
+    pub first_run: bool,
+    pub matches: usize,
+    pub new_posts: Vec<Post>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CommentsRequest {
+    pub sort: String,
+    pub limit: u32,
+    pub depth: u32,
+    pub raw_json: u8,
+}
+
+/// A comment from a `/comments/<id>` thread together with its loaded replies.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TreeComment {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub score: i64,
+    #[serde(default)]
+    pub parent_id: String,
+    #[serde(default)]
+    pub created_utc: f64,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub stickied: bool,
+    /// `moderator` or `admin` when the comment was distinguished.
+    #[serde(default)]
+    pub distinguished: Option<String>,
+    /// Only sent by `/api/morechildren`, whose comments come as a flat list.
+    #[serde(default)]
+    pub depth: Option<u32>,
+    #[serde(default)]
+    pub replies: CommentTree,
+}
+
+/// The comment children of a thread listing, with the `more` stubs that stand
+/// in for the replies Reddit left out. The empty string Reddit sends for
+/// comments without replies parses as an empty tree.
+#[derive(Debug, Clone, Default)]
+pub struct CommentTree(pub Vec<TreeComment>, pub Vec<MoreComments>);
+
+/// A `more` stub: comments of a thread that were not loaded.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct MoreComments {
+    pub id: String,
+    #[serde(default)]
+    pub parent_id: String,
+    /// Number of hidden comments, replies included.
+    #[serde(default)]
+    pub count: i64,
+    /// Ids of the hidden comments, for expanding them. Empty when the thread
+    /// is only cut off by depth and has to be continued from `parent_id`.
+    #[serde(default)]
+    pub children: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for CommentTree {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if !value.is_object() {
+            return Ok(Self::default());
+        }
+
+        let listing: Listing<serde_json::Value> =
+            serde_json::from_value(value).map_err(D::Error::custom)?;
+        let mut tree = Self::default();
+        for child in listing.data.children {
+            match child.kind.as_str() {
+                "t1" => tree
+                    .0
+                    .push(serde_json::from_value(child.data).map_err(D::Error::custom)?),
+                "more" => tree
+                    .1
+                    .push(serde_json::from_value(child.data).map_err(D::Error::custom)?),
+                _ => {}
+            }
+        }
+        Ok(tree)
+    }
+}
+
+/// Query of `/api/morechildren`, which loads the comments of `more` stubs.
+#[derive(Debug, serde::Serialize)]
+pub struct MoreChildrenRequest {
+    pub link_id: String,
+    /// Comma-separated comment ids.
+    pub children: String,
+    pub sort: String,
+    pub limit_children: bool,
+    pub api_type: &'static str,
+    pub raw_json: u8,
+}
+
+/// The comments and `more` stubs of `/api/morechildren`, as a flat list in
+/// thread order.
+#[derive(Debug, Deserialize)]
+pub struct MoreChildrenData {
+    #[serde(default)]
+    pub things: Vec<Thing<serde_json::Value>>,
+}
+
+/// Query of `/comments/<post>/_/<comment>`, the thread around one comment.
+#[derive(Debug, serde::Serialize)]
+pub struct CommentContextRequest {
+    /// Number of parent comments to include above the comment.
+    pub context: u32,
+    pub depth: u32,
+    pub limit: u32,
+    pub raw_json: u8,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ContextComment {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub score: i64,
+    pub body: String,
+    pub created_utc: i64,
+    pub permalink: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentContextResponse {
+    pub post: PostSummary,
+    /// Parent levels asked for.
+    pub levels: u32,
+    /// Parent comments, from the topmost one fetched down to the direct parent.
+    pub parents: Vec<ContextComment>,
+    pub comment: ContextComment,
+    /// Set when the chain continues above the topmost parent returned.
+    pub more_parents: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ContextBundleResponse {
+    pub post: Post,
+    /// Parent comments, from the top-level comment down to the direct parent.
+    pub ancestors: Vec<ContextComment>,
+    pub comment: ContextComment,
+    pub replies: Vec<ContextComment>,
+    /// Set when the top of the ancestor chain could not be fetched in time.
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+/// A comment of get_comments with its loaded replies.
+#[derive(Debug, serde::Serialize)]
+pub struct CommentNode {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub score: i64,
+    pub body: String,
+    pub created_utc: i64,
+    pub depth: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    pub stickied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinguished: Option<String>,
+    /// Replies at any depth below the comment, loaded or hidden in `more`.
+    pub reply_count: i64,
+    pub replies: Vec<CommentNode>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub more: Vec<MoreComments>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentsResponse {
+    pub post: Post,
+    pub sort: String,
+    /// stickied or op when the comments were filtered; they are then a flat
+    /// list without replies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only: Option<String>,
+    pub comments: Vec<CommentNode>,
+    /// Top-level comments that were not loaded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub more: Vec<MoreComments>,
+    /// Comments in the returned tree.
+    pub loaded: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ExpandedCommentsResponse {
+    pub link_id: String,
+    /// The expanded comments with their replies, each under its own parent.
+    pub comments: Vec<CommentNode>,
+    /// Stubs among the expanded comments whose parent was not expanded, e.g. a
+    /// further top-level `more`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub more: Vec<MoreComments>,
+    pub requested: usize,
+    pub loaded: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HighlightReply {
+    pub id: String,
+    pub author: String,
+    pub score: i64,
+    pub body: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ThreadHighlight {
+    pub id: String,
+    pub author: String,
+    pub score: i64,
+    pub body: String,
+    pub best_replies: Vec<HighlightReply>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ThreadHighlightsResponse {
+    pub post: Post,
+    pub top_level_comments: usize,
+    pub highlights: Vec<ThreadHighlight>,
+    pub chars_used: usize,
+    pub trimmed: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SortedComment {
+    pub id: String,
+    pub author: String,
+    pub score: i64,
+    pub excerpt: String,
+    /// 1-based position of the comment under each sort it appeared in.
+    pub positions: std::collections::BTreeMap<String, usize>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentSortComparisonResponse {
+    pub post_id: String,
+    pub title: String,
+    pub sorts: Vec<String>,
+    pub per_sort: usize,
+    /// Comments that appeared under every sort.
+    pub in_all_sorts: usize,
+    /// Comments that appeared only under the given sort.
+    pub exclusive: std::collections::BTreeMap<String, usize>,
+    pub comments: Vec<SortedComment>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MentionContext {
+    /// Comment id, or the post id for mentions in the post itself.
+    pub id: String,
+    pub author: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct Mention {
+    pub kind: &'static str,
+    pub name: String,
+    pub count: usize,
+    pub contexts: Vec<MentionContext>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ThreadMentionsResponse {
+    pub post_id: String,
+    pub title: String,
+    pub comments_scanned: usize,
+    pub users: Vec<Mention>,
+    pub subreddits: Vec<Mention>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct UserList<T = UserSummary> {
+    pub data: UserListData<T>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct UserListData<T = UserSummary> {
+    pub children: Vec<T>,
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct DraftsResponse {
+    #[serde(default)]
+    pub drafts: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ThingRequest {
+    pub id: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SubscribeRequest {
+    pub action: String,
+    pub sr_name: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HygieneItem {
+    pub fullname: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub created_utc: f64,
+    pub permalink: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct InactiveSubscription {
+    pub subreddit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_post_utc: Option<f64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AccountHygieneReport {
+    pub username: String,
+    pub low_score_posts: Vec<HygieneItem>,
+    pub low_score_comments: Vec<HygieneItem>,
+    pub subscriptions: usize,
+    pub subscriptions_checked: usize,
+    pub inactive_subscriptions: Vec<InactiveSubscription>,
+    pub blocked_users: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drafts: Option<usize>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FailedTarget {
+    pub target: String,
+    pub error: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CleanupResponse {
+    pub action: String,
+    pub confirmed: bool,
+    pub targets: Vec<String>,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<FailedTarget>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct EditUserTextRequest {
+    pub thing_id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct DeletionFilters {
+    pub kinds: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub subreddits: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub exclude_subreddits: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionStatus {
+    Pending,
+    Deleted,
+    Failed,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct DeletionItem {
+    pub fullname: String,
+    pub subreddit: String,
+    pub score: i64,
+    pub created_utc: f64,
+    pub excerpt: String,
+    /// Text posts and comments can be overwritten before deletion; link posts cannot.
+    pub editable: bool,
+    pub status: DeletionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A previewed bulk deletion, persisted so interrupted runs can resume.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct DeletionPlan {
+    pub id: String,
+    pub username: String,
+    pub created_utc: i64,
+    pub filters: DeletionFilters,
+    pub items: Vec<DeletionItem>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeletionProgress {
+    pub plan_id: String,
+    pub total: usize,
+    pub pending: usize,
+    pub deleted: usize,
+    pub failed: usize,
+    pub complete: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeletionPreviewResponse {
+    pub plan_id: String,
+    pub filters: DeletionFilters,
+    pub scanned: usize,
+    pub matched: usize,
+    pub items: Vec<DeletionItem>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DeletionRunResponse {
+    #[serde(flatten)]
+    pub progress: DeletionProgress,
+    pub job_id: String,
+    pub processed_this_run: usize,
+    pub overwrite: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped_reason: Option<String>,
+    pub failures: Vec<DeletionItem>,
+}
+
+/// What a resumable job does, with the arguments it was started with.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobSpec {
+    BanListExport {
+        subreddit: String,
+        format: String,
+        duration: Option<String>,
+        older_than_days: Option<i64>,
+        note_contains: Option<String>,
+        limit: u32,
+        scrub: bool,
+    },
+    PostSearch {
+        query: String,
+        subreddit: Option<String>,
+        start: i64,
+        end: i64,
+        window: String,
+        limit: usize,
+        #[serde(default)]
+        exclude_seen: bool,
+    },
+    HistoryDeletion {
+        plan_id: String,
+        overwrite: bool,
+        overwrite_text: String,
+    },
+}
+
+/// Checkpoint of a bulk operation, saved after every page or item so that
+/// resume_job can continue where it stopped.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Job {
+    pub id: String,
+    /// Name of the account the job runs as.
+    pub account: String,
+    pub created_utc: i64,
+    pub updated_utc: i64,
+    #[serde(flatten)]
+    pub spec: JobSpec,
+    /// Listing cursor to continue paging from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Fullnames or usernames already handled, skipped when listings shift.
+    #[serde(default)]
+    pub processed_ids: Vec<String>,
+    /// Items collected so far.
+    #[serde(default)]
+    pub items: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub pages: usize,
+    #[serde(default)]
+    pub scanned: usize,
+    pub complete: bool,
+    /// Stopped by cancel_job; resume_job refuses it.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SnapshotPageResponse {
+    pub snapshot_id: String,
+    pub listing: String,
+    pub page: usize,
+    pub posts: Vec<Post>,
+    pub duplicates_skipped: usize,
+    pub exhausted: bool,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubmitRequest {
+    pub sr: String,
+    pub kind: &'static str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair_text: Option<String>,
+    pub nsfw: bool,
+    pub spoiler: bool,
+    /// Post a `crosspost` kind submission re-shares.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crosspost_fullname: Option<String>,
+}
+
+/// A form posted with `api_type=json`, so that Reddit answers with
+/// [`ApiJsonResponse`] instead of the legacy `jquery` format.
+#[derive(Debug, serde::Serialize)]
+pub struct ApiForm<D> {
+    #[serde(flatten)]
+    pub form: D,
+    pub api_type: &'static str,
+}
+
+/// Envelope of endpoints called with `api_type=json`. Endpoints that ignore
+/// `api_type` answer with a bare object, or with the legacy `jquery` array
+/// and a `success` flag, and parse with an empty body.
+#[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct ApiJsonResponse<T> {
+    #[serde(default)]
+    pub json: ApiJsonBody<T>,
+    pub success: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiJsonBody<T> {
+    #[serde(default)]
+    pub errors: Vec<ApiError>,
+    pub data: Option<T>,
+}
+
+impl<T> Default for ApiJsonBody<T> {
+    fn default() -> Self {
+        Self {
+            errors: Vec::new(),
+            data: None,
+        }
+    }
+}
+
+/// One `[code, message, field]` error triple of an `api_type=json` response.
+#[derive(Debug, Clone, Default)]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+    pub field: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for ApiError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut parts = Vec::<Option<String>>::deserialize(deserializer)?.into_iter();
+        let mut next = || parts.next().flatten();
+        Ok(Self {
+            code: next().unwrap_or_default(),
+            message: next().unwrap_or_default(),
+            field: next().filter(|field| !field.is_empty()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct SubmitData {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+}
+
+/// A submission made through this server, logged per subreddit.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SubmissionRecord {
+    pub name: String,
+    pub title: String,
+    pub created_utc: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CooldownStatus {
+    pub subreddit: String,
+    pub rule: CooldownRule,
+    pub recent_submissions: Vec<SubmissionRecord>,
+    pub allowed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_allowed_utc: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubmitPostResponse {
+    #[serde(flatten)]
+    pub post: SubmitData,
+    pub cooldown_overridden: bool,
+    pub warnings: Vec<String>,
+    pub verification: SubmissionVerification,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantState {
+    /// Waiting for its submit_at time.
+    Scheduled,
+    /// Submitted, engagement not recorded yet.
+    Observing,
+    Complete,
+    Failed,
+}
+
+/// Engagement of a post when it was measured.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct Engagement {
+    pub score: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upvote_ratio: Option<f64>,
+    pub num_comments: i64,
+    pub measured_utc: i64,
+}
+
+/// One title of an A/B title experiment.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ExperimentVariant {
+    pub title: String,
+    pub subreddit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flair_text: Option<String>,
+    pub submit_at_utc: i64,
+    pub state: VariantState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post: Option<SubmitData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitted_utc: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observe_until_utc: Option<i64>,
+    /// Measured at the end of the observation window.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engagement: Option<Engagement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// An A/B title experiment, stored under its name.
+#[derive(Debug, Deserialize, serde::Serialize)]
+pub struct Experiment {
+    pub name: String,
+    /// Name of the account its variants are submitted as.
+    #[serde(default = "default_account")]
+    pub account: String,
+    pub observe_hours: u32,
+    pub created_utc: i64,
+    pub variants: Vec<ExperimentVariant>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubmitVariantResponse {
+    pub experiment: String,
+    pub variant_index: usize,
+    #[serde(flatten)]
+    pub variant: ExperimentVariant,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<SubmissionVerification>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct VariantResult {
+    pub variant_index: usize,
+    #[serde(flatten)]
+    pub variant: ExperimentVariant,
+    /// Engagement so far of a variant still being observed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Engagement>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ExperimentResultsResponse {
+    pub experiment: String,
+    pub observe_hours: u32,
+    /// Title of the completed variant with the highest score.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+    pub variants: Vec<VariantResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetOutcome {
+    Submitted,
+    Crossposted,
+    /// Not attempted because of a rule conflict or a blocking cooldown.
+    Skipped,
+    Failed,
+    /// Not reached before the call's time budget ran out.
+    NotAttempted,
+}
+
+/// What happened to one subreddit of submit_to_multiple.
+#[derive(Debug, serde::Serialize)]
+pub struct SubmitTargetResult {
+    pub subreddit: String,
+    pub outcome: TargetOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post: Option<SubmitData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<SubmissionVerification>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubmitToMultipleResponse {
+    pub submitted: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub not_attempted: usize,
+    /// Why the remaining subreddits were not attempted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+    /// Post the later subreddits crossposted, in crosspost mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crosspost_of: Option<String>,
+    pub targets: Vec<SubmitTargetResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    Live,
+    /// Filtered into the mod queue by AutoModerator until a moderator approves it.
+    HeldForReview,
+    /// Caught by Reddit's spam filter.
+    FilteredAsSpam,
+    RemovedByAutomod,
+    RemovedByModerator,
+    Removed,
+    Deleted,
+    /// The item could not be looked up again.
+    Unknown,
+}
+
+/// The state of a new post or comment when it was fetched again.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SubmissionVerification {
+    pub status: SubmissionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed_by_category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RemoveRequest {
+    pub id: String,
+    pub spam: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BanRequest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UnbanRequest {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModNoteRequest {
+    pub subreddit: String,
+    pub user: String,
+    pub note: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub reddit_id: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReplyRequest {
+    pub thing_id: String,
+    pub text: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct DistinguishRequest {
+    pub id: String,
+    pub how: &'static str,
+    pub sticky: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroStepStatus {
+    Done,
+    Failed,
+    /// Not attempted because an earlier step failed.
+    Skipped,
+    RolledBack,
+    /// Completed before a later failure and could not be undone.
+    NotRolledBack,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MacroStepOutcome {
+    pub action: &'static str,
+    pub status: MacroStepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MacroRunResponse {
+    #[serde(rename = "macro")]
+    pub macro_name: String,
+    pub target: String,
+    pub author: String,
+    pub subreddit: String,
+    pub success: bool,
+    pub steps: Vec<MacroStepOutcome>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MacroSummary {
+    pub name: String,
+    #[serde(flatten)]
+    pub config: MacroConfig,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RemovalReason {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RemovalReasonsResponse {
+    #[serde(default)]
+    pub data: HashMap<String, RemovalReason>,
+    #[serde(default)]
+    pub order: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ItemReport {
+    pub source: &'static str,
+    pub reason: String,
+    pub count: i64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RemovalReasonSuggestion {
+    #[serde(flatten)]
+    pub reason: RemovalReason,
+    pub confidence: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_report: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RemovalReasonSuggestionsResponse {
+    pub target: String,
+    pub subreddit: String,
+    pub reports: Vec<ItemReport>,
+    pub suggestions: Vec<RemovalReasonSuggestion>,
+    pub removal_reasons_total: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailRequest {
+    #[serde(rename = "markRead")]
+    pub mark_read: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailReplyRequest {
+    pub body: String,
+    #[serde(rename = "isAuthorHidden")]
+    pub is_author_hidden: bool,
+    #[serde(rename = "isInternal")]
+    pub is_internal: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModmailConversationResponse {
+    pub conversation: ModmailConversation,
+    #[serde(default)]
+    pub messages: HashMap<String, ModmailMessage>,
+    #[serde(default)]
+    pub user: Option<ModmailUser>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailConversation {
+    pub id: String,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub is_internal: bool,
+    pub owner: ModmailOwner,
+    #[serde(default)]
+    pub participant: Option<ModmailAuthor>,
+    #[serde(default)]
+    pub obj_ids: Vec<ModmailObjectId>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailOwner {
+    pub display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModmailObjectId {
+    pub id: String,
+    pub key: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModmailAuthor {
+    pub name: String,
+    pub is_mod: bool,
+    pub is_admin: bool,
+    pub is_hidden: bool,
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailMessage {
+    #[serde(default)]
+    pub author: ModmailAuthor,
+    #[serde(default)]
+    pub body_markdown: String,
+    #[serde(default)]
+    pub date: String,
+    #[serde(default)]
+    pub is_internal: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailUser {
+    pub name: String,
+    #[serde(default)]
+    pub created: Option<String>,
+    #[serde(default)]
+    pub ban_status: Option<ModmailRestriction>,
+    #[serde(default)]
+    pub mute_status: Option<ModmailRestriction>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub recent_posts: HashMap<String, ModmailRecentItem>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub recent_comments: HashMap<String, ModmailRecentItem>,
+}
+
+/// Ban or mute state of a modmail participant. Bans report `isBanned`,
+/// mutes `isMuted`.
+#[derive(Debug, Default, Deserialize, serde::Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModmailRestriction {
+    #[serde(alias = "isBanned", alias = "isMuted", skip_serializing)]
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    pub is_permanent: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ModmailRecentItem {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub comment: String,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub date: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailContextMessage {
+    pub author: String,
+    pub role: &'static str,
+    pub date: String,
+    pub internal: bool,
+    pub body: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailUserContext {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ban: Option<ModmailRestriction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute: Option<ModmailRestriction>,
+    pub posts_in_subreddit: Vec<ModmailRecentItem>,
+    pub comments_in_subreddit: Vec<ModmailRecentItem>,
+    pub recent_posts: Vec<HygieneItem>,
+    pub recent_comments: Vec<HygieneItem>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailContextResponse {
+    pub conversation_id: String,
+    pub subreddit: String,
+    pub subject: String,
+    pub internal_conversation: bool,
+    pub messages: Vec<ModmailContextMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<ModmailUserContext>,
+    pub rules: Vec<SubredditRule>,
+    /// Facts the reply should take into account, followed by how to send it.
+    pub drafting_notes: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailReplyResponse {
+    pub conversation_id: String,
+    pub sent: bool,
+    pub as_subreddit: bool,
+    pub internal: bool,
+    pub message_count: usize,
+}
+
+/// `/user/{name}/about`. Suspended accounts only report their name.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct UserAbout {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_utc: Option<f64>,
+    #[serde(default)]
+    pub link_karma: i64,
+    #[serde(default)]
+    pub comment_karma: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_verified_email: Option<bool>,
+    #[serde(default)]
+    pub is_suspended: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_karma: Option<i64>,
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub is_gold: bool,
+    #[serde(default)]
+    pub is_mod: bool,
+    #[serde(default)]
+    pub is_employee: bool,
+    /// The profile's own subreddit, which holds its title and description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subreddit: Option<UserProfile>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, serde::Serialize)]
+pub struct UserProfile {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub public_description: String,
+    #[serde(default)]
+    pub over_18: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserAboutResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_utc: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_days: Option<i64>,
+    pub link_karma: i64,
+    pub comment_karma: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_karma: Option<i64>,
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_email: Option<bool>,
+    pub premium: bool,
+    pub moderator: bool,
+    pub employee: bool,
+    pub suspended: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub profile_title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub profile_description: String,
+    pub profile_nsfw: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BannedUserRequest {
+    pub user: String,
+    pub raw_json: u8,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct BannedUser {
+    pub name: String,
+    #[serde(default)]
+    pub date: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Absent for permanent bans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_left: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModNotesRequest {
+    pub subreddit: String,
+    pub user: String,
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModNotesResponse {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub mod_notes: Vec<ModNote>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModNote {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub operator: String,
+    #[serde(default)]
+    pub user_note_data: Option<UserNoteData>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UserNoteData {
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AccountRisk {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_utc: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_days: Option<i64>,
+    pub link_karma: i64,
+    pub comment_karma: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_email: Option<bool>,
+    pub suspended: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ActivityRisk {
+    pub posts_sampled: usize,
+    pub comments_sampled: usize,
+    pub items_last_day: usize,
+    pub items_per_day_last_30: f64,
+    pub share_in_subreddit: f64,
+    pub distinct_subreddits: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_active_utc: Option<f64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModActionSummary {
+    pub action: String,
+    pub moderator: String,
+    pub created_utc: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModNoteSummary {
+    pub created_at: i64,
+    pub moderator: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub note: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModerationHistory {
+    pub mod_log_available: bool,
+    pub mod_log_entries_scanned: usize,
+    pub removals: usize,
+    pub spam_removals: usize,
+    pub bans: usize,
+    pub approvals: usize,
+    pub recent_actions: Vec<ModActionSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_ban: Option<BannedUser>,
+    pub notes_available: bool,
+    pub notes: Vec<ModNoteSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserRiskSummary {
+    pub username: String,
+    pub subreddit: String,
+    pub risk_level: RiskLevel,
+    pub signals: Vec<String>,
+    pub account: AccountRisk,
+    pub activity: ActivityRisk,
+    pub moderation: ModerationHistory,
+}
+
+/// A post or comment waiting in the mod queue.
+#[derive(Debug, Deserialize)]
+pub struct ModqueueItem {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub num_reports: Option<i64>,
+    #[serde(default)]
+    pub created_utc: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct ModqueueEntry {
+    pub fullname: String,
+    pub author: String,
+    pub permalink: String,
+    pub excerpt: String,
+    pub reports: i64,
+    pub created_utc: f64,
+}
+
+/// The mod queue as seen by the previous modqueue_changes call.
+#[derive(Debug, Deserialize, serde::Serialize)]
+pub struct ModqueueSnapshot {
+    pub checked_utc: i64,
+    pub items: Vec<ModqueueEntry>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModqueueChangesResponse {
+    pub subreddit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_check_utc: Option<i64>,
+    pub queue_size: usize,
+    pub added: Vec<ModqueueEntry>,
+    pub resolved: Vec<ModqueueEntry>,
+    pub unchanged: usize,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModmailConversationsRequest {
+    pub entity: String,
+    pub state: &'static str,
+    pub sort: &'static str,
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModmailConversationList {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub conversations: HashMap<String, ModmailConversationSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailConversationSummary {
+    pub owner: ModmailOwner,
+    #[serde(default)]
+    pub last_unread: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct SubredditQueueCounts {
+    pub subreddit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribers: Option<i64>,
+    pub modqueue: usize,
+    pub unmoderated: usize,
+    pub unread_modmail: usize,
+    /// Counts stopped at the cap, so the real numbers may be higher.
+    pub capped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct QueueTotals {
+    pub modqueue: usize,
+    pub unmoderated: usize,
+    pub unread_modmail: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModDashboardResponse {
+    pub moderated_subreddits: usize,
+    pub totals: QueueTotals,
+    pub subreddits: Vec<SubredditQueueCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modmail_unread_by_folder: Option<HashMap<String, i64>>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+/// Which new posts a monitor reacts to. Empty lists match everything.
+#[derive(Debug, Clone, Default, Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct PostFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flairs: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+    /// `link` or `self`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MonitorRule {
+    /// Reply to matching posts with a distinguished, stickied comment.
+    StickyComment {
+        filter: PostFilter,
+        template: String,
+    },
+    /// Alert when hourly traffic or new-post velocity spikes above its recent baseline.
+    TrafficAnomaly {
+        threshold_sigma: f64,
+        /// User to send a private message to for every spike.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        notify_user: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct MonitorEvent {
+    pub checked_utc: i64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub post: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<AnomalyStat>,
+    /// `commented`, `would_comment`, `notified`, `would_notify` or `failed`.
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// One hour of a metric compared with the hours before it.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct AnomalyStat {
+    /// `pageviews`, `uniques` or `new_posts`.
+    pub metric: String,
+    pub hour_utc: i64,
+    pub value: f64,
+    pub baseline_mean: f64,
+    pub baseline_std_dev: f64,
+    pub baseline_hours: usize,
+    /// Standard deviations above the baseline mean.
+    pub sigma: f64,
+    pub spike: bool,
+}
+
+/// A background rule polled by the server, persisted in the monitors namespace.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct Monitor {
+    pub name: String,
+    /// Name of the account the monitor checks and acts as.
+    #[serde(default = "default_account")]
+    pub account: String,
+    pub subreddit: String,
+    pub created_utc: i64,
+    pub dry_run: bool,
+    pub rule: MonitorRule,
+    /// Creation time of the newest post already considered; for traffic
+    /// anomaly monitors the start of the newest post velocity hour checked.
+    pub watermark_utc: f64,
+    /// Start of the newest traffic hour already checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic_watermark_utc: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked_utc: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub events: Vec<MonitorEvent>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MonitorSummary {
+    pub name: String,
+    pub account: String,
+    pub subreddit: String,
+    pub kind: &'static str,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checked_utc: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub events: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MonitorPreviewItem {
+    pub post: String,
+    pub title: String,
+    pub permalink: String,
+    pub comment: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MonitorPreviewResponse {
+    pub name: String,
+    pub subreddit: String,
+    pub posts_checked: usize,
+    pub matches: Vec<MonitorPreviewItem>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ComposeRequest {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+}
+
+/// Where a comment or message drafted through the tools goes.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplyTarget {
+    /// A reply to the post or comment with this fullname.
+    Comment {
+        parent: String,
+        /// Post it even when the account already left a near-identical
+        /// comment on the same post.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        allow_duplicate: bool,
+    },
+    Message {
+        to: String,
+        subject: String,
+    },
+    Modmail {
+        conversation_id: String,
+        as_subreddit: bool,
+        internal: bool,
+    },
+}
+
+/// A comment or message waiting in the approval queue.
+#[derive(Debug, Deserialize, serde::Serialize)]
+pub struct PendingReply {
+    pub id: String,
+    #[serde(flatten)]
+    pub target: ReplyTarget,
+    pub body: String,
+    /// Account it was drafted as, and is sent as.
+    pub account: String,
+    pub drafted_utc: i64,
+    /// Why the last approve_reply could not send it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReplyQueuedResponse {
+    pub queued: bool,
+    #[serde(flatten)]
+    pub reply: PendingReply,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReplySentResponse {
+    /// The pending reply that was approved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub target: ReplyTarget,
+    /// Fullname of the new comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReplyRejectedResponse {
+    pub id: String,
+    pub rejected: bool,
+    #[serde(flatten)]
+    pub target: ReplyTarget,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PendingRepliesResponse {
+    pub require_approval: bool,
+    pub replies: Vec<PendingReply>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlairEnforcementStatus {
+    InGracePeriod,
+    WouldAction,
+    Actioned,
+    Failed,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FlairEnforcementItem {
+    pub fullname: String,
+    pub title: String,
+    pub author: String,
+    pub permalink: String,
+    pub age_minutes: i64,
+    pub status: FlairEnforcementStatus,
+    pub messaged: bool,
+    pub removed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FlairEnforcementResponse {
+    pub subreddit: String,
+    pub flair_required: bool,
+    pub dry_run: bool,
+    pub posts_scanned: usize,
+    pub missing_flair: usize,
+    pub items: Vec<FlairEnforcementItem>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BanListRow {
+    pub username: String,
+    pub banned_utc: i64,
+    pub banned_at: String,
+    pub permanent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_left: Option<i64>,
+    pub note: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BanListExport {
+    pub job_id: String,
+    pub subreddit: String,
+    pub format: &'static str,
+    pub scanned: usize,
+    pub matched: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bans: Option<Vec<BanListRow>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv: Option<String>,
+    /// Usernames are pseudonyms and notes have no emails or phone numbers.
+    pub scrubbed: bool,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModeratorActivity {
+    pub moderator: String,
+    pub total: usize,
+    /// Share of all counted actions, 0-1.
+    pub share: f64,
+    pub actions: BTreeMap<String, usize>,
+    pub last_action_utc: f64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ModTeamActivityResponse {
+    pub subreddit: String,
+    pub period_start_utc: i64,
+    pub period_end_utc: i64,
+    pub actions_counted: usize,
+    pub totals_by_action: BTreeMap<String, usize>,
+    pub moderators: Vec<ModeratorActivity>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+/// A removed post or comment as listed in the spam queue.
+#[derive(Debug, Deserialize)]
+pub struct SpamQueueItem {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub permalink: String,
+    /// The remover's name, or `true` when Reddit's spam filter removed the item.
+    #[serde(default)]
+    pub banned_by: Option<serde_json::Value>,
+    #[serde(default)]
+    pub banned_at_utc: Option<f64>,
+    #[serde(default)]
+    pub mod_reason_title: Option<String>,
+    pub created_utc: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalSource {
+    ModLog,
+    SpamQueue,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RemovedItem {
+    pub fullname: String,
+    pub kind: &'static str,
+    pub author: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub excerpt: String,
+    pub permalink: String,
+    pub removed_by: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removal_reason: Option<String>,
+    pub spam: bool,
+    pub removed_utc: f64,
+    /// False once the item was approved again or deleted by its author.
+    pub still_removed: bool,
+    pub source: RemovalSource,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RecentRemovalsResponse {
+    pub subreddit: String,
+    pub mod_log_entries_scanned: usize,
+    pub spam_queue_available: bool,
+    pub items: Vec<RemovedItem>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RestoreOutcome {
+    pub fullname: String,
+    pub restored: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RestoreResponse {
+    pub restored: usize,
+    pub failed: usize,
+    pub items: Vec<RestoreOutcome>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AutomodCheck {
+    pub condition: String,
+    pub matched: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AutomodRuleResult {
+    /// Position of the rule in the YAML, starting at 1.
+    pub rule: usize,
+    /// Whether every evaluated condition matched.
+    pub matched: bool,
+    /// False when some conditions could not be evaluated, see `skipped`.
+    pub conclusive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_reason: Option<String>,
+    pub checks: Vec<AutomodCheck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AutomodTestResponse {
+    pub kind: &'static str,
+    pub rules_tested: usize,
+    pub rules_matched: usize,
+    pub results: Vec<AutomodRuleResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WikiPage {
+    pub content_md: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ConfigBackupSchedule {
+    pub subreddit: String,
+    /// Name of the account the backups are taken as.
+    #[serde(default = "default_account")]
+    pub account: String,
+    pub interval_hours: u32,
+    pub created_utc: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_backup_utc: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// The configuration of a subreddit at one point in time. Sections are kept as
+/// Reddit returned them so they can be restored field by field.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct ConfigSnapshot {
+    pub subreddit: String,
+    pub taken_utc: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub automoderator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_flair_templates: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_flair_templates: Option<serde_json::Value>,
+    /// Sections that could not be read, with the reason.
+    #[serde(default)]
+    pub unavailable: BTreeMap<String, String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ConfigBackupSummary {
+    pub taken_utc: i64,
+    pub taken_at: String,
+    pub sections: Vec<&'static str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub unavailable: BTreeMap<String, String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ConfigBackupsResponse {
+    pub subreddit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ConfigBackupSchedule>,
+    pub backups: Vec<ConfigBackupSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub change: ConfigChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TextDiff {
+    pub added_lines: Vec<String>,
+    pub removed_lines: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ConfigDiffResponse {
+    pub subreddit: String,
+    pub baseline_taken_at: String,
+    pub compared_at: String,
+    pub unchanged: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automoderator: Option<TextDiff>,
+    pub changes: Vec<ConfigChange>,
+    /// Sections missing from the backup or the live configuration, which are not compared.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub not_compared: Vec<String>,
+}
+
+/// Everything `bootstrap_subreddit` applies to a subreddit, in TOML or JSON.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapConfig {
+    /// Subreddit settings by their `about/edit` names, e.g. `title` or `public_description`.
+    #[serde(default)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    pub sidebar: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<BootstrapRule>,
+    #[serde(default)]
+    pub post_flairs: Vec<BootstrapFlair>,
+    #[serde(default)]
+    pub user_flairs: Vec<BootstrapFlair>,
+    #[serde(default)]
+    pub welcome_post: Option<BootstrapWelcomePost>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapRule {
+    pub short_name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub violation_reason: Option<String>,
+    /// `link`, `comment` or `all` (default).
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapFlair {
+    pub text: String,
+    #[serde(default)]
+    pub css_class: String,
+    #[serde(default)]
+    pub background_color: String,
+    /// `dark` or `light`.
+    #[serde(default)]
+    pub text_color: Option<String>,
+    #[serde(default)]
+    pub mod_only: bool,
+    #[serde(default)]
+    pub text_editable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapWelcomePost {
+    pub title: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct AddRuleRequest {
+    pub r: String,
+    pub short_name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violation_reason: Option<String>,
+    pub kind: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FlairTemplateRequest {
+    pub flair_type: &'static str,
+    pub text: String,
+    pub css_class: String,
+    pub background_color: String,
+    pub text_color: String,
+    pub mod_only: bool,
+    pub text_editable: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct StickyRequest {
+    pub id: String,
+    pub state: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStepStatus {
+    Planned,
+    Done,
+    Failed,
+    /// Not attempted because a step it depends on failed.
+    Skipped,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BootstrapStep {
+    pub step: String,
+    pub status: BootstrapStepStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct BootstrapResponse {
+    pub subreddit: String,
+    pub dry_run: bool,
+    pub completed: usize,
+    pub failed: usize,
+    pub steps: Vec<BootstrapStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub welcome_post: Option<String>,
+}
+
+/// Hourly entries are `[timestamp, uniques, pageviews]`, newest first.
+#[derive(Debug, Deserialize)]
+pub struct TrafficResponse {
+    #[serde(default)]
+    pub hour: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TrafficPreviewResponse {
+    pub name: String,
+    pub subreddit: String,
+    pub threshold_sigma: f64,
+    pub stats: Vec<AnomalyStat>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
+
+/// Users who asked not to be messaged by notify_thread_participants, keyed
+/// by lowercase name.
+#[derive(Debug, Default, Deserialize, serde::Serialize)]
+pub struct NotificationOptOuts {
+    #[serde(default)]
+    pub users: BTreeMap<String, OptOut>,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct OptOut {
+    pub username: String,
+    pub added_utc: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// When each user, by lowercase name, was last notified.
+#[derive(Debug, Default, Deserialize, serde::Serialize)]
+pub struct NotifiedUsers {
+    #[serde(default)]
+    pub last_notified_utc: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ParticipantNotice {
+    pub username: String,
+    /// sent, queued, would_send, failed, opted_out, notified_recently,
+    /// over_cap or not_reached.
+    pub status: &'static str,
+    /// Id of the queued message, for approve_reply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ThreadNotificationResponse {
+    pub post_id: String,
+    pub title: String,
+    pub dry_run: bool,
+    pub require_approval: bool,
+    pub participants: usize,
+    pub notified: usize,
+    pub recipients: Vec<ParticipantNotice>,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated_reason: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct OptOutsResponse {
+    pub opt_outs: Vec<OptOut>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct OptOutChangedResponse {
+    pub username: String,
+    pub opted_out: bool,
+    /// False when the list already was that way.
+    pub changed: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PollOptionTally {
+    /// Position in the option list; absent for free text answers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<usize>,
+    pub option: String,
+    pub votes: usize,
+    /// Share of all counted responses, 0-1.
+    pub share: f64,
+    pub voters: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct UnparsedPollAnswer {
+    pub comment_id: String,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CommentPollResponse {
+    pub post_id: String,
+    pub title: String,
+    /// `given`, `post` (the numbered list in the post) or `free_text`.
+    pub options_source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub comments_scanned: usize,
+    pub responses: usize,
+    /// Later answers of users whose first answer was already counted.
+    pub duplicates: usize,
+    pub unparsed: usize,
+    pub results: Vec<PollOptionTally>,
+    pub unparsed_samples: Vec<UnparsedPollAnswer>,
+    /// Comments Reddit did not load; their answers are missing from the tally.
+    pub hidden_comments: i64,
+}
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::reddit::error::RedditError;
+use crate::reddit::models::Post;
+
+const MAX_SNAPSHOTS: usize = 32;
+const MAX_FALLBACK_ANCHORS: usize = 5;
+
+/// A multi-page read of one listing that behaves like a consistent snapshot.
+///
+/// Every accepted post is recorded, so posts that slide down onto a later page
+/// while the listing changes are skipped instead of being returned twice, and
+/// pages that were already served are replayed unchanged.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub id: String,
+    pub listing: String,
+    pub pages: Vec<Vec<Post>>,
+    pub exhausted: bool,
+    fullnames: HashSet<String>,
+    last_used: Instant,
+}
+
+impl Snapshot {
+    fn new(listing: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            listing: listing.to_string(),
+            pages: Vec::new(),
+            exhausted: false,
+            fullnames: HashSet::new(),
+            last_used: Instant::now(),
+        }
+    }
+
+    /// Cursors to continue from, newest first. Later entries are used when the
+    /// preferred anchor was deleted and Reddit returns an empty page for it.
+    pub fn anchors(&self) -> Vec<String> {
+        self.pages
+            .iter()
+            .flatten()
+            .rev()
+            .take(MAX_FALLBACK_ANCHORS)
+            .map(|post| post.name.clone())
+            .collect()
+    }
+
+    /// Keeps the candidates that are not part of the snapshot yet.
+    /// Returns the accepted posts and the number of duplicates skipped.
+    pub fn accept(&mut self, candidates: Vec<Post>, room: usize) -> (Vec<Post>, usize) {
+        let mut accepted = Vec::new();
+        let mut duplicates = 0;
+
+        for post in candidates {
+            if accepted.len() == room {
+                break;
+            }
+            if self.fullnames.insert(post.name.clone()) {
+                accepted.push(post);
+            } else {
+                duplicates += 1;
+            }
+        }
+
+        (accepted, duplicates)
+    }
+
+    pub fn record_page(&mut self, posts: Vec<Post>) {
+        self.pages.push(posts);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotPager {
+    snapshots: Arc<Mutex<HashMap<String, Snapshot>>>,
+}
+
+impl SnapshotPager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks a snapshot out for the duration of a page fetch, creating one when `id` is `None`.
+    pub fn checkout(&self, id: Option<&str>, listing: &str) -> Result<Snapshot, RedditError> {
+        let mut snapshots = self.snapshots.lock().expect("snapshot lock poisoned");
+
+        match id {
+            None => Ok(Snapshot::new(listing)),
+            Some(id) => {
+                let snapshot = snapshots.remove(id).ok_or_else(|| {
+                    RedditError::not_found(
+                        format!("Unknown or busy snapshot_id {}", id),
+                        "omit snapshot_id to start a new snapshot; snapshots expire when many others are created",
+                    )
+                })?;
+                if snapshot.listing != listing {
+                    let error = RedditError::invalid_input(
+                        format!(
+                            "snapshot_id {} belongs to {}, not {}",
+                            id, snapshot.listing, listing
+                        ),
+                        "use the same subreddit and sort for every page of a snapshot",
+                    );
+                    snapshots.insert(id.to_string(), snapshot);
+                    return Err(error);
+                }
+                Ok(snapshot)
+            }
+        }
+    }
+
+    pub fn checkin(&self, mut snapshot: Snapshot) {
+        let mut snapshots = self.snapshots.lock().expect("snapshot lock poisoned");
+        snapshot.last_used = Instant::now();
+
+        if snapshots.len() >= MAX_SNAPSHOTS {
+            let oldest = snapshots
+                .values()
+                .min_by_key(|snapshot| snapshot.last_used)
+                .map(|snapshot| snapshot.id.clone());
+            if let Some(oldest) = oldest {
+                snapshots.remove(&oldest);
+            }
+        }
+        snapshots.insert(snapshot.id.clone(), snapshot);
+    }
+}
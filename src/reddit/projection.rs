@@ -0,0 +1,107 @@
+use rmcp::model::{CallToolRequestParam, CallToolResult, RawContent, Tool};
+use serde_json::Value;
+
+use crate::reddit::error::RedditError;
+use crate::reddit::quota::ToolCategory;
+
+/// Adds the optional `fields` parameter to read tools.
+pub fn with_fields_parameter(mut tool: Tool) -> Tool {
+    if ToolCategory::of(&tool.name) != ToolCategory::Read {
+        return tool;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(properties) = properties.as_object_mut() {
+        properties.insert(
+            "fields".to_string(),
+            serde_json::json!({
+                "type": ["string", "null"],
+                "description": "Comma-separated fields to return for each item, e.g. title,score,permalink (default: all fields)",
+            }),
+        );
+    }
+    tool.input_schema = schema.into();
+    tool
+}
+
+/// Takes the `fields` argument out of a tool call. Empty when it is missing.
+pub fn take_fields(request: &mut CallToolRequestParam) -> Result<Vec<String>, RedditError> {
+    let argument = request
+        .arguments
+        .as_mut()
+        .and_then(|arguments| arguments.remove("fields"));
+    let fields = match argument {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::String(fields)) => fields.split(',').map(str::to_string).collect(),
+        Some(Value::Array(fields)) => fields
+            .into_iter()
+            .map(|field| match field {
+                Value::String(field) => Ok(field),
+                other => Err(other),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|other| {
+                RedditError::invalid_input(
+                    format!("fields must contain field names, got {}", other),
+                    "pass fields as a comma-separated string, e.g. title,score,permalink",
+                )
+            })?,
+        Some(other) => {
+            return Err(RedditError::invalid_input(
+                format!("fields must be a string, got {}", other),
+                "pass fields as a comma-separated string, e.g. title,score,permalink",
+            ));
+        }
+    };
+    Ok(fields
+        .iter()
+        .map(|field| field.trim().to_string())
+        .filter(|field| !field.is_empty())
+        .collect())
+}
+
+/// Keeps only `fields` of the items in `value`. Items are the objects holding
+/// at least one of the fields; the objects around them, such as listing
+/// envelopes with their cursors, keep their other members.
+pub fn project(value: Value, fields: &[String]) -> Value {
+    match value {
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| project(item, fields))
+            .collect(),
+        Value::Object(object) if fields.iter().any(|field| object.contains_key(field)) => object
+            .into_iter()
+            .filter(|(key, _)| fields.contains(key))
+            .collect(),
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| (key, project(value, fields)))
+            .collect(),
+        other => other,
+    }
+}
+
+/// Rewrites the JSON text of a successful tool result with `map`.
+pub fn map_json_result(mut result: CallToolResult, map: impl Fn(Value) -> Value) -> CallToolResult {
+    if result.is_error == Some(true) {
+        return result;
+    }
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw
+            && let Ok(value) = serde_json::from_str::<Value>(&text.text)
+        {
+            text.text = map(value).to_string();
+        }
+    }
+    result
+}
+
+/// Projects the JSON text of a successful tool result.
+pub fn project_result(result: CallToolResult, fields: &[String]) -> CallToolResult {
+    if fields.is_empty() {
+        return result;
+    }
+    map_json_result(result, |value| project(value, fields))
+}
@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::config::QuotaConfig;
+
+/// Tools that change something on Reddit. Their dry runs count as well.
+const WRITE_TOOLS: [&str; 21] = [
+    "add_post_to_collection",
+    "approve_reply",
+    "bootstrap_subreddit",
+    "cleanup_account",
+    "create_collection",
+    "create_sticky_comment_monitor",
+    "create_traffic_anomaly_monitor",
+    "enforce_post_flair",
+    "notify_thread_participants",
+    "post_comment",
+    "remove_post_from_collection",
+    "restore_removed_items",
+    "resume_job",
+    "revoke_token",
+    "run_history_deletion",
+    "run_macro",
+    "send_message",
+    "send_modmail_reply",
+    "submit_post",
+    "submit_title_variant",
+    "submit_to_multiple",
+];
+
+/// Tools that call Reddit but change nothing there.
+const READ_TOOLS: [&str; 59] = [
+    "authorize_account",
+    "backup_subreddit_config",
+    "check_post_against_rules",
+    "check_username",
+    "compare_comment_sorts",
+    "compare_subreddits",
+    "diff_config",
+    "expand_more_comments",
+    "export_ban_list",
+    "extract_thread_mentions",
+    "find_flashpoints",
+    "find_rising_posts",
+    "find_similar_subreddits",
+    "get_access_token",
+    "get_account_hygiene_report",
+    "get_best_posting_times",
+    "get_collection",
+    "get_comment_context",
+    "get_comments",
+    "get_context_bundle",
+    "get_experiment_results",
+    "get_home_feed",
+    "get_inbox",
+    "get_mod_dashboard",
+    "get_mod_team_activity",
+    "get_modmail_context",
+    "get_most_awarded",
+    "get_new_posts",
+    "get_post",
+    "get_posts_page",
+    "get_predictions",
+    "get_site_feed",
+    "get_sticky_posts",
+    "get_subreddit_about",
+    "get_subreddit_posts",
+    "get_subreddit_rules",
+    "get_subreddit_sidebar",
+    "get_thread_highlights",
+    "get_topic_timeline",
+    "get_user_about",
+    "get_user_comments",
+    "get_user_comments_with_context",
+    "get_user_overview",
+    "get_user_posts",
+    "get_user_risk_summary",
+    "list_recent_removals",
+    "list_subreddit_awards",
+    "list_subreddit_collections",
+    "list_subreddit_emojis",
+    "modqueue_changes",
+    "preview_history_deletion",
+    "preview_monitor",
+    "run_saved_search",
+    "schedule_config_backup",
+    "search_posts_between",
+    "search_subreddit_names",
+    "suggest_removal_reasons",
+    "tally_comment_poll",
+    "whoami",
+];
+
+/// Tools that only touch the local state and never call Reddit.
+const LOCAL_TOOLS: [&str; 24] = [
+    "add_notification_opt_out",
+    "cancel_job",
+    "check_posting_cooldown",
+    "delete_monitor",
+    "delete_saved_search",
+    "get_deletion_progress",
+    "get_job_status",
+    "get_monitor",
+    "get_quota_usage",
+    "get_server_capabilities",
+    "list_accounts",
+    "list_config_backups",
+    "list_jobs",
+    "list_macros",
+    "list_monitors",
+    "list_notification_opt_outs",
+    "list_pending_replies",
+    "list_saved_searches",
+    "pause_job",
+    "reject_reply",
+    "remove_notification_opt_out",
+    "save_search",
+    "state_info",
+    "test_automod_rule",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCategory {
+    Read,
+    Write,
+    /// Not counted against any quota.
+    Local,
+}
+
+/// Every tool named in the classification lists.
+#[cfg(test)]
+pub fn classified_tools() -> impl Iterator<Item = &'static str> {
+    WRITE_TOOLS.into_iter().chain(READ_TOOLS).chain(LOCAL_TOOLS)
+}
+
+impl ToolCategory {
+    /// The category `tool` is listed in, if any.
+    pub fn classify(tool: &str) -> Option<Self> {
+        if WRITE_TOOLS.contains(&tool) {
+            Some(ToolCategory::Write)
+        } else if READ_TOOLS.contains(&tool) {
+            Some(ToolCategory::Read)
+        } else if LOCAL_TOOLS.contains(&tool) {
+            Some(ToolCategory::Local)
+        } else {
+            None
+        }
+    }
+
+    /// The category of `tool`. A tool missing from the lists counts as a write,
+    /// so that it is never let through as read-only.
+    pub fn of(tool: &str) -> Self {
+        Self::classify(tool).unwrap_or(ToolCategory::Write)
+    }
+
+    fn limit(self, config: &QuotaConfig) -> Option<u32> {
+        match self {
+            ToolCategory::Read => config.read,
+            ToolCategory::Write => config.write,
+            ToolCategory::Local => None,
+        }
+    }
+}
+
+/// Usage of one category within the current window.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CategoryUsage {
+    pub category: ToolCategory,
+    pub used: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u32>,
+    /// Seconds until the oldest counted call leaves the window.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_slot_in_secs: Option<u64>,
+}
+
+/// Sliding-window counters of the tool calls made since the server started.
+#[derive(Debug, Clone, Default)]
+pub struct QuotaTracker {
+    calls: Arc<Mutex<[VecDeque<Instant>; 2]>>,
+}
+
+impl QuotaTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn index(category: ToolCategory) -> Option<usize> {
+        match category {
+            ToolCategory::Read => Some(0),
+            ToolCategory::Write => Some(1),
+            ToolCategory::Local => None,
+        }
+    }
+
+    /// Counts a call of `tool`, or returns the usage of its category when the
+    /// quota is used up.
+    pub fn try_acquire(&self, tool: &str, config: &QuotaConfig) -> Result<(), CategoryUsage> {
+        let category = ToolCategory::of(tool);
+        let Some(index) = Self::index(category) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let mut calls = self.calls.lock().expect("quota lock poisoned");
+        let window = &mut calls[index];
+        Self::expire(window, now, config.window());
+
+        if let Some(limit) = category.limit(config)
+            && window.len() >= limit as usize
+        {
+            return Err(Self::usage_of(category, window, now, config));
+        }
+        window.push_back(now);
+        Ok(())
+    }
+
+    pub fn usage(&self, config: &QuotaConfig) -> Vec<CategoryUsage> {
+        let now = Instant::now();
+        let mut calls = self.calls.lock().expect("quota lock poisoned");
+        [ToolCategory::Read, ToolCategory::Write]
+            .into_iter()
+            .filter_map(|category| {
+                let window = &mut calls[Self::index(category)?];
+                Self::expire(window, now, config.window());
+                Some(Self::usage_of(category, window, now, config))
+            })
+            .collect()
+    }
+
+    fn expire(window: &mut VecDeque<Instant>, now: Instant, length: Duration) {
+        while window
+            .front()
+            .is_some_and(|call| now.duration_since(*call) >= length)
+        {
+            window.pop_front();
+        }
+    }
+
+    fn usage_of(
+        category: ToolCategory,
+        window: &VecDeque<Instant>,
+        now: Instant,
+        config: &QuotaConfig,
+    ) -> CategoryUsage {
+        let limit = category.limit(config);
+        CategoryUsage {
+            category,
+            used: window.len(),
+            limit,
+            remaining: limit.map(|limit| limit.saturating_sub(window.len() as u32)),
+            next_slot_in_secs: window.front().map(|oldest| {
+                config
+                    .window()
+                    .saturating_sub(now.duration_since(*oldest))
+                    .as_secs()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn every_tool_is_listed_once() {
+        let mut seen = HashSet::new();
+        for tool in classified_tools() {
+            assert!(seen.insert(tool), "{} is classified twice", tool);
+        }
+    }
+
+    #[test]
+    fn lists_are_sorted() {
+        for list in [&WRITE_TOOLS[..], &READ_TOOLS, &LOCAL_TOOLS] {
+            assert!(list.is_sorted(), "{:?} is not sorted", list);
+        }
+    }
+
+    #[test]
+    fn unlisted_tools_count_as_writes() {
+        assert_eq!(ToolCategory::classify("no_such_tool"), None);
+        assert_eq!(ToolCategory::of("no_such_tool"), ToolCategory::Write);
+        assert_eq!(ToolCategory::of("get_post"), ToolCategory::Read);
+        assert_eq!(ToolCategory::of("state_info"), ToolCategory::Local);
+    }
+
+    #[test]
+    fn quota_blocks_the_category_only() {
+        let config = QuotaConfig {
+            window_minutes: 60,
+            read: Some(1),
+            write: Some(0),
+        };
+        let tracker = QuotaTracker::new();
+        assert!(tracker.try_acquire("get_post", &config).is_ok());
+        assert!(tracker.try_acquire("get_comments", &config).is_err());
+        assert!(tracker.try_acquire("resume_job", &config).is_err());
+        assert!(tracker.try_acquire("state_info", &config).is_ok());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+
+use crate::reddit::quota::ToolCategory;
+
+/// Tools that work with any token, or without one.
+const UNSCOPED_TOOLS: [&str; 4] = [
+    "authorize_account",
+    "get_access_token",
+    "list_accounts",
+    "revoke_token",
+];
+
+/// OAuth scopes of the tools that need more than `read`.
+const TOOL_SCOPES: [(&str, &[&str]); 32] = [
+    ("add_post_to_collection", &["modposts"]),
+    ("backup_subreddit_config", &["modconfig"]),
+    (
+        "bootstrap_subreddit",
+        &["modconfig", "modflair", "modposts", "submit"],
+    ),
+    ("cleanup_account", &["history", "edit", "subscribe"]),
+    ("create_collection", &["modposts"]),
+    ("create_sticky_comment_monitor", &["modposts", "submit"]),
+    ("create_traffic_anomaly_monitor", &["modconfig"]),
+    ("diff_config", &["modconfig"]),
+    ("enforce_post_flair", &["modposts", "privatemessages"]),
+    ("get_account_hygiene_report", &["history", "mysubreddits"]),
+    ("get_inbox", &["privatemessages"]),
+    ("get_mod_dashboard", &["mysubreddits", "modmail"]),
+    ("get_mod_team_activity", &["modlog"]),
+    ("get_modmail_context", &["modmail"]),
+    ("get_user_comments_with_context", &["history"]),
+    ("get_user_risk_summary", &["history"]),
+    ("list_recent_removals", &["modlog"]),
+    ("notify_thread_participants", &["privatemessages"]),
+    ("post_comment", &["submit"]),
+    ("preview_history_deletion", &["history"]),
+    ("remove_post_from_collection", &["modposts"]),
+    ("restore_removed_items", &["modlog", "modposts"]),
+    ("run_history_deletion", &["history", "edit"]),
+    ("run_macro", &["modposts", "submit"]),
+    ("schedule_config_backup", &["modconfig"]),
+    ("send_message", &["privatemessages"]),
+    ("send_modmail_reply", &["modmail"]),
+    ("submit_post", &["submit"]),
+    ("submit_title_variant", &["submit"]),
+    ("submit_to_multiple", &["submit"]),
+    ("suggest_removal_reasons", &["modposts"]),
+    ("whoami", &["identity"]),
+];
+
+/// The OAuth scopes `tool` needs. Tools that only touch the local state need none.
+pub fn required_scopes(tool: &str) -> &'static [&'static str] {
+    if UNSCOPED_TOOLS.contains(&tool) || ToolCategory::of(tool) == ToolCategory::Local {
+        return &[];
+    }
+    TOOL_SCOPES
+        .iter()
+        .find(|(name, _)| *name == tool)
+        .map_or(&["read"], |(_, scopes)| scopes)
+}
+
+/// The scopes a token was granted, from the `scope` of the token response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrantedScopes {
+    /// `*`, as granted to script apps signed in with a password.
+    All,
+    Only(BTreeSet<String>),
+}
+
+impl GrantedScopes {
+    pub fn parse(scope: &str) -> Self {
+        let scopes: BTreeSet<String> = scope
+            .split([' ', ','])
+            .filter(|scope| !scope.is_empty())
+            .map(str::to_string)
+            .collect();
+        if scopes.is_empty() || scopes.contains("*") {
+            GrantedScopes::All
+        } else {
+            GrantedScopes::Only(scopes)
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            GrantedScopes::All => vec!["*".to_string()],
+            GrantedScopes::Only(scopes) => scopes.iter().cloned().collect(),
+        }
+    }
+
+    /// The scopes `tool` needs that were not granted.
+    pub fn missing(&self, tool: &str) -> Vec<&'static str> {
+        match self {
+            GrantedScopes::All => Vec::new(),
+            GrantedScopes::Only(scopes) => required_scopes(tool)
+                .iter()
+                .copied()
+                .filter(|scope| !scopes.contains(*scope))
+                .collect(),
+        }
+    }
+
+    /// Every scope some tool needs that was not granted.
+    pub fn missing_for_any_tool(&self) -> BTreeSet<&'static str> {
+        let GrantedScopes::Only(scopes) = self else {
+            return BTreeSet::new();
+        };
+        TOOL_SCOPES
+            .iter()
+            .flat_map(|(_, needed)| needed.iter().copied())
+            .chain(["read"])
+            .filter(|scope| !scopes.contains(*scope))
+            .collect()
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use ring::hmac;
+
+/// Keys pseudonyms when `[exports] pseudonym_key` is not set. Anyone can then
+/// recompute the pseudonym of a known username.
+const DEFAULT_PSEUDONYM_KEY: &str = "reddit-mcp";
+/// Hex digits of the keyed hash kept in a pseudonym.
+const PSEUDONYM_HEX_CHARS: usize = 12;
+const MIN_PHONE_DIGITS: usize = 9;
+const MAX_PHONE_DIGITS: usize = 15;
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+        .expect("valid email regex")
+});
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\+?\d[\d \t().-]{6,}\d").expect("valid phone regex"));
+static USER_MENTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w/])/?[uU]/([A-Za-z0-9_-]{2,21})").expect("valid user mention regex")
+});
+
+/// Removes personal data from exported datasets: usernames become stable
+/// pseudonyms, emails and phone numbers are replaced with placeholders.
+pub struct Scrubber {
+    key: hmac::Key,
+}
+
+impl Scrubber {
+    pub fn new(pseudonym_key: Option<&str>) -> Self {
+        let secret = pseudonym_key.unwrap_or(DEFAULT_PSEUDONYM_KEY);
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+        }
+    }
+
+    /// The same pseudonym for the same username and key, regardless of case.
+    pub fn pseudonym(&self, username: &str) -> String {
+        let tag = hmac::sign(&self.key, username.to_ascii_lowercase().as_bytes());
+        let hex: String = tag
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("user_{}", &hex[..PSEUDONYM_HEX_CHARS])
+    }
+
+    /// Replaces emails, phone numbers and u/ mentions in free text.
+    pub fn scrub_text(&self, text: &str) -> String {
+        let text = EMAIL.replace_all(text, "[email]");
+        let text = PHONE.replace_all(&text, |captures: &regex::Captures| {
+            let number = &captures[0];
+            let digits = number.chars().filter(char::is_ascii_digit).count();
+            if (MIN_PHONE_DIGITS..=MAX_PHONE_DIGITS).contains(&digits) {
+                "[phone]".to_string()
+            } else {
+                number.to_string()
+            }
+        });
+        USER_MENTION
+            .replace_all(&text, |captures: &regex::Captures| {
+                let mention = &captures[0];
+                let username = &captures[1];
+                format!(
+                    "{}{}",
+                    &mention[..mention.len() - username.len()],
+                    self.pseudonym(username)
+                )
+            })
+            .into_owned()
+    }
+}
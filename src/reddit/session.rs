@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Anything Reddit identifies with a fullname (`t3_abc123`, `t1_def456`, ...).
+pub trait Fullname {
+    fn fullname(&self) -> &str;
+}
+
+/// Fullnames returned to the MCP client during the current session. Every
+/// session is served by its own `RedditClient::for_session` clone, which
+/// starts with an empty set.
+#[derive(Debug, Clone, Default)]
+pub struct SeenItems {
+    fullnames: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SeenItems {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops items that were already returned earlier in the session.
+    /// Returns the remaining items and how many were filtered out.
+    pub fn exclude_seen<T: Fullname>(&self, items: Vec<T>) -> (Vec<T>, usize) {
+        let fullnames = self.fullnames.lock().expect("seen items lock poisoned");
+        let before = items.len();
+        let unseen: Vec<T> = items
+            .into_iter()
+            .filter(|item| !fullnames.contains(item.fullname()))
+            .collect();
+        let excluded = before - unseen.len();
+        (unseen, excluded)
+    }
+
+    pub fn mark_seen<T: Fullname>(&self, items: &[T]) {
+        let mut fullnames = self.fullnames.lock().expect("seen items lock poisoned");
+        fullnames.extend(items.iter().map(|item| item.fullname().to_string()));
+    }
+}
@@ -0,0 +1,201 @@
+use rmcp::model::{CallToolRequestParam, CallToolResult, RawContent, Tool};
+use serde_json::{Map, Value};
+
+use crate::reddit::error::RedditError;
+use crate::reddit::quota::ToolCategory;
+
+/// Longest cell before it is cut with an ellipsis.
+const MAX_CELL_CHARS: usize = 80;
+
+/// How a read tool's result is rendered, from its `output_format` argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Markdown,
+    /// Columns aligned with spaces.
+    Text,
+}
+
+/// Adds the optional `output_format` parameter to read tools.
+pub fn with_format_parameter(mut tool: Tool) -> Tool {
+    if ToolCategory::of(&tool.name) != ToolCategory::Read {
+        return tool;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(properties) = properties.as_object_mut() {
+        properties.insert(
+            "output_format".to_string(),
+            serde_json::json!({
+                "type": ["string", "null"],
+                "enum": ["json", "markdown", "text", null],
+                "description": "json (default), or markdown/text to render the returned items as a table whose columns are the fields argument",
+            }),
+        );
+    }
+    tool.input_schema = schema.into();
+    tool
+}
+
+/// Takes the `output_format` argument out of a tool call.
+pub fn take_format(request: &mut CallToolRequestParam) -> Result<OutputFormat, RedditError> {
+    let argument = request
+        .arguments
+        .as_mut()
+        .and_then(|arguments| arguments.remove("output_format"));
+    match argument {
+        None | Some(Value::Null) => Ok(OutputFormat::Json),
+        Some(Value::String(format)) if format == "json" => Ok(OutputFormat::Json),
+        Some(Value::String(format)) if format == "markdown" => Ok(OutputFormat::Markdown),
+        Some(Value::String(format)) if format == "text" => Ok(OutputFormat::Text),
+        Some(other) => Err(RedditError::invalid_input(
+            format!("Unknown output_format {}", other),
+            "set output_format to json, markdown or text",
+        )),
+    }
+}
+
+fn cell(value: Option<&Value>) -> String {
+    let text = match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    };
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > MAX_CELL_CHARS {
+        let cut: String = text.chars().take(MAX_CELL_CHARS - 1).collect();
+        format!("{}…", cut)
+    } else {
+        text
+    }
+}
+
+type Row<'a> = &'a Map<String, Value>;
+/// Plain members shown above the table, e.g. the cursor of a listing.
+type Summary<'a> = Vec<(&'a str, &'a Value)>;
+
+fn objects(items: &[Value]) -> Option<Vec<Row<'_>>> {
+    items.iter().map(Value::as_object).collect()
+}
+
+/// The items of a result: the result itself when it is a list, otherwise its
+/// longest list of objects, with the other plain members as a summary.
+fn rows(value: &Value) -> Option<(Vec<Row<'_>>, Summary<'_>)> {
+    match value {
+        Value::Array(items) => Some((objects(items)?, Vec::new())),
+        Value::Object(object) => {
+            let (_, items) = object
+                .iter()
+                .filter_map(|(key, value)| Some((key, objects(value.as_array()?)?)))
+                .filter(|(_, items)| !items.is_empty())
+                .max_by_key(|(_, items)| items.len())?;
+            let summary = object
+                .iter()
+                .filter(|(_, value)| !value.is_array() && !value.is_object() && !value.is_null())
+                .map(|(key, value)| (key.as_str(), value))
+                .collect();
+            Some((items, summary))
+        }
+        _ => None,
+    }
+}
+
+/// Renders `value` as a table, or `None` when it holds no list of items.
+fn render_table(value: &Value, format: OutputFormat, columns: &[String]) -> Option<String> {
+    if format == OutputFormat::Json {
+        return None;
+    }
+    let (items, summary) = rows(value)?;
+    let columns: Vec<String> = if columns.is_empty() {
+        items
+            .first()?
+            .iter()
+            .filter(|(_, value)| !value.is_array() && !value.is_object())
+            .map(|(key, _)| key.clone())
+            .collect()
+    } else {
+        columns.to_vec()
+    };
+    let table: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|column| cell(item.get(column)))
+                .collect()
+        })
+        .collect();
+
+    let mut out = String::new();
+    for (key, value) in summary {
+        out.push_str(&format!("{}: {}\n", key, cell(Some(value))));
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    match format {
+        OutputFormat::Json => {}
+        OutputFormat::Markdown => {
+            let escape = |text: &str| text.replace('|', "\\|");
+            let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+            out.push_str(&line(columns.iter().map(|c| escape(c)).collect()));
+            out.push_str(&line(columns.iter().map(|_| "---".to_string()).collect()));
+            for row in &table {
+                out.push_str(&line(row.iter().map(|c| escape(c)).collect()));
+            }
+        }
+        OutputFormat::Text => {
+            let widths: Vec<usize> = columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    table
+                        .iter()
+                        .map(|row| row[index].chars().count())
+                        .chain([column.chars().count()])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+            let line = |cells: &[String]| {
+                let padded: Vec<String> = cells
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| {
+                        let padding = width - cell.chars().count();
+                        format!("{}{}", cell, " ".repeat(padding))
+                    })
+                    .collect();
+                format!("{}\n", padded.join("  ").trim_end())
+            };
+            out.push_str(&line(&columns));
+            for row in &table {
+                out.push_str(&line(row));
+            }
+        }
+    }
+    Some(out)
+}
+
+/// Renders the JSON text of a successful tool result in `format`. Results
+/// without a list of items stay JSON.
+pub fn render_result(
+    mut result: CallToolResult,
+    format: OutputFormat,
+    columns: &[String],
+) -> CallToolResult {
+    if format == OutputFormat::Json || result.is_error == Some(true) {
+        return result;
+    }
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw
+            && let Ok(value) = serde_json::from_str::<Value>(&text.text)
+            && let Some(table) = render_table(&value, format, columns)
+        {
+            text.text = table;
+        }
+    }
+    result
+}
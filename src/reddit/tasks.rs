@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Skips its runs, or for a bulk job, stopped until resume_job.
+    Paused,
+    Cancelled,
+    Finished,
+}
+
+/// A background task or bulk job as shown by list_jobs and get_job_status.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaskStatus {
+    pub id: String,
+    pub kind: &'static str,
+    pub description: String,
+    pub state: TaskState,
+    /// Account a bulk job runs as; background tasks serve every account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    pub started_utc: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_utc: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Items a bulk job has collected or processed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Task {
+    status: TaskStatus,
+    cancel: CancellationToken,
+}
+
+/// Tasks running besides the tool calls, and bulk jobs while a call runs them,
+/// so they can be listed, paused and cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct TaskRegistry {
+    tasks: Arc<Mutex<BTreeMap<String, Task>>>,
+}
+
+/// Removes a bulk job from the registry when the call running it ends.
+pub struct TrackedTask {
+    registry: TaskRegistry,
+    id: String,
+}
+
+impl Drop for TrackedTask {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.id);
+    }
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a running task, replacing an earlier one with the same id.
+    /// The returned token is cancelled by `cancel`.
+    pub fn register(
+        &self,
+        id: &str,
+        kind: &'static str,
+        description: impl Into<String>,
+    ) -> CancellationToken {
+        self.insert(id, kind, description.into(), None)
+    }
+
+    /// Registers a bulk job for as long as the returned guard lives.
+    pub fn track(
+        &self,
+        id: &str,
+        kind: &'static str,
+        description: String,
+        account: &str,
+    ) -> TrackedTask {
+        self.insert(id, kind, description, Some(account.to_string()));
+        TrackedTask {
+            registry: self.clone(),
+            id: id.to_string(),
+        }
+    }
+
+    fn insert(
+        &self,
+        id: &str,
+        kind: &'static str,
+        description: String,
+        account: Option<String>,
+    ) -> CancellationToken {
+        let cancel = CancellationToken::new();
+        let task = Task {
+            status: TaskStatus {
+                id: id.to_string(),
+                kind,
+                description,
+                state: TaskState::Running,
+                account,
+                started_utc: chrono::Utc::now().timestamp(),
+                last_run_utc: None,
+                last_error: None,
+                items: None,
+            },
+            cancel: cancel.clone(),
+        };
+        self.lock().insert(id.to_string(), task);
+        cancel
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Task>> {
+        self.tasks.lock().expect("task registry lock poisoned")
+    }
+
+    pub fn is_paused(&self, id: &str) -> bool {
+        self.lock()
+            .get(id)
+            .is_some_and(|task| task.status.state == TaskState::Paused)
+    }
+
+    /// Records the outcome of one run of a periodic task.
+    pub fn record_run(&self, id: &str, error: Option<String>) {
+        if let Some(task) = self.lock().get_mut(id) {
+            task.status.last_run_utc = Some(chrono::Utc::now().timestamp());
+            task.status.last_error = error;
+        }
+    }
+
+    /// Marks a task that ended by itself as finished.
+    pub fn finish(&self, id: &str) {
+        if let Some(task) = self.lock().get_mut(id)
+            && task.status.state != TaskState::Cancelled
+        {
+            task.status.state = TaskState::Finished;
+        }
+    }
+
+    pub fn list(&self) -> Vec<TaskStatus> {
+        self.lock()
+            .values()
+            .map(|task| task.status.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<TaskStatus> {
+        self.lock().get(id).map(|task| task.status.clone())
+    }
+
+    /// Pauses or resumes a running or paused task and returns its status.
+    /// Cancelled and finished tasks keep their state.
+    pub fn set_paused(&self, id: &str, paused: bool) -> Option<TaskStatus> {
+        let mut tasks = self.lock();
+        let task = tasks.get_mut(id)?;
+        if matches!(task.status.state, TaskState::Running | TaskState::Paused) {
+            task.status.state = if paused {
+                TaskState::Paused
+            } else {
+                TaskState::Running
+            };
+        }
+        Some(task.status.clone())
+    }
+
+    pub fn cancel(&self, id: &str) -> Option<TaskStatus> {
+        let mut tasks = self.lock();
+        let task = tasks.get_mut(id)?;
+        if task.status.state != TaskState::Finished {
+            task.status.state = TaskState::Cancelled;
+            task.cancel.cancel();
+        }
+        Some(task.status.clone())
+    }
+
+    /// The state a bulk job was asked to stop in at its next checkpoint.
+    pub fn stop_requested(&self, id: &str) -> Option<TaskState> {
+        self.lock()
+            .get(id)
+            .map(|task| task.status.state)
+            .filter(|state| matches!(state, TaskState::Paused | TaskState::Cancelled))
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::LazyLock;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::config::EmojiMode;
+use crate::reddit::validation::edit_distance;
+
+/// Only this many characters of each text are compared by `near_duplicate`.
+const NEAR_DUPLICATE_CHARS: usize = 1000;
+/// Stands in for a masked word, so the reader knows something was redacted.
+const MASK_MARKER: &str = "[masked]";
+
+/// Links without text, which old-Reddit stylesheets turn into icons and banners.
+static EMPTY_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\]\([^)]*\)").expect("valid empty link regex"));
+static BLANK_LINES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n{3,}").expect("valid blank lines regex"));
+
+/// Shortens `text` to at most `max_chars` characters, marking the cut with an ellipsis.
+pub fn snippet(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// The part of `text` around the byte range `start..end`, widened by up to
+/// `radius` characters on each side and flattened to a single line.
+pub fn excerpt(text: &str, start: usize, end: usize, radius: usize) -> String {
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .nth(radius.saturating_sub(1))
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    let to = text[end..]
+        .char_indices()
+        .nth(radius)
+        .map(|(index, _)| end + index)
+        .unwrap_or(text.len());
+
+    let mut excerpt = text[from..to]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if from > 0 {
+        excerpt.insert(0, '…');
+    }
+    if to < text.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+/// Whether two texts match after ignoring case, punctuation and whitespace,
+/// allowing edits in up to a tenth of their characters.
+pub fn near_duplicate(a: &str, b: &str) -> bool {
+    let normalize = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .take(NEAR_DUPLICATE_CHARS)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return true;
+    }
+    let (a_len, b_len) = (a.chars().count(), b.chars().count());
+    let longest = a_len.max(b_len);
+    if a_len.abs_diff(b_len) * 10 > longest {
+        return false;
+    }
+    edit_distance(&a, &b) * 10 <= longest
+}
+
+/// Text names of common emoji, used when transliterating.
+const EMOJI_NAMES: [(char, &str); 30] = [
+    ('😀', ":grinning:"),
+    ('😂', ":joy:"),
+    ('🤣', ":rofl:"),
+    ('😊', ":blush:"),
+    ('🙂', ":slight_smile:"),
+    ('😉', ":wink:"),
+    ('😍', ":heart_eyes:"),
+    ('😎', ":sunglasses:"),
+    ('😅', ":sweat_smile:"),
+    ('😢', ":cry:"),
+    ('😭', ":sob:"),
+    ('😡', ":rage:"),
+    ('🤔', ":thinking:"),
+    ('🤷', ":shrug:"),
+    ('🙄', ":eye_roll:"),
+    ('💀', ":skull:"),
+    ('👀', ":eyes:"),
+    ('👍', ":thumbsup:"),
+    ('👎', ":thumbsdown:"),
+    ('👏', ":clap:"),
+    ('🙏', ":pray:"),
+    ('💯', ":100:"),
+    ('🔥', ":fire:"),
+    ('🎉', ":tada:"),
+    ('🚀', ":rocket:"),
+    ('❤', ":heart:"),
+    ('⭐', ":star:"),
+    ('✅', ":white_check_mark:"),
+    ('❌', ":x:"),
+    ('⚠', ":warning:"),
+];
+
+/// Emoji, pictographs and the joiners and selectors that combine them.
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1_0000.. // astral planes: emoji, pictographs, flags, ...
+        | 0x2300..=0x23FF
+        | 0x2600..=0x27BF
+        | 0x2B00..=0x2BFF
+        | 0x200D
+        | 0x20E3
+        | 0xFE0E..=0xFE0F
+        | 0x3030
+        | 0x303D
+    )
+}
+
+/// ASCII stand-ins for typographic punctuation.
+fn ascii_punctuation(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\u{201C}' | '\u{201D}' | '\u{201E}' => "\"",
+        '\u{2018}' | '\u{2019}' | '\u{201A}' => "'",
+        '\u{2013}' | '\u{2014}' => "-",
+        '\u{2026}' => "...",
+        '\u{00A0}' => " ",
+        _ => return None,
+    })
+}
+
+/// Tidies markdown written for old Reddit: drops empty styling links and the
+/// zero-width spaces used as spacers, and trims trailing whitespace and runs
+/// of blank lines.
+pub fn clean_markdown(text: &str) -> String {
+    let text = text
+        .replace("\r\n", "\n")
+        .replace("&#x200B;", "")
+        .replace('\u{200B}', "");
+    let text = EMPTY_LINK.replace_all(&text, "");
+    let text = text
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    BLANK_LINES.replace_all(&text, "\n\n").trim().to_string()
+}
+
+/// Removes emoji from `text`, or with `Transliterate` replaces common ones
+/// with their :name: and typographic punctuation with ASCII.
+pub fn sanitize_emoji(text: &str, mode: EmojiMode) -> String {
+    if mode == EmojiMode::Keep {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if mode == EmojiMode::Transliterate {
+            if let Some((_, name)) = EMOJI_NAMES.iter().find(|(emoji, _)| *emoji == c) {
+                out.push_str(name);
+                continue;
+            }
+            if let Some(ascii) = ascii_punctuation(c) {
+                out.push_str(ascii);
+                continue;
+            }
+        }
+        if !is_emoji(c) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The pattern matching any of `words` as a whole word, ignoring case, or
+/// `None` when there is nothing to mask.
+pub fn mask_pattern(words: &[String]) -> Option<Regex> {
+    let words: Vec<String> = words
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .map(regex::escape)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    RegexBuilder::new(&format!(
+        r"\b{{start-half}}(?:{})\b{{end-half}}",
+        words.join("|")
+    ))
+    .case_insensitive(true)
+    .build()
+    .inspect_err(|e| tracing::error!("Invalid masked_words: {}", e))
+    .ok()
+}
+
+/// Replaces the matches of `pattern` in every string of `value` with
+/// `[masked]`. Links are left alone so they keep working.
+pub fn mask_value(value: serde_json::Value, pattern: &Regex) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => Value::String(pattern.replace_all(&text, MASK_MARKER).into_owned()),
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| mask_value(item, pattern))
+            .collect(),
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| {
+                if key == "url" || key == "permalink" || key.ends_with("_url") {
+                    (key, value)
+                } else {
+                    (key, mask_value(value, pattern))
+                }
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+/// Applies [`sanitize_emoji`] to every string in `value`.
+pub fn sanitize_value(value: serde_json::Value, mode: EmojiMode) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => Value::String(sanitize_emoji(&text, mode)),
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| sanitize_value(item, mode))
+            .collect(),
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| (key, sanitize_value(value, mode)))
+            .collect(),
+        other => other,
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, Once};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::config::env_or_file;
+use crate::reddit::accounts::DEFAULT_ACCOUNT;
+use crate::reddit::error::{ErrorKind, RedditError};
+use crate::reddit::models::StoredTokens;
+use crate::storage::{Namespace, Storage};
+
+const SALT_LEN: usize = 16;
+const DEVICE_ID_KEY: &str = "device-id";
+/// Reddit asks for a device id of 20 to 30 characters.
+const DEVICE_ID_LEN: usize = 30;
+const PBKDF2_ITERATIONS: NonZeroU32 = NonZeroU32::new(100_000).expect("non-zero iterations");
+
+/// The file format of tokens encrypted with `TOKEN_STORE_KEY`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct EncryptedTokens {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Token persistence in the `tokens` area of the state directory. With
+/// `TOKEN_STORE_KEY` set the tokens are encrypted (ChaCha20-Poly1305 with a
+/// key derived from the secret by PBKDF2) into files only the owner can read.
+/// Without it tokens are never written to disk and only last until the
+/// server exits.
+#[derive(Debug, Clone)]
+pub struct TokenStore {
+    storage: Storage,
+    secret: Option<String>,
+    memory: Arc<Mutex<HashMap<String, StoredTokens>>>,
+}
+
+fn cipher_key(secret: &str, salt: &[u8]) -> LessSafeKey {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        PBKDF2_ITERATIONS,
+        salt,
+        secret.as_bytes(),
+        &mut key,
+    );
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &key).expect("32-byte key"))
+}
+
+fn corrupt(detail: impl std::fmt::Display) -> RedditError {
+    RedditError::new(
+        ErrorKind::Storage,
+        format!("The stored tokens cannot be read: {}", detail),
+        "set the TOKEN_STORE_KEY they were saved with, or delete the tokens directory in the state directory and sign in again",
+    )
+}
+
+/// The default account keeps the key used before there were several accounts.
+fn tokens_key(account: &str) -> String {
+    if account == DEFAULT_ACCOUNT {
+        "tokens".to_string()
+    } else {
+        format!("tokens-{}", account)
+    }
+}
+
+impl TokenStore {
+    pub fn new(storage: Storage) -> Self {
+        Self {
+            storage,
+            secret: env_or_file("TOKEN_STORE_KEY").filter(|secret| !secret.is_empty()),
+            memory: Arc::default(),
+        }
+    }
+
+    pub fn load(&self, account: &str) -> Result<Option<StoredTokens>, RedditError> {
+        let Some(secret) = &self.secret else {
+            return Ok(self
+                .memory
+                .lock()
+                .expect("token store lock poisoned")
+                .get(account)
+                .cloned());
+        };
+        let Some(value) = self
+            .storage
+            .read_json::<serde_json::Value>(Namespace::Tokens, &tokens_key(account))
+            .map_err(RedditError::storage)?
+        else {
+            return Ok(None);
+        };
+        let encrypted: EncryptedTokens = serde_json::from_value(value).map_err(corrupt)?;
+        let decode = |field: &str| BASE64.decode(field).map_err(corrupt);
+        let salt = decode(&encrypted.salt)?;
+        let nonce = Nonce::try_assume_unique_for_key(&decode(&encrypted.nonce)?)
+            .map_err(|_| corrupt("the nonce is invalid"))?;
+        let mut in_out = decode(&encrypted.ciphertext)?;
+        let plaintext = cipher_key(secret, &salt)
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| corrupt("TOKEN_STORE_KEY does not match"))?;
+        serde_json::from_slice(plaintext).map(Some).map_err(corrupt)
+    }
+
+    pub fn save(&self, account: &str, tokens: &StoredTokens) -> Result<(), RedditError> {
+        let Some(secret) = &self.secret else {
+            static WARN_MEMORY_ONLY: Once = Once::new();
+            WARN_MEMORY_ONLY.call_once(|| {
+                tracing::warn!(
+                    "TOKEN_STORE_KEY is not set; tokens are kept in memory only and are lost on restart"
+                );
+            });
+            self.memory
+                .lock()
+                .expect("token store lock poisoned")
+                .insert(account.to_string(), tokens.clone());
+            return Ok(());
+        };
+
+        let random = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        random
+            .fill(&mut salt)
+            .and_then(|()| random.fill(&mut nonce))
+            .map_err(|_| {
+                RedditError::new(
+                    ErrorKind::Other,
+                    "The system random number generator failed",
+                    "retry; the tokens were not saved",
+                )
+            })?;
+        let mut in_out = serde_json::to_vec(tokens).map_err(RedditError::serialize)?;
+        cipher_key(secret, &salt)
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| corrupt("encryption failed"))?;
+
+        self.storage
+            .write_json_private(
+                Namespace::Tokens,
+                &tokens_key(account),
+                &EncryptedTokens {
+                    salt: BASE64.encode(salt),
+                    nonce: BASE64.encode(nonce),
+                    ciphertext: BASE64.encode(in_out),
+                },
+            )
+            .map_err(RedditError::storage)
+    }
+
+    /// The device id application-only tokens of installed apps are requested
+    /// for: `REDDIT_DEVICE_ID`, or one generated once and kept in the store.
+    pub fn device_id(&self) -> Result<String, RedditError> {
+        if let Some(device_id) = env_or_file("REDDIT_DEVICE_ID") {
+            return Ok(device_id);
+        }
+        if let Some(device_id) = self
+            .storage
+            .read_json::<String>(Namespace::Tokens, DEVICE_ID_KEY)
+            .map_err(RedditError::storage)?
+        {
+            return Ok(device_id);
+        }
+        let mut device_id = uuid::Uuid::new_v4().simple().to_string();
+        device_id.truncate(DEVICE_ID_LEN);
+        self.storage
+            .write_json(Namespace::Tokens, DEVICE_ID_KEY, &device_id)
+            .map_err(RedditError::storage)?;
+        Ok(device_id)
+    }
+}
@@ -0,0 +1,258 @@
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::{Client, Url, header, redirect};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::reddit::models::{LinkPreview, Post};
+
+const UNFURL_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_UNFURL_BYTES: usize = 256 * 1024;
+const MAX_CONCURRENT_UNFURLS: usize = 8;
+const MAX_REDIRECTS: usize = 3;
+/// Hosts whose links are Reddit media rather than pages worth previewing.
+const REDDIT_MEDIA_HOSTS: [&str; 4] = [
+    "reddit.com",
+    "redd.it",
+    "redditmedia.com",
+    "reddit.app.link",
+];
+
+static TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid title regex"));
+static META: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<meta\s[^>]*>").expect("valid meta regex"));
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)([a-z:_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid attribute regex")
+});
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads the title, description and preview image from the `<head>` of a page.
+fn parse_preview(html: &str) -> LinkPreview {
+    let mut preview = LinkPreview::default();
+    let mut og_title = None;
+
+    for tag in META.find_iter(html) {
+        let mut key = None;
+        let mut content = None;
+        for attribute in ATTRIBUTE.captures_iter(tag.as_str()) {
+            let value = attribute
+                .get(2)
+                .or_else(|| attribute.get(3))
+                .map(|value| decode_entities(value.as_str()));
+            match attribute[1].to_ascii_lowercase().as_str() {
+                "property" | "name" => key = value.map(|key| key.to_ascii_lowercase()),
+                "content" => content = value,
+                _ => {}
+            }
+        }
+        let (Some(key), Some(content)) = (key, content.filter(|content| !content.is_empty()))
+        else {
+            continue;
+        };
+        match key.as_str() {
+            "og:title" | "twitter:title" => {
+                og_title.get_or_insert(content);
+            }
+            "og:description" | "twitter:description" | "description" => {
+                preview.description.get_or_insert(content);
+            }
+            "og:image" | "og:image:url" | "twitter:image" => {
+                preview.image.get_or_insert(content);
+            }
+            "og:site_name" => {
+                preview.site_name.get_or_insert(content);
+            }
+            _ => {}
+        }
+    }
+
+    preview.title = og_title.or_else(|| {
+        TITLE
+            .captures(html)
+            .map(|title| decode_entities(&title[1]))
+            .filter(|title| !title.is_empty())
+    });
+    preview
+}
+
+/// Refuses hosts that would let a post make the server probe its own network.
+fn is_public_url(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    if host.eq_ignore_ascii_case("localhost") || host.ends_with(".localhost") {
+        return false;
+    }
+    match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        Ok(IpAddr::V6(ip)) => !(ip.is_loopback() || ip.is_unspecified()),
+        Err(_) => true,
+    }
+}
+
+fn is_unfurlable(post: &Post) -> Option<Url> {
+    if post.is_self {
+        return None;
+    }
+    let url = Url::parse(&post.url).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    let reddit_media = REDDIT_MEDIA_HOSTS
+        .iter()
+        .any(|media| host == *media || host.ends_with(&format!(".{}", media)));
+    (!reddit_media && is_public_url(&url)).then_some(url)
+}
+
+/// Fetches previews of the pages link posts point to. Every fetch is bounded
+/// by a short timeout and a size cap, and failures just leave the preview out.
+#[derive(Debug, Clone)]
+pub struct Unfurler {
+    client: Client,
+    permits: Arc<Semaphore>,
+}
+
+impl Unfurler {
+    pub fn new(user_agent: &str) -> reqwest::Result<Self> {
+        let client = Client::builder()
+            .user_agent(user_agent)
+            .timeout(UNFURL_TIMEOUT)
+            .redirect(redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS || !is_public_url(attempt.url()) {
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()?;
+
+        Ok(Self {
+            client,
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_UNFURLS)),
+        })
+    }
+
+    async fn fetch(&self, url: Url) -> Option<LinkPreview> {
+        let _permit = self.permits.acquire().await.ok()?;
+
+        let mut response = self
+            .client
+            .get(url.clone())
+            .header(header::ACCEPT, "text/html")
+            .send()
+            .await
+            .ok()?;
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("html"));
+        if !response.status().is_success() || !is_html {
+            return None;
+        }
+
+        let mut body = Vec::new();
+        while let Ok(Some(chunk)) = response.chunk().await {
+            body.extend_from_slice(&chunk);
+            if body.len() >= MAX_UNFURL_BYTES {
+                body.truncate(MAX_UNFURL_BYTES);
+                break;
+            }
+        }
+
+        let preview = parse_preview(&String::from_utf8_lossy(&body));
+        (preview.title.is_some() || preview.description.is_some()).then_some(preview)
+    }
+
+    /// Downloads an image of at most `max_bytes`, returning its bytes and
+    /// MIME type. Anything else, or anything larger, is None.
+    pub async fn download_image(&self, url: Url, max_bytes: usize) -> Option<(Vec<u8>, String)> {
+        if !is_public_url(&url) {
+            return None;
+        }
+        let _permit = self.permits.acquire().await.ok()?;
+
+        let mut response = self
+            .client
+            .get(url)
+            .header(header::ACCEPT, "image/*")
+            .send()
+            .await
+            .ok()?;
+        let mime_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| value.starts_with("image/"))?;
+        if !response.status().is_success()
+            || response
+                .content_length()
+                .is_some_and(|length| length > max_bytes as u64)
+        {
+            return None;
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.ok()? {
+            body.extend_from_slice(&chunk);
+            if body.len() > max_bytes {
+                return None;
+            }
+        }
+        Some((body, mime_type))
+    }
+
+    /// Follows the redirects of a link, e.g. a shortened one, to the URL it
+    /// ends at.
+    pub async fn resolve(&self, url: Url) -> Option<Url> {
+        if !is_public_url(&url) {
+            return None;
+        }
+        let _permit = self.permits.acquire().await.ok()?;
+        let response = self.client.get(url).send().await.ok()?;
+        (!response.status().is_redirection()).then(|| response.url().clone())
+    }
+
+    /// Attaches a `link_preview` to every link post whose page could be read.
+    pub async fn unfurl(&self, posts: &mut [Post]) {
+        let mut fetches = JoinSet::new();
+        for (index, post) in posts.iter().enumerate() {
+            if let Some(url) = is_unfurlable(post) {
+                let unfurler = self.clone();
+                fetches.spawn(async move { (index, unfurler.fetch(url).await) });
+            }
+        }
+
+        while let Some(result) = fetches.join_next().await {
+            if let Ok((index, preview)) = result {
+                posts[index].link_preview = preview;
+            }
+        }
+    }
+}
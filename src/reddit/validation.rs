@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, NaiveDate};
+
+use crate::reddit::error::RedditError;
+
+const REDDIT_HOSTS: [&str; 5] = [
+    "reddit.com",
+    "www.reddit.com",
+    "old.reddit.com",
+    "new.reddit.com",
+    "np.reddit.com",
+];
+
+/// Normalizes user supplied subreddit strings such as `r/Rust/`, `/R/rust` or
+/// `https://www.reddit.com/r/rust/comments/...` to a bare subreddit name.
+pub fn normalize_subreddit(input: &str) -> Result<String, RedditError> {
+    let path = strip_reddit_host(input.trim());
+    let path = path.trim_start_matches('/');
+    let path = match path.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("r/") => &path[2..],
+        _ => path,
+    };
+    let name = path.split(['/', '?', '#']).next().unwrap_or_default();
+
+    let valid = (2..=21).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid subreddit name", input),
+            "pass a subreddit name of 2-21 letters, digits or underscores, e.g. rust or r/rust",
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Normalizes user supplied usernames such as `u/spez`, `/user/spez/` or a profile URL.
+pub fn normalize_username(input: &str) -> Result<String, RedditError> {
+    let path = strip_reddit_host(input.trim());
+    let path = path.trim_start_matches('/');
+    let lowercase = path.to_ascii_lowercase();
+    let path = if lowercase.starts_with("user/") {
+        &path[5..]
+    } else if lowercase.starts_with("u/") {
+        &path[2..]
+    } else {
+        path
+    };
+    let name = path.split(['/', '?', '#']).next().unwrap_or_default();
+
+    let valid = (3..=20).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid username", input),
+            "pass a username of 3-20 letters, digits, underscores or dashes, e.g. spez or u/spez",
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Normalizes a post id given as `abc123`, `t3_abc123`, a permalink or a redd.it link.
+pub fn normalize_post_id(input: &str) -> Result<String, RedditError> {
+    let input = input.trim();
+    let path = strip_reddit_host(input);
+    let path = path.strip_prefix("redd.it/").unwrap_or(path);
+    let id = match path
+        .split('/')
+        .skip_while(|part| *part != "comments")
+        .nth(1)
+    {
+        Some(id) => id,
+        None => path.trim_matches('/'),
+    };
+    let id = id.strip_prefix("t3_").unwrap_or(id);
+    let id = id.split(['?', '#']).next().unwrap_or_default();
+
+    let valid = (1..=12).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(RedditError::invalid_input(
+            format!("{:?} is not a valid post id", input),
+            "pass a post id such as 1abcde, its fullname t3_1abcde or the post's reddit.com URL",
+        ));
+    }
+
+    Ok(id.to_ascii_lowercase())
+}
+
+/// Normalizes a post or comment given as a `t1_`/`t3_` fullname, a permalink or a
+/// bare post id to its fullname.
+pub fn normalize_thing_id(input: &str) -> Result<String, RedditError> {
+    let input = input.trim();
+    let path = input.split(['?', '#']).next().unwrap_or_default();
+    let comment_id = path.strip_prefix("t1_").or_else(|| {
+        path.split('/')
+            .skip_while(|part| *part != "comments")
+            .nth(3)
+            .filter(|id| !id.is_empty())
+    });
+    match comment_id {
+        Some(id) if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            Ok(format!("t1_{}", id.to_ascii_lowercase()))
+        }
+        Some(_) => Err(RedditError::invalid_input(
+            format!("{:?} is not a valid comment", input),
+            "pass a fullname such as t1_abc123 or the comment's permalink",
+        )),
+        None => normalize_post_id(input).map(|id| format!("t3_{}", id)),
+    }
+}
+
+/// Parses a unix timestamp, an RFC 3339 date-time or a `YYYY-MM-DD` date (midnight UTC)
+/// into seconds since the epoch.
+pub fn parse_timestamp(input: &str, field: &str) -> Result<i64, RedditError> {
+    let input = input.trim();
+    if let Ok(seconds) = input.parse::<i64>() {
+        return Ok(seconds);
+    }
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(date_time.timestamp());
+    }
+    if let Some(midnight) = NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+    {
+        return Ok(midnight.and_utc().timestamp());
+    }
+
+    Err(RedditError::invalid_input(
+        format!("{} {:?} is not a valid time", field, input),
+        "pass a unix timestamp, an RFC 3339 time such as 2024-05-01T12:00:00Z or a date such as 2024-05-01",
+    ))
+}
+
+fn strip_reddit_host(input: &str) -> &str {
+    let without_scheme = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+
+    REDDIT_HOSTS
+        .iter()
+        .find_map(|host| without_scheme.strip_prefix(host))
+        .unwrap_or(without_scheme)
+}
+
+/// Case-insensitive edit distance, used to rank "did you mean" suggestions.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Returns up to `count` candidates closest to `target`.
+pub fn closest_matches(target: &str, candidates: Vec<String>, count: usize) -> Vec<String> {
+    let mut ranked: Vec<(usize, String)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(target, &candidate), candidate))
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.1.eq_ignore_ascii_case(&b.1));
+    ranked
+        .into_iter()
+        .take(count)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Names that were already confirmed to exist, keyed by their lowercase form.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatedNames {
+    canonical: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl ValidatedNames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        let canonical = self
+            .canonical
+            .lock()
+            .expect("validated names lock poisoned");
+        canonical.get(&name.to_lowercase()).cloned()
+    }
+
+    pub fn insert(&self, canonical_name: &str) {
+        let mut canonical = self
+            .canonical
+            .lock()
+            .expect("validated names lock poisoned");
+        canonical.insert(canonical_name.to_lowercase(), canonical_name.to_string());
+    }
+}
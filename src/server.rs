@@ -0,0 +1,106 @@
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use axum::{Json, Router, routing::get};
+use rmcp::ServiceExt;
+use rmcp::transport::{SseServer, stdio};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::env_or_file;
+use crate::reddit::client::RedditClient;
+
+const DEFAULT_HTTP_BIND: &str = "0.0.0.0:8080";
+const DEFAULT_HEALTH_BIND: &str = "0.0.0.0:8081";
+
+/// Serves a single MCP session over stdin/stdout.
+pub async fn serve_stdio(client: RedditClient) -> Result<()> {
+    let ct = CancellationToken::new();
+    let service = client
+        .serve_with_ct(stdio(), ct.clone())
+        .await
+        .inspect_err(|e| {
+            tracing::error!("Server error: {:?}", e);
+        })?;
+
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        ct.cancel();
+    });
+
+    service.waiting().await?;
+
+    Ok(())
+}
+
+/// Container mode: MCP over HTTP (SSE) on `MCP_BIND` and a `/healthz` probe on `HEALTH_BIND`.
+/// Every connection gets its own session backed by the shared client.
+pub async fn serve_http(client: RedditClient) -> Result<()> {
+    let bind = bind_address("MCP_BIND", DEFAULT_HTTP_BIND)?;
+    let health_bind = bind_address("HEALTH_BIND", DEFAULT_HEALTH_BIND)?;
+
+    let sse_server = SseServer::serve(bind)
+        .await
+        .with_context(|| format!("Failed to bind MCP_BIND {}", bind))?;
+    let ct = sse_server.with_service(move || client.for_session());
+    tracing::info!("Serving MCP over HTTP on {} (SSE at /sse)", bind);
+
+    let health_listener = tokio::net::TcpListener::bind(health_bind)
+        .await
+        .with_context(|| format!("Failed to bind HEALTH_BIND {}", health_bind))?;
+    let health_app = Router::new().route("/healthz", get(healthz));
+    let health_ct = ct.clone();
+    let health_server = tokio::spawn(async move {
+        axum::serve(health_listener, health_app)
+            .with_graceful_shutdown(async move { health_ct.cancelled().await })
+            .await
+    });
+    tracing::info!("Serving /healthz on {}", health_bind);
+
+    shutdown_signal().await;
+    ct.cancel();
+    health_server.await??;
+
+    Ok(())
+}
+
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+fn bind_address(key: &str, default: &str) -> Result<SocketAddr> {
+    let value = env_or_file(key).unwrap_or_else(|| default.to_string());
+    value
+        .parse()
+        .with_context(|| format!("{} must be an address like {}, got {}", key, default, value))
+}
+
+/// Resolves on SIGINT or SIGTERM. Running as PID 1 in a container there are no
+/// default signal handlers, so without this `docker stop` would hang until SIGKILL.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received SIGINT, shutting down"),
+        _ = terminate => tracing::info!("Received SIGTERM, shutting down"),
+    }
+}
@@ -0,0 +1,319 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const STATE_DIR_NAME: &str = "reddit-mcp";
+const VERSION_FILE: &str = "state_version";
+const CURRENT_VERSION: u32 = 1;
+
+type Migration = fn(&Path) -> io::Result<()>;
+
+/// Migrations indexed by the version they upgrade from.
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Version 1 introduces one directory per feature namespace.
+fn migrate_v0_to_v1(root: &Path) -> io::Result<()> {
+    for namespace in Namespace::ALL {
+        fs::create_dir_all(root.join(namespace.dir_name()))?;
+    }
+    Ok(())
+}
+
+/// Areas of the state directory, one subdirectory each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    Tokens,
+    Schedules,
+    Monitors,
+    Archives,
+    Searches,
+    Deletions,
+    Submissions,
+    Modqueues,
+    Backups,
+    Idempotency,
+    Jobs,
+    Experiments,
+    Replies,
+    Notifications,
+}
+
+impl Namespace {
+    pub const ALL: [Namespace; 14] = [
+        Namespace::Tokens,
+        Namespace::Schedules,
+        Namespace::Monitors,
+        Namespace::Archives,
+        Namespace::Searches,
+        Namespace::Deletions,
+        Namespace::Submissions,
+        Namespace::Modqueues,
+        Namespace::Backups,
+        Namespace::Idempotency,
+        Namespace::Jobs,
+        Namespace::Experiments,
+        Namespace::Replies,
+        Namespace::Notifications,
+    ];
+
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Namespace::Tokens => "tokens",
+            Namespace::Schedules => "schedules",
+            Namespace::Monitors => "monitors",
+            Namespace::Archives => "archives",
+            Namespace::Searches => "searches",
+            Namespace::Deletions => "deletions",
+            Namespace::Submissions => "submissions",
+            Namespace::Modqueues => "modqueues",
+            Namespace::Backups => "backups",
+            Namespace::Idempotency => "idempotency",
+            Namespace::Jobs => "jobs",
+            Namespace::Experiments => "experiments",
+            Namespace::Replies => "replies",
+            Namespace::Notifications => "notifications",
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct NamespaceInfo {
+    pub name: &'static str,
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct StateInfo {
+    pub root: PathBuf,
+    pub version: u32,
+    pub namespaces: Vec<NamespaceInfo>,
+}
+
+/// Versioned local state directory shared by every feature that persists data.
+///
+/// The location is `REDDIT_MCP_STATE_DIR` when set, otherwise
+/// `$XDG_STATE_HOME/reddit-mcp` or `~/.local/state/reddit-mcp`. Opening the
+/// directory runs any pending migrations.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    root: PathBuf,
+}
+
+impl Storage {
+    pub fn open_from_env() -> io::Result<Self> {
+        Self::open(Self::default_root()?)
+    }
+
+    fn default_root() -> io::Result<PathBuf> {
+        if let Some(root) = env::var_os("REDDIT_MCP_STATE_DIR").filter(|root| !root.is_empty()) {
+            return Ok(PathBuf::from(root));
+        }
+        if let Some(state_home) = env::var_os("XDG_STATE_HOME").filter(|home| !home.is_empty()) {
+            return Ok(PathBuf::from(state_home).join(STATE_DIR_NAME));
+        }
+        let home = env::var_os("HOME").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "HOME is not set; set REDDIT_MCP_STATE_DIR to choose a state directory",
+            )
+        })?;
+        Ok(PathBuf::from(home)
+            .join(".local")
+            .join("state")
+            .join(STATE_DIR_NAME))
+    }
+
+    pub fn open(root: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&root)?;
+        let storage = Self { root };
+        storage.migrate()?;
+        Ok(storage)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn version(&self) -> io::Result<u32> {
+        match fs::read_to_string(self.root.join(VERSION_FILE)) {
+            Ok(version) => version.trim().parse().map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Corrupt {}: {}", VERSION_FILE, e),
+                )
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn migrate(&self) -> io::Result<()> {
+        let version = self.version()?;
+        if version > CURRENT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "State directory {} has version {}, newer than the supported {}",
+                    self.root.display(),
+                    version,
+                    CURRENT_VERSION
+                ),
+            ));
+        }
+
+        for from in version..CURRENT_VERSION {
+            tracing::info!("Migrating state directory from version {}", from);
+            MIGRATIONS[from as usize](&self.root)?;
+            self.write_atomic(
+                &self.root.join(VERSION_FILE),
+                (from + 1).to_string().as_bytes(),
+                false,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn namespace_dir(&self, namespace: Namespace) -> PathBuf {
+        self.root.join(namespace.dir_name())
+    }
+
+    fn write_atomic(&self, path: &Path, bytes: &[u8], private: bool) -> io::Result<()> {
+        let temporary = path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        #[cfg(not(unix))]
+        let _ = private;
+        let mut file = options.open(&temporary)?;
+        // The mode only applies to new files; a leftover one keeps its own.
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(bytes)?;
+        fs::rename(&temporary, path)
+    }
+
+    fn entry_path(&self, namespace: Namespace, key: &str) -> io::Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not a valid state entry name", key),
+            ));
+        }
+        Ok(self.namespace_dir(namespace).join(format!("{}.json", key)))
+    }
+
+    pub fn read_json<T>(&self, namespace: Namespace, key: &str) -> io::Result<Option<T>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        match fs::read(self.entry_path(namespace, key)?) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn write_json<T>(&self, namespace: Namespace, key: &str, value: &T) -> io::Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let bytes = serde_json::to_vec_pretty(value)?;
+        self.write_atomic(&self.entry_path(namespace, key)?, &bytes, false)
+    }
+
+    /// Like `write_json`, but the file is only readable by its owner.
+    pub fn write_json_private<T>(
+        &self,
+        namespace: Namespace,
+        key: &str,
+        value: &T,
+    ) -> io::Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let bytes = serde_json::to_vec_pretty(value)?;
+        self.write_atomic(&self.entry_path(namespace, key)?, &bytes, true)
+    }
+
+    /// Removes an entry, returning whether it existed.
+    pub fn remove(&self, namespace: Namespace, key: &str) -> io::Result<bool> {
+        match fs::remove_file(self.entry_path(namespace, key)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Names of the JSON entries stored in a namespace, sorted.
+    pub fn keys(&self, namespace: Namespace) -> io::Result<Vec<String>> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(self.namespace_dir(namespace))? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+            {
+                keys.push(stem.to_string());
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    pub fn info(&self) -> io::Result<StateInfo> {
+        let mut namespaces = Vec::new();
+        for namespace in Namespace::ALL {
+            let (entries, bytes) = directory_usage(&self.namespace_dir(namespace))?;
+            namespaces.push(NamespaceInfo {
+                name: namespace.dir_name(),
+                entries,
+                bytes,
+            });
+        }
+
+        Ok(StateInfo {
+            root: self.root.clone(),
+            version: self.version()?,
+            namespaces,
+        })
+    }
+}
+
+fn directory_usage(directory: &Path) -> io::Result<(usize, u64)> {
+    let mut entries = 0;
+    let mut bytes = 0;
+
+    let listing = match fs::read_dir(directory) {
+        Ok(listing) => listing,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(e),
+    };
+    for entry in listing {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            let (nested_entries, nested_bytes) = directory_usage(&entry.path())?;
+            entries += nested_entries;
+            bytes += nested_bytes;
+        } else {
+            entries += 1;
+            bytes += metadata.len();
+        }
+    }
+
+    Ok((entries, bytes))
+}
@@ -0,0 +1,80 @@
+use std::env;
+
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+const SERVICE_NAME: &str = "reddit-mcp";
+
+/// Sets up stderr logging (plain text, or JSON lines with `LOG_FORMAT=json`) and, when `OTEL_EXPORTER_OTLP_ENDPOINT` (or
+/// `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set, OTLP/HTTP export of the tool
+/// call and Reddit request spans. The returned provider must be shut down on
+/// exit to flush pending spans.
+pub fn init() -> Option<SdkTracerProvider> {
+    let otlp_configured = [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    ]
+    .iter()
+    .any(|key| env::var(key).is_ok_and(|value| !value.is_empty()));
+
+    let mut exporter_error = None;
+    let provider = if otlp_configured {
+        match SpanExporter::builder().with_http().build() {
+            Ok(exporter) => Some(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+                    .build(),
+            ),
+            Err(e) => {
+                exporter_error = Some(e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)));
+
+    let json_logs = env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(false);
+    // JSON lines keep their field names stable: timestamp, level, target, message,
+    // the event fields flattened next to them and the enclosing span as `span`.
+    let fmt_layer = if json_logs {
+        fmt_layer
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed()
+    } else {
+        fmt_layer.boxed()
+    };
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into()))
+        .with(fmt_layer)
+        .with(otel_layer)
+        .init();
+
+    if let Some(e) = exporter_error {
+        tracing::warn!("Failed to create the OTLP span exporter: {}", e);
+    }
+
+    provider
+}
+
+pub fn shutdown(provider: Option<SdkTracerProvider>) {
+    if let Some(provider) = provider
+        && let Err(e) = provider.shutdown()
+    {
+        tracing::warn!("Failed to flush OpenTelemetry spans: {}", e);
+    }
+}